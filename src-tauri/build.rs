@@ -1,3 +1,47 @@
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Embed the build commit as `RESTY_BUILD_COMMIT` (CI may set it explicitly).
+fn embed_build_commit() {
+    println!("cargo:rerun-if-env-changed=RESTY_BUILD_COMMIT");
+    if Path::new("../.git/HEAD").exists() {
+        println!("cargo:rerun-if-changed=../.git/HEAD");
+    }
+
+    let commit = std::env::var("RESTY_BUILD_COMMIT")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .or_else(|| {
+            Command::new("git")
+                .args(["rev-parse", "--short", "HEAD"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        })
+        .unwrap_or_default();
+    println!("cargo:rustc-env=RESTY_BUILD_COMMIT={}", commit);
+}
+
+/// Embed the build time (Unix seconds) as `RESTY_BUILD_TIMESTAMP`, honouring
+/// `SOURCE_DATE_EPOCH` for reproducible builds.
+fn embed_build_timestamp() {
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    let timestamp = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs() as i64)
+                .unwrap_or_default()
+        });
+    println!("cargo:rustc-env=RESTY_BUILD_TIMESTAMP={}", timestamp);
+}
+
 fn main() {
+    embed_build_commit();
+    embed_build_timestamp();
     tauri_build::build()
 }
@@ -1,23 +1,118 @@
+use crate::dispatch_control_action;
 use crate::models::{
-    AchievementUnlock, AnalyticsData, AnalyticsQuery, FloatingPosition, MonitorInfo, Session,
-    SessionsBounds, Settings, SystemStatus, TimerInfo,
+    AccessibilityPrefs, AchievementUnlock, AnalyticsData, AnalyticsQuery, AppInfo, AudioStatus,
+    Bootstrap, BreakContent, BreakExercise, BreakTheme, ControlSource, CustomExercise,
+    DataExportEstimate, DataExportProgress, DataExportSummary, DurationHistogram, EncryptionStatus,
+    FloatingPosition, FocusScore, HydrationProgress, LifetimeStats, MonitorInfo,
+    MusicTrackUnavailable, OutputCheck, PhaseChangeCause, PowerMonitorHealth, QuoteProvider,
+    RatingStats, ReminderFailure, ReminderOutputTest, ScheduledPhase, Session, SessionListPage,
+    SessionListQuery, SessionsBounds, SettingChange, Settings, SettingsChanged, StorageInfo,
+    SystemStatus, TickerHealth, TimerAccuracy, TimerInfo, TimerPhase, TimerPreset, WorkSegment,
+    MAX_SEGMENT_LABEL_CHARS, MAX_SEGMENT_MINUTES, MAX_SEGMENT_REPEAT,
 };
-use crate::services::{updater::UpdateManifest, DatabaseService, TimerService};
-use crate::handle_tray_action;
-use crate::utils::AppError;
-use chrono::{DateTime, Utc};
+use crate::services::{
+    break_themes, error_log, exercises, power, system_status,
+    updater::{self, StagedUpdate, UpdateManifest},
+    webhook, DatabaseService, TimerService,
+};
+use crate::utils::data_export::{self, DataExport};
+use crate::utils::time_entries::{self, TimeEntryFormat};
+use crate::utils::{AppError, AppResult};
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveDate, NaiveTime, Timelike, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 use tauri::{AppHandle, Emitter, Manager, State};
 
+/// Upper bound accepted for `maxSuppressionMinutes` (one day).
+const MAX_SUPPRESSION_CAP_MINUTES: u32 = 24 * 60;
+/// Upper bound accepted for `suppressionExpiryWarningMinutes`.
+const MAX_SUPPRESSION_EXPIRY_WARNING_MINUTES: u32 = 60;
+/// Range accepted for `anomalyDetection.breakOverrunRatio`.
+const MIN_BREAK_OVERRUN_RATIO: f64 = 1.5;
+const MAX_BREAK_OVERRUN_RATIO: f64 = 100.0;
+/// Range accepted for `anomalyDetection.maxWorkMinutes`.
+const MIN_ANOMALY_WORK_MINUTES: u32 = 60;
+const MAX_ANOMALY_WORK_MINUTES: u32 = 24 * 60;
+/// Longest accepted `sessionIdPrefix`.
+const MAX_SESSION_ID_PREFIX_CHARS: usize = 16;
+/// Upper bound accepted for `breakJitterMinutes`.
+const MAX_BREAK_JITTER_MINUTES: u32 = 30;
+/// Upper bound accepted for `longAbsenceMinutes`.
+const MAX_LONG_ABSENCE_MINUTES: u32 = 24 * 60;
+/// Upper bound accepted for `maxTotalExtendMinutes`.
+const MAX_TOTAL_EXTEND_MINUTES: u32 = 24 * 60;
+/// Upper bound accepted for `workStartCountdownSeconds`.
+const MAX_WORK_START_COUNTDOWN_SECONDS: u32 = 30;
+/// Upper bound accepted for `minSessionSeconds`.
+const MAX_MIN_SESSION_SECONDS: u32 = 10 * 60;
+/// Limits accepted for `customExercises` (ids and categories share the first).
+const MAX_EXERCISE_ID_CHARS: usize = 32;
+const MAX_EXERCISE_TEXT_CHARS: usize = 200;
+const MAX_EXERCISE_SECONDS: u32 = 10 * 60;
+/// Longest accepted `analyticsLoadWindowDays` (0 = load everything).
+const MAX_ANALYTICS_LOAD_WINDOW_DAYS: u32 = 3650;
+/// Limits accepted for `breakThemes` (names share the preset name limit).
+const MAX_BREAK_THEMES: usize = 31;
+const MAX_THEME_MESSAGES: usize = 100;
+const MAX_THEME_MESSAGE_CHARS: usize = 500;
+/// Limits accepted for `hydration`.
+const MAX_HYDRATION_GOAL_GLASSES: u32 = 50;
+const MAX_HYDRATION_INTERVAL_MINUTES: u32 = 8 * 60;
+/// Largest page `list_sessions` returns.
+const MAX_SESSION_LIST_LIMIT: usize = 500;
+/// Most `networkQuietHours` ranges accepted.
+const MAX_NETWORK_QUIET_HOURS: usize = 16;
+/// Range accepted for `minRealBreakMinutes`.
+const MIN_REAL_BREAK_MINUTES: u32 = 1;
+const MAX_REAL_BREAK_MINUTES: u32 = 120;
+/// Range accepted for `activitySampleSeconds`; `activityIdleThresholdSeconds`
+/// must be at least the interval and at most the upper bound below.
+const MIN_ACTIVITY_SAMPLE_SECONDS: u32 = 5;
+const MAX_ACTIVITY_SAMPLE_SECONDS: u32 = 300;
+const MAX_ACTIVITY_IDLE_THRESHOLD_SECONDS: u32 = 3600;
+/// Work sessions shorter than this are left out of time entry exports by default.
+const DEFAULT_TIME_ENTRY_MIN_MINUTES: u32 = 1;
+/// Longest accepted timer preset name.
+const MAX_PRESET_NAME_CHARS: usize = 64;
+/// Imported configs larger than this are rejected before parsing
+const MAX_IMPORT_BYTES: usize = 256 * 1024;
+/// Written to exported configs as `app` and checked on import when present
+const CONFIG_APP_ID: &str = "com.youtonghy.resty";
+/// Data packages (settings plus full history) larger than this are rejected
+const MAX_APP_DATA_IMPORT_BYTES: u64 = 64 * 1024 * 1024;
+/// At most this many unknown keys are named in an import error
+const MAX_REPORTED_UNKNOWN_KEYS: usize = 5;
+/// How long the reminder bootstrap waits for (possibly remote) break content.
+const BOOTSTRAP_CONTENT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
 /// Shared application state for Tauri commands.
+#[derive(Clone)]
 pub struct AppState {
     pub timer_service: Arc<TimerService>,
     pub database_service: Arc<tokio::sync::Mutex<DatabaseService>>,
+    /// When the tray menu last hid itself on focus loss (reopen debounce).
     pub last_auto_close: Arc<std::sync::Mutex<Option<Instant>>>,
+    /// Held while the tray menu window is looked up or built, so concurrent
+    /// clicks cannot create two instances.
+    pub tray_menu_lock: Arc<std::sync::Mutex<()>>,
+    /// Last time each tray action id was handled (per-action debounce).
+    pub last_tray_actions: Arc<std::sync::Mutex<HashMap<String, Instant>>>,
+    /// Reminder windows created hidden and waiting for their page to report ready.
+    pub reminder_ready_signals: crate::ReminderReadySignals,
+    /// Main window state captured when it was put away for the current break.
+    pub main_window_break_snapshot: crate::MainWindowBreakState,
+    /// Hidden reminder windows loaded ahead of time, waiting for the next break.
+    pub prewarmed_reminders: Arc<std::sync::Mutex<HashSet<String>>>,
+    /// Transient "be quiet right now" flag; independent of the saved `play_sound`.
+    pub sound_muted: Arc<AtomicBool>,
+    /// False while no tray icon could be created; closing the main window then
+    /// minimizes it instead of hiding it.
+    pub tray_available: Arc<AtomicBool>,
 }
 
 /// Check for a signed update using the official Tauri updater.
@@ -36,6 +131,22 @@ pub async fn install_update(app: AppHandle) -> Result<(), String> {
         .map_err(|e| e.to_string())
 }
 
+/// Download and verify the latest update without installing it.
+#[tauri::command]
+pub async fn download_update(app: AppHandle) -> Result<StagedUpdate, String> {
+    crate::services::updater::download_update(&app)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Install an update staged by `download_update`, saving the running session first.
+#[tauri::command]
+pub async fn install_downloaded_update(app: AppHandle, path: PathBuf) -> Result<(), String> {
+    crate::services::updater::install_downloaded_update(&app, &path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Load application settings
 #[tauri::command]
 pub async fn load_settings(state: State<'_, AppState>) -> Result<Settings, String> {
@@ -46,29 +157,70 @@ pub async fn load_settings(state: State<'_, AppState>) -> Result<Settings, Strin
 /// Save application settings
 #[tauri::command]
 pub async fn save_settings(
+    app: AppHandle,
     mut settings: Settings,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     if !settings.autostart && settings.silent_autostart {
         settings.silent_autostart = false;
     }
+    clear_stale_active_preset(&mut settings);
     // Validate settings
     validate_settings(&settings)?;
 
     // Update timer durations
-    state.timer_service.update_timer_configuration(
-        settings.work_duration,
-        settings.break_duration,
-        settings.segmented_work_enabled,
-        settings.work_segments.clone(),
-    );
     state
         .timer_service
-        .update_flow_mode(settings.flow_mode_enabled)
+        .apply_settings(&settings)
         .map_err(|e| e.to_string())?;
+    crate::apply_runtime_settings(&app, &settings);
     // Save to database
     let db = state.database_service.lock().await;
-    db.save_settings(&settings).await.map_err(|e| e.to_string())
+    let previous = db.load_settings().await.ok();
+    db.save_settings(&settings)
+        .await
+        .map_err(|e| e.to_string())?;
+    drop(db);
+
+    if let Some(previous) = previous {
+        emit_settings_changed(&app, &previous, &settings);
+    }
+    Ok(())
+}
+
+/// Emit `settings-changed` with the top-level fields that differ, if any, so
+/// open windows (e.g. a break reminder) can pick the new values up.
+fn emit_settings_changed(app: &AppHandle, previous: &Settings, current: &Settings) {
+    let keys = changed_setting_keys(previous, current);
+    if keys.is_empty() {
+        return;
+    }
+    if let Err(e) = app.emit("settings-changed", SettingsChanged { keys }) {
+        eprintln!("Failed to emit settings-changed: {}", e);
+    }
+}
+
+/// Top-level settings fields whose values differ; nested changes (e.g. one work
+/// segment) are reported under their top-level field.
+fn changed_setting_keys(previous: &Settings, current: &Settings) -> Vec<String> {
+    let (Ok(old), Ok(new)) = (
+        serde_json::to_value(previous),
+        serde_json::to_value(current),
+    ) else {
+        return Vec::new();
+    };
+    let mut changes = Vec::new();
+    diff_settings_json("", &old, &new, &mut changes);
+    let mut keys: Vec<String> = changes
+        .into_iter()
+        .map(|change| {
+            let top = change.field.split('.').next().unwrap_or_default();
+            top.to_string()
+        })
+        .collect();
+    // Leaves come out grouped by field, so repeats are adjacent
+    keys.dedup();
+    keys
 }
 
 /// List audio files in the configured rest music directory.
@@ -77,12 +229,104 @@ pub async fn get_rest_music_files(state: State<'_, AppState>) -> Result<Vec<Stri
     let directory = {
         let db = state.database_service.lock().await;
         let settings = db.load_settings().await.map_err(|e| e.to_string())?;
-        settings.rest_music_directory.clone()
+        break_themes::music_directory(&settings).to_string()
+    };
+    Ok(list_rest_music_files(&directory))
+}
+
+/// Pick a rest music track for the break, avoiding `previous` when another
+/// track exists. Returns None when no track can be played. There is no stored
+/// playlist: the candidates are listed from the music directory on every pick,
+/// so a deleted track drops out by itself and there are no entries to prune.
+#[tauri::command]
+pub async fn pick_rest_music_track(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    previous: Option<String>,
+) -> Result<Option<String>, String> {
+    let directory = {
+        let db = state.database_service.lock().await;
+        let settings = db.load_settings().await.map_err(|e| e.to_string())?;
+        break_themes::music_directory(&settings).to_string()
     };
+    Ok(pick_playable_music_track(
+        &app,
+        list_rest_music_files(&directory),
+        previous.as_deref(),
+    ))
+}
+
+/// First track of `files`, in random order with `previous` last, that can
+/// still be opened: files deleted or on a drive unplugged since the listing are
+/// skipped and reported through `music-track-unavailable`. When none opens,
+/// the break-start chime plays instead so the break does not start silently.
+/// 选择休息音乐前先确认文件仍可读取，失败则尝试下一首；全部失败时改播提示音。
+fn pick_playable_music_track(
+    app: &AppHandle,
+    mut files: Vec<String>,
+    previous: Option<&str>,
+) -> Option<String> {
+    if files.is_empty() {
+        return None;
+    }
+    let start = (uuid::Uuid::new_v4().as_u128() % files.len() as u128) as usize;
+    files.rotate_left(start);
+    if let Some(index) = files
+        .iter()
+        .position(|file| Some(file.as_str()) == previous)
+    {
+        let repeat = files.remove(index);
+        files.push(repeat);
+    }
+
+    let mut unavailable = Vec::new();
+    let mut picked = None;
+    for file in files {
+        let readable = std::fs::File::open(&file)
+            .and_then(|opened| opened.metadata())
+            .map(|metadata| metadata.is_file());
+        match readable {
+            Ok(true) => {
+                picked = Some(file);
+                break;
+            }
+            Ok(false) => unavailable.push(file),
+            Err(e) => {
+                eprintln!("Rest music track {} is unavailable: {}", file, e);
+                unavailable.push(file);
+            }
+        }
+    }
+    if unavailable.is_empty() {
+        return picked;
+    }
+
+    let fell_back_to_chime = picked.is_none();
+    if fell_back_to_chime {
+        error_log::record(
+            "Music",
+            &format!(
+                "No rest music track could be opened ({}); playing the chime instead",
+                unavailable.join(", ")
+            ),
+        );
+        crate::play_break_start_chime(app);
+    }
+    let _ = app.emit(
+        "music-track-unavailable",
+        MusicTrackUnavailable {
+            paths: unavailable,
+            fell_back_to_chime,
+        },
+    );
+    picked
+}
 
+/// Supported audio files in `directory`, sorted by path.
+fn list_rest_music_files(directory: &str) -> Vec<String> {
     let path = PathBuf::from(directory);
     if !path.exists() {
-        return Ok(vec![]);
+        return vec![];
     }
 
     let mut files: Vec<String> = Vec::new();
@@ -113,13 +357,16 @@ pub async fn get_rest_music_files(state: State<'_, AppState>) -> Result<Vec<Stri
     }
 
     files.sort();
-    Ok(files)
+    files
 }
 
 /// Start work session
 #[tauri::command]
 pub fn start_work(state: State<'_, AppState>) -> Result<(), String> {
-    state.timer_service.start_work().map_err(|e| e.to_string())
+    state
+        .timer_service
+        .start_work(PhaseChangeCause::UserStart)
+        .map_err(|e| e.to_string())
 }
 
 /// Start break session
@@ -131,7 +378,7 @@ pub fn start_work(state: State<'_, AppState>) -> Result<(), String> {
 pub fn start_break(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
     state
         .timer_service
-        .start_break()
+        .start_break(PhaseChangeCause::UserStart)
         .map_err(|e| e.to_string())?;
     let _ = app.emit("show-break-reminder", ());
     Ok(())
@@ -150,18 +397,31 @@ pub fn resume_timer(state: State<'_, AppState>) -> Result<(), String> {
 }
 
 /// Skip current phase
+///
+/// `force` bypasses the guard that ignores skips of a phase younger than two
+/// seconds (used when the user explicitly confirmed the skip).
 #[tauri::command]
-pub async fn skip_phase(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
-    let (session, should_show_reminder) = match state.timer_service.skip().map_err(|e| e.to_string())? {
+pub async fn skip_phase(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    force: Option<bool>,
+) -> Result<(), String> {
+    let skipped = state
+        .timer_service
+        .skip(force.unwrap_or(false), PhaseChangeCause::UserSkip)
+        .map_err(|e| e.to_string())?;
+    let (session, should_show_reminder) = match skipped {
         Some(v) => v,
         None => return Ok(()),
     };
 
-    // Save session to database
-    let db = state.database_service.lock().await;
-    db.save_or_update_session(&session)
-        .await
-        .map_err(|e| e.to_string())?;
+    // Save session to database (None when it was too short to keep)
+    if let Some(session) = session {
+        let db = state.database_service.lock().await;
+        db.save_or_update_session(&session)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
 
     // Trigger break reminder after the command completes
     if should_show_reminder {
@@ -177,12 +437,64 @@ pub fn extend_phase(state: State<'_, AppState>) -> Result<(), String> {
     state.timer_service.extend(5).map_err(|e| e.to_string())
 }
 
+/// Push the active break suppression back by `minutes`
+#[tauri::command]
+pub fn extend_suppression(state: State<'_, AppState>, minutes: i64) -> Result<(), String> {
+    state
+        .timer_service
+        .extend_suppression(minutes)
+        .map_err(|e| e.to_string())
+}
+
+/// End break suppression now
+#[tauri::command]
+pub fn resume_breaks(state: State<'_, AppState>) -> Result<(), String> {
+    state
+        .timer_service
+        .resume_breaks()
+        .map_err(|e| e.to_string())
+}
+
 /// Get current timer info
 #[tauri::command]
 pub fn get_timer_info(state: State<'_, AppState>) -> Result<TimerInfo, String> {
     Ok(state.timer_service.get_info())
 }
 
+/// Largest countdown drift seen during the current phase, for diagnostics
+#[tauri::command]
+pub fn get_timer_accuracy(state: State<'_, AppState>) -> TimerAccuracy {
+    state.timer_service.timer_accuracy()
+}
+
+/// Preview the work/break schedule for the next `hours` without changing the timer
+#[tauri::command]
+pub fn simulate_schedule(
+    hours: u32,
+    state: State<'_, AppState>,
+) -> Result<Vec<ScheduledPhase>, String> {
+    Ok(state.timer_service.simulate_schedule(hours))
+}
+
+/// Export the breaks projected for the next `hours` as iCalendar text
+#[tauri::command]
+pub async fn export_schedule_ics(hours: u32, state: State<'_, AppState>) -> Result<String, String> {
+    let language = {
+        let db = state.database_service.lock().await;
+        db.load_settings()
+            .await
+            .map_err(|e| e.to_string())?
+            .language
+    };
+    let phases = state.timer_service.simulate_schedule(hours);
+    let summary = crate::utils::format::locale_table(&language).break_event_summary;
+    Ok(crate::utils::ical::render_break_schedule(
+        &phases,
+        summary,
+        Utc::now(),
+    ))
+}
+
 /// Get analytics data for a date range
 #[tauri::command]
 pub async fn get_analytics(
@@ -193,161 +505,733 @@ pub async fn get_analytics(
     db.get_analytics(&query).await.map_err(|e| e.to_string())
 }
 
-/// Get sessions time bounds
+/// Get work and break duration histograms for a date range
 #[tauri::command]
-pub async fn get_sessions_bounds(state: State<'_, AppState>) -> Result<SessionsBounds, String> {
+pub async fn get_duration_histogram(
+    query: AnalyticsQuery,
+    bucket_minutes: u32,
+    state: State<'_, AppState>,
+) -> Result<DurationHistogram, String> {
     let db = state.database_service.lock().await;
-    db.get_sessions_bounds().await.map_err(|e| e.to_string())
+    db.get_duration_histogram(&query, bucket_minutes)
+        .await
+        .map_err(|e| e.to_string())
 }
 
-/// Clear analytics session data
+/// Stop reporting a session's anomaly in analytics
 #[tauri::command]
-pub async fn clear_analytics_data(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn dismiss_anomaly(
+    session_id: String,
+    state: State<'_, AppState>,
+) -> Result<Session, String> {
     let db = state.database_service.lock().await;
-    db.clear_sessions().await.map_err(|e| e.to_string())
+    db.dismiss_anomaly(&session_id)
+        .await
+        .map_err(|e| e.to_string())
 }
 
-/// Get achievements unlock list
+/// Rate a session 1-5 (or clear the rating)
 #[tauri::command]
-pub async fn get_achievements(state: State<'_, AppState>) -> Result<Vec<AchievementUnlock>, String> {
+pub async fn rate_session(
+    id: String,
+    rating: Option<u8>,
+    state: State<'_, AppState>,
+) -> Result<Session, String> {
     let db = state.database_service.lock().await;
-    db.get_achievements().await.map_err(|e| e.to_string())
+    db.rate_session(&id, rating)
+        .await
+        .map_err(|e| e.to_string())
 }
 
-/// Import configuration from JSON
+/// Save the note typed into the work-note prompt (empty clears it)
 #[tauri::command]
-pub async fn import_config(
-    json_str: String,
+pub async fn update_session_note(
+    id: String,
+    note: Option<String>,
     state: State<'_, AppState>,
-) -> Result<Settings, String> {
-    let mut settings: Settings = serde_json::from_str(&json_str)
-        .map_err(|e| AppError::ImportFailed(e.to_string()).to_string())?;
+) -> Result<Session, String> {
+    let db = state.database_service.lock().await;
+    db.update_session_note(&id, note)
+        .await
+        .map_err(|e| e.to_string())
+}
 
-    if !settings.autostart && settings.silent_autostart {
-        settings.silent_autostart = false;
-    }
+/// Get rating statistics for a date range
+#[tauri::command]
+pub async fn get_rating_stats(
+    query: AnalyticsQuery,
+    state: State<'_, AppState>,
+) -> Result<RatingStats, String> {
+    let db = state.database_service.lock().await;
+    db.get_rating_stats(&query).await.map_err(|e| e.to_string())
+}
 
-    validate_settings(&settings)?;
+/// Get the focus score of a local day with its breakdown
+#[tauri::command]
+pub async fn get_focus_score(
+    date: NaiveDate,
+    state: State<'_, AppState>,
+) -> Result<FocusScore, String> {
+    let db = state.database_service.lock().await;
+    db.get_focus_score(date).await.map_err(|e| e.to_string())
+}
 
-    state.timer_service.update_timer_configuration(
-        settings.work_duration,
-        settings.break_duration,
-        settings.segmented_work_enabled,
-        settings.work_segments.clone(),
-    );
-    state
-        .timer_service
-        .update_flow_mode(settings.flow_mode_enabled)
-        .map_err(|e| e.to_string())?;
+/// Projected time at which today's work reaches the focus score's daily work
+/// goal at the current pace; None when the goal is met or not reachable today
+#[tauri::command]
+pub async fn get_goal_eta(state: State<'_, AppState>) -> Result<Option<DateTime<Utc>>, String> {
+    let (done_seconds, goal_minutes) = {
+        let db = state.database_service.lock().await;
+        let settings = db.load_settings().await.map_err(|e| e.to_string())?;
+        let done = db
+            .finished_work_seconds_on(Local::now().date_naive())
+            .await
+            .map_err(|e| e.to_string())?;
+        (done, settings.focus_score.daily_work_goal_minutes)
+    };
+    if goal_minutes == 0 {
+        return Ok(None);
+    }
+    Ok(state.timer_service.project_goal_eta(
+        done_seconds,
+        i64::from(goal_minutes) * 60,
+        today_query().end_date,
+    ))
+}
 
+/// Today's glasses of water against the daily goal
+#[tauri::command]
+pub async fn get_hydration_progress(
+    state: State<'_, AppState>,
+) -> Result<HydrationProgress, String> {
     let db = state.database_service.lock().await;
-    db.save_settings(&settings)
+    db.get_hydration_progress(Local::now().date_naive())
         .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(settings)
+        .map_err(|e| e.to_string())
 }
 
-/// Export configuration to JSON
+/// Log one glass of water today and emit `hydration-changed` with the new progress
 #[tauri::command]
-pub async fn export_config(state: State<'_, AppState>) -> Result<String, String> {
+pub async fn increment_hydration(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<HydrationProgress, String> {
     let db = state.database_service.lock().await;
     let settings = db.load_settings().await.map_err(|e| e.to_string())?;
-
-    serde_json::to_string_pretty(&settings)
-        .map_err(|e| AppError::ExportFailed(e.to_string()).to_string())
+    if !settings.hydration.enabled {
+        return Err(
+            AppError::ValidationError("Hydration tracking is disabled".to_string()).to_string(),
+        );
+    }
+    let progress = db
+        .increment_hydration(Local::now().date_naive())
+        .await
+        .map_err(|e| e.to_string())?;
+    state.timer_service.record_hydration(&progress);
+    let _ = app.emit("hydration-changed", &progress);
+    Ok(progress)
 }
 
-fn default_schema_version() -> u32 {
-    1
+/// Today's hydration progress for the break screen; None while the tracker is off.
+async fn break_hydration(db: &DatabaseService, settings: &Settings) -> Option<HydrationProgress> {
+    if !settings.hydration.enabled {
+        return None;
+    }
+    match db.get_hydration_progress(Local::now().date_naive()).await {
+        Ok(progress) => Some(progress),
+        Err(e) => {
+            eprintln!("Failed to load hydration progress: {}", e);
+            None
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct AppDataPackage {
-    #[serde(default = "default_schema_version")]
-    schema_version: u32,
-    #[serde(default)]
-    exported_at: Option<DateTime<Utc>>,
-    settings: Settings,
-    #[serde(default)]
-    sessions: Vec<Session>,
-    #[serde(default)]
-    achievements: Vec<AchievementUnlock>,
+/// Whether loading adjusted the settings file (migration or normalization), so
+/// the UI can tell the user their settings were changed
+#[tauri::command]
+pub async fn is_settings_normalized(state: State<'_, AppState>) -> Result<bool, String> {
+    let db = state.database_service.lock().await;
+    Ok(db.is_settings_normalized())
 }
 
-/// Export settings and analytics data to a file
+/// One page of sessions matching the history filters, with the match count
 #[tauri::command]
-pub async fn export_app_data_to_file(
+pub async fn list_sessions(
+    query: SessionListQuery,
     state: State<'_, AppState>,
-    path: String,
-) -> Result<(), String> {
-    let target = PathBuf::from(path.trim());
-    if target.as_os_str().is_empty() {
-        return Err(AppError::ExportFailed("Missing export path".to_string()).to_string());
-    }
-    if let Some(parent) = target.parent() {
-        if let Err(err) = std::fs::create_dir_all(parent) {
-            return Err(AppError::ExportFailed(err.to_string()).to_string());
-        }
+) -> Result<SessionListPage, String> {
+    if query.limit == 0 || query.limit > MAX_SESSION_LIST_LIMIT {
+        return Err(AppError::ValidationError(format!(
+            "limit must be between 1 and {}",
+            MAX_SESSION_LIST_LIMIT
+        ))
+        .to_string());
     }
+    let db = state.database_service.lock().await;
+    db.list_sessions(&query).await.map_err(|e| e.to_string())
+}
 
+/// Get sessions time bounds
+#[tauri::command]
+pub async fn get_sessions_bounds(state: State<'_, AppState>) -> Result<SessionsBounds, String> {
     let db = state.database_service.lock().await;
-    let settings = db.load_settings().await.map_err(|e| e.to_string())?;
-    let sessions = db.get_sessions().await.map_err(|e| e.to_string())?;
-    let achievements = db.get_achievements().await.map_err(|e| e.to_string())?;
+    db.get_sessions_bounds().await.map_err(|e| e.to_string())
+}
 
-    let payload = AppDataPackage {
-        schema_version: default_schema_version(),
-        exported_at: Some(Utc::now()),
-        settings,
-        sessions,
-        achievements,
-    };
+/// Get all-time statistics from the local lifetime rollup
+#[tauri::command]
+pub async fn get_lifetime_stats(state: State<'_, AppState>) -> Result<LifetimeStats, String> {
+    let db = state.database_service.lock().await;
+    db.get_lifetime_stats().await.map_err(|e| e.to_string())
+}
 
-    let json = serde_json::to_string_pretty(&payload)
-        .map_err(|e| AppError::ExportFailed(e.to_string()).to_string())?;
+/// Recompute the lifetime rollup from the stored sessions
+#[tauri::command]
+pub async fn rebuild_lifetime_stats(state: State<'_, AppState>) -> Result<LifetimeStats, String> {
+    let db = state.database_service.lock().await;
+    db.rebuild_lifetime_stats().await.map_err(|e| e.to_string())
+}
 
-    std::fs::write(&target, json)
-        .map_err(|e| AppError::ExportFailed(e.to_string()).to_string())?;
+/// Clear analytics session data
+#[tauri::command]
+pub async fn clear_analytics_data(state: State<'_, AppState>) -> Result<(), String> {
+    let db = state.database_service.lock().await;
+    db.clear_sessions().await.map_err(|e| e.to_string())
+}
 
-    Ok(())
+/// Reload sessions from disk after the JSON file was edited outside the app
+#[tauri::command]
+pub async fn reload_sessions(state: State<'_, AppState>) -> Result<usize, String> {
+    let db = state.database_service.lock().await;
+    db.reload_sessions().await.map_err(|e| e.to_string())
 }
 
-/// Import settings and analytics data from a file
+/// Get achievements unlock list
 #[tauri::command]
-pub async fn import_app_data_from_file(
-    path: String,
+pub async fn get_achievements(
     state: State<'_, AppState>,
-) -> Result<Settings, String> {
-    let target = PathBuf::from(path.trim());
-    if target.as_os_str().is_empty() {
-        return Err(AppError::ImportFailed("Missing import path".to_string()).to_string());
+) -> Result<Vec<AchievementUnlock>, String> {
+    let db = state.database_service.lock().await;
+    db.get_achievements().await.map_err(|e| e.to_string())
+}
+
+/// Parse and validate an imported configuration without applying it. Oversized
+/// input is refused up front and the parse runs off the async runtime.
+/// 解析并校验导入的配置：先限制大小，再在阻塞线程中解析，未知字段与其他应用的配置会被拒绝。
+async fn parse_imported_settings(json_str: String) -> Result<Settings, String> {
+    if json_str.len() > MAX_IMPORT_BYTES {
+        return Err(AppError::ImportFailed(format!(
+            "file is larger than {} KB",
+            MAX_IMPORT_BYTES / 1024
+        ))
+        .to_string());
     }
+    tauri::async_runtime::spawn_blocking(move || parse_imported_settings_blocking(&json_str))
+        .await
+        .map_err(|e| AppError::ImportFailed(e.to_string()).to_string())?
+}
+
+fn parse_imported_settings_blocking(json_str: &str) -> Result<Settings, String> {
+    // serde_json's default recursion limit (128 levels) stays on for this parse
+    let mut value: Value = serde_json::from_str(json_str)
+        .map_err(|e| AppError::ImportFailed(e.to_string()).to_string())?;
+    let Value::Object(fields) = &mut value else {
+        return Err(AppError::ImportFailed("expected a JSON object".to_string()).to_string());
+    };
+    check_config_identity(fields)?;
+    fields.remove("$schema");
+    fields.remove("app");
 
-    let content = std::fs::read_to_string(&target)
+    let ImportedSettings(mut settings) = serde_json::from_value(value)
         .map_err(|e| AppError::ImportFailed(e.to_string()).to_string())?;
-    let payload: AppDataPackage =
-        serde_json::from_str(&content).map_err(|e| AppError::ImportFailed(e.to_string()).to_string())?;
 
-    let mut settings = payload.settings;
     if !settings.autostart && settings.silent_autostart {
         settings.silent_autostart = false;
     }
+    // Imported hook commands only run once the user enables them on this machine
+    settings.allow_hooks = false;
 
     validate_settings(&settings)?;
+    Ok(settings)
+}
 
-    state.timer_service.update_timer_configuration(
-        settings.work_duration,
-        settings.break_duration,
-        settings.segmented_work_enabled,
-        settings.work_segments.clone(),
-    );
-    state
-        .timer_service
-        .update_flow_mode(settings.flow_mode_enabled)
+/// Refuse configs whose `app` or `$schema` marks them as another app's.
+fn check_config_identity(fields: &serde_json::Map<String, Value>) -> Result<(), String> {
+    if let Some(app) = fields.get("app") {
+        if app.as_str() != Some(CONFIG_APP_ID) {
+            return Err(AppError::ImportFailed(format!(
+                "this config belongs to another app ({})",
+                app
+            ))
+            .to_string());
+        }
+    }
+    if let Some(schema) = fields.get("$schema") {
+        let ours = schema
+            .as_str()
+            .is_some_and(|schema| schema.to_ascii_lowercase().contains("resty"));
+        if !ours {
+            return Err(AppError::ImportFailed(format!(
+                "this config uses another app's schema ({})",
+                schema
+            ))
+            .to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Settings read from an imported file, refusing unknown fields at every level
+/// (`focusScore`, `hydration`, ...) as `#[serde(deny_unknown_fields)]` would.
+/// `Settings` itself keeps accepting them so a settings file written by a newer
+/// version still loads.
+/// 导入专用的设置类型：任意层级出现未知字段都会被拒绝。
+struct ImportedSettings(Settings);
+
+impl<'de> Deserialize<'de> for ImportedSettings {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let raw = Value::deserialize(deserializer)?;
+        let settings: Settings = serde_json::from_value(raw.clone()).map_err(D::Error::custom)?;
+        // Every field `Settings` knows is written back, so anything missing was dropped
+        let known = serde_json::to_value(&settings).map_err(D::Error::custom)?;
+        let mut unknown = Vec::new();
+        collect_unknown_fields("", &raw, &known, &mut unknown);
+        if unknown.is_empty() {
+            return Ok(Self(settings));
+        }
+
+        let mut listed = unknown
+            .iter()
+            .take(MAX_REPORTED_UNKNOWN_KEYS)
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+        if unknown.len() > MAX_REPORTED_UNKNOWN_KEYS {
+            listed.push_str(&format!(
+                " and {} more",
+                unknown.len() - MAX_REPORTED_UNKNOWN_KEYS
+            ));
+        }
+        Err(D::Error::custom(format!(
+            "unknown fields {}; is this a RESTY config?",
+            listed
+        )))
+    }
+}
+
+/// Paths (`hydration.cups`, `workSegments[0].name`) of fields in `raw` that
+/// `known` does not have.
+fn collect_unknown_fields(prefix: &str, raw: &Value, known: &Value, unknown: &mut Vec<String>) {
+    match (raw, known) {
+        (Value::Object(raw_map), Value::Object(known_map)) => {
+            for (key, value) in raw_map {
+                let field = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                match known_map.get(key) {
+                    Some(known_value) => {
+                        collect_unknown_fields(&field, value, known_value, unknown)
+                    }
+                    None => unknown.push(field),
+                }
+            }
+        }
+        (Value::Array(raw_items), Value::Array(known_items)) => {
+            for (index, (value, known_value)) in raw_items.iter().zip(known_items).enumerate() {
+                collect_unknown_fields(
+                    &format!("{}[{}]", prefix, index),
+                    value,
+                    known_value,
+                    unknown,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collect the leaf fields that differ between two serialized settings.
+fn diff_settings_json(prefix: &str, old: &Value, new: &Value, changes: &mut Vec<SettingChange>) {
+    if let (Value::Object(old_map), Value::Object(new_map)) = (old, new) {
+        let keys: std::collections::BTreeSet<&String> =
+            old_map.keys().chain(new_map.keys()).collect();
+        for key in keys {
+            // Metadata changes on every save and is not a user-facing difference
+            if prefix.is_empty() && key == "updatedAt" {
+                continue;
+            }
+            let field = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            diff_settings_json(
+                &field,
+                old_map.get(key).unwrap_or(&Value::Null),
+                new_map.get(key).unwrap_or(&Value::Null),
+                changes,
+            );
+        }
+    } else if old != new {
+        changes.push(SettingChange {
+            field: prefix.to_string(),
+            old_value: old.clone(),
+            new_value: new.clone(),
+        });
+    }
+}
+
+/// Preview which settings an imported config would change, without saving
+#[tauri::command]
+pub async fn preview_import(
+    json_str: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<SettingChange>, String> {
+    let incoming = parse_imported_settings(json_str).await?;
+
+    let db = state.database_service.lock().await;
+    let current = db.load_settings().await.map_err(|e| e.to_string())?;
+    drop(db);
+
+    let old = serde_json::to_value(&current).map_err(|e| e.to_string())?;
+    let new = serde_json::to_value(&incoming).map_err(|e| e.to_string())?;
+    let mut changes = Vec::new();
+    diff_settings_json("", &old, &new, &mut changes);
+    Ok(changes)
+}
+
+/// Import configuration from JSON
+#[tauri::command]
+pub async fn import_config(
+    app: AppHandle,
+    json_str: String,
+    state: State<'_, AppState>,
+) -> Result<Settings, String> {
+    let settings = parse_imported_settings(json_str).await?;
+
+    state
+        .timer_service
+        .apply_settings(&settings)
+        .map_err(|e| e.to_string())?;
+    crate::apply_runtime_settings(&app, &settings);
+
+    let db = state.database_service.lock().await;
+    let previous = db.load_settings().await.ok();
+    db.save_settings(&settings)
+        .await
+        .map_err(|e| e.to_string())?;
+    drop(db);
+
+    if let Some(previous) = previous {
+        emit_settings_changed(&app, &previous, &settings);
+    }
+    Ok(settings)
+}
+
+/// Export configuration to JSON
+#[tauri::command]
+pub async fn export_config(state: State<'_, AppState>) -> Result<String, String> {
+    let db = state.database_service.lock().await;
+    let settings = db.load_settings().await.map_err(|e| e.to_string())?;
+    exported_config_json(&settings)
+}
+
+/// `settings` as an exported config file, tagged with the app id.
+fn exported_config_json(settings: &Settings) -> Result<String, String> {
+    let mut value = serde_json::to_value(settings)
+        .map_err(|e| AppError::ExportFailed(e.to_string()).to_string())?;
+    if let Value::Object(fields) = &mut value {
+        fields.insert("app".to_string(), Value::from(CONFIG_APP_ID));
+    }
+    serde_json::to_string_pretty(&value)
+        .map_err(|e| AppError::ExportFailed(e.to_string()).to_string())
+}
+
+/// Export configuration as a QR code PNG data URL for cross-device transfer
+#[tauri::command]
+pub async fn export_config_qr(state: State<'_, AppState>) -> Result<String, String> {
+    let db = state.database_service.lock().await;
+    let settings = db.load_settings().await.map_err(|e| e.to_string())?;
+    drop(db);
+
+    let json = serde_json::to_string(&settings)
+        .map_err(|e| AppError::ExportFailed(e.to_string()).to_string())?;
+    let payload = crate::utils::qr::encode_config_payload(&json).map_err(|e| e.to_string())?;
+    crate::utils::qr::render_qr_data_url(&payload).map_err(|e| e.to_string())
+}
+
+/// Import configuration from the text of a scanned QR code
+#[tauri::command]
+pub async fn import_config_from_qr(
+    app: AppHandle,
+    payload: String,
+    state: State<'_, AppState>,
+) -> Result<Settings, String> {
+    let json_str = crate::utils::qr::decode_config_payload(&payload).map_err(|e| e.to_string())?;
+    import_config(app, json_str, state).await
+}
+
+fn default_schema_version() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppDataPackage {
+    #[serde(default = "default_schema_version")]
+    schema_version: u32,
+    #[serde(default)]
+    exported_at: Option<DateTime<Utc>>,
+    settings: Settings,
+    #[serde(default)]
+    sessions: Vec<Session>,
+    #[serde(default)]
+    achievements: Vec<AchievementUnlock>,
+}
+
+/// A data package as read back on import; unknown fields are refused here and
+/// inside `settings`. The schema version and export time are accepted but unused.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[allow(dead_code)]
+struct AppDataImport {
+    #[serde(default = "default_schema_version")]
+    schema_version: u32,
+    #[serde(default)]
+    exported_at: Option<DateTime<Utc>>,
+    settings: ImportedSettings,
+    #[serde(default)]
+    sessions: Vec<Session>,
+    #[serde(default)]
+    achievements: Vec<AchievementUnlock>,
+}
+
+/// Export work sessions in `query` as CSV time entries (Toggl import or generic).
+/// Work sessions shorter than `min_minutes` (default 1) are skipped.
+/// Returns the number of entries written.
+#[tauri::command]
+pub async fn export_time_entries(
+    state: State<'_, AppState>,
+    query: AnalyticsQuery,
+    format: TimeEntryFormat,
+    path: String,
+    min_minutes: Option<u32>,
+) -> Result<usize, String> {
+    let target = PathBuf::from(path.trim());
+    if target.as_os_str().is_empty() {
+        return Err(AppError::ExportFailed("Missing export path".to_string()).to_string());
+    }
+    if let Some(parent) = target.parent() {
+        if let Err(err) = std::fs::create_dir_all(parent) {
+            return Err(AppError::ExportFailed(err.to_string()).to_string());
+        }
+    }
+
+    let sessions = {
+        let db = state.database_service.lock().await;
+        db.get_sessions().await.map_err(|e| e.to_string())?
+    };
+    let min_seconds = min_minutes.unwrap_or(DEFAULT_TIME_ENTRY_MIN_MINUTES) as i64 * 60;
+    let entries =
+        time_entries::time_entries(&sessions, query.start_date, query.end_date, min_seconds);
+    let csv = time_entries::render_csv(&entries, format);
+
+    std::fs::write(&target, csv).map_err(|e| AppError::ExportFailed(e.to_string()).to_string())?;
+    Ok(entries.len())
+}
+
+/// Export settings and analytics data to a file
+#[tauri::command]
+pub async fn export_app_data_to_file(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<(), String> {
+    let target = PathBuf::from(path.trim());
+    if target.as_os_str().is_empty() {
+        return Err(AppError::ExportFailed("Missing export path".to_string()).to_string());
+    }
+    if let Some(parent) = target.parent() {
+        if let Err(err) = std::fs::create_dir_all(parent) {
+            return Err(AppError::ExportFailed(err.to_string()).to_string());
+        }
+    }
+
+    let db = state.database_service.lock().await;
+    let settings = db.load_settings().await.map_err(|e| e.to_string())?;
+    let sessions = db.get_sessions().await.map_err(|e| e.to_string())?;
+    let achievements = db.get_achievements().await.map_err(|e| e.to_string())?;
+
+    let payload = AppDataPackage {
+        schema_version: default_schema_version(),
+        exported_at: Some(Utc::now()),
+        settings,
+        sessions,
+        achievements,
+    };
+
+    let mut value = serde_json::to_value(&payload)
+        .map_err(|e| AppError::ExportFailed(e.to_string()).to_string())?;
+    if let Value::Object(fields) = &mut value {
+        fields.insert("app".to_string(), Value::from(CONFIG_APP_ID));
+    }
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| AppError::ExportFailed(e.to_string()).to_string())?;
+
+    std::fs::write(&target, json).map_err(|e| AppError::ExportFailed(e.to_string()).to_string())?;
+
+    Ok(())
+}
+
+/// Everything stored locally, for the full data export
+async fn gather_data_export(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<DataExport, String> {
+    let app_info = get_app_info(app, state.clone()).await?;
+    let db = state.database_service.lock().await;
+    Ok(DataExport {
+        app_id: CONFIG_APP_ID,
+        app_info,
+        settings: db.load_settings().await.map_err(|e| e.to_string())?,
+        sessions: db.get_sessions().await.map_err(|e| e.to_string())?,
+        achievements: db.get_achievements().await.map_err(|e| e.to_string())?,
+        lifetime_stats: db.get_lifetime_stats().await.ok(),
+        day_starts: db.get_day_starts().await,
+        screen_continuity: db.load_screen_continuity(),
+        hydration: db.load_hydration_log(),
+    })
+}
+
+/// Size of a full data export, to show before starting it
+#[tauri::command]
+pub async fn estimate_data_export(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<DataExportEstimate, String> {
+    let export = gather_data_export(app, state).await?;
+    Ok(DataExportEstimate {
+        sessions: export.sessions.len(),
+        estimated_bytes: data_export::estimate_bytes(&export).map_err(|e| e.to_string())?,
+    })
+}
+
+/// Export everything RESTY stores (settings with secrets redacted, all
+/// sessions, achievements, lifetime stats, day starts, screen time, hydration
+/// and app info) to one JSON file with a schema version per section. Large
+/// histories report `data-export-progress`. Export only; nothing reads it back yet.
+/// 导出全部个人数据到单个 JSON 文件（数据可携带），每部分带版本号；会话较多时发送进度事件。
+#[tauri::command]
+pub async fn export_all_data(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<DataExportSummary, String> {
+    let target = PathBuf::from(path.trim());
+    if target.as_os_str().is_empty() {
+        return Err(AppError::ExportFailed("Missing export path".to_string()).to_string());
+    }
+    if let Some(parent) = target.parent() {
+        if let Err(err) = std::fs::create_dir_all(parent) {
+            return Err(AppError::ExportFailed(err.to_string()).to_string());
+        }
+    }
+
+    let export = gather_data_export(app.clone(), state).await?;
+    let total_sessions = export.sessions.len();
+    let estimated_bytes = data_export::estimate_bytes(&export).map_err(|e| e.to_string())?;
+    let report_progress = total_sessions > data_export::PROGRESS_CHUNK;
+    let summary_path = target.display().to_string();
+    let bytes = tauri::async_runtime::spawn_blocking(move || {
+        data_export::write_export(&export, &target, |sessions_written, bytes_written| {
+            if report_progress {
+                let _ = app.emit(
+                    "data-export-progress",
+                    DataExportProgress {
+                        sessions_written,
+                        total_sessions,
+                        bytes_written,
+                        estimated_bytes,
+                    },
+                );
+            }
+        })
+    })
+    .await
+    .map_err(|e| AppError::ExportFailed(e.to_string()).to_string())?
+    .map_err(|e| e.to_string())?;
+
+    Ok(DataExportSummary {
+        path: summary_path,
+        sessions: total_sessions,
+        bytes,
+    })
+}
+
+/// Read and parse a data package, refusing oversized files, other apps' exports
+/// and unknown fields.
+fn read_app_data_package(target: &std::path::Path) -> Result<AppDataImport, String> {
+    let size = std::fs::metadata(target)
+        .map_err(|e| AppError::ImportFailed(e.to_string()).to_string())?
+        .len();
+    if size > MAX_APP_DATA_IMPORT_BYTES {
+        return Err(AppError::ImportFailed(format!(
+            "file is larger than {} MB",
+            MAX_APP_DATA_IMPORT_BYTES / (1024 * 1024)
+        ))
+        .to_string());
+    }
+
+    let content = std::fs::read_to_string(target)
+        .map_err(|e| AppError::ImportFailed(e.to_string()).to_string())?;
+    let mut value: Value = serde_json::from_str(&content)
+        .map_err(|e| AppError::ImportFailed(e.to_string()).to_string())?;
+    let Value::Object(fields) = &mut value else {
+        return Err(AppError::ImportFailed("expected a JSON object".to_string()).to_string());
+    };
+    check_config_identity(fields)?;
+    fields.remove("$schema");
+    fields.remove("app");
+
+    serde_json::from_value(value).map_err(|e| AppError::ImportFailed(e.to_string()).to_string())
+}
+
+/// Import settings and analytics data from a file
+#[tauri::command]
+pub async fn import_app_data_from_file(
+    app: AppHandle,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<Settings, String> {
+    let target = PathBuf::from(path.trim());
+    if target.as_os_str().is_empty() {
+        return Err(AppError::ImportFailed("Missing import path".to_string()).to_string());
+    }
+
+    let payload = tauri::async_runtime::spawn_blocking(move || read_app_data_package(&target))
+        .await
+        .map_err(|e| AppError::ImportFailed(e.to_string()).to_string())??;
+
+    let ImportedSettings(mut settings) = payload.settings;
+    if !settings.autostart && settings.silent_autostart {
+        settings.silent_autostart = false;
+    }
+    settings.allow_hooks = false;
+
+    validate_settings(&settings)?;
+
+    state
+        .timer_service
+        .apply_settings(&settings)
         .map_err(|e| e.to_string())?;
+    crate::apply_runtime_settings(&app, &settings);
 
+    state
+        .timer_service
+        .seed_session_ids(payload.sessions.iter().map(|s| s.id.clone()));
     let db = state.database_service.lock().await;
     db.replace_sessions(payload.sessions)
         .await
@@ -376,96 +1260,891 @@ pub fn get_monitors(_app: AppHandle) -> Result<Vec<MonitorInfo>, String> {
     }])
 }
 
-/// Get system status (fullscreen, DND, etc.)
+/// Get system status (fullscreen, DND, remote session)
 #[tauri::command]
-pub fn get_system_status() -> Result<SystemStatus, String> {
-    // This is a placeholder implementation
-    // In production, you would check actual system status
-    Ok(SystemStatus {
-        is_fullscreen: false,
-        is_do_not_disturb: false,
+pub fn get_system_status(state: State<'_, AppState>) -> Result<SystemStatus, String> {
+    let mut status = system_status::current();
+    status.tray_available = state.tray_available.load(Ordering::SeqCst);
+    Ok(status)
+}
+
+/// Version, platform and build details of the running app
+#[tauri::command]
+pub async fn get_app_info(app: AppHandle, state: State<'_, AppState>) -> Result<AppInfo, String> {
+    let commit = env!("RESTY_BUILD_COMMIT");
+    let build_timestamp = env!("RESTY_BUILD_TIMESTAMP")
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0));
+    let data_dir = state.database_service.lock().await.storage_info().data_dir;
+    Ok(AppInfo {
+        version: updater::current_version(&app),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        commit: (!commit.is_empty()).then(|| commit.to_string()),
+        build_timestamp,
+        is_dev_build: updater::is_dev_build(&app),
+        is_portable: updater::is_portable_build(),
+        data_dir,
     })
 }
 
-/// Open reminder window
+/// Data directory in use, including whether a fallback location was needed
 #[tauri::command]
-pub fn open_reminder_window(
+pub async fn get_storage_info(state: State<'_, AppState>) -> Result<StorageInfo, String> {
+    let db = state.database_service.lock().await;
+    Ok(db.storage_info())
+}
+
+/// Whether data encryption is on and still waiting for the passphrase
+#[tauri::command]
+pub async fn get_encryption_status(state: State<'_, AppState>) -> Result<EncryptionStatus, String> {
+    let db = state.database_service.lock().await;
+    Ok(EncryptionStatus {
+        enabled: db.is_encrypted(),
+        locked: db.is_locked(),
+    })
+}
+
+/// Unlock encrypted data with its passphrase, apply the loaded settings and
+/// start the timer held off at launch.
+/// A wrong passphrase fails with "Wrong passphrase" so the UI can ask again.
+/// 输入密码解锁加密数据，加载后应用设置并启动计时；密码错误时返回错误以便重新输入。
+#[tauri::command]
+pub async fn unlock_data(
     app: AppHandle,
-    fullscreen: bool,
-    floating_position: Option<FloatingPosition>,
-) -> Result<(), String> {
-    let position = floating_position.unwrap_or(FloatingPosition::TopRight);
-    crate::show_break_reminder_window(&app, fullscreen, position).map_err(|e| e.to_string())
+    state: State<'_, AppState>,
+    passphrase: String,
+) -> Result<Settings, String> {
+    let db = state.database_service.lock().await;
+    let was_locked = db.is_locked();
+    db.unlock(&passphrase).await.map_err(|e| e.to_string())?;
+    let settings = db.load_settings().await.map_err(|e| e.to_string())?;
+    let sessions = db.get_sessions().await.map_err(|e| e.to_string())?;
+    drop(db);
+
+    state
+        .timer_service
+        .seed_session_ids(sessions.into_iter().map(|s| s.id));
+    state
+        .timer_service
+        .apply_settings(&settings)
+        .map_err(|e| e.to_string())?;
+    crate::apply_runtime_settings(&app, &settings);
+    if was_locked {
+        // Held off at startup while sessions could not be saved
+        crate::start_timer(&state.timer_service);
+    }
+    Ok(settings)
+}
+
+/// Encrypt settings and sessions with a key derived from `passphrase`
+#[tauri::command]
+pub async fn enable_data_encryption(
+    state: State<'_, AppState>,
+    passphrase: String,
+) -> Result<(), String> {
+    let db = state.database_service.lock().await;
+    db.enable_encryption(&passphrase)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Store settings and sessions as plaintext again after checking `passphrase`
+#[tauri::command]
+pub async fn disable_data_encryption(
+    state: State<'_, AppState>,
+    passphrase: String,
+) -> Result<(), String> {
+    let db = state.database_service.lock().await;
+    db.disable_encryption(&passphrase)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Turn travel mode (no display-off / suspend pausing) on or off and save it
+#[tauri::command]
+pub async fn set_travel_mode(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<Settings, String> {
+    let db = state.database_service.lock().await;
+    let mut settings = db.load_settings().await.map_err(|e| e.to_string())?;
+    settings.travel_mode = enabled;
+    state
+        .timer_service
+        .set_travel_mode(enabled)
+        .map_err(|e| e.to_string())?;
+    db.save_settings(&settings)
+        .await
+        .map_err(|e| e.to_string())?;
+    crate::refresh_tray_tooltip(&app, &settings.language);
+    Ok(settings)
+}
+
+/// Temporarily mute all sounds without touching the saved `play_sound` preference
+#[tauri::command]
+pub fn set_sound_muted(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    muted: bool,
+) -> Result<(), String> {
+    if state.sound_muted.swap(muted, Ordering::SeqCst) != muted {
+        app.emit("sound-muted-changed", muted)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Saved sound preference combined with the runtime mute flag
+#[tauri::command]
+pub async fn get_audio_status(state: State<'_, AppState>) -> Result<AudioStatus, String> {
+    let play_sound = {
+        let db = state.database_service.lock().await;
+        db.load_settings()
+            .await
+            .map_err(|e| e.to_string())?
+            .play_sound
+    };
+    let muted = state.sound_muted.load(Ordering::SeqCst);
+    Ok(AudioStatus {
+        play_sound,
+        muted,
+        sound_enabled: play_sound && !muted,
+    })
+}
+
+/// Open reminder window
+#[tauri::command]
+pub fn open_reminder_window(
+    app: AppHandle,
+    fullscreen: bool,
+    floating_position: Option<FloatingPosition>,
+) -> Result<(), String> {
+    let position = floating_position.unwrap_or(FloatingPosition::TopRight);
+    crate::show_break_reminder_window(&app, fullscreen, position).map_err(|e| e.to_string())
+}
+
+/// Most recent reminder window creation failure since launch, for diagnostics
+#[tauri::command]
+pub fn get_last_reminder_failure() -> Option<ReminderFailure> {
+    crate::last_reminder_failure()
+}
+
+/// Display power monitor health (heartbeat and watchdog restarts), for diagnostics
+#[tauri::command]
+pub fn get_power_monitor_health() -> PowerMonitorHealth {
+    power::monitor_health()
+}
+
+/// Timer ticker health (last tick, watchdog restarts, failed ticks), for diagnostics
+#[tauri::command]
+pub fn get_ticker_health(state: State<'_, AppState>) -> TickerHealth {
+    state.timer_service.ticker_health()
+}
+
+/// Exercise the reminder outputs for (possibly unsaved) settings from the "Test" button
+#[tauri::command]
+pub async fn test_reminder_output(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    settings: Settings,
+) -> Result<ReminderOutputTest, String> {
+    use tauri_plugin_notification::{NotificationExt, PermissionState};
+
+    // Sounds are played by the webview pages; there is no backend audio output to probe.
+    let sound = if state.sound_muted.load(Ordering::SeqCst) {
+        OutputCheck::Skipped {
+            reason: "Sound is muted".to_string(),
+        }
+    } else if settings.play_sound {
+        OutputCheck::Skipped {
+            reason: "No backend audio output is available".to_string(),
+        }
+    } else {
+        OutputCheck::Skipped {
+            reason: "Sound is disabled".to_string(),
+        }
+    };
+
+    let notification = if !settings.rest_start_soon_notification_enabled {
+        OutputCheck::Skipped {
+            reason: "Notifications are disabled".to_string(),
+        }
+    } else {
+        match app.notification().permission_state() {
+            Ok(PermissionState::Granted) => {
+                let body = crate::utils::format::locale_table(&settings.language).test_notification;
+                match app
+                    .notification()
+                    .builder()
+                    .title("RESTY")
+                    .body(body)
+                    .show()
+                {
+                    Ok(()) => OutputCheck::Passed,
+                    Err(e) => OutputCheck::Failed {
+                        error: e.to_string(),
+                    },
+                }
+            }
+            Ok(_) => OutputCheck::Failed {
+                error: "Notification permission denied".to_string(),
+            },
+            Err(e) => OutputCheck::Failed {
+                error: e.to_string(),
+            },
+        }
+    };
+
+    let preview = match crate::show_reminder_preview_window(&app, &settings) {
+        Ok(()) => OutputCheck::Passed,
+        Err(e) => OutputCheck::Failed {
+            error: e.to_string(),
+        },
+    };
+
+    Ok(ReminderOutputTest {
+        sound,
+        notification,
+        preview,
+    })
+}
+
+/// Show reminder window once frontend is ready
+#[tauri::command]
+pub fn show_reminder_window(app: AppHandle) -> Result<(), String> {
+    // Show all reminder windows (across monitors)
+    for window in crate::windows::reminder_windows(&app) {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    Ok(())
+}
+
+/// Reminder page finished mounting; reveal its window.
+#[tauri::command]
+pub fn reminder_ready(
+    app: AppHandle,
+    label: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let is_prewarmed = state
+        .prewarmed_reminders
+        .lock()
+        .map(|labels| labels.contains(&label))
+        .unwrap_or(false);
+    if is_prewarmed {
+        // Pre-warmed windows stay hidden until a break actually starts.
+        return Ok(());
+    }
+    if !crate::signal_reminder_ready(&app, &label) {
+        // Not created through the coordinator (e.g. reused window); show directly.
+        if let Some(window) = app.get_webview_window(&label) {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+    Ok(())
+}
+
+/// Close reminder window. A finished break waiting for acknowledgment keeps
+/// its reminder open (settings `require_break_acknowledgment`).
+#[tauri::command]
+pub fn close_reminder_window(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    if state.timer_service.is_break_dismissal_locked() {
+        return Err(AppError::ForceBreakLocked.to_string());
+    }
+    if state.timer_service.is_break_acknowledgment_pending() {
+        return Ok(());
+    }
+    close_break_reminder_windows(&app, &state);
+    Ok(())
+}
+
+/// Sent by reminder pages every couple of seconds during a break; a visible
+/// reminder that stops sending it is recreated by the watchdog.
+#[tauri::command]
+pub fn reminder_heartbeat(label: String) {
+    crate::record_reminder_heartbeat(&label);
+}
+
+/// Acknowledge the finished break from the reminder window, then close it
+#[tauri::command]
+pub fn acknowledge_break(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<Session>, String> {
+    let session = state
+        .timer_service
+        .acknowledge_break()
+        .map_err(|e| e.to_string())?;
+    close_break_reminder_windows(&app, &state);
+    Ok(session)
+}
+
+/// Close every open break reminder window except the prewarmed ones.
+fn close_break_reminder_windows(app: &AppHandle, state: &AppState) {
+    let prewarmed = state
+        .prewarmed_reminders
+        .lock()
+        .map(|labels| labels.clone())
+        .unwrap_or_default();
+    for window in crate::windows::reminder_windows(app) {
+        if !prewarmed.contains(window.label()) {
+            crate::close_reminder_from_app(&window);
+        }
+    }
+}
+
+/// Tray "no breaks" actions allowed under the configured suppression cap
+#[tauri::command]
+pub async fn get_tray_suppression_actions(
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let db = state.database_service.lock().await;
+    let settings = db.load_settings().await.map_err(|e| e.to_string())?;
+    Ok(
+        crate::allowed_suppression_actions(settings.max_suppression_minutes)
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// Fast-forward the running phase by `seconds` (requires the `devMode` setting)
+#[cfg(feature = "dev-tools")]
+#[tauri::command]
+pub async fn debug_advance_timer(seconds: u32, state: State<'_, AppState>) -> Result<(), String> {
+    let dev_mode = {
+        let db = state.database_service.lock().await;
+        db.load_settings()
+            .await
+            .map_err(|e| e.to_string())?
+            .dev_mode
+    };
+    if !dev_mode {
+        return Err(
+            AppError::ValidationError("Developer mode is disabled".to_string()).to_string(),
+        );
+    }
+
+    state
+        .timer_service
+        .debug_advance(seconds)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Put the timer into a clean `phase` with `minutes` left, ending whatever
+/// runs now (requires the `devMode` setting). A recovery escape hatch for a
+/// timer stuck in a bad state; unlike `debug_advance_timer` it exists in every
+/// build.
+#[tauri::command]
+pub async fn set_timer_phase(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    phase: TimerPhase,
+    minutes: u32,
+) -> Result<(), String> {
+    let db = state.database_service.lock().await;
+    let dev_mode = db
+        .load_settings()
+        .await
+        .map_err(|e| e.to_string())?
+        .dev_mode;
+    if !dev_mode {
+        return Err(
+            AppError::ValidationError("Developer mode is disabled".to_string()).to_string(),
+        );
+    }
+    if phase != TimerPhase::Idle && (minutes == 0 || minutes > 120) {
+        return Err(AppError::InvalidDuration.to_string());
+    }
+
+    let session = state
+        .timer_service
+        .force_phase(phase.clone(), minutes)
+        .map_err(|e| e.to_string())?;
+    if let Some(session) = session {
+        db.save_or_update_session(&session)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    drop(db);
+
+    if phase == TimerPhase::Break {
+        let _ = app.emit("show-break-reminder", ());
+    } else {
+        close_break_reminder_windows(&app, &state);
+    }
+    Ok(())
+}
+
+/// High-contrast / reduced-motion preferences (OS detection plus overrides)
+#[tauri::command]
+pub fn get_accessibility_prefs() -> AccessibilityPrefs {
+    crate::services::accessibility::current_prefs()
+}
+
+/// Pin or unpin the main window without waiting for settings to be saved
+#[tauri::command]
+pub fn set_main_window_always_on_top(app: AppHandle, enabled: bool) -> Result<(), String> {
+    crate::set_main_always_on_top(&app, enabled);
+    Ok(())
+}
+
+/// Show the custom tray menu near physical screen position `x`, `y`
+#[tauri::command]
+pub fn open_tray_menu(app: AppHandle, x: f64, y: f64) {
+    crate::open_tray_menu_window(&app, x, y);
+}
+
+/// Hide the custom tray menu window (Escape in the menu webview)
+#[tauri::command]
+pub fn close_tray_menu(app: AppHandle) {
+    crate::close_tray_menu_window(&app, false);
+}
+
+/// Create hidden reminder windows ahead of the next break
+#[tauri::command]
+pub fn prewarm_reminder(app: AppHandle) -> Result<(), String> {
+    crate::prewarm_reminder_windows(&app).map_err(|e| e.to_string())
+}
+
+/// Show main window (used by frontend after initialization)
+#[tauri::command]
+pub fn show_main_window(app: AppHandle) -> Result<(), String> {
+    if let Some(window) = crate::windows::main_window(&app) {
+        crate::set_main_skip_taskbar(&window, false);
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    Ok(())
+}
+
+/// Handle tray menu actions from custom menu window
+#[tauri::command]
+pub async fn tray_menu_action(
+    action: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let cloned_state = state.inner().clone();
+    dispatch_control_action(action.as_str(), ControlSource::Tray, app, cloned_state).await
+}
+
+/// Quote sources from the saved settings.
+async fn quote_providers(state: &State<'_, AppState>) -> Result<Vec<QuoteProvider>, String> {
+    let db = state.database_service.lock().await;
+    let settings = db.load_settings().await.map_err(|e| e.to_string())?;
+    Ok(settings.quote_providers)
+}
+
+/// Fetch tip quote text from the configured quote providers.
+#[tauri::command]
+pub async fn fetch_tip_quote(
+    language: String,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let providers = quote_providers(&state).await?;
+    crate::services::remote::fetch_tip_quote(&language, &providers)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Fetch one quote from `config` (a provider being set up) and return the
+/// extracted text, or why none came back: network, HTTP status, invalid JSON
+/// or no text at the configured pointers.
+#[tauri::command]
+pub async fn test_quote_source(config: QuoteProvider) -> Result<String, String> {
+    validate_quote_provider(&config)?;
+    crate::services::remote::fetch_provider_quote(&config)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// POST a sample transition to `url` and return the HTTP status.
+#[tauri::command]
+pub async fn test_webhook(url: String) -> Result<u16, String> {
+    if !webhook::is_valid_url(&url) {
+        return Err(AppError::ValidationError(format!(
+            "webhookUrl must be an http(s) URL: {}",
+            url
+        ))
+        .to_string());
+    }
+    webhook::post(&url, &webhook::WebhookPayload::sample())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get break text using today's theme or the remote → cache → local fallback chain.
+#[tauri::command]
+pub async fn get_break_content(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    language: String,
+    source: Option<String>,
+) -> Result<BreakContent, String> {
+    let (settings, hydration) = {
+        let db = state.database_service.lock().await;
+        let settings = db.load_settings().await.map_err(|e| e.to_string())?;
+        let hydration = break_hydration(&db, &settings).await;
+        (settings, hydration)
+    };
+    let mut content = crate::services::remote::get_break_content(
+        &app,
+        &language,
+        source.as_deref(),
+        &settings.quote_providers,
+        break_themes::today(&settings.break_themes),
+    )
+    .await;
+    content.hydration = hydration;
+    Ok(content)
+}
+
+/// Next exercise to suggest on the break screen, in `language`. Reminder
+/// windows asking during the same break all get the same exercise.
+#[tauri::command]
+pub async fn get_break_exercise(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    language: String,
+) -> Result<Option<BreakExercise>, String> {
+    let translation = match crate::services::remote::load_translation(&app, &language).await {
+        Ok(translation) => Some(translation),
+        Err(e) => {
+            eprintln!("Failed to load exercise texts: {:#}", e);
+            None
+        }
+    };
+    let session_id = state.timer_service.current_session_id();
+    let db = state.database_service.lock().await;
+    let settings = db.load_settings().await.map_err(|e| e.to_string())?;
+    let mut rotation = db.load_exercise_rotation();
+    let exercise = exercises::next_exercise(
+        &settings,
+        &mut rotation,
+        translation.as_ref(),
+        session_id.as_deref(),
+    );
+    db.save_exercise_rotation(&rotation)
+        .map_err(|e| e.to_string())?;
+    Ok(exercise)
+}
+
+/// Load translation content.
+#[tauri::command]
+pub async fn load_translation(app: AppHandle, language: String) -> Result<Value, String> {
+    crate::services::remote::load_translation(&app, &language)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Everything `window` ("main", "tray-menu", "work-note", "reminder…") needs for its
+/// first paint, in one call instead of several.
+///
+/// The database lock is taken exactly once (settings and today's totals); the
+/// translation bundle, break content and music track are then gathered concurrently.
+/// A part that fails is reported in `errors` and left empty rather than failing
+/// the whole bootstrap.
+#[tauri::command]
+pub async fn get_bootstrap(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    window: String,
+) -> Result<Bootstrap, String> {
+    let is_main = window == "main";
+    let is_reminder = window.starts_with("reminder");
+    let mut errors = Vec::new();
+
+    let (settings, today, hydration) = {
+        let db = state.database_service.lock().await;
+        bootstrap_records(&*db, is_main, is_reminder, &mut errors).await?
+    };
+    let timer_info = state.timer_service.get_info();
+    let language = settings.language.code();
+
+    let translation = crate::services::remote::load_translation(&app, language);
+    let break_content = async {
+        if !is_reminder {
+            return Ok(None);
+        }
+        let content = crate::services::remote::get_break_content(
+            &app,
+            language,
+            None,
+            &settings.quote_providers,
+            break_themes::today(&settings.break_themes),
+        );
+        tokio::time::timeout(BOOTSTRAP_CONTENT_TIMEOUT, content)
+            .await
+            .map(Some)
+    };
+    let music_track = async {
+        if !is_reminder || !settings.rest_music_enabled {
+            return None;
+        }
+        let files = list_rest_music_files(break_themes::music_directory(&settings));
+        pick_playable_music_track(&app, files, None)
+    };
+    let (translation, break_content, music_track) =
+        tokio::join!(translation, break_content, music_track);
+
+    let translation = match translation {
+        Ok(bundle) => Some(bundle),
+        Err(e) => {
+            errors.push(format!("translation: {}", e));
+            None
+        }
+    };
+    let break_content = match break_content {
+        Ok(content) => content.map(|content| BreakContent {
+            hydration,
+            ..content
+        }),
+        Err(_) => {
+            errors.push("breakContent: timed out".to_string());
+            None
+        }
+    };
+
+    Ok(Bootstrap {
+        settings,
+        timer_info,
+        today,
+        translation,
+        break_content,
+        music_track,
+        errors,
+    })
+}
+
+/// The database reads behind `get_bootstrap`, made while the caller holds the
+/// lock once: settings, plus today's totals (main window) or the hydration
+/// progress (reminder windows).
+trait BootstrapSource {
+    async fn settings(&self) -> AppResult<Settings>;
+    async fn today(&self) -> AppResult<AnalyticsData>;
+    async fn hydration(&self, settings: &Settings) -> Option<HydrationProgress>;
+}
+
+impl BootstrapSource for DatabaseService {
+    async fn settings(&self) -> AppResult<Settings> {
+        self.load_settings().await
+    }
+
+    async fn today(&self) -> AppResult<AnalyticsData> {
+        self.get_analytics(&today_query()).await
+    }
+
+    async fn hydration(&self, settings: &Settings) -> Option<HydrationProgress> {
+        break_hydration(self, settings).await
+    }
+}
+
+async fn bootstrap_records(
+    db: &impl BootstrapSource,
+    is_main: bool,
+    is_reminder: bool,
+    errors: &mut Vec<String>,
+) -> Result<(Settings, Option<AnalyticsData>, Option<HydrationProgress>), String> {
+    let settings = db.settings().await.map_err(|e| e.to_string())?;
+    let hydration = if is_reminder {
+        db.hydration(&settings).await
+    } else {
+        None
+    };
+    let today = if is_main {
+        match db.today().await {
+            Ok(data) => Some(data),
+            Err(e) => {
+                errors.push(format!("today: {}", e));
+                None
+            }
+        }
+    } else {
+        None
+    };
+    Ok((settings, today, hydration))
 }
 
-/// Show reminder window once frontend is ready
-#[tauri::command]
-pub fn show_reminder_window(app: AppHandle) -> Result<(), String> {
-    // Show all reminder windows (across monitors)
-    for (label, window) in app.webview_windows() {
-        if label.starts_with("break-reminder") {
-            let _ = window.show();
-            let _ = window.set_focus();
+/// Work/break durations and the segment list shared by settings and presets.
+fn validate_timer_config(
+    work_duration: u32,
+    break_duration: u32,
+    segmented_enabled: bool,
+    segments: &[WorkSegment],
+) -> Result<(), String> {
+    if work_duration == 0 || work_duration > 120 {
+        return Err(AppError::InvalidDuration.to_string());
+    }
+    if break_duration == 0 || break_duration > 120 {
+        return Err(AppError::InvalidDuration.to_string());
+    }
+    if segmented_enabled {
+        if segments.is_empty() {
+            return Err(AppError::InvalidWorkSegments.to_string());
+        }
+        for segment in segments {
+            if segment.work_minutes == 0 || segment.work_minutes > MAX_SEGMENT_MINUTES {
+                return Err(AppError::InvalidDuration.to_string());
+            }
+            if segment.break_minutes == 0 || segment.break_minutes > MAX_SEGMENT_MINUTES {
+                return Err(AppError::InvalidDuration.to_string());
+            }
+            if segment.repeat == 0 || segment.repeat > MAX_SEGMENT_REPEAT {
+                return Err(AppError::InvalidWorkSegments.to_string());
+            }
+            let label_chars = segment
+                .label
+                .as_ref()
+                .map_or(0, |label| label.chars().count());
+            if label_chars > MAX_SEGMENT_LABEL_CHARS {
+                return Err(AppError::InvalidWorkSegments.to_string());
+            }
         }
     }
     Ok(())
 }
 
-/// Close reminder window
-#[tauri::command]
-pub fn close_reminder_window(app: AppHandle) -> Result<(), String> {
-    let mut to_close = vec![];
-    for (label, window) in app.webview_windows() {
-        if label.starts_with("break-reminder") {
-            to_close.push(window);
-        }
+fn validate_preset(preset: &TimerPreset) -> Result<(), String> {
+    if preset.name.trim().is_empty() || preset.name.chars().count() > MAX_PRESET_NAME_CHARS {
+        return Err(AppError::ValidationError(format!(
+            "Preset name must be 1-{} characters",
+            MAX_PRESET_NAME_CHARS
+        ))
+        .to_string());
     }
-    for w in to_close {
-        let _ = w.close();
+    validate_timer_config(
+        preset.work_duration,
+        preset.break_duration,
+        preset.segmented_work_enabled,
+        &preset.work_segments,
+    )
+}
+
+/// Local calendar day containing now, as an analytics range.
+fn today_query() -> AnalyticsQuery {
+    let now = Local::now();
+    let start = now
+        .date_naive()
+        .and_time(NaiveTime::MIN)
+        .and_local_timezone(Local)
+        .earliest()
+        .unwrap_or(now);
+    AnalyticsQuery {
+        start_date: start.with_timezone(&Utc),
+        end_date: (start + ChronoDuration::days(1)).with_timezone(&Utc),
     }
-    Ok(())
 }
 
-/// Show main window (used by frontend after initialization)
+/// Save a timer preset, replacing any preset with the same name
 #[tauri::command]
-pub fn show_main_window(app: AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("main") {
-        let _ = window.set_skip_taskbar(false);
-        let _ = window.show();
-        let _ = window.set_focus();
+pub async fn save_preset(
+    state: State<'_, AppState>,
+    mut preset: TimerPreset,
+) -> Result<Vec<TimerPreset>, String> {
+    preset.name = preset.name.trim().to_string();
+    validate_preset(&preset)?;
+
+    let db = state.database_service.lock().await;
+    let mut settings = db.load_settings().await.map_err(|e| e.to_string())?;
+    match settings.presets.iter_mut().find(|p| p.name == preset.name) {
+        Some(existing) => *existing = preset,
+        None => settings.presets.push(preset),
     }
-    Ok(())
+    db.save_settings(&settings)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(settings.presets)
 }
 
-/// Handle tray menu actions from custom menu window
+/// Delete a timer preset by name
 #[tauri::command]
-pub async fn tray_menu_action(
-    action: String,
-    app: AppHandle,
+pub async fn delete_preset(
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let cloned_state = AppState {
-        timer_service: state.timer_service.clone(),
-        database_service: state.database_service.clone(),
-        last_auto_close: state.last_auto_close.clone(),
-    };
-    handle_tray_action(action.as_str(), app, cloned_state).await
+    name: String,
+) -> Result<Vec<TimerPreset>, String> {
+    let db = state.database_service.lock().await;
+    let mut settings = db.load_settings().await.map_err(|e| e.to_string())?;
+    let before = settings.presets.len();
+    settings.presets.retain(|p| p.name != name);
+    if settings.presets.len() == before {
+        return Err(AppError::PresetNotFound(name).to_string());
+    }
+    if settings.active_preset.as_deref() == Some(name.as_str()) {
+        settings.active_preset = None;
+    }
+    db.save_settings(&settings)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(settings.presets)
 }
 
-/// Fetch tip quote text.
+/// Switch to a saved preset: reconfigure the live timer and remember it as active.
+/// The running phase keeps its length; the new durations apply from the next phase.
 #[tauri::command]
-pub async fn fetch_tip_quote(language: String) -> Result<Option<String>, String> {
-    crate::services::remote::fetch_tip_quote(&language)
+pub async fn apply_preset(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<Settings, String> {
+    let db = state.database_service.lock().await;
+    let mut settings = db.load_settings().await.map_err(|e| e.to_string())?;
+    let preset = settings
+        .presets
+        .iter()
+        .find(|p| p.name == name)
+        .cloned()
+        .ok_or_else(|| AppError::PresetNotFound(name.clone()).to_string())?;
+
+    settings.work_duration = preset.work_duration;
+    settings.break_duration = preset.break_duration;
+    settings.segmented_work_enabled = preset.segmented_work_enabled;
+    if !preset.work_segments.is_empty() {
+        settings.work_segments = preset.work_segments;
+    }
+    settings.active_preset = Some(preset.name);
+    validate_settings(&settings)?;
+
+    state.timer_service.update_timer_configuration(
+        settings.work_duration,
+        settings.break_duration,
+        settings.segmented_work_enabled,
+        settings.work_segments.clone(),
+    );
+    state
+        .timer_service
+        .set_active_preset(settings.active_preset.clone())
+        .map_err(|e| e.to_string())?;
+    db.save_settings(&settings)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    crate::refresh_tray_tooltip(&app, &settings.language);
+    Ok(settings)
 }
 
-/// Load translation content.
+/// Name of the preset the timer is currently running, if any
 #[tauri::command]
-pub async fn load_translation(app: AppHandle, language: String) -> Result<Value, String> {
-    crate::services::remote::load_translation(&app, &language)
-        .await
-        .map_err(|e| e.to_string())
+pub fn get_active_preset(state: State<'_, AppState>) -> Option<String> {
+    state.timer_service.active_preset()
+}
+
+/// Forget the active preset once durations were edited by hand (or it was deleted).
+fn clear_stale_active_preset(settings: &mut Settings) {
+    let still_matches = settings.active_preset.as_deref().is_some_and(|name| {
+        settings
+            .presets
+            .iter()
+            .any(|preset| preset.name == name && preset.matches(settings))
+    });
+    if !still_matches {
+        settings.active_preset = None;
+    }
 }
 
 /// Send a Windows native toast with action buttons for the pre-break reminder.
@@ -483,46 +2162,19 @@ pub async fn send_pre_break_toast(
     dismiss_label: String,
     break_now_label: String,
 ) -> Result<(), String> {
-    use tauri_winrt_notification::{Duration, Toast};
-
-    let app_id = app.config().identifier.clone();
-    let emit_app = app.clone();
-
-    let result = Toast::new(&app_id)
-        .title(&title)
-        .text1(&body)
-        .duration(Duration::Short)
-        .add_button(&dismiss_label, "dismiss")
-        .add_button(&break_now_label, "break-now")
-        .on_activated(move |action| {
-            if let Some(action_id) = action {
-                let _ = emit_app.emit("pre-break-action", action_id);
-            }
-            Ok(())
-        })
-        .show();
+    use crate::services::notifications::{self, NotificationAction};
 
-    match result {
-        Ok(_) => Ok(()),
-        Err(err) => {
-            // Fallback to the powershell workaround app id (useful in dev where
-            // the AppUserModelID may not be registered yet).
-            Toast::new(Toast::POWERSHELL_APP_ID)
-                .title(&title)
-                .text1(&body)
-                .duration(Duration::Short)
-                .add_button(&dismiss_label, "dismiss")
-                .add_button(&break_now_label, "break-now")
-                .on_activated(move |action| {
-                    if let Some(action_id) = action {
-                        let _ = app.emit("pre-break-action", action_id);
-                    }
-                    Ok(())
-                })
-                .show()
-                .map_err(|e| format!("{err}; fallback: {e}"))
-        }
-    }
+    let actions = [
+        NotificationAction {
+            id: "dismiss",
+            label: &dismiss_label,
+        },
+        NotificationAction {
+            id: "break-now",
+            label: &break_now_label,
+        },
+    ];
+    notifications::show_with_actions(&app, &title, &body, &actions)
 }
 
 /// Non-Windows stub so the JS side can uniformly invoke the command.
@@ -539,27 +2191,368 @@ pub async fn send_pre_break_toast(
 }
 
 /// Validate settings before persistence.
+/// Clock-anchored ranges: exclusive with sequential segments, valid "HH:MM"
+/// bounds and durations, and no two ranges covering the same time of day.
+fn validate_time_anchored_segments(settings: &Settings) -> Result<(), String> {
+    let segments = &settings.time_anchored_segments;
+    if segments.is_empty() {
+        return Ok(());
+    }
+    if settings.segmented_work_enabled {
+        return Err(AppError::ValidationError(
+            "timeAnchoredSegments cannot be combined with segmented work".to_string(),
+        )
+        .to_string());
+    }
+
+    // Each range as minute-of-day intervals, split in two when it crosses midnight
+    let mut spans: Vec<Vec<(u32, u32)>> = Vec::with_capacity(segments.len());
+    for (index, segment) in segments.iter().enumerate() {
+        let parse = |value: &str| {
+            NaiveTime::parse_from_str(value.trim(), "%H:%M")
+                .map(|time| time.hour() * 60 + time.minute())
+                .map_err(|_| {
+                    AppError::ValidationError(format!(
+                        "timeAnchoredSegments[{}] times must be HH:MM, got {}",
+                        index, value
+                    ))
+                    .to_string()
+                })
+        };
+        let start = parse(&segment.start)?;
+        let end = parse(&segment.end)?;
+        if start == end {
+            return Err(AppError::ValidationError(format!(
+                "timeAnchoredSegments[{}] starts and ends at the same time",
+                index
+            ))
+            .to_string());
+        }
+        if segment.work_minutes == 0 || segment.work_minutes > 120 {
+            return Err(AppError::InvalidDuration.to_string());
+        }
+        if segment.break_minutes == 0 || segment.break_minutes > 120 {
+            return Err(AppError::InvalidDuration.to_string());
+        }
+        spans.push(if start < end {
+            vec![(start, end)]
+        } else {
+            vec![(start, 24 * 60), (0, end)]
+        });
+    }
+
+    for (i, a) in spans.iter().enumerate() {
+        for (j, b) in spans.iter().enumerate().skip(i + 1) {
+            let overlaps = a.iter().any(|&(a_start, a_end)| {
+                b.iter()
+                    .any(|&(b_start, b_end)| a_start < b_end && b_start < a_end)
+            });
+            if overlaps {
+                return Err(AppError::ValidationError(format!(
+                    "timeAnchoredSegments[{}] overlaps timeAnchoredSegments[{}]",
+                    i, j
+                ))
+                .to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Network quiet hours: valid "HH:MM" bounds that differ and weekdays 1–7.
+/// Unlike time-anchored segments, ranges may overlap.
+fn validate_network_quiet_hours(settings: &Settings) -> Result<(), String> {
+    let ranges = &settings.network_quiet_hours;
+    if ranges.len() > MAX_NETWORK_QUIET_HOURS {
+        return Err(AppError::ValidationError(format!(
+            "networkQuietHours allows at most {} ranges",
+            MAX_NETWORK_QUIET_HOURS
+        ))
+        .to_string());
+    }
+    for (index, range) in ranges.iter().enumerate() {
+        let parse = |value: &str| {
+            NaiveTime::parse_from_str(value.trim(), "%H:%M").map_err(|_| {
+                AppError::ValidationError(format!(
+                    "networkQuietHours[{}] times must be HH:MM, got {}",
+                    index, value
+                ))
+                .to_string()
+            })
+        };
+        if parse(&range.start)? == parse(&range.end)? {
+            return Err(AppError::ValidationError(format!(
+                "networkQuietHours[{}] starts and ends at the same time",
+                index
+            ))
+            .to_string());
+        }
+        if range.days.iter().any(|day| !(1..=7).contains(day)) {
+            return Err(AppError::ValidationError(format!(
+                "networkQuietHours[{}] days must be 1 (Monday) to 7 (Sunday)",
+                index
+            ))
+            .to_string());
+        }
+    }
+    Ok(())
+}
+
 fn validate_settings(settings: &Settings) -> Result<(), String> {
-    if settings.work_duration == 0 || settings.work_duration > 120 {
-        return Err(AppError::InvalidDuration.to_string());
+    validate_timer_config(
+        settings.work_duration,
+        settings.break_duration,
+        settings.segmented_work_enabled,
+        &settings.work_segments,
+    )?;
+    if settings.segmented_work_enabled && matches!(settings.program_repeat, Some(0)) {
+        return Err(AppError::InvalidWorkSegments.to_string());
     }
-    if settings.break_duration == 0 || settings.break_duration > 120 {
+    validate_time_anchored_segments(settings)?;
+    validate_network_quiet_hours(settings)?;
+    if settings.flow_mode_enabled && settings.segmented_work_enabled {
+        // Allowed: flow mode wins, segments keep advancing without breaks
+        eprintln!(
+            "Settings warning: flow mode and segmented work are both enabled; \
+             segment breaks are skipped while flow mode is on"
+        );
+    }
+    let mut preset_names = HashSet::new();
+    for preset in &settings.presets {
+        validate_preset(preset)?;
+        if !preset_names.insert(preset.name.as_str()) {
+            return Err(AppError::ValidationError(format!(
+                "Duplicate preset name: {}",
+                preset.name
+            ))
+            .to_string());
+        }
+    }
+    if settings.max_reminder_minutes == 0 || settings.max_reminder_minutes > 120 {
         return Err(AppError::InvalidDuration.to_string());
     }
-    if settings.segmented_work_enabled {
-        if settings.work_segments.is_empty() {
-            return Err(AppError::InvalidWorkSegments.to_string());
+    if let Some(minutes) = settings.max_suppression_minutes {
+        if minutes == 0 || minutes > MAX_SUPPRESSION_CAP_MINUTES {
+            return Err(AppError::ValidationError(format!(
+                "maxSuppressionMinutes must be between 1 and {}",
+                MAX_SUPPRESSION_CAP_MINUTES
+            ))
+            .to_string());
         }
-        for segment in &settings.work_segments {
-            if segment.work_minutes == 0 || segment.work_minutes > 120 {
-                return Err(AppError::InvalidDuration.to_string());
-            }
-            if segment.break_minutes == 0 || segment.break_minutes > 120 {
-                return Err(AppError::InvalidDuration.to_string());
-            }
-            if segment.repeat == 0 || segment.repeat > 12 {
-                return Err(AppError::InvalidWorkSegments.to_string());
-            }
+    }
+    if settings.break_jitter_minutes > MAX_BREAK_JITTER_MINUTES {
+        return Err(AppError::ValidationError(format!(
+            "breakJitterMinutes must be at most {}",
+            MAX_BREAK_JITTER_MINUTES
+        ))
+        .to_string());
+    }
+    if settings.reminder_click_through && settings.enable_force_break {
+        return Err(AppError::ValidationError(
+            "reminderClickThrough cannot be combined with force break".to_string(),
+        )
+        .to_string());
+    }
+    if settings.reminder_click_through && settings.require_break_acknowledgment {
+        return Err(AppError::ValidationError(
+            "reminderClickThrough cannot be combined with requireBreakAcknowledgment".to_string(),
+        )
+        .to_string());
+    }
+    if let Some(url) = settings.webhook_url.as_deref() {
+        if !url.trim().is_empty() && !webhook::is_valid_url(url) {
+            return Err(AppError::ValidationError(format!(
+                "webhookUrl must be an http(s) URL: {}",
+                url
+            ))
+            .to_string());
+        }
+    }
+    if let Some(minutes) = settings.long_absence_minutes {
+        if minutes == 0 || minutes > MAX_LONG_ABSENCE_MINUTES {
+            return Err(AppError::ValidationError(format!(
+                "longAbsenceMinutes must be between 1 and {}",
+                MAX_LONG_ABSENCE_MINUTES
+            ))
+            .to_string());
+        }
+    }
+    if let Some(minutes) = settings.max_total_extend_minutes {
+        if minutes == 0 || minutes > MAX_TOTAL_EXTEND_MINUTES {
+            return Err(AppError::ValidationError(format!(
+                "maxTotalExtendMinutes must be between 1 and {}",
+                MAX_TOTAL_EXTEND_MINUTES
+            ))
+            .to_string());
+        }
+    }
+    if settings.suppression_expiry_warning_minutes > MAX_SUPPRESSION_EXPIRY_WARNING_MINUTES {
+        return Err(AppError::ValidationError(format!(
+            "suppressionExpiryWarningMinutes must be at most {}",
+            MAX_SUPPRESSION_EXPIRY_WARNING_MINUTES
+        ))
+        .to_string());
+    }
+    if settings.work_start_countdown_seconds > MAX_WORK_START_COUNTDOWN_SECONDS {
+        return Err(AppError::ValidationError(format!(
+            "workStartCountdownSeconds must be at most {}",
+            MAX_WORK_START_COUNTDOWN_SECONDS
+        ))
+        .to_string());
+    }
+    if settings.min_session_seconds > MAX_MIN_SESSION_SECONDS {
+        return Err(AppError::ValidationError(format!(
+            "minSessionSeconds must be at most {}",
+            MAX_MIN_SESSION_SECONDS
+        ))
+        .to_string());
+    }
+    if !(MIN_REAL_BREAK_MINUTES..=MAX_REAL_BREAK_MINUTES).contains(&settings.min_real_break_minutes)
+    {
+        return Err(AppError::ValidationError(format!(
+            "minRealBreakMinutes must be between {} and {}",
+            MIN_REAL_BREAK_MINUTES, MAX_REAL_BREAK_MINUTES
+        ))
+        .to_string());
+    }
+    if !(MIN_ACTIVITY_SAMPLE_SECONDS..=MAX_ACTIVITY_SAMPLE_SECONDS)
+        .contains(&settings.activity_sample_seconds)
+    {
+        return Err(AppError::ValidationError(format!(
+            "activitySampleSeconds must be between {} and {}",
+            MIN_ACTIVITY_SAMPLE_SECONDS, MAX_ACTIVITY_SAMPLE_SECONDS
+        ))
+        .to_string());
+    }
+    // A threshold below the interval would miss input between two samples
+    if !(settings.activity_sample_seconds..=MAX_ACTIVITY_IDLE_THRESHOLD_SECONDS)
+        .contains(&settings.activity_idle_threshold_seconds)
+    {
+        return Err(AppError::ValidationError(format!(
+            "activityIdleThresholdSeconds must be between activitySampleSeconds and {}",
+            MAX_ACTIVITY_IDLE_THRESHOLD_SECONDS
+        ))
+        .to_string());
+    }
+    if settings.analytics_load_window_days > MAX_ANALYTICS_LOAD_WINDOW_DAYS {
+        return Err(AppError::ValidationError(format!(
+            "analyticsLoadWindowDays must be at most {}",
+            MAX_ANALYTICS_LOAD_WINDOW_DAYS
+        ))
+        .to_string());
+    }
+    if settings.rest_debt_compensation_percent > 100 {
+        return Err(AppError::ValidationError(
+            "restDebtCompensationPercent must be between 0 and 100".to_string(),
+        )
+        .to_string());
+    }
+    let prefix = &settings.session_id_prefix;
+    let prefix_ok = prefix.chars().count() <= MAX_SESSION_ID_PREFIX_CHARS
+        && prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !prefix_ok {
+        return Err(AppError::ValidationError(format!(
+            "sessionIdPrefix must be at most {} letters, digits, '-' or '_'",
+            MAX_SESSION_ID_PREFIX_CHARS
+        ))
+        .to_string());
+    }
+    let anomaly = &settings.anomaly_detection;
+    if !(MIN_BREAK_OVERRUN_RATIO..=MAX_BREAK_OVERRUN_RATIO).contains(&anomaly.break_overrun_ratio) {
+        return Err(AppError::ValidationError(format!(
+            "anomalyDetection.breakOverrunRatio must be between {} and {}",
+            MIN_BREAK_OVERRUN_RATIO, MAX_BREAK_OVERRUN_RATIO
+        ))
+        .to_string());
+    }
+    if !(MIN_ANOMALY_WORK_MINUTES..=MAX_ANOMALY_WORK_MINUTES).contains(&anomaly.max_work_minutes) {
+        return Err(AppError::ValidationError(format!(
+            "anomalyDetection.maxWorkMinutes must be between {} and {}",
+            MIN_ANOMALY_WORK_MINUTES, MAX_ANOMALY_WORK_MINUTES
+        ))
+        .to_string());
+    }
+    let focus = &settings.focus_score;
+    if focus.daily_work_goal_minutes == 0 || focus.daily_work_goal_minutes > 24 * 60 {
+        return Err(AppError::ValidationError(
+            "focusScore.dailyWorkGoalMinutes must be between 1 and 1440".to_string(),
+        )
+        .to_string());
+    }
+    let weights = [
+        focus.work_goal_weight,
+        focus.break_completion_weight,
+        focus.low_skips_weight,
+    ];
+    if weights.iter().any(|weight| *weight > 100) || weights.iter().all(|weight| *weight == 0) {
+        return Err(AppError::ValidationError(
+            "focusScore weights must be between 0 and 100 and not all 0".to_string(),
+        )
+        .to_string());
+    }
+    let hydration = &settings.hydration;
+    if !(1..=MAX_HYDRATION_GOAL_GLASSES).contains(&hydration.daily_goal_glasses) {
+        return Err(AppError::ValidationError(format!(
+            "hydration.dailyGoalGlasses must be between 1 and {}",
+            MAX_HYDRATION_GOAL_GLASSES
+        ))
+        .to_string());
+    }
+    if hydration.reminder_interval_minutes > MAX_HYDRATION_INTERVAL_MINUTES {
+        return Err(AppError::ValidationError(format!(
+            "hydration.reminderIntervalMinutes must be at most {}",
+            MAX_HYDRATION_INTERVAL_MINUTES
+        ))
+        .to_string());
+    }
+    if !(0.0..=1.0).contains(&settings.min_break_completion_ratio) {
+        return Err(AppError::ValidationError(
+            "minBreakCompletionRatio must be between 0 and 1".to_string(),
+        )
+        .to_string());
+    }
+    if settings.wind_down.enabled {
+        let wind_down = &settings.wind_down;
+        if chrono::NaiveTime::parse_from_str(wind_down.end_of_day.trim(), "%H:%M").is_err() {
+            return Err(AppError::ValidationError(format!(
+                "windDown.endOfDay must be HH:MM, got {}",
+                wind_down.end_of_day
+            ))
+            .to_string());
+        }
+        if wind_down.starts_before_end_minutes == 0 || wind_down.starts_before_end_minutes > 240 {
+            return Err(AppError::ValidationError(
+                "windDown.startsBeforeEndMinutes must be between 1 and 240".to_string(),
+            )
+            .to_string());
+        }
+        if wind_down.work_minutes == 0 || wind_down.work_minutes > 120 {
+            return Err(AppError::InvalidDuration.to_string());
+        }
+        if wind_down.break_minutes == 0 || wind_down.break_minutes > 120 {
+            return Err(AppError::InvalidDuration.to_string());
+        }
+    }
+    for provider in &settings.quote_providers {
+        validate_quote_provider(provider)?;
+    }
+    validate_custom_exercises(&settings.custom_exercises)?;
+    validate_break_themes(&settings.break_themes)?;
+    for hotkey in [
+        &settings.hotkeys.open_tray_menu,
+        &settings.hotkeys.emergency_dismiss,
+    ] {
+        let hotkey = hotkey.trim();
+        if !hotkey.is_empty()
+            && hotkey
+                .parse::<tauri_plugin_global_shortcut::Shortcut>()
+                .is_err()
+        {
+            return Err(
+                AppError::ValidationError(format!("Invalid hotkey: {}", hotkey)).to_string(),
+            );
         }
     }
     if settings.opacity > 100 {
@@ -567,3 +2560,441 @@ fn validate_settings(settings: &Settings) -> Result<(), String> {
     }
     Ok(())
 }
+
+/// Custom exercises need a unique id not used by a built-in exercise, a text,
+/// a category and a duration, all within bounds.
+fn validate_custom_exercises(custom: &[CustomExercise]) -> Result<(), String> {
+    let mut ids = HashSet::new();
+    for (i, exercise) in custom.iter().enumerate() {
+        let id = exercise.id.trim();
+        let problem = if id.is_empty() || id.chars().count() > MAX_EXERCISE_ID_CHARS {
+            Some(format!("id must be 1-{} characters", MAX_EXERCISE_ID_CHARS))
+        } else if exercises::is_builtin_id(id) || !ids.insert(id) {
+            Some(format!("id {} is already in use", id))
+        } else if exercise.text.trim().is_empty()
+            || exercise.text.chars().count() > MAX_EXERCISE_TEXT_CHARS
+        {
+            Some(format!(
+                "text must be 1-{} characters",
+                MAX_EXERCISE_TEXT_CHARS
+            ))
+        } else if exercise.category.trim().is_empty()
+            || exercise.category.chars().count() > MAX_EXERCISE_ID_CHARS
+        {
+            Some(format!(
+                "category must be 1-{} characters",
+                MAX_EXERCISE_ID_CHARS
+            ))
+        } else if !(1..=MAX_EXERCISE_SECONDS).contains(&exercise.duration_seconds) {
+            Some(format!(
+                "durationSeconds must be between 1 and {}",
+                MAX_EXERCISE_SECONDS
+            ))
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            return Err(
+                AppError::ValidationError(format!("customExercises[{}]: {}", i, problem))
+                    .to_string(),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Break themes need a name, and their lists and texts must stay within bounds.
+fn validate_break_themes(themes: &[BreakTheme]) -> Result<(), String> {
+    if themes.len() > MAX_BREAK_THEMES {
+        return Err(AppError::ValidationError(format!(
+            "breakThemes: at most {} themes",
+            MAX_BREAK_THEMES
+        ))
+        .to_string());
+    }
+    for (i, theme) in themes.iter().enumerate() {
+        let name = theme.name.trim();
+        let problem = if name.is_empty() || name.chars().count() > MAX_PRESET_NAME_CHARS {
+            Some(format!(
+                "name must be 1-{} characters",
+                MAX_PRESET_NAME_CHARS
+            ))
+        } else if theme.messages.len() > MAX_THEME_MESSAGES {
+            Some(format!("at most {} messages", MAX_THEME_MESSAGES))
+        } else if theme
+            .messages
+            .iter()
+            .any(|message| message.chars().count() > MAX_THEME_MESSAGE_CHARS)
+        {
+            Some(format!(
+                "messages must be at most {} characters",
+                MAX_THEME_MESSAGE_CHARS
+            ))
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            return Err(
+                AppError::ValidationError(format!("breakThemes[{}]: {}", i, problem)).to_string(),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// A quote provider needs an http(s) URL and JSON pointers such as `/quote`.
+fn validate_quote_provider(provider: &QuoteProvider) -> Result<(), String> {
+    if !webhook::is_valid_url(&provider.url) {
+        return Err(AppError::ValidationError(format!(
+            "quoteProviders: {} needs an http(s) URL",
+            provider.name
+        ))
+        .to_string());
+    }
+    let pointers_ok = !provider.pointers.is_empty()
+        && provider
+            .pointers
+            .iter()
+            .all(|pointer| pointer.is_empty() || pointer.starts_with('/'));
+    if !pointers_ok {
+        return Err(AppError::ValidationError(format!(
+            "quoteProviders: {} needs JSON pointers such as /quote",
+            provider.name
+        ))
+        .to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::EffectiveTimerMode;
+
+    /// A file under the temp dir named after the test, removed when dropped.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, content: &[u8]) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("resty-{}-{}.json", std::process::id(), name));
+            std::fs::write(&path, content).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn settings_json() -> Value {
+        serde_json::to_value(Settings::default()).unwrap()
+    }
+
+    fn package_json(settings: Value) -> Value {
+        serde_json::json!({
+            "app": CONFIG_APP_ID,
+            "schemaVersion": 1,
+            "settings": settings,
+            "sessions": [],
+            "achievements": [],
+        })
+    }
+
+    fn read_package(name: &str, value: &Value) -> Result<AppDataImport, String> {
+        let file = TempFile::new(name, value.to_string().as_bytes());
+        read_app_data_package(&file.0)
+    }
+
+    #[test]
+    fn an_exported_package_reads_back() {
+        let package = read_package("exported", &package_json(settings_json())).unwrap();
+
+        assert_eq!(
+            package.settings.0.work_duration,
+            Settings::default().work_duration
+        );
+    }
+
+    #[test]
+    fn ten_megabytes_of_garbage_are_refused() {
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        let garbage: Vec<u8> = (0..10 * 1024 * 1024)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect();
+        let binary = TempFile::new("garbage-binary", &garbage);
+        // Valid UTF-8 that is not JSON either
+        let text: Vec<u8> = garbage.iter().map(|byte| b'a' + byte % 26).collect();
+        let text = TempFile::new("garbage-text", &text);
+
+        for file in [&binary, &text] {
+            let err = read_app_data_package(&file.0).err().unwrap();
+            assert!(err.starts_with("Failed to import configuration"), "{err}");
+        }
+    }
+
+    #[test]
+    fn another_apps_file_is_refused() {
+        let mut marked = package_json(settings_json());
+        marked["app"] = Value::from("com.example.other");
+        let err = read_package("other-app", &marked).err().unwrap();
+        assert!(err.contains("another app"), "{err}");
+
+        // No marker, but nothing in it is ours
+        let foreign = serde_json::json!({
+            "version": 3,
+            "preferences": { "theme": "dark", "breakEvery": 20 },
+        });
+        assert!(read_package("foreign", &foreign).is_err());
+        let err = parse_imported_settings_blocking(&foreign.to_string())
+            .err()
+            .unwrap();
+        assert!(err.starts_with("Failed to import configuration"), "{err}");
+    }
+
+    #[test]
+    fn unknown_nested_settings_fields_are_refused() {
+        let mut settings = settings_json();
+        settings["hydration"]["cups"] = Value::from(8);
+        settings["focusScore"]["bonus"] = Value::from(true);
+
+        let err = parse_imported_settings_blocking(&settings.to_string())
+            .err()
+            .unwrap();
+        assert!(err.contains("hydration.cups"), "{err}");
+        assert!(err.contains("focusScore.bonus"), "{err}");
+
+        let err = read_package("nested", &package_json(settings))
+            .err()
+            .unwrap();
+        assert!(err.contains("hydration.cups"), "{err}");
+    }
+
+    #[test]
+    fn unknown_package_fields_are_refused() {
+        let mut package = package_json(settings_json());
+        package["history"] = Value::Array(Vec::new());
+
+        let err = read_package("package-field", &package).err().unwrap();
+        assert!(err.contains("history"), "{err}");
+    }
+
+    /// Counts the reads `bootstrap_records` makes; today's totals always fail.
+    #[derive(Default)]
+    struct CountingSource {
+        reads: std::sync::Mutex<Vec<&'static str>>,
+    }
+
+    impl BootstrapSource for CountingSource {
+        async fn settings(&self) -> AppResult<Settings> {
+            self.reads.lock().unwrap().push("settings");
+            Ok(Settings {
+                hydration: crate::models::HydrationSettings {
+                    enabled: true,
+                    ..Default::default()
+                },
+                ..Settings::default()
+            })
+        }
+
+        async fn today(&self) -> AppResult<AnalyticsData> {
+            self.reads.lock().unwrap().push("today");
+            Err(AppError::DatabaseError("disk unplugged".to_string()))
+        }
+
+        async fn hydration(&self, settings: &Settings) -> Option<HydrationProgress> {
+            self.reads.lock().unwrap().push("hydration");
+            Some(HydrationProgress {
+                date: NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+                count: 0,
+                goal: settings.hydration.daily_goal_glasses,
+            })
+        }
+    }
+
+    /// Reads of one bootstrap, made under a single acquisition of the lock.
+    fn bootstrap_reads(window: &str) -> (Vec<&'static str>, Vec<String>) {
+        let db = tokio::sync::Mutex::new(CountingSource::default());
+        let mut errors = Vec::new();
+        tauri::async_runtime::block_on(async {
+            let source = db.lock().await;
+            let is_reminder = window.starts_with("reminder");
+            let (_, _, hydration) =
+                bootstrap_records(&*source, window == "main", is_reminder, &mut errors)
+                    .await
+                    .unwrap();
+            assert_eq!(hydration.is_some(), is_reminder);
+        });
+        let reads = db.into_inner().reads.into_inner().unwrap();
+        (reads, errors)
+    }
+
+    #[test]
+    fn the_reminder_bootstrap_reads_the_database_a_bounded_number_of_times() {
+        let (reads, errors) = bootstrap_reads("reminder-0");
+        assert_eq!(reads, ["settings", "hydration"]);
+        assert!(errors.is_empty());
+
+        let (reads, _) = bootstrap_reads("tray-menu");
+        assert_eq!(reads, ["settings"]);
+    }
+
+    #[test]
+    fn a_failing_part_does_not_fail_the_main_bootstrap() {
+        let (reads, errors) = bootstrap_reads("main");
+        assert_eq!(reads, ["settings", "today"]);
+        assert_eq!(errors, ["today: Database error: disk unplugged"]);
+    }
+
+    fn segment(work_minutes: u32, break_minutes: u32) -> WorkSegment {
+        WorkSegment {
+            work_minutes,
+            break_minutes,
+            repeat: 1,
+            label: None,
+        }
+    }
+
+    fn segmented_settings() -> Settings {
+        Settings {
+            work_segments: vec![segment(25, 5), segment(50, 10)],
+            ..Settings::default()
+        }
+    }
+
+    fn diff(previous: &Settings, current: &Settings) -> Vec<SettingChange> {
+        let mut changes = Vec::new();
+        diff_settings_json(
+            "",
+            &serde_json::to_value(previous).unwrap(),
+            &serde_json::to_value(current).unwrap(),
+            &mut changes,
+        );
+        changes
+    }
+
+    #[test]
+    fn saving_the_same_settings_changes_nothing() {
+        let previous = segmented_settings();
+        let current = Settings {
+            updated_at: previous.updated_at + chrono::Duration::minutes(5),
+            ..previous.clone()
+        };
+
+        assert!(diff(&previous, &current).is_empty());
+        assert!(changed_setting_keys(&previous, &current).is_empty());
+    }
+
+    #[test]
+    fn a_changed_segment_reports_the_whole_segment_list() {
+        let previous = segmented_settings();
+        let mut current = previous.clone();
+        current.work_segments[1].break_minutes = 15;
+
+        let changes = diff(&previous, &current);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "workSegments");
+        assert_eq!(changes[0].old_value[1]["breakMinutes"], 10);
+        assert_eq!(changes[0].new_value[1]["breakMinutes"], 15);
+        assert_eq!(changed_setting_keys(&previous, &current), ["workSegments"]);
+    }
+
+    #[test]
+    fn added_and_removed_segments_are_one_change() {
+        let previous = segmented_settings();
+        let mut added = previous.clone();
+        added.work_segments.push(segment(90, 20));
+        let mut removed = previous.clone();
+        removed.work_segments.truncate(1);
+
+        for current in [added, removed] {
+            let changes = diff(&previous, &current);
+            assert_eq!(changes.len(), 1);
+            assert_eq!(
+                changes[0].new_value.as_array().unwrap().len(),
+                current.work_segments.len()
+            );
+            assert_eq!(changed_setting_keys(&previous, &current), ["workSegments"]);
+        }
+    }
+
+    #[test]
+    fn nested_changes_are_listed_once_under_their_top_level_key() {
+        let previous = segmented_settings();
+        let mut current = previous.clone();
+        current.work_segments[0].work_minutes = 30;
+        current.hotkeys.open_tray_menu = "Ctrl+Alt+R".to_string();
+        current.opacity = 60;
+        current.time_anchored_segments = vec![crate::models::TimeAnchoredSegment {
+            start: "22:00".to_string(),
+            end: "02:00".to_string(),
+            work_minutes: 25,
+            break_minutes: 5,
+        }];
+
+        let fields: Vec<String> = diff(&previous, &current)
+            .into_iter()
+            .map(|change| change.field)
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                "hotkeys.openTrayMenu",
+                "opacity",
+                "timeAnchoredSegments",
+                "workSegments"
+            ]
+        );
+        assert_eq!(
+            changed_setting_keys(&previous, &current),
+            ["hotkeys", "opacity", "timeAnchoredSegments", "workSegments"]
+        );
+    }
+
+    /// Mode of a timer running on `settings`, as `TimerInfo` reports it.
+    fn effective_mode(settings: &Settings) -> EffectiveTimerMode {
+        use crate::services::timer_deps::fakes::{FakeClock, MemoryStore, RecordingEvents};
+        let timer = TimerService::with_dependencies(
+            Arc::new(RecordingEvents::default()),
+            Arc::new(MemoryStore::default()),
+            Arc::new(FakeClock::at(chrono::Utc::now())),
+        );
+        timer.apply_settings(settings).unwrap();
+        timer.get_info().effective_mode
+    }
+
+    #[test]
+    fn exported_mode_combinations_import_unchanged() {
+        // (flow mode, segmented work, effective mode, as reported in TimerInfo)
+        let combinations = [
+            (true, true, EffectiveTimerMode::Flow, "flow"),
+            (true, false, EffectiveTimerMode::Flow, "flow"),
+            (false, true, EffectiveTimerMode::Segmented, "segmented"),
+            (false, false, EffectiveTimerMode::Simple, "simple"),
+        ];
+        for (flow, segmented, mode, name) in combinations {
+            let exported = Settings {
+                flow_mode_enabled: flow,
+                segmented_work_enabled: segmented,
+                ..segmented_settings()
+            };
+            let json = exported_config_json(&exported).unwrap();
+            let imported = parse_imported_settings_blocking(&json).unwrap();
+
+            assert!(
+                diff(&exported, &imported).is_empty(),
+                "flow {flow}, segmented {segmented}"
+            );
+            assert_eq!(effective_mode(&exported), mode);
+            assert_eq!(effective_mode(&imported), mode);
+            assert_eq!(serde_json::to_value(mode).unwrap(), name);
+        }
+    }
+}
@@ -2,32 +2,633 @@ mod commands;
 mod models;
 mod services;
 mod utils;
-
-use crate::models::{FloatingPosition, Theme as SettingsTheme};
+mod windows;
+
+use crate::models::{
+    ActionGroup, ControlSource, DayStarted, FloatingPosition, HotkeySettings, Language,
+    MainWindowBreakAction, PhaseChange, PhaseChangeCause, ReminderFailure, ReminderVisible,
+    Settings, SettingsChanged, SoundEventSettings, SuppressionExpiring, Theme as SettingsTheme,
+    TimerPhase, TrayUnavailable, WorkNotePrompt,
+};
 use commands::AppState;
 use dark_light::Mode as SystemTheme;
-use services::{updater, DatabaseService, TimerService};
+use services::system_status::{self, BreakPresentation};
+use services::{
+    accessibility, error_log, hooks, network_policy, notifications, panics, updater, webhook,
+    DatabaseService, TimerService,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tauri::image::Image;
 use tauri::tray::{TrayIcon, TrayIconBuilder};
-use tauri::{Emitter, Listener, Manager, Theme, WebviewUrl, WebviewWindowBuilder};
+use tauri::{Emitter, Listener, Manager, Theme};
+use tauri_plugin_global_shortcut::Shortcut;
+use utils::format::{format_duration, format_local_time, locale_table, DurationStyle};
+use utils::{AppError, AppResult};
+// `crate::` keeps the module apart from the `windows` crate on Windows
+use crate::windows::{
+    ReminderWindowOptions, WindowKind, FLOATING_WINDOW_HEIGHT, FLOATING_WINDOW_WIDTH,
+    REMINDER_PREVIEW_LABEL, TRAY_MENU_HEIGHT, TRAY_MENU_LABEL, TRAY_MENU_WIDTH,
+};
 
 const TRAY_ICON_LIGHT: &[u8] = include_bytes!("../icons/128x128.png");
 const TRAY_ICON_DARK: &[u8] = include_bytes!("../icons/128x128Night.png");
 const MAIN_TRAY_ID: &str = "resty-main-tray";
-#[cfg(target_os = "windows")]
-const TRAY_MENU_WIDTH: f64 = 240.0;
-#[cfg(target_os = "windows")]
-const TRAY_MENU_HEIGHT: f64 = 192.0;
+/// Label and lifetime of the window opened by the reminder output test.
+const REMINDER_PREVIEW_SECONDS: u64 = 5;
 const FLOATING_MARGIN_X: i32 = 20;
 const FLOATING_MARGIN_Y: i32 = 96;
-const FLOATING_WINDOW_WIDTH: f64 = 340.0;
-const FLOATING_WINDOW_HEIGHT: f64 = 300.0;
+const TRAY_ACTION_DEBOUNCE_MS: u128 = 750;
+const REMINDER_READY_TIMEOUT_MS: u64 = 2000;
+/// Delay before the single retry of a failed reminder window build.
+const REMINDER_RETRY_DELAY_MS: u64 = 500;
+const REMINDER_PREWARM_DELAY_MS: u64 = 10_000;
+/// How often the watchdog checks the heartbeats of the visible reminder windows.
+const REMINDER_WATCHDOG_INTERVAL_SECS: u64 = 2;
+/// A visible reminder window silent for this long counts as hung.
+const REMINDER_HEARTBEAT_TIMEOUT_SECS: u64 = 10;
+/// Longest wait for the running session to be saved before the app exits anyway.
+const EXIT_FLUSH_TIMEOUT_SECS: u64 = 10;
+/// Delay before creating the tray icon a second time after it failed at startup.
+const TRAY_RETRY_DELAY_SECS: u64 = 30;
+/// Settings fields (as in `settings-changed`) applied to open reminder windows
+const REMINDER_WINDOW_SETTING_KEYS: [&str; 3] =
+    ["reminderMode", "floatingPosition", "remoteSessionBehavior"];
+
+/// Tray "no breaks" actions: id, native menu label, length in minutes
+/// (`None` = until tomorrow morning, which can exceed any cap).
+const SUPPRESSION_TRAY_ACTIONS: [(&str, &str, Option<u32>); 4] = [
+    ("no_break_1h", "No breaks for 1 hour", Some(60)),
+    ("no_break_2h", "No breaks for 2 hours", Some(120)),
+    ("no_break_5h", "No breaks for 5 hours", Some(300)),
+    (
+        "no_break_tomorrow",
+        "No breaks until tomorrow morning",
+        None,
+    ),
+];
+const FALLBACK_TRAY_ICON_SIZE: u32 = 32;
+const FALLBACK_TRAY_ICON_COLOR: [u8; 4] = [0x4c, 0xaf, 0x50, 0xff];
+
+/// Pending ready signals for hidden reminder windows, keyed by window label.
+pub(crate) type ReminderReadySignals =
+    Arc<std::sync::Mutex<HashMap<String, tokio::sync::oneshot::Sender<()>>>>;
+
+/// Tray suppression action ids whose length fits within `max_suppression_minutes`.
+pub(crate) fn allowed_suppression_actions(
+    max_suppression_minutes: Option<u32>,
+) -> Vec<&'static str> {
+    SUPPRESSION_TRAY_ACTIONS
+        .iter()
+        .filter(|(_, _, minutes)| match (max_suppression_minutes, minutes) {
+            (None, _) => true,
+            (Some(cap), Some(minutes)) => *minutes <= cap,
+            (Some(_), None) => false,
+        })
+        .map(|(id, _, _)| *id)
+        .collect()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn build_tray_menu<M: Manager<tauri::Wry>>(
+    app: &M,
+    max_suppression_minutes: Option<u32>,
+) -> tauri::Result<tauri::menu::Menu<tauri::Wry>> {
+    use tauri::menu::{MenuBuilder, MenuItemBuilder};
+
+    let allowed = allowed_suppression_actions(max_suppression_minutes);
+    let mut builder = MenuBuilder::new(app)
+        .item(&MenuItemBuilder::with_id("skip", "Skip current break").build(app)?);
+    for (id, label, _) in SUPPRESSION_TRAY_ACTIONS {
+        if allowed.contains(&id) {
+            builder = builder.item(&MenuItemBuilder::with_id(id, label).build(app)?);
+        }
+    }
+    builder
+        .separator()
+        .item(&MenuItemBuilder::with_id("settings", "Settings").build(app)?)
+        .item(&MenuItemBuilder::with_id("quit", "Quit").build(app)?)
+        .build()
+}
+
+/// Rebuild the native tray menu after the suppression cap changed.
+/// On Windows the custom menu webview queries the allowed actions itself.
+pub(crate) fn refresh_tray_menu(app: &tauri::AppHandle, max_suppression_minutes: Option<u32>) {
+    #[cfg(not(target_os = "windows"))]
+    {
+        let Some(tray) = app.tray_by_id(MAIN_TRAY_ID) else {
+            return;
+        };
+        match build_tray_menu(app, max_suppression_minutes) {
+            Ok(menu) => {
+                if let Err(e) = tray.set_menu(Some(menu)) {
+                    eprintln!("Failed to update tray menu: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to build tray menu: {}", e),
+        }
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let _ = (app, max_suppression_minutes);
+    }
+}
+
+/// Shortcut of the emergency reminder dismiss, as registered from settings.
+static EMERGENCY_DISMISS_SHORTCUT: std::sync::Mutex<Option<Shortcut>> = std::sync::Mutex::new(None);
+
+/// (Re-)register the global shortcuts from settings. The tray menu shortcut is
+/// Windows-only (the custom tray menu); the emergency dismiss works everywhere.
+pub(crate) fn apply_hotkeys(app: &tauri::AppHandle, hotkeys: &HotkeySettings) {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    let shortcuts = app.global_shortcut();
+    if let Err(e) = shortcuts.unregister_all() {
+        eprintln!("Failed to unregister hotkeys: {}", e);
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let accelerator = hotkeys.open_tray_menu.trim();
+        if !accelerator.is_empty() {
+            if let Err(e) = shortcuts.register(accelerator) {
+                eprintln!("Failed to register tray menu hotkey {}: {}", accelerator, e);
+            }
+        }
+    }
+
+    let accelerator = hotkeys.emergency_dismiss.trim();
+    let emergency = accelerator.parse::<Shortcut>().ok();
+    if let Some(shortcut) = emergency {
+        if let Err(e) = shortcuts.register(shortcut) {
+            eprintln!(
+                "Failed to register emergency dismiss hotkey {}: {}",
+                accelerator, e
+            );
+        }
+    }
+    if let Ok(mut slot) = EMERGENCY_DISMISS_SHORTCUT.lock() {
+        *slot = emergency;
+    }
+}
+
+/// Handle a pressed global shortcut: the emergency dismiss, or else the tray menu.
+fn handle_hotkey(app: &tauri::AppHandle, shortcut: &Shortcut) {
+    let is_emergency = EMERGENCY_DISMISS_SHORTCUT
+        .lock()
+        .map(|slot| slot.as_ref() == Some(shortcut))
+        .unwrap_or(false);
+    if is_emergency {
+        emergency_dismiss_reminders(app);
+    } else {
+        toggle_tray_menu_from_hotkey(app);
+    }
+}
+
+/// Destroy every shown reminder window from Rust alone, so a hung reminder page
+/// can always be removed. Force break is not enforced here; the break itself
+/// keeps running.
+/// 紧急关闭所有休息提醒窗口（不依赖网页响应，也不受强制休息限制），休息计时继续。
+fn emergency_dismiss_reminders(app: &tauri::AppHandle) {
+    let prewarmed = app
+        .try_state::<AppState>()
+        .and_then(|state| state.prewarmed_reminders.lock().ok().map(|l| l.clone()))
+        .unwrap_or_default();
+    for window in windows::reminder_windows(app) {
+        if prewarmed.contains(window.label()) {
+            continue;
+        }
+        let label = window.label().to_string();
+        eprintln!("Emergency dismiss: destroying {}", label);
+        if let Err(e) = window.destroy() {
+            eprintln!("Failed to destroy reminder window {}: {}", label, e);
+        }
+    }
+}
+
+/// Toggle the custom tray menu from the keyboard, anchored at the
+/// bottom-right corner of the primary work area (where the tray lives).
+fn toggle_tray_menu_from_hotkey(app: &tauri::AppHandle) {
+    #[cfg(target_os = "windows")]
+    {
+        // Unlike tray clicks, no `last_auto_close` debounce: the hotkey never
+        // steals focus from the menu, so a press while open is a real toggle.
+        let Some(monitor) = app.primary_monitor().ok().flatten() else {
+            return;
+        };
+        let visible = app
+            .get_webview_window(TRAY_MENU_LABEL)
+            .and_then(|window| window.is_visible().ok())
+            .unwrap_or(false);
+        if visible {
+            close_tray_menu_window(app, false);
+            return;
+        }
+        let area = monitor.work_area();
+        let x = area.position.x as f64 + area.size.width as f64;
+        let y = area.position.y as f64 + area.size.height as f64;
+        show_tray_menu_window(app, x, y);
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = app;
+    }
+}
+
+/// Start the ticker and auto-start the work session on launch, unless it waits
+/// for the day's first activity (settings `auto_start_on_first_activity`).
+/// 启动计时循环并自动开始工作；数据加密未解锁时推迟到解锁成功后调用。
+pub(crate) fn start_timer(timer: &Arc<TimerService>) {
+    timer.clone().start_ticker();
+    if !timer.awaiting_first_activity() {
+        let _ = timer.start_work(PhaseChangeCause::Startup);
+    }
+}
+
+/// Push settings that live outside the timer (tray, hotkeys, windows) to the running app.
+/// 将托盘、快捷键、窗口等非计时器设置应用到运行中的应用。
+pub(crate) fn apply_runtime_settings(app: &tauri::AppHandle, settings: &Settings) {
+    refresh_tray_menu(app, settings.max_suppression_minutes);
+    apply_hotkeys(app, &settings.hotkeys);
+    set_main_always_on_top(app, settings.always_on_top);
+    REMINDER_CLICK_THROUGH.store(settings.reminder_click_through, Ordering::SeqCst);
+    refresh_tray_tooltip(app, &settings.language);
+    accessibility::set_overrides(settings.force_high_contrast, settings.force_reduced_motion);
+    network_policy::set_policy(settings);
+}
+
+/// Show the next break time in the tray tooltip, formatted for the user's language.
+pub(crate) fn refresh_tray_tooltip(app: &tauri::AppHandle, language: &Language) {
+    let Some(tray) = app.tray_by_id(MAIN_TRAY_ID) else {
+        return;
+    };
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let info = state.timer_service.get_info();
+    let title = match &info.active_preset {
+        Some(preset) => format!("RESTY - {}", preset),
+        None => "RESTY".to_string(),
+    };
+    if info.phase != TimerPhase::Break {
+        REMINDER_FALLBACK_ACTIVE.store(false, Ordering::SeqCst);
+    }
+    let tooltip = match info.next_break_time {
+        _ if REMINDER_FALLBACK_ACTIVE.load(Ordering::SeqCst) => {
+            format!("{}\n{}", title, locale_table(language).reminder_failed)
+        }
+        Some(at) => {
+            let remaining = (at - chrono::Utc::now()).num_seconds();
+            format!(
+                "{}\n{} {} ({})",
+                title,
+                locale_table(language).next_break,
+                format_local_time(at, language),
+                format_duration(remaining, language, DurationStyle::Short)
+            )
+        }
+        None => title,
+    };
+    let tooltip = if info.continuous_screen_seconds >= 60 {
+        format!(
+            "{}\n{} {}",
+            tooltip,
+            locale_table(language).screen_time,
+            format_duration(
+                info.continuous_screen_seconds as i64,
+                language,
+                DurationStyle::Short
+            )
+        )
+    } else {
+        tooltip
+    };
+    let tooltip = if info.travel_mode {
+        format!("{}\n{}", tooltip, locale_table(language).travel_mode)
+    } else {
+        tooltip
+    };
+    let in_call = info.interruptions_blocked_by.iter().any(|r| r == "call");
+    let tooltip = if in_call {
+        format!("{}\n{}", tooltip, locale_table(language).call_in_progress)
+    } else {
+        tooltip
+    };
+    if let Err(e) = tray.set_tooltip(Some(&tooltip)) {
+        eprintln!("Failed to update tray tooltip: {}", e);
+    }
+}
+
+/// Refresh the tray tooltip once the language has been read from settings.
+fn schedule_tray_tooltip_refresh(app: &tauri::AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(state) = app.try_state::<AppState>() else {
+            return;
+        };
+        let language = match state.database_service.lock().await.load_settings().await {
+            Ok(settings) => settings.language,
+            Err(_) => Language::EnglishUnitedStates,
+        };
+        refresh_tray_tooltip(&app, &language);
+    });
+}
+
+/// Surface a rejected tray suppression request as a system notification.
+async fn notify_suppression_result(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    result: AppResult<()>,
+) -> Result<(), String> {
+    use tauri_plugin_notification::NotificationExt;
+
+    let Err(e) = result else {
+        return Ok(());
+    };
+    let body = match &e {
+        AppError::SuppressionTooLong(minutes) => {
+            let language = match state.database_service.lock().await.load_settings().await {
+                Ok(settings) => settings.language,
+                Err(_) => Language::EnglishUnitedStates,
+            };
+            format!(
+                "{} {}",
+                locale_table(&language).suppression_too_long,
+                format_duration(*minutes as i64 * 60, &language, DurationStyle::Long)
+            )
+        }
+        other => other.to_string(),
+    };
+    if let Err(err) = app
+        .notification()
+        .builder()
+        .title("RESTY")
+        .body(&body)
+        .show()
+    {
+        eprintln!("Failed to show suppression notification: {}", err);
+    }
+    Err(e.to_string())
+}
+
+/// Main window state recorded before it is put away for a break.
+/// 休息开始前记录的主窗口状态，用于休息结束后原样恢复。
+#[derive(Clone, Copy)]
+pub(crate) struct MainWindowBreakSnapshot {
+    minimized: bool,
+    focused: bool,
+    skip_taskbar: bool,
+}
+
+pub(crate) type MainWindowBreakState = Arc<std::sync::Mutex<Option<MainWindowBreakSnapshot>>>;
+
+/// Last skip_taskbar value applied to the main window (Tauri offers no getter).
+static MAIN_SKIP_TASKBAR: AtomicBool = AtomicBool::new(false);
+
+/// Set skip_taskbar on the main window and remember the value.
+pub(crate) fn set_main_skip_taskbar(window: &tauri::WebviewWindow, skip: bool) {
+    MAIN_SKIP_TASKBAR.store(skip, Ordering::SeqCst);
+    let _ = window.set_skip_taskbar(skip);
+}
+
+/// User preference for pinning the main window on top.
+static MAIN_ALWAYS_ON_TOP: AtomicBool = AtomicBool::new(false);
+
+/// Remember the always-on-top preference and apply it to the main window.
+pub(crate) fn set_main_always_on_top(app: &tauri::AppHandle, enabled: bool) {
+    MAIN_ALWAYS_ON_TOP.store(enabled, Ordering::SeqCst);
+    sync_main_always_on_top(app);
+}
+
+/// Apply the always-on-top preference, lifting it while a break runs so the
+/// main window can never cover the reminder windows.
+/// 休息期间暂时取消主窗口置顶，休息结束后恢复。
+/// A transition that can play the chime, each with its own `sound_events` toggle.
+#[derive(Debug, Clone, Copy)]
+enum ChimeEvent {
+    WorkEnd,
+    BreakStart,
+    BreakEnd,
+}
+
+impl ChimeEvent {
+    fn as_str(self) -> &'static str {
+        match self {
+            ChimeEvent::WorkEnd => "workEnd",
+            ChimeEvent::BreakStart => "breakStart",
+            ChimeEvent::BreakEnd => "breakEnd",
+        }
+    }
+
+    fn enabled(self, settings: &SoundEventSettings) -> bool {
+        match self {
+            ChimeEvent::WorkEnd => settings.work_end,
+            ChimeEvent::BreakStart => settings.break_start,
+            ChimeEvent::BreakEnd => settings.break_end,
+        }
+    }
+}
+
+/// Play the break-start chime in place of rest music that could not be played.
+pub(crate) fn play_break_start_chime(app: &tauri::AppHandle) {
+    let _ = app.emit("play-chime", ChimeEvent::BreakStart.as_str());
+}
+
+/// Chime events for a `previous` → `next` transition, most specific first; at
+/// most one chime is played per transition.
+/// 阶段切换对应的提示音事件，按优先级排列，每次切换最多播放一次。
+fn chime_events(previous: &TimerPhase, next: &TimerPhase) -> Vec<ChimeEvent> {
+    match (previous, next) {
+        (TimerPhase::Work, TimerPhase::Break) => {
+            vec![ChimeEvent::BreakStart, ChimeEvent::WorkEnd]
+        }
+        (TimerPhase::Work, _) => vec![ChimeEvent::WorkEnd],
+        (TimerPhase::Break, TimerPhase::Break) => Vec::new(),
+        (TimerPhase::Break, _) => vec![ChimeEvent::BreakEnd],
+        (_, TimerPhase::Break) => vec![ChimeEvent::BreakStart],
+        _ => Vec::new(),
+    }
+}
+
+fn sync_main_always_on_top(app: &tauri::AppHandle) {
+    let in_break = app
+        .try_state::<AppState>()
+        .map(|state| state.timer_service.get_info().phase == TimerPhase::Break)
+        .unwrap_or(false);
+    let pinned = MAIN_ALWAYS_ON_TOP.load(Ordering::SeqCst) && !in_break;
+    if let Some(window) = windows::main_window(app) {
+        if let Err(e) = window.set_always_on_top(pinned) {
+            eprintln!("Failed to update main window always-on-top: {}", e);
+        }
+    }
+}
 
 fn load_tray_image(bytes: &[u8]) -> Option<Image<'static>> {
     Image::from_bytes(bytes).ok()
 }
 
+/// Solid-color icon generated in memory when no bundled icon can be decoded.
+/// 当内置图标均无法解码时，在内存中生成的纯色图标
+fn fallback_tray_image() -> Image<'static> {
+    let buffer = image::RgbaImage::from_pixel(
+        FALLBACK_TRAY_ICON_SIZE,
+        FALLBACK_TRAY_ICON_SIZE,
+        image::Rgba(FALLBACK_TRAY_ICON_COLOR),
+    );
+    Image::new_owned(
+        buffer.into_raw(),
+        FALLBACK_TRAY_ICON_SIZE,
+        FALLBACK_TRAY_ICON_SIZE,
+    )
+}
+
+/// Create the main tray icon with its menu (a custom menu window on Windows),
+/// themed icon and tooltip.
+fn build_main_tray(app: &tauri::AppHandle, settings: &Settings) -> tauri::Result<TrayIcon> {
+    #[cfg(not(target_os = "windows"))]
+    let tray_builder = {
+        use tauri::tray::TrayIconEvent;
+
+        let menu = build_tray_menu(app, settings.max_suppression_minutes)?;
+        TrayIconBuilder::with_id(MAIN_TRAY_ID)
+            .menu(&menu)
+            .on_menu_event(|app, event| {
+                let app = app.clone();
+                let action = event.id().as_ref().to_string();
+                let cloned_state = app.state::<AppState>().inner().clone();
+
+                tauri::async_runtime::spawn(async move {
+                    let _ =
+                        dispatch_control_action(&action, ControlSource::Tray, app, cloned_state)
+                            .await;
+                });
+            })
+            .on_tray_icon_event(|tray, event| {
+                if let TrayIconEvent::Click {
+                    button: tauri::tray::MouseButton::Left,
+                    button_state: tauri::tray::MouseButtonState::Up,
+                    ..
+                } = event
+                {
+                    if let Some(win) = windows::main_window(tray.app_handle()) {
+                        set_main_skip_taskbar(&win, false);
+                        let _ = win.show();
+                        let _ = win.unminimize();
+                        let _ = win.set_focus();
+                    }
+                }
+            })
+            .tooltip("RESTY")
+    };
+
+    // Build tray icon without menu, use custom window
+    #[cfg(target_os = "windows")]
+    let tray_builder = TrayIconBuilder::with_id(MAIN_TRAY_ID)
+        .show_menu_on_left_click(false)
+        .on_tray_icon_event(|tray, event| {
+            if let tauri::tray::TrayIconEvent::Click {
+                button,
+                button_state,
+                position,
+                ..
+            } = event
+            {
+                // Only handle button release to avoid double triggers
+                if button_state != tauri::tray::MouseButtonState::Up {
+                    return;
+                }
+
+                let app = tray.app_handle();
+                match button {
+                    tauri::tray::MouseButton::Left => {
+                        // Hide tray menu if open
+                        close_tray_menu_window(app, false);
+                        if let Some(win) = windows::main_window(app) {
+                            set_main_skip_taskbar(&win, false);
+                            let _ = win.show();
+                            let _ = win.unminimize();
+                            let _ = win.set_focus();
+                        }
+                    }
+                    tauri::tray::MouseButton::Right => {
+                        open_tray_menu_window(app, position.x, position.y);
+                    }
+                    _ => {}
+                }
+            }
+        })
+        .tooltip("RESTY");
+
+    let tray_icon = tray_builder.icon(initial_tray_image(app)).build(app)?;
+    apply_tray_theme_icon(&tray_icon, resolve_tray_theme(&settings.theme));
+    refresh_tray_tooltip(app, &settings.language);
+    Ok(tray_icon)
+}
+
+/// No tray icon (e.g. GNOME without the AppIndicator extension): remember it so
+/// closing the main window minimizes instead of hiding, make sure the window
+/// can be found on the taskbar, and tell the UI (`tray-unavailable`). With
+/// `retry`, creating the tray is tried once more after
+/// `TRAY_RETRY_DELAY_SECS`, as some status notifier hosts register late.
+/// 托盘不可用时的降级处理：关闭主窗口改为最小化，并通知前端显示提示横幅。
+fn handle_tray_unavailable(app: &tauri::AppHandle, error: &tauri::Error, retry: bool) {
+    eprintln!("[Tray] Tray icon unavailable: {}", error);
+    app.state::<AppState>()
+        .tray_available
+        .store(false, Ordering::SeqCst);
+    if let Some(main) = windows::main_window(app) {
+        set_main_skip_taskbar(&main, false);
+        if !main.is_visible().unwrap_or(false) {
+            let _ = main.show();
+            let _ = main.minimize();
+        }
+    }
+    let _ = app.emit(
+        "tray-unavailable",
+        TrayUnavailable {
+            error: error.to_string(),
+            retrying: retry,
+        },
+    );
+    if !retry {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs(TRAY_RETRY_DELAY_SECS)).await;
+        let settings = {
+            let state = app.state::<AppState>();
+            let db = state.database_service.lock().await;
+            db.load_settings().await.unwrap_or_default()
+        };
+        let handle = app.clone();
+        let _ = app.run_on_main_thread(move || match build_main_tray(&handle, &settings) {
+            Ok(_) => {
+                eprintln!("[Tray] Tray icon created on retry");
+                handle
+                    .state::<AppState>()
+                    .tray_available
+                    .store(true, Ordering::SeqCst);
+                let _ = handle.emit("tray-available", ());
+            }
+            Err(err) => handle_tray_unavailable(&handle, &err, false),
+        });
+    });
+}
+
+fn initial_tray_image(app: &tauri::AppHandle) -> Image<'static> {
+    load_tray_image(TRAY_ICON_LIGHT)
+        .or_else(|| {
+            app.default_window_icon()
+                .map(|icon| icon.clone().to_owned())
+        })
+        .unwrap_or_else(|| {
+            eprintln!("Tray icons failed to decode; using generated fallback icon");
+            fallback_tray_image()
+        })
+}
+
 fn apply_tray_theme_icon(tray: &TrayIcon, theme: Theme) {
     let icon_bytes = match theme {
         Theme::Dark => TRAY_ICON_DARK,
@@ -58,33 +659,104 @@ fn resolve_tray_theme(preference: &SettingsTheme) -> Theme {
     }
 }
 
-/// Shared handler for tray actions used by both native menus and the custom window.
-pub(crate) async fn handle_tray_action(
+/// Returns true when the same tray action was handled within the debounce
+/// window before `now`; otherwise records it as handled at `now`.
+fn is_tray_action_debounced(
+    last_tray_actions: &std::sync::Mutex<HashMap<String, std::time::Instant>>,
+    action: &str,
+    now: std::time::Instant,
+) -> bool {
+    let Ok(mut last_actions) = last_tray_actions.lock() else {
+        return false;
+    };
+    if let Some(last) = last_actions.get(action) {
+        if now.duration_since(*last).as_millis() < TRAY_ACTION_DEBOUNCE_MS {
+            return true;
+        }
+    }
+    last_actions.insert(action.to_string(), now);
+    false
+}
+
+/// Permission group an action belongs to (see `external_control_permissions`).
+fn control_action_group(action: &str) -> ActionGroup {
+    match action {
+        "quit" => ActionGroup::Quit,
+        "settings" => ActionGroup::ReadStatus,
+        _ => ActionGroup::TimerControl,
+    }
+}
+
+/// Single dispatcher for control actions from every entry point. `source` is
+/// checked against `external_control_permissions`; denials are logged and
+/// returned as `ActionNotPermitted`.
+/// 所有入口共用的操作分发：按来源校验权限，拒绝时记录日志并返回错误。
+pub(crate) async fn dispatch_control_action(
     action: &str,
+    source: ControlSource,
     app: tauri::AppHandle,
     state: AppState,
 ) -> Result<(), String> {
+    let group = control_action_group(action);
+    let allowed = match state.database_service.lock().await.load_settings().await {
+        Ok(settings) => settings.allows_control(source, group),
+        Err(_) => source.default_permissions().contains(&group),
+    };
+    if !allowed {
+        eprintln!(
+            "[Control] Denied {} from {:?} (needs {:?})",
+            action, source, group
+        );
+        return Err(
+            AppError::ActionNotPermitted(format!("{} from {:?}", action, source)).to_string(),
+        );
+    }
+
+    if is_tray_action_debounced(&state.last_tray_actions, action, std::time::Instant::now()) {
+        eprintln!(
+            "Ignoring repeated tray action within debounce window: {}",
+            action
+        );
+        return Ok(());
+    }
+
     match action {
         "skip" => {
-            if let Some((session, should_show_reminder)) =
-                state.timer_service.skip().map_err(|e| e.to_string())?
+            if let Some((session, should_show_reminder)) = state
+                .timer_service
+                .skip(false, PhaseChangeCause::UserSkip)
+                .map_err(|e| e.to_string())?
             {
-                let db_guard = state.database_service.lock().await;
-                let _ = db_guard.save_or_update_session(&session).await;
-                drop(db_guard);
+                if let Some(session) = session {
+                    let db_guard = state.database_service.lock().await;
+                    let _ = db_guard.save_or_update_session(&session).await;
+                    drop(db_guard);
+                }
 
                 if should_show_reminder {
                     let _ = app.emit("show-break-reminder", ());
                 }
             }
         }
-        "no_break_1h" => state.timer_service.suppress_breaks_for_hours(1),
-        "no_break_2h" => state.timer_service.suppress_breaks_for_hours(2),
-        "no_break_5h" => state.timer_service.suppress_breaks_for_hours(5),
-        "no_break_tomorrow" => state.timer_service.suppress_breaks_until_tomorrow_morning(),
+        "no_break_1h" => {
+            let result = state.timer_service.suppress_breaks_for_hours(1);
+            notify_suppression_result(&app, &state, result).await?
+        }
+        "no_break_2h" => {
+            let result = state.timer_service.suppress_breaks_for_hours(2);
+            notify_suppression_result(&app, &state, result).await?
+        }
+        "no_break_5h" => {
+            let result = state.timer_service.suppress_breaks_for_hours(5);
+            notify_suppression_result(&app, &state, result).await?
+        }
+        "no_break_tomorrow" => {
+            let result = state.timer_service.suppress_breaks_until_tomorrow_morning();
+            notify_suppression_result(&app, &state, result).await?
+        }
         "settings" => {
-            if let Some(win) = app.get_webview_window("main") {
-                let _ = win.set_skip_taskbar(false);
+            if let Some(win) = windows::main_window(&app) {
+                set_main_skip_taskbar(&win, false);
                 let _ = win.show();
                 let _ = win.set_focus();
                 let _ = win.unminimize();
@@ -92,6 +764,7 @@ pub(crate) async fn handle_tray_action(
             let _ = app.emit("open-settings", ());
         }
         "quit" => {
+            prepare_for_exit(&app).await;
             std::process::exit(0);
         }
         _ => {}
@@ -100,10 +773,76 @@ pub(crate) async fn handle_tray_action(
     Ok(())
 }
 
-/// Show custom tray menu window at the specified position
-#[cfg(target_os = "windows")]
+/// Finish the running session and write it to disk before the app exits (quit,
+/// installing an update), giving up after `EXIT_FLUSH_TIMEOUT_SECS`.
+/// 退出前结束当前会话并写入磁盘，最多等待 `EXIT_FLUSH_TIMEOUT_SECS` 秒。
+pub(crate) async fn prepare_for_exit(app: &tauri::AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let timer = Arc::clone(&state.timer_service);
+    let db = Arc::clone(&state.database_service);
+    let flush = async move {
+        match timer.finish_for_exit() {
+            Ok(Some(session)) => {
+                if let Err(e) = db.lock().await.save_or_update_session(&session).await {
+                    eprintln!("Failed to save the running session before exit: {}", e);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("Failed to stop the timer before exit: {}", e),
+        }
+    };
+    let limit = tokio::time::Duration::from_secs(EXIT_FLUSH_TIMEOUT_SECS);
+    if tokio::time::timeout(limit, flush).await.is_err() {
+        eprintln!(
+            "Saving the running session timed out after {}s; exiting anyway",
+            EXIT_FLUSH_TIMEOUT_SECS
+        );
+    }
+}
+
+/// A tray click this soon after the menu hid itself on focus loss is the same
+/// click that caused the focus loss, not a request to reopen.
+const TRAY_MENU_REOPEN_DEBOUNCE_MS: u128 = 200;
+
+/// Show the custom tray menu near the physical cursor position `x`, `y`, unless
+/// it just closed itself (see `TRAY_MENU_REOPEN_DEBOUNCE_MS`).
+/// 在光标附近显示自定义托盘菜单；菜单刚因失焦隐藏时忽略本次点击。
+pub(crate) fn open_tray_menu_window(app: &tauri::AppHandle, x: f64, y: f64) {
+    let recently_closed = app
+        .state::<AppState>()
+        .last_auto_close
+        .lock()
+        .ok()
+        .and_then(|last| *last)
+        .is_some_and(|time| time.elapsed().as_millis() <= TRAY_MENU_REOPEN_DEBOUNCE_MS);
+    if !recently_closed {
+        show_tray_menu_window(app, x, y);
+    }
+}
+
+/// Hide the custom tray menu. The window is kept (hidden) for the next open;
+/// `auto` marks a close caused by focus loss, which starts the reopen debounce.
+/// 隐藏托盘菜单窗口（不销毁，下次打开直接复用）。
+pub(crate) fn close_tray_menu_window(app: &tauri::AppHandle, auto: bool) {
+    if let Some(window) = app.get_webview_window(TRAY_MENU_LABEL) {
+        let _ = window.hide();
+    }
+    if auto {
+        if let Ok(mut last) = app.state::<AppState>().last_auto_close.lock() {
+            *last = Some(std::time::Instant::now());
+        }
+    }
+}
+
+/// Show custom tray menu window at the specified position, building it on
+/// first use and repositioning the same hidden instance afterwards.
 fn show_tray_menu_window(app: &tauri::AppHandle, x: f64, y: f64) {
-    let window = app.get_webview_window("tray-menu");
+    // Concurrent clicks must not both see "no window" and build two
+    let state = app.state::<AppState>();
+    let _guard = state.tray_menu_lock.lock();
+    let window = app.get_webview_window(TRAY_MENU_LABEL);
 
     // Get screen dimensions and scale factor
     let (screen_width, screen_height, scale_factor) = app
@@ -113,11 +852,7 @@ fn show_tray_menu_window(app: &tauri::AppHandle, x: f64, y: f64) {
         .map(|m| {
             let size = m.size();
             let scale = m.scale_factor();
-            (
-                size.width as f64 / scale,
-                size.height as f64 / scale,
-                scale,
-            )
+            (size.width as f64 / scale, size.height as f64 / scale, scale)
         })
         .unwrap_or((1920.0, 1080.0, 1.0));
 
@@ -151,37 +886,11 @@ fn show_tray_menu_window(app: &tauri::AppHandle, x: f64, y: f64) {
     });
 
     if let Some(w) = window {
-        if w.is_visible().unwrap_or(false) {
-            let _ = w.hide();
-        } else {
-            let _ = w.set_position(position);
-            let _ = w.show();
-            let _ = w.set_focus();
-        }
+        let _ = w.set_position(position);
+        let _ = w.show();
+        let _ = w.set_focus();
     } else {
-        let mut window_builder = WebviewWindowBuilder::new(
-            app,
-            "tray-menu",
-            WebviewUrl::App("index.html#tray-menu".into()),
-        )
-        .title("")
-        .inner_size(TRAY_MENU_WIDTH, TRAY_MENU_HEIGHT)
-        .position(menu_x, menu_y)
-        .resizable(false)
-        .decorations(false)
-        .always_on_top(true)
-        .skip_taskbar(true)
-        .focused(true)
-            .visible(true);
-
-        #[cfg(target_os = "windows")]
-        {
-            window_builder = window_builder.transparent(true);
-        }
-
-        let window = window_builder.build();
-
-        match window {
+        match windows::create_tray_menu_window(app, menu_x, menu_y) {
             Ok(w) => {
                 let _ = w.set_focus();
             }
@@ -193,59 +902,85 @@ fn show_tray_menu_window(app: &tauri::AppHandle, x: f64, y: f64) {
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 /// Build and run the Tauri application.
 pub fn run() {
-    std::panic::set_hook(Box::new(|info| {
-        let msg = match info.payload().downcast_ref::<&str>() {
-            Some(s) => *s,
-            None => match info.payload().downcast_ref::<String>() {
-                Some(s) => &s[..],
-                None => "Box<Any>",
-            },
-        };
-        let location = info.location().map(|l| l.to_string()).unwrap_or_else(|| "unknown".to_string());
-        let log = format!("Panic occurred at {}: {}", location, msg);
-        eprintln!("{}", log);
-        let _ = std::fs::write("panic.log", log);
-    }));
+    panics::install_hook();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| {
+                    if event.state() == tauri_plugin_global_shortcut::ShortcutState::Pressed {
+                        handle_hotkey(app, shortcut);
+                    }
+                })
+                .build(),
+        )
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
-            Some(vec!["--autostart".into()]),
+            Some(vec!["--autostart"]),
         ))
         .on_window_event(move |window, event| {
             match event {
                 tauri::WindowEvent::CloseRequested { api, .. } => {
-                    // Only affect the main window
-                    if window.label() == "main" {
-                        api.prevent_close();
-                        // Hide window and keep app running in tray
-                        let _ = window.hide();
-                        let _ = window.set_skip_taskbar(true);
+                    match windows::CloseRequest::for_label(window.label()) {
+                        windows::CloseRequest::HideToTray => {
+                            api.prevent_close();
+                            let tray_available = window
+                                .app_handle()
+                                .state::<AppState>()
+                                .tray_available
+                                .load(Ordering::SeqCst);
+                            if !tray_available {
+                                // Without a tray a hidden window could not be reopened
+                                let _ = window.minimize();
+                                return;
+                            }
+                            // Hide window and keep app running in tray
+                            let _ = window.hide();
+                            if let Some(main) = windows::main_window(window.app_handle()) {
+                                set_main_skip_taskbar(&main, true);
+                            }
+                        }
+                        windows::CloseRequest::HideTrayMenu => {
+                            api.prevent_close();
+                            close_tray_menu_window(window.app_handle(), false);
+                        }
+                        windows::CloseRequest::SyncBreak => {
+                            if !handle_reminder_close_request(window) {
+                                api.prevent_close();
+                            }
+                        }
+                        windows::CloseRequest::Close => {}
+                    }
+                }
+                tauri::WindowEvent::Destroyed => {
+                    if let Ok(mut closing) = APP_CLOSED_REMINDERS.lock() {
+                        if let Some(set) = closing.as_mut() {
+                            set.remove(window.label());
+                        }
                     }
                 }
                 tauri::WindowEvent::Focused(focused) => {
                     // Hide tray menu when it loses focus
-                    if !focused && window.label() == "tray-menu" {
-                        let _ = window.hide();
-                        // Record close time to prevent immediate reopen on tray click
-                        {
-                            let state = window.state::<AppState>();
-                            let last_auto_close = state.last_auto_close.clone();
-                            if let Ok(mut last) = last_auto_close.lock() {
-                                *last = Some(std::time::Instant::now());
-                            };
-                        }
+                    if !focused && window.label() == TRAY_MENU_LABEL {
+                        close_tray_menu_window(window.app_handle(), true);
+                    }
+                    if !focused {
+                        keep_force_break_reminder_visible(window);
                     }
                 }
+                // Minimizing (Win+D, Win+M) arrives as a resize
+                tauri::WindowEvent::Resized(_) => keep_force_break_reminder_visible(window),
                 _ => {}
             }
         })
         .setup(|app| {
             let app_handle = app.handle().clone();
+            panics::init(&app_handle);
+            error_log::init(&app_handle);
 
             // Initialize database service
             let db_service = Arc::new(tokio::sync::Mutex::new(DatabaseService::new(
@@ -265,22 +1000,30 @@ pub fn run() {
             let db_clone = Arc::clone(&db_service);
             let (initial_settings, timer_service) = tauri::async_runtime::block_on(async move {
                 let db = db_clone.lock().await;
+                let locked = db.is_locked();
                 let settings = db.load_settings().await.unwrap_or_default();
-                let timer = TimerService::new(
-                    app_handle,
-                    Arc::clone(&db_clone),
-                    settings.work_duration,
-                    settings.break_duration,
-                    settings.flow_mode_enabled,
-                    settings.segmented_work_enabled,
-                    settings.work_segments.clone(),
-                );
-
-                // Start the ticker
-                timer.clone().start_ticker();
-
-                // Auto-start work session when app launches
-                let _ = timer.start_work();
+                let timer = TimerService::new(app_handle, Arc::clone(&db_clone));
+                if let Err(e) = timer.apply_settings(&settings) {
+                    eprintln!("Failed to apply timer settings: {}", e);
+                }
+                match db.get_sessions().await {
+                    Ok(sessions) => timer.seed_session_ids(sessions.into_iter().map(|s| s.id)),
+                    Err(e) => eprintln!("Failed to load session IDs: {}", e),
+                }
+                timer.seed_day_start(db.last_day_start().await);
+                timer.restore_screen_time(db.load_screen_continuity());
+
+                timer.clone().start_rest_debt_ticker(Arc::clone(&db_clone));
+                timer
+                    .clone()
+                    .start_hydration_reminders(Arc::clone(&db_clone));
+
+                // Locked data holds the defaults and refuses session writes;
+                // `unlock_data` starts the timer once the passphrase is entered
+                if !locked {
+                    start_timer(&timer);
+                }
+                crate::services::activity::start_first_activity_monitor(timer.clone());
 
                 // Begin monitoring display power state (Windows) to auto pause when screen turns off.
                 crate::services::power::start_display_power_monitor(timer.clone());
@@ -291,16 +1034,47 @@ pub fn run() {
             // Set up application state
             let db_clone_for_state = Arc::clone(&db_service);
             let last_auto_close = Arc::new(std::sync::Mutex::new(None));
+            let tray_menu_lock = Arc::new(std::sync::Mutex::new(()));
+            let last_tray_actions = Arc::new(std::sync::Mutex::new(Default::default()));
+            let reminder_ready_signals = Arc::new(std::sync::Mutex::new(HashMap::new()));
+            let main_window_break_snapshot = Arc::new(std::sync::Mutex::new(None));
+            let prewarmed_reminders = Arc::new(std::sync::Mutex::new(HashSet::new()));
+            let sound_muted = Arc::new(std::sync::atomic::AtomicBool::new(false));
 
             app.manage(AppState {
                 timer_service,
                 database_service: db_clone_for_state,
                 last_auto_close,
+                tray_menu_lock,
+                last_tray_actions,
+                reminder_ready_signals,
+                main_window_break_snapshot,
+                prewarmed_reminders,
+                sound_muted,
+                tray_available: Arc::new(AtomicBool::new(true)),
             });
 
             // Start background updater task on Windows (no-op on other platforms).
             {
                 let state = app.state::<AppState>();
+                notifications::init(
+                    app.handle(),
+                    state.timer_service.clone(),
+                    state.database_service.clone(),
+                );
+                crate::services::connectivity::start_connectivity_monitor(app.handle().clone());
+                start_reminder_watchdog(app.handle().clone());
+                crate::services::activity::start_screen_time_monitor(
+                    app.handle().clone(),
+                    state.timer_service.clone(),
+                    state.database_service.clone(),
+                );
+                crate::services::activity::start_active_time_monitor(state.timer_service.clone());
+                crate::services::calls::start_call_monitor(
+                    app.handle().clone(),
+                    state.timer_service.clone(),
+                    state.database_service.clone(),
+                );
                 updater::start_windows_auto_updater(
                     app.handle().clone(),
                     state.timer_service.clone(),
@@ -316,10 +1090,10 @@ pub fn run() {
 
             // Window is now invisible by default (visible: false in tauri.conf.json)
             // Only show window if NOT silent autostart
-            if let Some(main_window) = app.get_webview_window("main") {
+            if let Some(main_window) = windows::main_window(app.handle()) {
                 if is_silent_autostart {
                     // Keep window hidden and skip taskbar for silent autostart
-                    let _ = main_window.set_skip_taskbar(true);
+                    set_main_skip_taskbar(&main_window, true);
                 } else {
                     // Normal launch: show window after setup is complete
                     let _ = main_window.show();
@@ -346,10 +1120,20 @@ pub fn run() {
                         }
                     };
 
-                    let is_fullscreen = matches!(
-                        settings.reminder_mode,
-                        crate::models::ReminderMode::Fullscreen
-                    );
+                    // Re-checked every break: sessions move between console and RDP
+                    let presentation = system_status::break_presentation(&settings);
+                    let is_fullscreen = match presentation {
+                        BreakPresentation::Window { fullscreen } => fullscreen,
+                        BreakPresentation::Notification => {
+                            let body = locale_table(&settings.language).break_started;
+                            notifications::show(&app, "RESTY", body, &[]);
+                            return;
+                        }
+                        BreakPresentation::Hidden => {
+                            eprintln!("Remote session: break reminder suppressed");
+                            return;
+                        }
+                    };
                     let floating_position = settings.floating_position.clone();
 
                     if let Err(e) =
@@ -357,139 +1141,307 @@ pub fn run() {
                     {
                         eprintln!("Failed to show break reminder: {}", e);
                     }
+
+                    if settings.hide_main_window_during_break {
+                        put_away_main_window_for_break(&app, &settings.main_window_break_action);
+                    }
                 });
             });
 
-            // Create system tray
-            #[cfg(not(target_os = "windows"))]
-            {
-                use tauri::menu::{MenuBuilder, MenuItemBuilder};
-                use tauri::tray::TrayIconEvent;
-
-                let menu = MenuBuilder::new(app)
-                    .item(&MenuItemBuilder::with_id("skip", "Skip current break").build(app)?)
-                    .item(&MenuItemBuilder::with_id("no_break_1h", "No breaks for 1 hour").build(app)?)
-                    .item(&MenuItemBuilder::with_id("no_break_2h", "No breaks for 2 hours").build(app)?)
-                    .item(&MenuItemBuilder::with_id("no_break_5h", "No breaks for 5 hours").build(app)?)
-                    .item(
-                        &MenuItemBuilder::with_id(
-                            "no_break_tomorrow",
-                            "No breaks until tomorrow morning",
-                        )
-                        .build(app)?,
-                    )
-                    .separator()
-                    .item(&MenuItemBuilder::with_id("settings", "Settings").build(app)?)
-                    .item(&MenuItemBuilder::with_id("quit", "Quit").build(app)?)
-                    .build()?;
-                let mut tray_builder = TrayIconBuilder::with_id(MAIN_TRAY_ID)
-                    .menu(&menu)
-                    .on_menu_event(|app, event| {
-                        let app = app.clone();
-                        let action = event.id().as_ref().to_string();
-                        let state = app.state::<AppState>();
-                        let cloned_state = AppState {
-                            timer_service: state.timer_service.clone(),
-                            database_service: state.database_service.clone(),
-                            last_auto_close: state.last_auto_close.clone(),
-                        };
-
-                        tauri::async_runtime::spawn(async move {
-                            let _ = handle_tray_action(&action, app, cloned_state).await;
-                        });
-                    })
-                    .on_tray_icon_event(|tray, event| match event {
-                        TrayIconEvent::Click {
-                            button: tauri::tray::MouseButton::Left,
-                            button_state: tauri::tray::MouseButtonState::Up,
-                            ..
-                        } => {
-                            if let Some(win) = tray.app_handle().get_webview_window("main") {
-                                let _ = win.set_skip_taskbar(false);
-                                let _ = win.show();
-                                let _ = win.unminimize();
-                                let _ = win.set_focus();
-                            }
-                        }
-                        _ => {}
-                    })
-                    .tooltip("RESTY");
-
-                if let Some(icon) =
-                    load_tray_image(TRAY_ICON_LIGHT).or_else(|| app.default_window_icon().cloned())
-                {
-                    tray_builder = tray_builder.icon(icon);
+            // Settings saved mid-break: window-level ones are applied here, the
+            // reminder webviews re-fetch their bootstrap for the rest
+            let app_handle = app.handle().clone();
+            let db_clone = Arc::clone(&db_service);
+            app.listen("settings-changed", move |event| {
+                let changed: SettingsChanged = match serde_json::from_str(event.payload()) {
+                    Ok(changed) => changed,
+                    Err(e) => {
+                        eprintln!("Invalid settings-changed payload: {}", e);
+                        return;
+                    }
+                };
+                let affects_windows = changed
+                    .keys
+                    .iter()
+                    .any(|key| REMINDER_WINDOW_SETTING_KEYS.contains(&key.as_str()));
+                if !affects_windows {
+                    return;
                 }
+                let app = app_handle.clone();
+                let db = db_clone.clone();
+                tauri::async_runtime::spawn(async move {
+                    match db.lock().await.load_settings().await {
+                        Ok(settings) => replace_open_reminder_windows(&app, &settings),
+                        Err(e) => eprintln!("Failed to load settings: {}", e),
+                    }
+                });
+            });
 
-                let tray_icon = tray_builder.build(app)?;
-                let initial_tray_theme = resolve_tray_theme(&initial_settings.theme);
-                apply_tray_theme_icon(&tray_icon, initial_tray_theme);
-            }
-
-            #[cfg(target_os = "windows")]
-            {
-                // Build tray icon without menu, use custom window
-                let mut tray_builder = TrayIconBuilder::with_id(MAIN_TRAY_ID)
-                    .show_menu_on_left_click(false)
-                    .on_tray_icon_event(|tray, event| match event {
-                        tauri::tray::TrayIconEvent::Click {
-                            button,
-                            button_state,
-                            position,
-                            ..
-                        } => {
-                            // Only handle button release to avoid double triggers
-                            if button_state != tauri::tray::MouseButtonState::Up {
-                                return;
-                            }
-
-                            let app = tray.app_handle();
-                            match button {
-                                tauri::tray::MouseButton::Left => {
-                                    // Close tray menu if open
-                                    if let Some(menu_win) = app.get_webview_window("tray-menu") {
-                                        let _ = menu_win.close();
-                                    }
-                                    if let Some(win) = app.get_webview_window("main") {
-                                        let _ = win.set_skip_taskbar(false);
-                                        let _ = win.show();
-                                        let _ = win.unminimize();
-                                        let _ = win.set_focus();
-                                    }
-                                }
-                                tauri::tray::MouseButton::Right => {
-                                    // Check if we just closed the menu (debounce)
-                                    let state = app.state::<AppState>();
-                                    let should_open = if let Ok(last) = state.last_auto_close.lock() {
-                                        if let Some(time) = *last {
-                                            time.elapsed().as_millis() > 200
-                                        } else {
-                                            true
-                                        }
-                                    } else {
-                                        true
-                                    };
-
-                                    if should_open {
-                                        show_tray_menu_window(app, position.x, position.y);
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
-                        _ => {}
-                    })
-                    .tooltip("RESTY");
+            // Open the quick-capture note window after a work session
+            let app_handle = app.handle().clone();
+            let db_clone = Arc::clone(&db_service);
+            app.listen("work-note-prompt", move |event| {
+                let prompt: WorkNotePrompt = match serde_json::from_str(event.payload()) {
+                    Ok(prompt) => prompt,
+                    Err(e) => {
+                        eprintln!("Invalid work-note-prompt payload: {}", e);
+                        return;
+                    }
+                };
+                let app = app_handle.clone();
+                let db = db_clone.clone();
+                tauri::async_runtime::spawn(async move {
+                    let floating_position = match db.lock().await.load_settings().await {
+                        Ok(settings) => settings.floating_position,
+                        Err(_) => FloatingPosition::TopRight,
+                    };
+                    if let Err(e) = show_work_note_window(&app, &prompt, floating_position) {
+                        eprintln!("Failed to show work note prompt: {}", e);
+                    }
+                });
+            });
 
-                if let Some(icon) =
-                    load_tray_image(TRAY_ICON_LIGHT).or_else(|| app.default_window_icon().cloned())
-                {
-                    tray_builder = tray_builder.icon(icon);
+            // Warn shortly before the break ends, with buttons to end or extend it
+            let app_handle = app.handle().clone();
+            let db_clone = Arc::clone(&db_service);
+            app.listen("break-ending", move |event| {
+                let remaining: u32 = serde_json::from_str(event.payload()).unwrap_or_default();
+                let app = app_handle.clone();
+                let db = db_clone.clone();
+                tauri::async_runtime::spawn(async move {
+                    let settings = match db.lock().await.load_settings().await {
+                        Ok(settings) => settings,
+                        Err(e) => {
+                            eprintln!("Failed to load settings: {}", e);
+                            return;
+                        }
+                    };
+                    if !settings.break_end_notification_enabled {
+                        return;
+                    }
+                    let table = locale_table(&settings.language);
+                    let body = format!(
+                        "{} {}",
+                        table.break_end_body,
+                        format_duration(remaining as i64, &settings.language, DurationStyle::Long)
+                    );
+                    let actions = [
+                        notifications::NotificationAction {
+                            id: notifications::ACTION_START_WORK,
+                            label: table.start_work_action,
+                        },
+                        notifications::NotificationAction {
+                            id: notifications::ACTION_EXTEND_BREAK,
+                            label: table.extend_break_action,
+                        },
+                    ];
+                    let actions: &[_] = if settings.notification_actions.break_end {
+                        &actions
+                    } else {
+                        &[]
+                    };
+                    notifications::show(&app, table.break_end_title, &body, actions);
+                });
+            });
+
+            // Ask before a long suppression runs out, with buttons to resume or extend it
+            let app_handle = app.handle().clone();
+            let db_clone = Arc::clone(&db_service);
+            app.listen("suppression-expiring", move |event| {
+                let expiring: SuppressionExpiring = match serde_json::from_str(event.payload()) {
+                    Ok(expiring) => expiring,
+                    Err(e) => {
+                        eprintln!("Invalid suppression-expiring payload: {}", e);
+                        return;
+                    }
+                };
+                let app = app_handle.clone();
+                let db = db_clone.clone();
+                tauri::async_runtime::spawn(async move {
+                    let language = match db.lock().await.load_settings().await {
+                        Ok(settings) => settings.language,
+                        Err(_) => Language::EnglishUnitedStates,
+                    };
+                    let table = locale_table(&language);
+                    let body = format!(
+                        "{} {}",
+                        table.suppression_expiring_body,
+                        format_duration(expiring.remaining_seconds, &language, DurationStyle::Long)
+                    );
+                    let actions = [
+                        notifications::NotificationAction {
+                            id: notifications::ACTION_RESUME_BREAKS,
+                            label: table.resume_breaks_action,
+                        },
+                        notifications::NotificationAction {
+                            id: notifications::ACTION_EXTEND_SUPPRESSION,
+                            label: table.extend_suppression_action,
+                        },
+                    ];
+                    notifications::show(&app, table.suppression_expiring_title, &body, &actions);
+                });
+            });
+
+            // Keep the day's start for analytics and for relaunches later that day
+            let db_clone = Arc::clone(&db_service);
+            app.listen("day-started", move |event| {
+                let day_started: DayStarted = match serde_json::from_str(event.payload()) {
+                    Ok(day_started) => day_started,
+                    Err(e) => {
+                        eprintln!("Invalid day-started payload: {}", e);
+                        return;
+                    }
+                };
+                let db = db_clone.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = db
+                        .lock()
+                        .await
+                        .record_day_start(day_started.started_at)
+                        .await
+                    {
+                        eprintln!("Failed to record day start: {}", e);
+                    }
+                });
+            });
+
+            // Run the user's phase hooks (settings `allow_hooks`)
+            let db_clone = Arc::clone(&db_service);
+            app.listen("phase-change", move |event| {
+                let change: PhaseChange = match serde_json::from_str(event.payload()) {
+                    Ok(change) => change,
+                    Err(e) => {
+                        eprintln!("Invalid phase-change payload: {}", e);
+                        return;
+                    }
+                };
+                let db = db_clone.clone();
+                tauri::async_runtime::spawn(async move {
+                    let Ok(settings) = db.lock().await.load_settings().await else {
+                        return;
+                    };
+                    if !settings.allow_hooks {
+                        return;
+                    }
+                    match change.phase {
+                        TimerPhase::Work => {
+                            hooks::run_hook("on_work_start", &settings.on_work_start_command)
+                        }
+                        TimerPhase::Break => {
+                            hooks::run_hook("on_break_start", &settings.on_break_start_command)
+                        }
+                        TimerPhase::Idle | TimerPhase::Transition => {}
+                    }
+                });
+            });
+
+            // POST phase transitions to the user's webhook (settings `webhook_url`)
+            let db_clone = Arc::clone(&db_service);
+            let app_handle = app.handle().clone();
+            let last_transition_at = Arc::new(std::sync::Mutex::new(None));
+            app.listen("phase-change", move |event| {
+                let Ok(change) = serde_json::from_str::<PhaseChange>(event.payload()) else {
+                    return;
+                };
+                let now = chrono::Utc::now();
+                let previous = last_transition_at.lock().unwrap().replace(now);
+                let payload = webhook::WebhookPayload {
+                    phase: change.phase,
+                    cause: change.cause,
+                    timestamp: now,
+                    planned_seconds: app_handle
+                        .state::<AppState>()
+                        .timer_service
+                        .get_info()
+                        .total_seconds,
+                    actual_seconds: previous.map(|at| (now - at).num_seconds()),
+                };
+                let db = db_clone.clone();
+                // Fire and forget: a slow webhook must never hold up the timer
+                tauri::async_runtime::spawn(async move {
+                    let Ok(settings) = db.lock().await.load_settings().await else {
+                        return;
+                    };
+                    let Some(url) = settings.webhook_url.filter(|url| !url.trim().is_empty())
+                    else {
+                        return;
+                    };
+                    if let Err(e) = webhook::post(&url, &payload).await {
+                        eprintln!("[Webhook] {}", e);
+                    }
+                });
+            });
+
+            // Ask the webview to play the chime for transitions the user left enabled
+            // (settings `play_sound` / `sound_events`)
+            let db_clone = Arc::clone(&db_service);
+            let sound_muted = app.state::<AppState>().sound_muted.clone();
+            let previous_phase = Arc::new(std::sync::Mutex::new(
+                app.state::<AppState>().timer_service.get_info().phase,
+            ));
+            let app_handle = app.handle().clone();
+            app.listen("phase-change", move |event| {
+                let Ok(change) = serde_json::from_str::<PhaseChange>(event.payload()) else {
+                    return;
+                };
+                let previous =
+                    std::mem::replace(&mut *previous_phase.lock().unwrap(), change.phase.clone());
+                // Only natural transitions chime; the user already knows about their own
+                if !matches!(
+                    change.cause,
+                    PhaseChangeCause::AutoCycle
+                        | PhaseChangeCause::Suppression
+                        | PhaseChangeCause::FlowMode
+                ) {
+                    return;
+                }
+                let events = chime_events(&previous, &change.phase);
+                if events.is_empty() {
+                    return;
                 }
+                let db = db_clone.clone();
+                let sound_muted = sound_muted.clone();
+                let app = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    let Ok(settings) = db.lock().await.load_settings().await else {
+                        return;
+                    };
+                    if !settings.play_sound || sound_muted.load(Ordering::SeqCst) {
+                        return;
+                    }
+                    if let Some(event) = events
+                        .into_iter()
+                        .find(|event| event.enabled(&settings.sound_events))
+                    {
+                        let _ = app.emit("play-chime", event.as_str());
+                    }
+                });
+            });
+
+            // Bring the main window back once the break ends or is skipped
+            let app_handle = app.handle().clone();
+            app.listen("phase-change", move |_event| {
+                restore_main_window_after_break(&app_handle);
+                sync_main_always_on_top(&app_handle);
+                schedule_tray_tooltip_refresh(&app_handle);
+                schedule_reminder_prewarm(&app_handle);
+            });
 
-                let tray_icon = tray_builder.build(app)?;
-                let initial_tray_theme = resolve_tray_theme(&initial_settings.theme);
-                apply_tray_theme_icon(&tray_icon, initial_tray_theme);
+            apply_hotkeys(app.handle(), &initial_settings.hotkeys);
+            set_main_always_on_top(app.handle(), initial_settings.always_on_top);
+            REMINDER_CLICK_THROUGH.store(initial_settings.reminder_click_through, Ordering::SeqCst);
+            accessibility::set_overrides(
+                initial_settings.force_high_contrast,
+                initial_settings.force_reduced_motion,
+            );
+            network_policy::set_policy(&initial_settings);
+
+            // Load the reminder webview in the background before the first break
+            schedule_reminder_prewarm(app.handle());
+
+            // Create system tray; some Linux desktops have no tray host
+            if let Err(err) = build_main_tray(app.handle(), &initial_settings) {
+                handle_tray_unavailable(app.handle(), &err, true);
             }
 
             Ok(())
@@ -503,27 +1455,91 @@ pub fn run() {
             commands::resume_timer,
             commands::skip_phase,
             commands::extend_phase,
+            commands::extend_suppression,
+            commands::resume_breaks,
             commands::get_timer_info,
+            commands::simulate_schedule,
+            commands::export_schedule_ics,
             commands::get_analytics,
+            commands::get_duration_histogram,
             commands::get_sessions_bounds,
+            commands::list_sessions,
+            commands::get_lifetime_stats,
+            commands::rebuild_lifetime_stats,
+            commands::rate_session,
+            commands::dismiss_anomaly,
+            commands::get_rating_stats,
+            commands::get_focus_score,
+            commands::get_goal_eta,
+            commands::get_hydration_progress,
+            commands::is_settings_normalized,
+            commands::increment_hydration,
+            commands::update_session_note,
             commands::clear_analytics_data,
+            commands::reload_sessions,
             commands::get_achievements,
+            commands::preview_import,
             commands::import_config,
             commands::export_config,
+            commands::export_config_qr,
+            commands::import_config_from_qr,
             commands::export_app_data_to_file,
+            commands::estimate_data_export,
+            commands::export_all_data,
+            commands::get_encryption_status,
+            commands::unlock_data,
+            commands::enable_data_encryption,
+            commands::disable_data_encryption,
             commands::import_app_data_from_file,
             commands::get_monitors,
             commands::get_system_status,
             commands::open_reminder_window,
             commands::show_reminder_window,
+            commands::reminder_ready,
+            commands::reminder_heartbeat,
             commands::close_reminder_window,
+            commands::acknowledge_break,
+            commands::prewarm_reminder,
+            commands::get_tray_suppression_actions,
+            commands::open_tray_menu,
+            commands::close_tray_menu,
+            commands::set_main_window_always_on_top,
+            commands::get_accessibility_prefs,
+            commands::get_app_info,
+            commands::get_storage_info,
+            commands::set_sound_muted,
+            commands::set_travel_mode,
+            commands::get_audio_status,
+            commands::test_reminder_output,
+            // QA/e2e only: shifts the running phase back in time and ticks once.
+            // Needs a `--features dev-tools` build and the `devMode` setting.
+            #[cfg(feature = "dev-tools")]
+            commands::debug_advance_timer,
+            commands::set_timer_phase,
             commands::show_main_window,
             commands::tray_menu_action,
             commands::get_rest_music_files,
+            commands::pick_rest_music_track,
             commands::check_for_updates,
             commands::install_update,
+            commands::download_update,
+            commands::install_downloaded_update,
             commands::fetch_tip_quote,
+            commands::test_quote_source,
+            commands::test_webhook,
+            commands::get_break_content,
+            commands::get_break_exercise,
             commands::load_translation,
+            commands::get_bootstrap,
+            commands::get_last_reminder_failure,
+            commands::get_power_monitor_health,
+            commands::get_ticker_health,
+            commands::get_timer_accuracy,
+            commands::save_preset,
+            commands::delete_preset,
+            commands::apply_preset,
+            commands::export_time_entries,
+            commands::get_active_preset,
             commands::send_pre_break_toast,
             update_tray_icon_theme,
         ])
@@ -548,7 +1564,7 @@ fn update_tray_icon_theme(app: tauri::AppHandle, theme: String) -> Result<(), St
     Ok(())
 }
 
-fn resolve_window_size_for_monitor(
+pub(crate) fn resolve_window_size_for_monitor(
     window: &tauri::WebviewWindow,
     monitor: &tauri::Monitor,
 ) -> tauri::PhysicalSize<u32> {
@@ -568,7 +1584,7 @@ fn resolve_window_size_for_monitor(
     }
 }
 
-fn resolve_floating_position(
+pub(crate) fn resolve_floating_position(
     origin: tauri::PhysicalPosition<i32>,
     screen: tauri::PhysicalSize<u32>,
     window_size: tauri::PhysicalSize<u32>,
@@ -602,28 +1618,765 @@ fn resolve_floating_position(
     }
 }
 
+/// Open (or reuse) the small undecorated window that collects a work-session note.
+/// A reused window picks up the new session from the `work-note-prompt` event.
+fn show_work_note_window(
+    app: &tauri::AppHandle,
+    prompt: &WorkNotePrompt,
+    floating_position: FloatingPosition,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let window = match app.get_webview_window(windows::WORK_NOTE_LABEL) {
+        Some(window) => window,
+        None => windows::create_work_note_window(app, &prompt.session_id)?,
+    };
+
+    windows::place_floating_window(&window, floating_position)?;
+    window.show()?;
+    Ok(())
+}
+
+/// Open a reminder preview for (possibly unsaved) settings and close it again
+/// after `REMINDER_PREVIEW_SECONDS`.
+pub(crate) fn show_reminder_preview_window(
+    app: &tauri::AppHandle,
+    settings: &Settings,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(existing) = app.get_webview_window(REMINDER_PREVIEW_LABEL) {
+        let _ = existing.close();
+    }
+
+    let is_fullscreen = matches!(
+        settings.reminder_mode,
+        crate::models::ReminderMode::Fullscreen
+    );
+    let window = windows::create_reminder_window(
+        app,
+        WindowKind::ReminderPreview,
+        None,
+        ReminderWindowOptions {
+            fullscreen: is_fullscreen,
+            floating: !is_fullscreen,
+            focused: true,
+        },
+    )?;
+
+    if !is_fullscreen {
+        windows::place_floating_window(&window, settings.floating_position.clone())?;
+    }
+    window.show()?;
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_secs(REMINDER_PREVIEW_SECONDS)).await;
+        if let Some(window) = app.get_webview_window(REMINDER_PREVIEW_LABEL) {
+            let _ = window.close();
+        }
+    });
+    Ok(())
+}
+
+/// Wait for a hidden reminder window to report ready (or time out), then show it
+/// and emit `reminder-visible` with the actual display timestamp.
+fn schedule_reminder_reveal(app: &tauri::AppHandle, label: &str) {
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    if let Some(state) = app.try_state::<AppState>() {
+        if let Ok(mut signals) = state.reminder_ready_signals.lock() {
+            signals.insert(label.to_string(), sender);
+        }
+    }
+
+    let app = app.clone();
+    let label = label.to_string();
+    tauri::async_runtime::spawn(async move {
+        let timeout = tokio::time::Duration::from_millis(REMINDER_READY_TIMEOUT_MS);
+        let forced = !matches!(tokio::time::timeout(timeout, receiver).await, Ok(Ok(())));
+        if forced {
+            if let Some(state) = app.try_state::<AppState>() {
+                if let Ok(mut signals) = state.reminder_ready_signals.lock() {
+                    signals.remove(&label);
+                }
+            }
+            eprintln!(
+                "Reminder window {} did not report ready within {} ms, showing anyway",
+                label, REMINDER_READY_TIMEOUT_MS
+            );
+        }
+
+        let Some(window) = app.get_webview_window(&label) else {
+            return;
+        };
+        reveal_reminder_window(&window);
+        let _ = app.emit(
+            "reminder-visible",
+            ReminderVisible {
+                label,
+                displayed_at: chrono::Utc::now(),
+                forced,
+            },
+        );
+    });
+}
+
+/// Hide or minimize the main window for the running break, recording its prior state.
+/// 休息开始时隐藏或最小化主窗口，并记录之前的状态。
+fn put_away_main_window_for_break(app: &tauri::AppHandle, action: &MainWindowBreakAction) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let Ok(mut snapshot) = state.main_window_break_snapshot.lock() else {
+        return;
+    };
+    if snapshot.is_some() {
+        return;
+    }
+    // The break may already have been skipped during the reminder delay; checking
+    // under the snapshot lock keeps this ordered with the restore listener.
+    if state.timer_service.get_info().phase != TimerPhase::Break {
+        return;
+    }
+    let Some(window) = windows::main_window(app) else {
+        return;
+    };
+    if !window.is_visible().unwrap_or(false) || window.is_minimized().unwrap_or(false) {
+        return;
+    }
+
+    let focused = window.is_focused().unwrap_or(false);
+    let minimized = matches!(action, MainWindowBreakAction::Minimize);
+    let result = if minimized {
+        window.minimize()
+    } else {
+        window.hide()
+    };
+    if let Err(e) = result {
+        eprintln!("Failed to put away main window for break: {}", e);
+        return;
+    }
+    *snapshot = Some(MainWindowBreakSnapshot {
+        minimized,
+        focused,
+        skip_taskbar: MAIN_SKIP_TASKBAR.load(Ordering::SeqCst),
+    });
+}
+
+/// Restore the main window put away for a break once the break is over.
+/// 休息结束或被跳过后，按记录的状态恢复主窗口。
+fn restore_main_window_after_break(app: &tauri::AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let Ok(mut snapshot) = state.main_window_break_snapshot.lock() else {
+        return;
+    };
+    // The post-break countdown still shows the reminder
+    if matches!(
+        state.timer_service.get_info().phase,
+        TimerPhase::Break | TimerPhase::Transition
+    ) {
+        return;
+    }
+    let Some(previous) = snapshot.take() else {
+        return;
+    };
+    let Some(window) = windows::main_window(app) else {
+        return;
+    };
+
+    set_main_skip_taskbar(&window, previous.skip_taskbar);
+    if previous.minimized {
+        let _ = window.unminimize();
+    } else {
+        let _ = window.show();
+    }
+    if previous.focused {
+        let _ = window.set_focus();
+    }
+}
+
+/// Pre-warm reminder windows after a short delay unless a break is running.
+fn schedule_reminder_prewarm(app: &tauri::AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_millis(
+            REMINDER_PREWARM_DELAY_MS,
+        ))
+        .await;
+        let Some(state) = app.try_state::<AppState>() else {
+            return;
+        };
+        if state.timer_service.get_info().phase == TimerPhase::Break {
+            return;
+        }
+        if let Err(e) = prewarm_reminder_windows(&app) {
+            eprintln!("Failed to pre-warm reminder windows: {}", e);
+        }
+    });
+}
+
+/// Create hidden, unfocused reminder windows (one per monitor) so the next break
+/// shows instantly. Placement is applied when the break actually starts.
+/// 预先创建隐藏且不抢焦点的提醒窗口（每个显示器一个），休息开始时再定位并显示。
+pub(crate) fn prewarm_reminder_windows(
+    app: &tauri::AppHandle,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(state) = app.try_state::<AppState>() else {
+        return Ok(());
+    };
+    if !windows::reminder_windows(app).is_empty() {
+        return Ok(());
+    }
+
+    let monitor_count = app.available_monitors().unwrap_or_default().len();
+    let monitor_indices: Vec<Option<usize>> = if monitor_count == 0 {
+        vec![None]
+    } else {
+        (0..monitor_count).map(Some).collect()
+    };
+
+    for monitor in monitor_indices {
+        let label = WindowKind::ReminderPrewarm.label(monitor);
+        // Register first so the page's ready call keeps the window hidden.
+        if let Ok(mut prewarmed) = state.prewarmed_reminders.lock() {
+            prewarmed.insert(label.clone());
+        }
+        let result = windows::create_reminder_window(
+            app,
+            WindowKind::ReminderPrewarm,
+            monitor,
+            ReminderWindowOptions {
+                fullscreen: false,
+                floating: false,
+                focused: false,
+            },
+        );
+        if let Err(e) = result {
+            if let Ok(mut prewarmed) = state.prewarmed_reminders.lock() {
+                prewarmed.remove(&label);
+            }
+            return Err(e.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Outcome of trying to use pre-warmed reminder windows for a break.
+enum PrewarmReveal {
+    Shown,
+    /// Windows no longer matched the monitor layout and are being closed.
+    Discarded(Vec<String>),
+    Unavailable,
+}
+
+/// Place pre-warmed reminder windows for the current settings and show them.
+fn reveal_prewarmed_reminders(
+    app: &tauri::AppHandle,
+    is_fullscreen: bool,
+    floating_position: &FloatingPosition,
+) -> PrewarmReveal {
+    let Some(state) = app.try_state::<AppState>() else {
+        return PrewarmReveal::Unavailable;
+    };
+    let labels: Vec<String> = match state.prewarmed_reminders.lock() {
+        Ok(mut prewarmed) => prewarmed.drain().collect(),
+        Err(_) => return PrewarmReveal::Unavailable,
+    };
+    let windows: Vec<_> = labels
+        .iter()
+        .filter_map(|label| app.get_webview_window(label))
+        .collect();
+    if windows.is_empty() {
+        return PrewarmReveal::Unavailable;
+    }
+
+    let monitors = app.available_monitors().unwrap_or_default();
+    if windows.len() != monitors.len().max(1) {
+        for window in windows {
+            close_reminder_from_app(&window);
+        }
+        return PrewarmReveal::Discarded(labels);
+    }
+
+    for window in windows {
+        place_reminder_window(&window, &monitors, is_fullscreen, floating_position);
+        reveal_reminder_window(&window);
+        let _ = app.emit(
+            "reminder-visible",
+            ReminderVisible {
+                label: window.label().to_string(),
+                displayed_at: chrono::Utc::now(),
+                forced: false,
+            },
+        );
+    }
+
+    PrewarmReveal::Shown
+}
+
+/// Size and position a reminder window for the reminder mode on the monitor its
+/// label's index names (falling back to the one it is on).
+pub(crate) fn place_reminder_window(
+    window: &tauri::WebviewWindow,
+    monitors: &[tauri::Monitor],
+    is_fullscreen: bool,
+    floating_position: &FloatingPosition,
+) {
+    let monitor = window
+        .label()
+        .rsplit('-')
+        .next()
+        .and_then(|idx| idx.parse::<usize>().ok())
+        .and_then(|idx| monitors.get(idx).cloned())
+        .or_else(|| window.current_monitor().ok().flatten());
+
+    if is_fullscreen {
+        if let Some(monitor) = &monitor {
+            let _ = window.set_position(tauri::Position::Physical(*monitor.position()));
+        }
+        let _ = window.set_fullscreen(true);
+    } else {
+        let _ = window.set_fullscreen(false);
+        let _ = window.set_size(tauri::Size::Logical(tauri::LogicalSize::new(
+            FLOATING_WINDOW_WIDTH,
+            FLOATING_WINDOW_HEIGHT,
+        )));
+        if let Some(monitor) = &monitor {
+            let window_size = resolve_window_size_for_monitor(window, monitor);
+            let position = resolve_floating_position(
+                *monitor.position(),
+                *monitor.size(),
+                window_size,
+                floating_position.clone(),
+            );
+            let _ = window.set_position(tauri::Position::Physical(position));
+        }
+    }
+}
+
+/// Re-place the visible reminder windows after the reminder mode or floating
+/// position changed during a break. Hidden pre-warmed windows are placed when
+/// they are revealed.
+/// 休息期间修改提醒模式或浮窗位置时，立即调整已显示的提醒窗口。
+fn replace_open_reminder_windows(app: &tauri::AppHandle, settings: &Settings) {
+    let BreakPresentation::Window {
+        fullscreen: is_fullscreen,
+    } = system_status::break_presentation(settings)
+    else {
+        return;
+    };
+    let monitors = app.available_monitors().unwrap_or_default();
+    for window in windows::reminder_windows(app) {
+        if window.is_visible().unwrap_or(false) {
+            place_reminder_window(
+                &window,
+                &monitors,
+                is_fullscreen,
+                &settings.floating_position,
+            );
+        }
+    }
+}
+
+/// Most recent reminder window creation failure, kept for diagnostics.
+static LAST_REMINDER_FAILURE: std::sync::Mutex<Option<ReminderFailure>> =
+    std::sync::Mutex::new(None);
+
+/// Set while the current break runs without any reminder window; the tray
+/// tooltip says so until the break ends.
+static REMINDER_FALLBACK_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Last reminder window creation failure, if any happened since launch.
+pub(crate) fn last_reminder_failure() -> Option<ReminderFailure> {
+    LAST_REMINDER_FAILURE
+        .lock()
+        .ok()
+        .and_then(|failure| failure.clone())
+}
+
+/// Build a reminder window, retrying once after `REMINDER_RETRY_DELAY_MS`.
+/// WebView2 occasionally fails to create a window on some Windows machines.
+fn build_reminder_window_with_retry(
+    label: &str,
+    build: impl Fn() -> tauri::Result<tauri::WebviewWindow>,
+) -> tauri::Result<tauri::WebviewWindow> {
+    match build() {
+        Ok(window) => Ok(window),
+        Err(e) => {
+            eprintln!(
+                "Failed to create reminder window {}: {}; retrying",
+                label, e
+            );
+            std::thread::sleep(std::time::Duration::from_millis(REMINDER_RETRY_DELAY_MS));
+            build()
+        }
+    }
+}
+
+/// Record a reminder window that failed twice. With `fallback`, no reminder
+/// window is showing at all, so tell the user through a notification and the
+/// tray tooltip that the break has started.
+/// 记录提醒窗口创建失败；若所有窗口都失败，则用系统通知和托盘提示告知休息已开始。
+fn report_reminder_failure(
+    app: &tauri::AppHandle,
+    label: &str,
+    error: &dyn std::fmt::Display,
+    fallback: bool,
+) {
+    let failure = ReminderFailure {
+        label: label.to_string(),
+        error: error.to_string(),
+        failed_at: chrono::Utc::now(),
+        fallback,
+    };
+    eprintln!(
+        "Reminder window {} could not be created (fallback={}): {}",
+        label, fallback, error
+    );
+    if let Ok(mut last) = LAST_REMINDER_FAILURE.lock() {
+        *last = Some(failure.clone());
+    }
+    let _ = app.emit("reminder-failed", failure);
+    if !fallback {
+        return;
+    }
+
+    REMINDER_FALLBACK_ACTIVE.store(true, Ordering::SeqCst);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        use tauri_plugin_notification::NotificationExt;
+
+        let Some(state) = app.try_state::<AppState>() else {
+            return;
+        };
+        let language = match state.database_service.lock().await.load_settings().await {
+            Ok(settings) => settings.language,
+            Err(_) => Language::EnglishUnitedStates,
+        };
+        if let Err(e) = app
+            .notification()
+            .builder()
+            .title("RESTY")
+            .body(locale_table(&language).reminder_failed)
+            .show()
+        {
+            eprintln!("Failed to show reminder fallback notification: {}", e);
+        }
+        refresh_tray_tooltip(&app, &language);
+    });
+}
+
+/// Heartbeats of the reminder windows shown for the current break.
+#[derive(Default)]
+struct ReminderWatchdog {
+    /// Last heartbeat per window label; a window is first seen when it shows
+    last_seen: HashMap<String, std::time::Instant>,
+    /// Windows already recreated once during this break
+    recreated: HashSet<String>,
+}
+
+fn reminder_watchdog() -> &'static std::sync::Mutex<ReminderWatchdog> {
+    static WATCHDOG: std::sync::OnceLock<std::sync::Mutex<ReminderWatchdog>> =
+        std::sync::OnceLock::new();
+    WATCHDOG.get_or_init(Default::default)
+}
+
+/// Note that the page of reminder window `label` is alive (`reminder_heartbeat`).
+pub(crate) fn record_reminder_heartbeat(label: &str) {
+    if !windows::is_reminder_label(label) {
+        return;
+    }
+    if let Ok(mut watchdog) = reminder_watchdog().lock() {
+        watchdog
+            .last_seen
+            .insert(label.to_string(), std::time::Instant::now());
+    }
+}
+
+/// During breaks, recreate a visible reminder window whose page stopped sending
+/// heartbeats; a window that hangs again is closed and the break is announced
+/// through the notification fallback instead.
+/// 休息期间监视提醒窗口心跳：首次无响应时重建窗口，再次无响应则关闭并改用通知。
+fn start_reminder_watchdog(app: tauri::AppHandle) {
+    panics::spawn_guarded("reminderWatchdog", async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+            REMINDER_WATCHDOG_INTERVAL_SECS,
+        ));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            let Some(state) = app.try_state::<AppState>() else {
+                continue;
+            };
+            if state.timer_service.get_info().phase != TimerPhase::Break {
+                if let Ok(mut watchdog) = reminder_watchdog().lock() {
+                    *watchdog = ReminderWatchdog::default();
+                }
+                continue;
+            }
+            for label in hung_reminder_windows(&app) {
+                recover_reminder_window(&app, &label).await;
+            }
+        }
+    });
+}
+
+/// Labels of the visible reminder windows silent for the heartbeat timeout.
+fn hung_reminder_windows(app: &tauri::AppHandle) -> Vec<String> {
+    let now = std::time::Instant::now();
+    let timeout = std::time::Duration::from_secs(REMINDER_HEARTBEAT_TIMEOUT_SECS);
+    let Ok(mut watchdog) = reminder_watchdog().lock() else {
+        return Vec::new();
+    };
+    let mut hung = Vec::new();
+    for window in windows::reminder_windows(app) {
+        if !window.is_visible().unwrap_or(false) {
+            continue;
+        }
+        let label = window.label().to_string();
+        let last_seen = *watchdog.last_seen.entry(label.clone()).or_insert(now);
+        if now.duration_since(last_seen) >= timeout {
+            hung.push(label);
+        }
+    }
+    hung
+}
+
+/// Replace the hung reminder window `label` once; the second time it hangs,
+/// close it for good and fall back to a notification.
+async fn recover_reminder_window(app: &tauri::AppHandle, label: &str) {
+    let first_hang = match reminder_watchdog().lock() {
+        Ok(mut watchdog) => {
+            watchdog.last_seen.remove(label);
+            watchdog.recreated.insert(label.to_string())
+        }
+        Err(_) => false,
+    };
+    if let Some(window) = app.get_webview_window(label) {
+        let _ = window.destroy();
+    }
+    let none_visible = || {
+        windows::reminder_windows(app)
+            .iter()
+            .all(|window| !window.is_visible().unwrap_or(false))
+    };
+    if !first_hang {
+        report_reminder_failure(
+            app,
+            label,
+            &"reminder page stopped responding again",
+            none_visible(),
+        );
+        return;
+    }
+
+    eprintln!(
+        "Reminder window {} sent no heartbeat for {}s; recreating it",
+        label, REMINDER_HEARTBEAT_TIMEOUT_SECS
+    );
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let settings = state
+        .database_service
+        .lock()
+        .await
+        .load_settings()
+        .await
+        .unwrap_or_default();
+    let presentation = system_status::break_presentation(&settings);
+    let BreakPresentation::Window { fullscreen } = presentation else {
+        return;
+    };
+    let monitor = label
+        .rsplit('-')
+        .next()
+        .and_then(|idx| idx.parse::<usize>().ok());
+    let built = build_reminder_window_with_retry(label, || {
+        windows::create_reminder_window(
+            app,
+            WindowKind::Reminder,
+            monitor,
+            ReminderWindowOptions {
+                fullscreen: false,
+                floating: !fullscreen,
+                focused: true,
+            },
+        )
+    });
+    match built {
+        Ok(window) => {
+            let monitors = app.available_monitors().unwrap_or_default();
+            place_reminder_window(&window, &monitors, fullscreen, &settings.floating_position);
+            schedule_reminder_reveal(app, label);
+        }
+        Err(e) => report_reminder_failure(app, label, &e, none_visible()),
+    }
+}
+
+/// Reminder windows the app itself is closing; their close requests are not
+/// reconciled with the timer.
+static APP_CLOSED_REMINDERS: std::sync::Mutex<Option<HashSet<String>>> =
+    std::sync::Mutex::new(None);
+
+/// Close a reminder window on the app's behalf (break over, skipped, discarded).
+pub(crate) fn close_reminder_from_app(window: &tauri::WebviewWindow) {
+    if let Ok(mut closing) = APP_CLOSED_REMINDERS.lock() {
+        closing
+            .get_or_insert_with(HashSet::new)
+            .insert(window.label().to_string());
+    }
+    let _ = window.close();
+}
+
+/// Bring a reminder window back when it was minimized (e.g. by an OS shortcut)
+/// while a force break still forbids dismissing it. Windows still hidden before
+/// their reveal are left alone, and so is mere loss of focus, so reminders on
+/// several monitors don't fight over it.
+/// 强制休息期间提醒窗口被最小化（如系统快捷键）时重新显示。
+fn keep_force_break_reminder_visible(window: &tauri::Window) {
+    if WindowKind::from_label(window.label()) != Some(WindowKind::Reminder) {
+        return;
+    }
+    let Some(state) = window.app_handle().try_state::<AppState>() else {
+        return;
+    };
+    if !state.timer_service.is_break_dismissal_locked() {
+        return;
+    }
+    if !window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    eprintln!(
+        "Reminder window {} was put away during a force break; showing it again",
+        window.label()
+    );
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+/// A reminder window is about to close. When the OS or the user closed it
+/// (Alt+F4, the taskbar) while a break is still running, bring the timer in
+/// line: strict mode keeps the reminder up, otherwise the break ends as if
+/// skipped. Returns whether the window may close.
+/// 提醒窗口被系统或用户（Alt+F4、任务栏）关闭时同步计时状态：强制休息时保持窗口，否则结束本次休息。
+fn handle_reminder_close_request(window: &tauri::Window) -> bool {
+    let label = window.label().to_string();
+    let closed_by_app = APP_CLOSED_REMINDERS
+        .lock()
+        .map(|mut closing| closing.as_mut().is_some_and(|set| set.remove(&label)))
+        .unwrap_or(false);
+    if closed_by_app {
+        return true;
+    }
+    let app = window.app_handle();
+    let Some(state) = app.try_state::<AppState>() else {
+        return true;
+    };
+    let prewarmed = state
+        .prewarmed_reminders
+        .lock()
+        .map(|labels| labels.contains(&label))
+        .unwrap_or(false);
+    if prewarmed || state.timer_service.get_info().phase != TimerPhase::Break {
+        return true;
+    }
+    if state.timer_service.is_break_dismissal_locked() {
+        eprintln!(
+            "Reminder window {} closed during a strict break; keeping it open",
+            label
+        );
+        let _ = window.show();
+        let _ = window.set_focus();
+        return false;
+    }
+
+    eprintln!(
+        "Reminder window {} closed outside the app; ending the break",
+        label
+    );
+    if let Ok(mut watchdog) = reminder_watchdog().lock() {
+        watchdog.last_seen.remove(&label);
+    }
+    REMINDER_FALLBACK_ACTIVE.store(false, Ordering::SeqCst);
+    match state
+        .timer_service
+        .skip(true, PhaseChangeCause::ReminderClosed)
+    {
+        Ok(Some((Some(session), _))) => {
+            let db = state.database_service.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = db.lock().await.save_or_update_session(&session).await {
+                    eprintln!("Failed to save break ended by closing its reminder: {}", e);
+                }
+            });
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("Failed to end break after its reminder closed: {}", e),
+    }
+    true
+}
+
+/// Whether reminder windows let clicks pass through (`reminder_click_through`).
+static REMINDER_CLICK_THROUGH: AtomicBool = AtomicBool::new(false);
+
+/// Show a reminder window, making it click-through when enabled. On Windows
+/// ignoring cursor events sets `WS_EX_TRANSPARENT | WS_EX_LAYERED`, so clicks
+/// reach the app beneath; such a window is not focused either.
+/// 显示提醒窗口；开启鼠标穿透时不抢占焦点，点击直接作用于下方应用。
+fn reveal_reminder_window(window: &tauri::WebviewWindow) {
+    let click_through = REMINDER_CLICK_THROUGH.load(Ordering::SeqCst);
+    if let Err(e) = window.set_ignore_cursor_events(click_through) {
+        eprintln!("Failed to update reminder click-through: {}", e);
+    }
+    let _ = window.show();
+    if !click_through {
+        let _ = window.set_focus();
+    }
+}
+
+/// Deliver the ready signal for a reminder window; false when none was pending.
+pub(crate) fn signal_reminder_ready(app: &tauri::AppHandle, label: &str) -> bool {
+    let Some(state) = app.try_state::<AppState>() else {
+        return false;
+    };
+    let sender = match state.reminder_ready_signals.lock() {
+        Ok(mut signals) => signals.remove(label),
+        Err(_) => None,
+    };
+    match sender {
+        Some(sender) => sender.send(()).is_ok(),
+        None => false,
+    }
+}
+
 /// Show the break reminder window.
+///
+/// Windows are created hidden; each one is revealed by
+/// `schedule_reminder_reveal` once its page calls `reminder_ready`.
+/// A failed window build is retried once; when no window could be created at
+/// all, the failure is reported and a notification is shown instead.
 pub fn show_break_reminder_window(
     app: &tauri::AppHandle,
     is_fullscreen: bool,
     floating_position: FloatingPosition,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    // Pre-warmed windows are already loaded; place and reveal them directly
+    let discarded = match reveal_prewarmed_reminders(app, is_fullscreen, &floating_position) {
+        PrewarmReveal::Shown => return Ok(()),
+        PrewarmReveal::Discarded(labels) => labels,
+        PrewarmReveal::Unavailable => Vec::new(),
+    };
+
     // If any reminder windows already exist, bring them to front
-    let existing: Vec<_> = app
-        .webview_windows()
-        .iter()
-        .filter_map(|(label, w)| {
-            if label.starts_with("break-reminder") {
-                Some(w.clone())
-            } else {
-                None
-            }
-        })
+    let existing: Vec<_> = windows::reminder_windows(app)
+        .into_iter()
+        // Discarded pre-warmed windows may still be closing
+        .filter(|w| !discarded.iter().any(|label| label == w.label()))
         .collect();
     if !existing.is_empty() {
         for w in existing {
-            let _ = w.show();
-            let _ = w.set_focus();
+            reveal_reminder_window(&w);
         }
         return Ok(());
     }
@@ -632,67 +2385,61 @@ pub fn show_break_reminder_window(
     let monitors = app.available_monitors().unwrap_or_default();
     if monitors.is_empty() {
         // Fallback to single-window behavior (current monitor)
-        if is_fullscreen {
-            let _window = WebviewWindowBuilder::new(
+        let label = WindowKind::Reminder.label(None);
+        let built = build_reminder_window_with_retry(&label, || {
+            windows::create_reminder_window(
                 app,
-                "break-reminder",
-                WebviewUrl::App("index.html#reminder".into()),
+                WindowKind::Reminder,
+                None,
+                ReminderWindowOptions {
+                    fullscreen: is_fullscreen,
+                    floating: !is_fullscreen,
+                    focused: true,
+                },
             )
-            .title("Break Time - RESTY")
-            .visible(false)
-            .fullscreen(true)
-            .resizable(false)
-            .decorations(false)
-            .always_on_top(true)
-            .skip_taskbar(true)
-            .build()?;
-        } else {
-            let window = WebviewWindowBuilder::new(
-                app,
-                "break-reminder",
-                WebviewUrl::App("index.html#reminder".into()),
-            )
-            .title("Break Time - RESTY")
-            .visible(false)
-            .inner_size(FLOATING_WINDOW_WIDTH, FLOATING_WINDOW_HEIGHT)
-            .resizable(false)
-            .maximized(false)
-            .decorations(false)
-            .always_on_top(true)
-            .skip_taskbar(true)
-            .build()?;
-
-            if let Ok(Some(monitor)) = window.current_monitor() {
-                let screen = *monitor.size();
-                let origin = *monitor.position();
-                let window_size = resolve_window_size_for_monitor(&window, &monitor);
-                let position =
-                    resolve_floating_position(origin, screen, window_size, floating_position);
-                window.set_position(tauri::Position::Physical(position))?;
+        });
+        let window = match built {
+            Ok(window) => window,
+            Err(e) => {
+                report_reminder_failure(app, &label, &e, true);
+                return Err(e.into());
             }
+        };
+        REMINDER_FALLBACK_ACTIVE.store(false, Ordering::SeqCst);
+
+        if !is_fullscreen {
+            windows::place_floating_window(&window, floating_position)?;
         }
+        schedule_reminder_reveal(app, &label);
         return Ok(());
     }
 
-    // Create a window on each monitor
+    // Create a window on each monitor; one failing monitor must not cost the others
+    let mut created = 0;
+    let mut last_failure = None;
     for (idx, monitor) in monitors.iter().enumerate() {
-        let label = format!("break-reminder-{}", idx);
-        let mut builder =
-            WebviewWindowBuilder::new(app, &label, WebviewUrl::App("index.html#reminder".into()))
-                .title("Break Time - RESTY")
-                .visible(false)
-                .resizable(false)
-                .decorations(false)
-                .always_on_top(true)
-                .skip_taskbar(true);
-
-        if !is_fullscreen {
-            builder = builder
-                .inner_size(FLOATING_WINDOW_WIDTH, FLOATING_WINDOW_HEIGHT)
-                .maximized(false);
-        }
-
-        let window = builder.build()?;
+        let label = WindowKind::Reminder.label(Some(idx));
+        let built = build_reminder_window_with_retry(&label, || {
+            // Fullscreen is applied once the window sits on its monitor
+            windows::create_reminder_window(
+                app,
+                WindowKind::Reminder,
+                Some(idx),
+                ReminderWindowOptions {
+                    fullscreen: false,
+                    floating: !is_fullscreen,
+                    focused: true,
+                },
+            )
+        });
+        let window = match built {
+            Ok(window) => window,
+            Err(e) => {
+                last_failure = Some((label, e));
+                continue;
+            }
+        };
+        created += 1;
 
         let origin = *monitor.position();
         if is_fullscreen {
@@ -706,7 +2453,47 @@ pub fn show_break_reminder_window(
                 resolve_floating_position(origin, screen, win_size, floating_position.clone());
             let _ = window.set_position(tauri::Position::Physical(position));
         }
+        schedule_reminder_reveal(app, &label);
     }
 
+    if let Some((label, e)) = last_failure {
+        let fallback = created == 0;
+        report_reminder_failure(app, &label, &e, fallback);
+        if fallback {
+            return Err(e.into());
+        }
+    }
+    REMINDER_FALLBACK_ACTIVE.store(false, Ordering::SeqCst);
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn a_second_skip_100_ms_later_is_debounced() {
+        let last_actions = std::sync::Mutex::new(HashMap::new());
+        let first = Instant::now();
+
+        assert!(!is_tray_action_debounced(&last_actions, "skip", first));
+        assert!(is_tray_action_debounced(
+            &last_actions,
+            "skip",
+            first + Duration::from_millis(100)
+        ));
+        // Other actions keep their own window
+        assert!(!is_tray_action_debounced(
+            &last_actions,
+            "pause",
+            first + Duration::from_millis(100)
+        ));
+        assert!(!is_tray_action_debounced(
+            &last_actions,
+            "skip",
+            first + Duration::from_millis(800)
+        ));
+    }
+}
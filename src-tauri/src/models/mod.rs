@@ -1,24 +1,25 @@
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-
-/// 默认的休息音乐目录：位于用户家目录下的 `RESTY/rest-music`。
-pub fn rest_music_directory_default() -> String {
-    let base = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
-    base.join("RESTY")
-        .join("rest-music")
-        .to_string_lossy()
-        .into_owned()
-}
-
-fn default_rest_music_enabled() -> bool {
-    false
-}
-
-fn default_rest_music_directory() -> String {
-    rest_music_directory_default()
-}
-
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// 默认的休息音乐目录：位于用户家目录下的 `RESTY/rest-music`。
+pub fn rest_music_directory_default() -> String {
+    let base = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    base.join("RESTY")
+        .join("rest-music")
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn default_rest_music_enabled() -> bool {
+    false
+}
+
+fn default_rest_music_directory() -> String {
+    rest_music_directory_default()
+}
+
 fn default_flow_mode() -> bool {
     false
 }
@@ -35,56 +36,329 @@ fn default_auto_silent_update_enabled() -> bool {
     false
 }
 
+fn default_auto_install_updates() -> bool {
+    true
+}
+
 fn default_rest_start_soon_notification_enabled() -> bool {
     false
 }
-
+
+fn default_break_end_notification_enabled() -> bool {
+    false
+}
+
+fn default_notification_actions_enabled() -> bool {
+    true
+}
+
+fn default_sound_event_enabled() -> bool {
+    true
+}
+
+fn default_break_overrun_ratio() -> f64 {
+    3.0
+}
+
+fn default_max_work_minutes() -> u32 {
+    9 * 60
+}
+
+fn default_flag_zero_duration() -> bool {
+    true
+}
+
+fn default_daily_work_goal_minutes() -> u32 {
+    4 * 60
+}
+
+fn default_work_goal_weight() -> u32 {
+    40
+}
+
+fn default_break_completion_weight() -> u32 {
+    40
+}
+
+fn default_low_skips_weight() -> u32 {
+    20
+}
+
+fn default_hydration_goal_glasses() -> u32 {
+    8
+}
+
+fn default_hydration_interval_minutes() -> u32 {
+    60
+}
+
+fn default_external_control_permissions() -> BTreeMap<ControlSource, Vec<ActionGroup>> {
+    ControlSource::ALL
+        .into_iter()
+        .map(|source| (source, source.default_permissions()))
+        .collect()
+}
+
+fn default_storage_format() -> StorageFormat {
+    StorageFormat::Json
+}
+
 fn default_segment_repeat() -> u32 {
     1
 }
 
+fn default_catch_up_break_enabled() -> bool {
+    false
+}
+
+fn default_max_reminder_minutes() -> u32 {
+    60
+}
+
+fn default_min_break_completion_ratio() -> f64 {
+    0.8
+}
+
+fn default_prompt_work_note() -> bool {
+    false
+}
+
+fn default_work_note_in_flow_mode() -> bool {
+    false
+}
+
+fn default_on_schedule_complete() -> ScheduleCompleteAction {
+    ScheduleCompleteAction::Loop
+}
+
+fn default_hide_main_window_during_break() -> bool {
+    false
+}
+
+fn default_main_window_break_action() -> MainWindowBreakAction {
+    MainWindowBreakAction::Hide
+}
+
+fn default_open_tray_menu_hotkey() -> String {
+    "CommandOrControl+Shift+R".to_string()
+}
+
+fn default_emergency_dismiss_hotkey() -> String {
+    "CommandOrControl+Alt+Escape".to_string()
+}
+
+fn default_min_real_break_minutes() -> u32 {
+    5
+}
+
+fn default_activity_sample_seconds() -> u32 {
+    15
+}
+
+fn default_activity_idle_threshold_seconds() -> u32 {
+    60
+}
+
+fn default_wind_down_end_of_day() -> String {
+    "18:00".to_string()
+}
+
+fn default_wind_down_lead_minutes() -> u32 {
+    10
+}
+
+fn default_wind_down_work_minutes() -> u32 {
+    15
+}
+
+fn default_wind_down_break_minutes() -> u32 {
+    5
+}
+
 fn default_reminder_fullscreen_display() -> ReminderFullscreenDisplay {
     ReminderFullscreenDisplay::Panel
 }
 
+/// Built-in quote sources: Hitokoto for Chinese, ViewBits for everything else.
+pub fn default_quote_providers() -> Vec<QuoteProvider> {
+    vec![
+        QuoteProvider {
+            name: "Hitokoto".to_string(),
+            url: "https://v1.hitokoto.cn/?encode=json".to_string(),
+            pointers: vec!["/hitokoto".to_string()],
+            language: Some("zh".to_string()),
+        },
+        QuoteProvider {
+            name: "ViewBits".to_string(),
+            url: "https://api.viewbits.com/v1/zenquotes?mode=random".to_string(),
+            pointers: vec![
+                "/q".to_string(),
+                "/data/0/q".to_string(),
+                "/0/q".to_string(),
+            ],
+            language: Some("en".to_string()),
+        },
+    ]
+}
+
+/// Segment list of a settings file written before segmented work existed.
 pub fn default_work_segments() -> Vec<WorkSegment> {
     vec![WorkSegment {
         work_minutes: 25,
         break_minutes: 5,
-        repeat: default_segment_repeat(),
-    }]
-}
-
-/// Theme preference
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
-pub enum Theme {
-    Light,
-    Dark,
-    Auto,
-}
-
-/// Language preference
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub enum Language {
-    #[serde(rename = "en-US", alias = "en", alias = "en-us")]
-    EnglishUnitedStates,
-    #[serde(rename = "en-GB", alias = "en-gb")]
-    EnglishUnitedKingdom,
-    #[serde(rename = "zh-CN", alias = "zh", alias = "zh-cn")]
-    ChineseSimplified,
-    #[serde(rename = "zh-TW", alias = "zh-tw", alias = "zh-HK", alias = "zh-hk")]
-    ChineseTraditional,
-}
-
-/// Reminder display mode
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
+        repeat: default_segment_repeat(),
+        label: None,
+    }]
+}
+
+/// A remote quote source for the tips card and break reminders
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteProvider {
+    pub name: String,
+    /// http(s) URL answering with JSON
+    pub url: String,
+    /// JSON pointers (RFC 6901) to the quote text, e.g. `/data/0/q`; the first
+    /// one holding a non-empty string wins
+    pub pointers: Vec<String>,
+    /// Language code prefix the provider is used for ("zh", "en-GB"); None = all
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+/// A look for breaks; the configured themes take turns by day
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakTheme {
+    pub name: String,
+    /// Image shown behind the reminder (file path or URL)
+    #[serde(default)]
+    pub background_image: Option<String>,
+    /// Break messages used instead of quotes and tips; empty = keep those
+    #[serde(default)]
+    pub messages: Vec<String>,
+    /// Rest music folder used instead of `rest_music_directory`
+    #[serde(default)]
+    pub music_directory: Option<String>,
+}
+
+/// An exercise the user added to the break suggestions
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomExercise {
+    /// Unique among custom exercises; shown exercises are remembered by it
+    pub id: String,
+    pub text: String,
+    pub duration_seconds: u32,
+    /// e.g. "stretch", "eyes", "movement", "breathing"
+    pub category: String,
+}
+
+/// Theme preference
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Light,
+    Dark,
+    Auto,
+}
+
+/// Language preference
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Language {
+    #[serde(rename = "en-US", alias = "en", alias = "en-us")]
+    EnglishUnitedStates,
+    #[serde(rename = "en-GB", alias = "en-gb")]
+    EnglishUnitedKingdom,
+    #[serde(rename = "zh-CN", alias = "zh", alias = "zh-cn")]
+    ChineseSimplified,
+    #[serde(rename = "zh-TW", alias = "zh-tw", alias = "zh-HK", alias = "zh-hk")]
+    ChineseTraditional,
+}
+
+impl Language {
+    /// Locale code used for translation bundles, e.g. "zh-CN".
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::EnglishUnitedStates => "en-US",
+            Language::EnglishUnitedKingdom => "en-GB",
+            Language::ChineseSimplified => "zh-CN",
+            Language::ChineseTraditional => "zh-TW",
+        }
+    }
+}
+
+/// Reminder display mode
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
 pub enum ReminderMode {
     Fullscreen,
     Floating,
 }
 
+/// How break reminders behave while the app runs in a remote desktop session
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RemoteSessionBehavior {
+    /// Same reminder as on the console
+    #[default]
+    Normal,
+    /// Floating reminder even when fullscreen is configured
+    FloatingOnly,
+    /// A system notification instead of a reminder window
+    NotificationsOnly,
+    /// No reminder at all; the break still runs
+    Suppress,
+}
+
+/// On-disk format of the session history
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageFormat {
+    /// Pretty-printed `sessions.json`
+    Json,
+    /// One compact record per line in `sessions.ndjson`, faster for large histories
+    Ndjson,
+}
+
+/// How new session IDs are formed
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionIdScheme {
+    /// Random UUID v4
+    #[default]
+    Uuid,
+    /// Zero-padded running number, e.g. `000042`
+    Sequential,
+    /// Local date and a per-day counter, e.g. `20240105-003`
+    Daily,
+}
+
+/// First day of the week for weekly analytics ranges and progress
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStartDay {
+    #[default]
+    Monday,
+    Sunday,
+}
+
+/// What happens once a segmented program finished its `program_repeat` passes
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleCompleteAction {
+    Loop,
+    Stop,
+}
+
+/// How the main window is put away while a break is running
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MainWindowBreakAction {
+    Hide,
+    Minimize,
+}
+
 /// Fullscreen reminder display style
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -98,209 +372,1664 @@ pub enum ReminderFullscreenDisplay {
 #[serde(rename_all = "kebab-case")]
 pub enum FloatingPosition {
     TopLeft,
-    TopRight,
-    BottomLeft,
-    BottomRight,
-}
-
-/// Timer phase
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
-pub enum TimerPhase {
-    Work,
-    Break,
-    Idle,
-}
-
-/// Timer state
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
-pub enum TimerState {
-    Running,
-    Paused,
-    Stopped,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct WorkSegment {
-    pub work_minutes: u32,
-    pub break_minutes: u32,
-    #[serde(default = "default_segment_repeat")]
-    pub repeat: u32,
-}
-
-/// Application settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Settings {
-    // Timer settings
-    pub work_duration: u32,  // in minutes
-    pub break_duration: u32, // in minutes
-    pub enable_force_break: bool,
-    #[serde(default = "default_flow_mode")]
-    pub flow_mode_enabled: bool,
-    #[serde(default = "default_more_rest_enabled")]
-    pub more_rest_enabled: bool,
-    #[serde(default = "default_segmented_work_enabled")]
-    pub segmented_work_enabled: bool,
-    #[serde(default = "default_work_segments")]
-    pub work_segments: Vec<WorkSegment>,
-
-    // Reminder settings
-    pub reminder_mode: ReminderMode,
-    #[serde(default = "default_reminder_fullscreen_display")]
-    pub reminder_fullscreen_display: ReminderFullscreenDisplay,
-    pub floating_position: FloatingPosition,
-    pub opacity: u8, // 0-100
-    pub play_sound: bool,
-    #[serde(default = "default_rest_music_enabled")]
-    pub rest_music_enabled: bool,
-    #[serde(default = "default_rest_music_directory")]
-    pub rest_music_directory: String,
-    #[serde(default = "default_rest_start_soon_notification_enabled")]
-    pub rest_start_soon_notification_enabled: bool,
-
-    // Appearance
-    pub theme: Theme,
-
-    // System
-    pub autostart: bool,
-    #[serde(default)]
-    pub silent_autostart: bool,
-    #[serde(default = "default_auto_silent_update_enabled")]
-    pub auto_silent_update_enabled: bool,
-    pub minimize_to_tray: bool,
-    pub close_to_tray: bool,
-    #[serde(default)]
-    pub disable_analytics: bool,
-
-    // Language
-    pub language: Language,
-
-    // Metadata
-    pub version: String,
-    pub updated_at: DateTime<Utc>,
-}
-
-impl Default for Settings {
-    fn default() -> Self {
-        Self {
-            work_duration: 25,
-            break_duration: 5,
-            enable_force_break: false,
-            flow_mode_enabled: default_flow_mode(),
-            more_rest_enabled: default_more_rest_enabled(),
-            segmented_work_enabled: default_segmented_work_enabled(),
-            work_segments: default_work_segments(),
-            reminder_mode: ReminderMode::Fullscreen,
-            reminder_fullscreen_display: ReminderFullscreenDisplay::Panel,
-            floating_position: FloatingPosition::TopRight,
-            opacity: 95,
-            play_sound: true,
-            rest_music_enabled: default_rest_music_enabled(),
-            rest_music_directory: rest_music_directory_default(),
-            rest_start_soon_notification_enabled: default_rest_start_soon_notification_enabled(),
-            theme: Theme::Auto,
-            autostart: false,
-            silent_autostart: false,
-            auto_silent_update_enabled: false,
-            minimize_to_tray: true,
-            close_to_tray: true,
-            disable_analytics: false,
-            language: Language::EnglishUnitedStates,
-            version: "1.0.0".to_string(),
-            updated_at: Utc::now(),
-        }
-    }
-}
-
-/// Work/Break session record
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Session {
-    pub id: String,
-    #[serde(rename = "type")]
-    pub session_type: SessionType,
-    pub start_time: DateTime<Utc>,
-    pub end_time: DateTime<Utc>,
-    pub duration: i64,         // actual duration in seconds
-    pub planned_duration: i64, // planned duration in seconds
-    pub is_skipped: bool,
-    pub extended_seconds: i64,
-    pub notes: Option<String>,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Timer phase
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TimerPhase {
+    Work,
+    Break,
+    Idle,
+    /// "Back to work in N…" countdown after a break (`work_start_countdown_seconds`);
+    /// never recorded as a session
+    Transition,
+}
+
+/// Which rule decides when breaks happen, after precedence is applied
+/// (see `Settings`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EffectiveTimerMode {
+    /// Flow mode: work sessions chain without breaks
+    Flow,
+    /// Segmented program
+    Segmented,
+    /// Plain work/break cycle
+    Simple,
 }
 
+/// What triggered a phase change
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PhaseChangeCause {
+    /// App launch started the first work session
+    Startup,
+    /// User explicitly started work or a break
+    UserStart,
+    /// User skipped the previous phase
+    UserSkip,
+    /// Previous phase finished and auto-cycle moved on
+    AutoCycle,
+    /// Break was replaced by work because breaks are suppressed
+    Suppression,
+    /// Break was replaced by work because flow mode is on
+    FlowMode,
+    /// Work restarted after display-off / suspend
+    ResumeFromIdle,
+    /// First sustained input of the day started work (settings `auto_start_on_first_activity`)
+    FirstActivity,
+    /// Break ended because its reminder window was closed outside the app
+    ReminderClosed,
+    /// Phase set by hand with `set_timer_phase` to recover from a bad state
+    Recovery,
+}
+
+/// Payload of the `phase-change` event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct AchievementUnlock {
-    pub id: String,
-    pub unlocked_at: DateTime<Utc>,
+pub struct PhaseChange {
+    pub phase: TimerPhase,
+    pub cause: PhaseChangeCause,
 }
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
-pub enum SessionType {
-    Work,
-    Break,
-}
-
-/// Timer information for UI
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct TimerInfo {
-    pub phase: TimerPhase,
-    pub state: TimerState,
-    pub remaining_seconds: u32,
-    pub total_seconds: u32,
-    pub next_transition_time: Option<DateTime<Utc>>,
-    // 下一次真正"开始休息"的时间（考虑了"X 小时不休息/直到明天早晨"抑制逻辑）。
-    pub next_break_time: Option<DateTime<Utc>>,
-}
-
-/// Analytics data
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct AnalyticsData {
-    pub total_work_seconds: i64,
-    pub total_break_seconds: i64,
-    pub break_count: usize,
-    pub completed_breaks: usize,
-    pub skipped_breaks: usize,
-    pub sessions: Vec<Session>,
+
+/// Payload of the `break-acknowledged` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakAcknowledged {
+    pub session_id: String,
 }
 
-/// Sessions bounds
+/// Payload of the `day-started` event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct SessionsBounds {
-    pub earliest_start: Option<DateTime<Utc>>,
-    pub latest_end: Option<DateTime<Utc>>,
+pub struct DayStarted {
+    /// When the first work session of the local day started
+    pub started_at: DateTime<Utc>,
 }
 
-/// Analytics query parameters
+/// Payload of the `clock-changed` event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct AnalyticsQuery {
-    pub start_date: DateTime<Utc>,
-    pub end_date: DateTime<Utc>,
-}
-
-/// Monitor information
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct MonitorInfo {
-    pub id: u32,
-    pub name: String,
-    pub width: u32,
-    pub height: u32,
-    pub is_primary: bool,
-}
-
-/// System status
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SystemStatus {
-    pub is_fullscreen: bool,
-    pub is_do_not_disturb: bool,
-}
+pub struct ClockChanged {
+    /// How far the system clock jumped (seconds, negative = backwards)
+    pub drift_seconds: i64,
+}
+
+/// Payload of the `connectivity-changed` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityChanged {
+    pub online: bool,
+}
+
+/// Payload of the `settings-changed` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsChanged {
+    /// Top-level settings fields (camelCase) whose values changed
+    pub keys: Vec<String>,
+}
+
+/// Payload of the `suppression-expiring` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuppressionExpiring {
+    /// When the suppression window ends and breaks resume
+    pub until: DateTime<Utc>,
+    pub remaining_seconds: i64,
+}
+
+/// Payload of the `work-note-prompt` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkNotePrompt {
+    pub session_id: String,
+}
+
+/// Timer state
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TimerState {
+    Running,
+    Paused,
+    Stopped,
+}
+
+/// Longest work or break of one segment, in minutes.
+pub const MAX_SEGMENT_MINUTES: u32 = 120;
+/// Most times one segment may repeat.
+pub const MAX_SEGMENT_REPEAT: u32 = 12;
+/// Longest segment name, in characters.
+pub const MAX_SEGMENT_LABEL_CHARS: usize = 40;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkSegment {
+    pub work_minutes: u32,
+    pub break_minutes: u32,
+    #[serde(default = "default_segment_repeat")]
+    pub repeat: u32,
+    /// 分段名称（可选），记录在该分段的会话上，可在历史中搜索。
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl WorkSegment {
+    /// Whether every value lies within the limits settings validation accepts.
+    pub fn is_within_bounds(&self) -> bool {
+        (1..=MAX_SEGMENT_MINUTES).contains(&self.work_minutes)
+            && (1..=MAX_SEGMENT_MINUTES).contains(&self.break_minutes)
+            && (1..=MAX_SEGMENT_REPEAT).contains(&self.repeat)
+            && self
+                .label
+                .as_ref()
+                .is_none_or(|label| label.chars().count() <= MAX_SEGMENT_LABEL_CHARS)
+    }
+}
+
+/// Work/break durations for a range of local clock time, e.g. "09:00"–"12:00"
+/// 按本地时间段生效的工作/休息时长；`end` 早于 `start` 时表示跨越午夜。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeAnchoredSegment {
+    /// 开始时间（"HH:MM"，含）。
+    pub start: String,
+    /// 结束时间（"HH:MM"，不含）。
+    pub end: String,
+    pub work_minutes: u32,
+    pub break_minutes: u32,
+}
+
+/// A range of local clock time during which background network calls (quote
+/// fetches, update checks) are deferred
+/// 网络静默时段：期间推迟后台网络请求；`end` 早于 `start` 时表示跨越午夜，归属于开始的那一天。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkQuietHours {
+    /// 开始时间（"HH:MM"，含）。
+    pub start: String,
+    /// 结束时间（"HH:MM"，不含）。
+    pub end: String,
+    /// 生效的星期（1 = 周一 … 7 = 周日）；为空表示每天。
+    #[serde(default)]
+    pub days: Vec<u32>,
+}
+
+/// A named work/break configuration, e.g. "Pomodoro (25/5)"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerPreset {
+    pub name: String,
+    pub work_duration: u32,  // in minutes
+    pub break_duration: u32, // in minutes
+    #[serde(default)]
+    pub segmented_work_enabled: bool,
+    #[serde(default)]
+    pub work_segments: Vec<WorkSegment>,
+}
+
+impl TimerPreset {
+    /// Whether the settings' timer configuration still matches this preset.
+    pub fn matches(&self, settings: &Settings) -> bool {
+        let segments_match = !self.segmented_work_enabled
+            || (self.work_segments.len() == settings.work_segments.len()
+                && self
+                    .work_segments
+                    .iter()
+                    .zip(&settings.work_segments)
+                    .all(|(a, b)| {
+                        a.work_minutes == b.work_minutes
+                            && a.break_minutes == b.break_minutes
+                            && a.repeat == b.repeat
+                    }));
+        self.work_duration == settings.work_duration
+            && self.break_duration == settings.break_duration
+            && self.segmented_work_enabled == settings.segmented_work_enabled
+            && segments_match
+    }
+}
+
+/// Global keyboard shortcuts (accelerator strings, empty = disabled)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeySettings {
+    /// 打开/关闭 Windows 自定义托盘菜单。
+    #[serde(default = "default_open_tray_menu_hotkey")]
+    pub open_tray_menu: String,
+    /// 紧急关闭所有休息提醒窗口（提醒页面无响应时使用）；为空表示不注册。
+    #[serde(default = "default_emergency_dismiss_hotkey")]
+    pub emergency_dismiss: String,
+}
+
+impl Default for HotkeySettings {
+    fn default() -> Self {
+        Self {
+            open_tray_menu: default_open_tray_menu_hotkey(),
+            emergency_dismiss: default_emergency_dismiss_hotkey(),
+        }
+    }
+}
+
+/// Which notifications carry action buttons (Windows toasts only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationActionSettings {
+    /// 休息即将开始通知：“知道了”/“立即休息”。
+    #[serde(default = "default_notification_actions_enabled")]
+    pub rest_start_soon: bool,
+    /// 休息即将结束通知：“开始工作”/“再休息 5 分钟”。
+    #[serde(default = "default_notification_actions_enabled")]
+    pub break_end: bool,
+}
+
+impl Default for NotificationActionSettings {
+    fn default() -> Self {
+        Self {
+            rest_start_soon: default_notification_actions_enabled(),
+            break_end: default_notification_actions_enabled(),
+        }
+    }
+}
+
+/// Entry point a control action (skip, suppress, quit, ...) came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ControlSource {
+    Tray,
+    Webview,
+    Http,
+    Cli,
+    Deeplink,
+}
+
+impl ControlSource {
+    pub const ALL: [ControlSource; 5] = [
+        ControlSource::Tray,
+        ControlSource::Webview,
+        ControlSource::Http,
+        ControlSource::Cli,
+        ControlSource::Deeplink,
+    ];
+
+    /// Groups allowed when `external_control_permissions` has no entry for the
+    /// source: everything for the app's own UI, read-only for external callers.
+    pub fn default_permissions(self) -> Vec<ActionGroup> {
+        match self {
+            ControlSource::Tray | ControlSource::Webview => vec![
+                ActionGroup::ReadStatus,
+                ActionGroup::TimerControl,
+                ActionGroup::SettingsWrite,
+                ActionGroup::Quit,
+            ],
+            ControlSource::Http | ControlSource::Cli | ControlSource::Deeplink => {
+                vec![ActionGroup::ReadStatus]
+            }
+        }
+    }
+}
+
+/// Permission groups of control actions
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ActionGroup {
+    /// Look at the timer or open windows
+    ReadStatus,
+    /// Skip, start, suppress breaks
+    TimerControl,
+    SettingsWrite,
+    Quit,
+}
+
+/// Thresholds for flagging odd sessions in analytics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnomalySettings {
+    /// 休息实际时长达到计划时长（含延长）的该倍数即视为异常（例如忘记关电脑）。
+    #[serde(default = "default_break_overrun_ratio")]
+    pub break_overrun_ratio: f64,
+    /// 工作会话超过该分钟数即视为异常（例如忘记停止计时）。
+    #[serde(default = "default_max_work_minutes")]
+    pub max_work_minutes: u32,
+    /// 标记时长为 0 的已结束会话。
+    #[serde(default = "default_flag_zero_duration")]
+    pub flag_zero_duration: bool,
+}
+
+impl Default for AnomalySettings {
+    fn default() -> Self {
+        Self {
+            break_overrun_ratio: default_break_overrun_ratio(),
+            max_work_minutes: default_max_work_minutes(),
+            flag_zero_duration: default_flag_zero_duration(),
+        }
+    }
+}
+
+/// Daily goal and component weights of the focus score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusScoreSettings {
+    /// 每日工作时长目标（分钟），工作时长达到该值时"工作目标"一项得满分。
+    #[serde(default = "default_daily_work_goal_minutes")]
+    pub daily_work_goal_minutes: u32,
+    /// 各项权重，按占总权重的比例折算为分数（总和不必为 100）。
+    #[serde(default = "default_work_goal_weight")]
+    pub work_goal_weight: u32,
+    #[serde(default = "default_break_completion_weight")]
+    pub break_completion_weight: u32,
+    #[serde(default = "default_low_skips_weight")]
+    pub low_skips_weight: u32,
+}
+
+impl Default for FocusScoreSettings {
+    fn default() -> Self {
+        Self {
+            daily_work_goal_minutes: default_daily_work_goal_minutes(),
+            work_goal_weight: default_work_goal_weight(),
+            break_completion_weight: default_break_completion_weight(),
+            low_skips_weight: default_low_skips_weight(),
+        }
+    }
+}
+
+/// Hydration tracker: daily goal and how often to remind
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HydrationSettings {
+    /// 是否启用饮水记录与提醒。
+    #[serde(default)]
+    pub enabled: bool,
+    /// 每日饮水目标（杯）。
+    #[serde(default = "default_hydration_goal_glasses")]
+    pub daily_goal_glasses: u32,
+    /// 饮水提醒间隔（分钟，工作期间计时，0 表示只记录不提醒）；达到目标后不再提醒。
+    #[serde(default = "default_hydration_interval_minutes")]
+    pub reminder_interval_minutes: u32,
+}
+
+impl Default for HydrationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            daily_goal_glasses: default_hydration_goal_glasses(),
+            reminder_interval_minutes: default_hydration_interval_minutes(),
+        }
+    }
+}
+
+/// Which phase transitions play the completion chime (when `play_sound` is on)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SoundEventSettings {
+    /// 工作阶段结束（包括心流/暂停休息时连续工作）。
+    #[serde(default = "default_sound_event_enabled")]
+    pub work_end: bool,
+    /// 休息开始。
+    #[serde(default = "default_sound_event_enabled")]
+    pub break_start: bool,
+    /// 休息结束。
+    #[serde(default = "default_sound_event_enabled")]
+    pub break_end: bool,
+}
+
+impl Default for SoundEventSettings {
+    fn default() -> Self {
+        Self {
+            work_end: default_sound_event_enabled(),
+            break_start: default_sound_event_enabled(),
+            break_end: default_sound_event_enabled(),
+        }
+    }
+}
+
+/// Gentler cadence shortly before the end of the work day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindDownSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 工作日结束时间（本地时间，"HH:MM"）。
+    #[serde(default = "default_wind_down_end_of_day")]
+    pub end_of_day: String,
+    /// 结束前多少分钟进入收尾模式。
+    #[serde(default = "default_wind_down_lead_minutes")]
+    pub starts_before_end_minutes: u32,
+    /// 收尾模式下的工作时长（分钟），不使用分段程序。
+    #[serde(default = "default_wind_down_work_minutes")]
+    pub work_minutes: u32,
+    /// 收尾模式下的休息时长（分钟）。
+    #[serde(default = "default_wind_down_break_minutes")]
+    pub break_minutes: u32,
+}
+
+impl Default for WindDownSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            end_of_day: default_wind_down_end_of_day(),
+            starts_before_end_minutes: default_wind_down_lead_minutes(),
+            work_minutes: default_wind_down_work_minutes(),
+            break_minutes: default_wind_down_break_minutes(),
+        }
+    }
+}
+
+/// Application settings
+///
+/// Precedence when several break rules are on at once:
+/// 1. A break suppression window (e.g. "no breaks for 2 hours") and flow mode
+///    (`flow_mode_enabled`) both hold breaks back; when a work session ends
+///    another one starts instead of a break.
+/// 2. Segmented work (`segmented_work_enabled`) still advances through its
+///    segments while breaks are held back, so the program resumes at the right
+///    segment once flow mode is turned off, but its breaks are not taken.
+/// 3. Otherwise the plain work/break cycle applies.
+///
+/// Turning on flow mode and segmented work together is allowed but logged as a
+/// warning when the settings are saved.
+/// 多个休息规则同时开启时：抑制与心流模式优先（不休息），分段程序仍推进位置但不触发休息。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Settings {
+    // Timer settings
+    pub work_duration: u32,  // in minutes
+    pub break_duration: u32, // in minutes
+    pub enable_force_break: bool,
+    /// 强制休息时提醒最长持续时间（分钟），超过后允许用户关闭提醒。
+    #[serde(default = "default_max_reminder_minutes")]
+    pub max_reminder_minutes: u32,
+    /// 休息结束后提醒保持显示，直到用户点击或按键确认（非强制模式也生效）。
+    #[serde(default)]
+    pub require_break_acknowledgment: bool,
+    /// 单次"暂不休息"的最长分钟数；强制休息时超出即拒绝，否则截断。
+    #[serde(default)]
+    pub max_suppression_minutes: Option<u32>,
+    /// 休息抑制结束前多少分钟发送 suppression-expiring 提示（0 表示关闭，最多 60）。
+    #[serde(default)]
+    pub suppression_expiry_warning_minutes: u32,
+    #[serde(default = "default_flow_mode")]
+    pub flow_mode_enabled: bool,
+    #[serde(default = "default_more_rest_enabled")]
+    pub more_rest_enabled: bool,
+    #[serde(default = "default_segmented_work_enabled")]
+    pub segmented_work_enabled: bool,
+    #[serde(default = "default_work_segments")]
+    pub work_segments: Vec<WorkSegment>,
+    /// 按一天中的时间段选择时长（与分段程序互斥）；时间段之间使用基础时长。
+    #[serde(default)]
+    pub time_anchored_segments: Vec<TimeAnchoredSegment>,
+    /// 整个分段程序运行的轮数；为空表示无限循环。
+    #[serde(default)]
+    pub program_repeat: Option<u32>,
+    /// 已保存的计时预设（工作/休息/分段配置）。
+    #[serde(default)]
+    pub presets: Vec<TimerPreset>,
+    /// 当前应用的预设名称；手动修改时长后为空。
+    #[serde(default)]
+    pub active_preset: Option<String>,
+    /// 工作日结束前的收尾模式：缩短工作时长并提醒收尾。
+    #[serde(default)]
+    pub wind_down: WindDownSettings,
+    /// 分段程序运行满 `program_repeat` 轮后重新开始或停止计时。
+    #[serde(default = "default_on_schedule_complete")]
+    pub on_schedule_complete: ScheduleCompleteAction,
+    /// 每段工作时长随机增减的最大分钟数（0 表示关闭），用于错开团队成员的休息时间。
+    #[serde(default)]
+    pub break_jitter_minutes: u32,
+    /// 随机化（如休息抖动）使用的种子；设置后结果可复现。
+    #[serde(default)]
+    pub random_seed: Option<u64>,
+    /// 抑制休息结束后，按抑制时长安排一次加长的"补偿休息"。
+    #[serde(default = "default_catch_up_break_enabled")]
+    pub catch_up_break_enabled: bool,
+    /// 休息因熄屏/睡眠中断超过该分钟数即视为已休息完毕；为空时仅以剩余休息时长判断。
+    #[serde(default)]
+    pub long_absence_minutes: Option<u32>,
+    /// 出行模式：忽略熄屏与睡眠/唤醒，不自动暂停或重启计时。
+    #[serde(default)]
+    pub travel_mode: bool,
+    /// 每个阶段最多可延长的次数；为空表示不限。
+    #[serde(default)]
+    pub max_extends_per_phase: Option<u32>,
+    /// 每个阶段累计最多可延长的分钟数；为空表示不限。
+    #[serde(default)]
+    pub max_total_extend_minutes: Option<u32>,
+    /// 休息结束后、开始工作前的倒计时秒数（0 表示关闭，最多 30）。
+    #[serde(default)]
+    pub work_start_countdown_seconds: u32,
+
+    // Reminder settings
+    pub reminder_mode: ReminderMode,
+    #[serde(default = "default_reminder_fullscreen_display")]
+    pub reminder_fullscreen_display: ReminderFullscreenDisplay,
+    pub floating_position: FloatingPosition,
+    pub opacity: u8, // 0-100
+    /// 提醒窗口鼠标穿透，点击直接作用于下方应用；强制休息时不可开启。
+    #[serde(default)]
+    pub reminder_click_through: bool,
+    /// 远程桌面会话中的提醒方式（正常/仅浮窗/仅通知/不提醒），每次休息开始时重新检测。
+    #[serde(default)]
+    pub remote_session_behavior: RemoteSessionBehavior,
+    pub play_sound: bool,
+    /// 分别控制各阶段切换是否播放提示音。
+    #[serde(default)]
+    pub sound_events: SoundEventSettings,
+    #[serde(default = "default_rest_music_enabled")]
+    pub rest_music_enabled: bool,
+    #[serde(default = "default_rest_music_directory")]
+    pub rest_music_directory: String,
+    #[serde(default = "default_rest_start_soon_notification_enabled")]
+    pub rest_start_soon_notification_enabled: bool,
+    /// 休息结束前发送通知。
+    #[serde(default = "default_break_end_notification_enabled")]
+    pub break_end_notification_enabled: bool,
+    #[serde(default)]
+    pub notification_actions: NotificationActionSettings,
+    /// 休息期间隐藏（或最小化）主窗口，休息结束或跳过后恢复原先状态。
+    #[serde(default = "default_hide_main_window_during_break")]
+    pub hide_main_window_during_break: bool,
+    #[serde(default = "default_main_window_break_action")]
+    pub main_window_break_action: MainWindowBreakAction,
+    /// 工作阶段结束后弹出小窗口记录本次工作内容。
+    #[serde(default = "default_prompt_work_note")]
+    pub prompt_work_note: bool,
+    /// 心流模式下连续的工作阶段之间也弹出记录窗口。
+    #[serde(default = "default_work_note_in_flow_mode")]
+    pub work_note_in_flow_mode: bool,
+
+    // Appearance
+    pub theme: Theme,
+
+    // System
+    pub autostart: bool,
+    #[serde(default)]
+    pub silent_autostart: bool,
+    #[serde(default = "default_auto_silent_update_enabled")]
+    pub auto_silent_update_enabled: bool,
+    /// 后台更新下载完成后自动安装；关闭时只发送 `update-ready-to-install`，由用户确认安装。
+    #[serde(default = "default_auto_install_updates")]
+    pub auto_install_updates: bool,
+    pub minimize_to_tray: bool,
+    pub close_to_tray: bool,
+    /// 主窗口置顶（休息提醒显示期间暂时取消，避免遮挡提醒窗口）。
+    #[serde(default)]
+    pub always_on_top: bool,
+    /// 无论系统设置如何都使用高对比度提醒界面。
+    #[serde(default)]
+    pub force_high_contrast: bool,
+    /// 无论系统设置如何都关闭提醒界面动画。
+    #[serde(default)]
+    pub force_reduced_motion: bool,
+    #[serde(default)]
+    pub disable_analytics: bool,
+    /// 维护本地的全部历史汇总（lifetime_stats.json），不联网；默认关闭。
+    #[serde(default)]
+    pub lifetime_stats_enabled: bool,
+    /// 允许在阶段切换时执行用户命令；默认关闭，导入配置时总是关闭。
+    #[serde(default)]
+    pub allow_hooks: bool,
+    /// 工作开始时通过系统 shell 执行的命令（为空表示不执行）。
+    #[serde(default)]
+    pub on_work_start_command: String,
+    /// 休息开始时通过系统 shell 执行的命令（为空表示不执行）。
+    #[serde(default)]
+    pub on_break_start_command: String,
+    /// 每次阶段切换时 POST JSON 通知的地址（如 Home Assistant）；为空表示关闭。
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// 名言来源列表，按顺序尝试，失败时回退到下一个；默认为 Hitokoto 与 ViewBits。
+    #[serde(default = "default_quote_providers")]
+    pub quote_providers: Vec<QuoteProvider>,
+    /// 网络静默时段列表，期间不获取名言、不检查更新（使用缓存或推迟）。
+    #[serde(default)]
+    pub network_quiet_hours: Vec<NetworkQuietHours>,
+    /// 按流量计费模式：开启后不进行后台网络请求。
+    #[serde(default)]
+    pub metered_mode: bool,
+    /// 自动检测按流量计费的网络连接（仅 Windows），检测到时等同开启按流量计费模式。
+    #[serde(default)]
+    pub auto_detect_metered: bool,
+    /// 休息时建议的练习类别（如 "eyes"、"stretch"）；为空表示全部类别。
+    #[serde(default)]
+    pub preferred_exercise_categories: Vec<String>,
+    /// 用户自定义的休息练习，与内置练习一起轮换。
+    #[serde(default)]
+    pub custom_exercises: Vec<CustomExercise>,
+    /// 休息主题列表，按日期每天轮换一个（背景图、文案、音乐文件夹）；为空表示不使用主题。
+    #[serde(default)]
+    pub break_themes: Vec<BreakTheme>,
+    /// 会话历史的存储格式；切换后自动迁移现有记录。
+    #[serde(default = "default_storage_format")]
+    pub storage_format: StorageFormat,
+    /// 启动时只把最近该天数内的会话作为常用数据（0 表示全部），更早的会话单独存放，在查询范围需要时才参与计算；下次加载会话时生效。
+    #[serde(default)]
+    pub analytics_load_window_days: u32,
+    /// 新会话 ID 的生成方式（默认 UUID），便于与外部系统对照。
+    #[serde(default)]
+    pub session_id_scheme: SessionIdScheme,
+    /// 新会话 ID 的前缀，例如 "resty-"。
+    #[serde(default)]
+    pub session_id_prefix: String,
+    /// 启动时不立即开始工作，等当天第一次持续使用电脑后再开始（每天一次）。
+    #[serde(default)]
+    pub auto_start_on_first_activity: bool,
+    /// 通话（麦克风正在使用）期间推迟休息，通话结束后再开始（仅 Windows 可检测）。
+    #[serde(default)]
+    pub defer_breaks_during_calls: bool,
+    /// 允许调用调试命令（仅 `dev-tools` 构建中存在），例如快进计时器；
+    /// 也用于解锁恢复命令 `set_timer_phase`（所有构建均可用）。
+    #[serde(default)]
+    pub dev_mode: bool,
+    /// 休息实际时长达到计划时长的该比例才计为"已完成"（0-1）。
+    #[serde(default = "default_min_break_completion_ratio")]
+    pub min_break_completion_ratio: f64,
+    /// 短于该秒数的会话不写入历史记录（0 表示全部保存），避免连续跳过产生碎片记录。
+    #[serde(default)]
+    pub min_session_seconds: u32,
+    /// 至少休息/离开该分钟数才算"真正休息"，连续使用电脑的计时随之清零。
+    #[serde(default = "default_min_real_break_minutes")]
+    pub min_real_break_minutes: u32,
+    /// 工作阶段中每隔该秒数检查一次键鼠空闲时间，用于统计实际在电脑前的时长。
+    #[serde(default = "default_activity_sample_seconds")]
+    pub activity_sample_seconds: u32,
+    /// 距上次键鼠输入不超过该秒数时，该次采样计为"活跃"。
+    #[serde(default = "default_activity_idle_threshold_seconds")]
+    pub activity_idle_threshold_seconds: u32,
+    /// 休息欠债达到阈值时，下一次休息延长欠债的该百分比（0 表示不延长，单次有上限）。
+    #[serde(default)]
+    pub rest_debt_compensation_percent: u32,
+    /// 每周从哪一天开始（周一/周日），用于"本周"范围和按周统计。
+    #[serde(default)]
+    pub week_start_day: WeekStartDay,
+    /// 分析页异常会话的检测阈值。
+    #[serde(default)]
+    pub anomaly_detection: AnomalySettings,
+    /// 每日专注评分的工作目标与各项权重。
+    #[serde(default)]
+    pub focus_score: FocusScoreSettings,
+    /// 饮水记录：每日目标与提醒间隔。
+    #[serde(default)]
+    pub hydration: HydrationSettings,
+    #[serde(default)]
+    pub hotkeys: HotkeySettings,
+    /// 各入口（托盘、网页视图、HTTP、命令行、深度链接）允许调用的操作组；缺省的入口使用默认权限。
+    #[serde(default = "default_external_control_permissions")]
+    pub external_control_permissions: BTreeMap<ControlSource, Vec<ActionGroup>>,
+
+    // Language
+    pub language: Language,
+
+    // Metadata
+    pub version: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Settings {
+    /// Whether `source` may run actions of `group`.
+    pub fn allows_control(&self, source: ControlSource, group: ActionGroup) -> bool {
+        match self.external_control_permissions.get(&source) {
+            Some(groups) => groups.contains(&group),
+            None => source.default_permissions().contains(&group),
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            work_duration: 25,
+            break_duration: 5,
+            enable_force_break: false,
+            max_reminder_minutes: default_max_reminder_minutes(),
+            require_break_acknowledgment: false,
+            max_suppression_minutes: None,
+            suppression_expiry_warning_minutes: 0,
+            flow_mode_enabled: default_flow_mode(),
+            more_rest_enabled: default_more_rest_enabled(),
+            segmented_work_enabled: default_segmented_work_enabled(),
+            work_segments: default_work_segments(),
+            time_anchored_segments: Vec::new(),
+            program_repeat: None,
+            presets: Vec::new(),
+            active_preset: None,
+            wind_down: WindDownSettings::default(),
+            on_schedule_complete: default_on_schedule_complete(),
+            break_jitter_minutes: 0,
+            random_seed: None,
+            catch_up_break_enabled: default_catch_up_break_enabled(),
+            long_absence_minutes: None,
+            travel_mode: false,
+            max_extends_per_phase: None,
+            max_total_extend_minutes: None,
+            work_start_countdown_seconds: 0,
+            reminder_mode: ReminderMode::Fullscreen,
+            reminder_fullscreen_display: ReminderFullscreenDisplay::Panel,
+            floating_position: FloatingPosition::TopRight,
+            opacity: 95,
+            reminder_click_through: false,
+            remote_session_behavior: RemoteSessionBehavior::Normal,
+            play_sound: true,
+            sound_events: SoundEventSettings::default(),
+            rest_music_enabled: default_rest_music_enabled(),
+            rest_music_directory: rest_music_directory_default(),
+            rest_start_soon_notification_enabled: default_rest_start_soon_notification_enabled(),
+            break_end_notification_enabled: default_break_end_notification_enabled(),
+            notification_actions: NotificationActionSettings::default(),
+            hide_main_window_during_break: default_hide_main_window_during_break(),
+            main_window_break_action: default_main_window_break_action(),
+            prompt_work_note: default_prompt_work_note(),
+            work_note_in_flow_mode: default_work_note_in_flow_mode(),
+            theme: Theme::Auto,
+            autostart: false,
+            silent_autostart: false,
+            auto_silent_update_enabled: false,
+            auto_install_updates: default_auto_install_updates(),
+            minimize_to_tray: true,
+            close_to_tray: true,
+            always_on_top: false,
+            force_high_contrast: false,
+            force_reduced_motion: false,
+            disable_analytics: false,
+            lifetime_stats_enabled: false,
+            allow_hooks: false,
+            on_work_start_command: String::new(),
+            on_break_start_command: String::new(),
+            webhook_url: None,
+            quote_providers: default_quote_providers(),
+            network_quiet_hours: Vec::new(),
+            metered_mode: false,
+            auto_detect_metered: false,
+            preferred_exercise_categories: Vec::new(),
+            custom_exercises: Vec::new(),
+            break_themes: Vec::new(),
+            storage_format: default_storage_format(),
+            analytics_load_window_days: 0,
+            session_id_scheme: SessionIdScheme::Uuid,
+            session_id_prefix: String::new(),
+            auto_start_on_first_activity: false,
+            defer_breaks_during_calls: false,
+            dev_mode: false,
+            min_break_completion_ratio: default_min_break_completion_ratio(),
+            min_session_seconds: 0,
+            min_real_break_minutes: default_min_real_break_minutes(),
+            activity_sample_seconds: default_activity_sample_seconds(),
+            activity_idle_threshold_seconds: default_activity_idle_threshold_seconds(),
+            rest_debt_compensation_percent: 0,
+            week_start_day: WeekStartDay::Monday,
+            anomaly_detection: AnomalySettings::default(),
+            focus_score: FocusScoreSettings::default(),
+            hydration: HydrationSettings::default(),
+            hotkeys: HotkeySettings::default(),
+            external_control_permissions: default_external_control_permissions(),
+            language: Language::EnglishUnitedStates,
+            version: "1.0.0".to_string(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// Work/Break session record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub session_type: SessionType,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub duration: i64,         // actual duration in seconds
+    pub planned_duration: i64, // planned duration in seconds
+    pub is_skipped: bool,
+    pub extended_seconds: i64,
+    pub notes: Option<String>,
+    /// Optional self-reported energy/mood rating (1-5)
+    #[serde(default)]
+    pub rating: Option<u8>,
+    /// The phase was still running when this record was written (placeholder or checkpoint)
+    #[serde(default)]
+    pub in_progress: bool,
+    /// The user reviewed this session's anomaly; it is no longer reported
+    #[serde(default)]
+    pub anomaly_dismissed: bool,
+    /// Whether the user acknowledged this finished break; None when no
+    /// acknowledgment was required (settings `require_break_acknowledgment`)
+    #[serde(default)]
+    pub acknowledged: Option<bool>,
+    /// Seconds of a work session with keyboard or mouse input (sampled, see
+    /// `activity_sample_seconds`); None for breaks, sessions recorded before
+    /// this was tracked, and platforms where input idle time is unknown
+    #[serde(default)]
+    pub active_seconds: Option<i64>,
+    /// Name of the work segment the session ran in (settings `work_segments`);
+    /// None outside segmented work and for unnamed segments
+    #[serde(default)]
+    pub segment_label: Option<String>,
+    /// Why the session ended; None while it runs and for sessions recorded
+    /// before this was tracked
+    #[serde(default)]
+    pub end_reason: Option<SessionEndReason>,
+}
+
+/// Why a session ended
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SessionEndReason {
+    /// The countdown ran out
+    Completed,
+    /// Skipped before its end
+    Skipped,
+    /// A break counted as done after an absence of its length
+    Absence,
+    /// Work ended by display-off or suspend
+    PowerInterrupt,
+    /// The app exited while it ran
+    Exit,
+    /// Replaced with `set_timer_phase`
+    Recovery,
+    /// Still running when the app stopped unexpectedly; closed at the next start
+    Interrupted,
+}
+
+impl SessionEndReason {
+    /// Name as serialized, e.g. `power-interrupt`; searched by `list_sessions`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Completed => "completed",
+            Self::Skipped => "skipped",
+            Self::Absence => "absence",
+            Self::PowerInterrupt => "power-interrupt",
+            Self::Exit => "exit",
+            Self::Recovery => "recovery",
+            Self::Interrupted => "interrupted",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AchievementUnlock {
+    pub id: String,
+    pub unlocked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionType {
+    Work,
+    Break,
+}
+
+/// Timer information for UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerInfo {
+    pub phase: TimerPhase,
+    pub state: TimerState,
+    pub remaining_seconds: u32,
+    pub total_seconds: u32,
+    pub next_transition_time: Option<DateTime<Utc>>,
+    // 下一次真正"开始休息"的时间（考虑了"X 小时不休息/直到明天早晨"抑制逻辑）。
+    pub next_break_time: Option<DateTime<Utc>>,
+    // 强制休息模式下允许关闭提醒的最早时间（受 max_reminder_minutes 限制）。
+    pub break_dismissible_at: Option<DateTime<Utc>>,
+    // 当前阻止休息/提醒的原因（如 "suppression"、"flowMode"、"call"），为空表示允许打断。
+    pub interruptions_blocked_by: Vec<String>,
+    // 当前是否按分段程序计时。
+    pub segmented: bool,
+    // 当前应用的计时预设名称（手动配置时为空）。
+    pub active_preset: Option<String>,
+    // 出行模式开启时熄屏/睡眠不会暂停计时。
+    pub travel_mode: bool,
+    // 休息已结束，提醒等待用户确认后才关闭。
+    pub awaiting_break_acknowledgment: bool,
+    // 距上次真正休息后连续使用电脑的秒数（与工作会话无关）。
+    pub continuous_screen_seconds: u64,
+    // 休息欠债（分钟，按时间衰减），每分钟刷新一次。
+    pub rest_debt_minutes: u32,
+    // 当前休息因休息欠债而延长的分钟数（非休息阶段为 0）。
+    pub rest_debt_extra_minutes: u32,
+    // 实际生效的计时模式（心流模式优先于分段程序）。
+    pub effective_mode: EffectiveTimerMode,
+}
+
+/// Time at the computer since the last real break, saved across restarts
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenContinuity {
+    pub seconds: u64,
+    /// When `seconds` was last brought up to date
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A projected phase in the simulated schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledPhase {
+    pub phase: TimerPhase,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Analytics data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticsData {
+    pub total_work_seconds: i64,
+    pub total_break_seconds: i64,
+    pub break_count: usize,
+    pub completed_breaks: usize,
+    pub skipped_breaks: usize,
+    /// Time added to sessions in range with `extend` (seconds)
+    pub total_extended_seconds: i64,
+    /// Breaks the user acknowledged / that ended without it (only breaks that required it)
+    pub acknowledged_breaks: usize,
+    pub unacknowledged_breaks: usize,
+    /// Odd sessions in range that were not dismissed yet
+    pub anomalies: Vec<SessionAnomaly>,
+    /// Starts of the local days in range (settings `auto_start_on_first_activity`)
+    pub day_starts: Vec<DateTime<Utc>>,
+    /// Active (at keyboard) seconds of work sessions that tracked it
+    pub total_active_seconds: i64,
+    /// Per local day, active time against the elapsed time of the same sessions
+    pub daily_activity: Vec<DailyActivity>,
+    pub sessions: Vec<Session>,
+}
+
+/// One local day of `AnalyticsData::daily_activity`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyActivity {
+    pub date: NaiveDate,
+    pub active_seconds: i64,
+    /// Elapsed work time of the sessions that tracked activity
+    pub tracked_seconds: i64,
+    /// active / tracked (0-1); None when no session of the day tracked activity
+    pub activity_ratio: Option<f64>,
+}
+
+/// Why a session looks wrong
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AnomalyKind {
+    /// Break ran far past its planned length (e.g. laptop left open)
+    BreakOverrun,
+    /// Work session longer than `max_work_minutes` (e.g. forgot to stop)
+    LongWork,
+    /// Finished session with no duration
+    ZeroDuration,
+}
+
+/// A session flagged by the anomaly thresholds
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionAnomaly {
+    pub session_id: String,
+    pub kind: AnomalyKind,
+    /// Human-readable explanation, e.g. "break ran 3.2× its planned 5 min"
+    pub detail: String,
+}
+
+/// Payload of the `analytics-delta` event: how one session write changes the totals
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticsDelta {
+    pub session_id: String,
+    /// Local calendar day the session starts on
+    pub date: NaiveDate,
+    pub work_seconds: i64,
+    pub break_seconds: i64,
+    pub break_count: i64,
+    pub completed_breaks: i64,
+    pub skipped_breaks: i64,
+}
+
+/// All-time counters of finished sessions
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LifetimeTotals {
+    pub work_sessions: i64,
+    pub work_seconds: i64,
+    pub breaks: i64,
+    pub completed_breaks: i64,
+    pub skipped_breaks: i64,
+    pub break_seconds: i64,
+}
+
+/// One calendar year of the lifetime statistics
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct YearStats {
+    pub year: i32,
+    pub totals: LifetimeTotals,
+    /// Month (1-12) with the highest share of completed breaks
+    pub most_disciplined_month: Option<u32>,
+}
+
+/// All-time aggregates returned by `get_lifetime_stats`
+/// 全部历史的汇总统计（删除会话记录后仍保留）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LifetimeStats {
+    /// Local day of the earliest recorded session
+    pub first_use_date: Option<NaiveDate>,
+    pub totals: LifetimeTotals,
+    /// Most consecutive local days with at least one completed break
+    pub longest_streak_days: u32,
+    /// "YYYY-MM" with the highest share of completed breaks
+    pub most_disciplined_month: Option<String>,
+    /// Oldest year first
+    pub years: Vec<YearStats>,
+}
+
+/// Which source produced the break content
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BreakContentSource {
+    Remote,
+    Cache,
+    Local,
+    /// Messages of today's break theme
+    Theme,
+}
+
+/// Break reminder text with its origin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakContent {
+    pub text: String,
+    pub source: BreakContentSource,
+    /// Today's break theme (settings `break_themes`), if any
+    #[serde(default)]
+    pub theme: Option<BreakTheme>,
+    /// Today's glasses of water, when the hydration tracker is on
+    #[serde(default)]
+    pub hydration: Option<HydrationProgress>,
+}
+
+/// Exercise suggested for a break (see `get_break_exercise`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakExercise {
+    pub id: String,
+    /// Text in the requested language
+    pub text: String,
+    pub duration_seconds: u32,
+    pub category: String,
+    /// Added by the user (settings `custom_exercises`)
+    pub custom: bool,
+}
+
+/// Exercises suggested since the rotation last started over, saved across restarts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExerciseRotation {
+    /// Oldest first
+    pub recent: Vec<String>,
+    /// Break the last suggestion was made for
+    #[serde(default)]
+    pub session_id: Option<String>,
+}
+
+/// First-paint payload for a window (see `get_bootstrap`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bootstrap {
+    pub settings: Settings,
+    pub timer_info: TimerInfo,
+    /// Today's totals (main window only)
+    pub today: Option<AnalyticsData>,
+    /// Translation bundle for `settings.language`
+    pub translation: Option<serde_json::Value>,
+    /// Break text (reminder windows only)
+    pub break_content: Option<BreakContent>,
+    /// Rest music file to play (reminder windows only, when rest music is enabled)
+    pub music_track: Option<String>,
+    /// Parts that failed to load; everything else in the payload is still valid
+    pub errors: Vec<String>,
+}
+
+/// Payload of `reminder-visible`: when a reminder window was actually shown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderVisible {
+    pub label: String,
+    pub displayed_at: DateTime<Utc>,
+    /// True when the page never reported ready and the window was force-shown.
+    pub forced: bool,
+}
+
+/// Rest music files that could not be opened (payload of `music-track-unavailable`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MusicTrackUnavailable {
+    pub paths: Vec<String>,
+    /// No track could be played; the break-start chime was played instead
+    pub fell_back_to_chime: bool,
+}
+
+/// Payload of `tray-unavailable`: the tray icon could not be created
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayUnavailable {
+    pub error: String,
+    /// Another attempt follows shortly (`tray-available` on success)
+    pub retrying: bool,
+}
+
+/// A reminder window that could not be created (payload of `reminder-failed`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderFailure {
+    pub label: String,
+    pub error: String,
+    pub failed_at: DateTime<Utc>,
+    /// True when no reminder window opened and the notification fallback was used
+    pub fallback: bool,
+}
+
+/// A history write that failed (payload of `storage-error`); the change was
+/// rolled back in memory, so the session is not in the history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageFailure {
+    /// Operation that failed, e.g. `saveSession`
+    pub operation: String,
+    pub error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+/// A background task panicked (payload of `backend-panic`); details are in the
+/// panic log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendPanic {
+    /// Task that panicked, e.g. `timer`
+    pub task: String,
+    pub message: String,
+    pub log_path: String,
+    /// Whether the task keeps running (the timer ticker does)
+    pub recovered: bool,
+}
+
+/// Whether settings and sessions are stored encrypted (`get_encryption_status`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    /// Encrypted and the passphrase not entered yet this launch
+    pub locked: bool,
+}
+
+/// Size of a full data export (`estimate_data_export`), shown before exporting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataExportEstimate {
+    pub sessions: usize,
+    pub estimated_bytes: u64,
+}
+
+/// Progress of `export_all_data` (payload of `data-export-progress`), sent
+/// every few hundred sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataExportProgress {
+    pub sessions_written: usize,
+    pub total_sessions: usize,
+    pub bytes_written: u64,
+    pub estimated_bytes: u64,
+}
+
+/// Result of `export_all_data`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataExportSummary {
+    pub path: String,
+    pub sessions: usize,
+    pub bytes: u64,
+}
+
+/// Work sessions sharing the same self-reported rating
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RatingBucket {
+    pub rating: u8,
+    pub session_count: usize,
+    pub average_work_seconds: i64,
+}
+
+/// Sessions whose duration falls in `[start_minutes, end_minutes)`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DurationBucket {
+    pub start_minutes: u32,
+    pub end_minutes: u32,
+    pub count: usize,
+}
+
+/// Distribution of work and break durations for a date range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DurationHistogram {
+    pub bucket_minutes: u32,
+    /// Contiguous from 0 up to the longest session; empty buckets included
+    pub work: Vec<DurationBucket>,
+    pub breaks: Vec<DurationBucket>,
+}
+
+/// One part of the focus score
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusScoreComponent {
+    /// How well the day did on this part, 0-1
+    pub ratio: f64,
+    /// Configured weight
+    pub weight: u32,
+    /// Share of the score earned by this part (`ratio` × weight / total weight × 100)
+    pub points: f64,
+}
+
+/// Focus score of one local day with its breakdown
+/// 某一天的专注评分（0-100）及各项明细。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusScore {
+    pub date: NaiveDate,
+    /// Sum of the components' points, rounded
+    pub score: u8,
+    pub work_seconds: i64,
+    pub goal_seconds: i64,
+    pub break_count: usize,
+    pub completed_breaks: usize,
+    pub skipped_breaks: usize,
+    pub work_goal: FocusScoreComponent,
+    pub break_completion: FocusScoreComponent,
+    pub low_skips: FocusScoreComponent,
+}
+
+/// Glasses of water logged on one local day, saved across restarts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HydrationLog {
+    /// None before anything was logged
+    pub date: Option<NaiveDate>,
+    pub count: u32,
+}
+
+/// Hydration progress of today, e.g. "3 of 8 glasses"; payload of the
+/// `hydration-reminder` and `hydration-changed` events
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HydrationProgress {
+    pub date: NaiveDate,
+    pub count: u32,
+    pub goal: u32,
+}
+
+/// Rating statistics for a date range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RatingStats {
+    pub rated_sessions: usize,
+    pub average_rating: Option<f64>,
+    pub buckets: Vec<RatingBucket>,
+}
+
+/// High-contrast / reduced-motion preferences passed to the reminder page
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessibilityPrefs {
+    pub high_contrast: bool,
+    pub reduced_motion: bool,
+}
+
+/// One setting that an imported config would change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingChange {
+    /// camelCase field path, nested fields joined with '.' (e.g. `hotkeys.openTrayMenu`)
+    pub field: String,
+    pub old_value: serde_json::Value,
+    pub new_value: serde_json::Value,
+}
+
+/// Sessions bounds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionsBounds {
+    pub earliest_start: Option<DateTime<Utc>>,
+    pub latest_end: Option<DateTime<Utc>>,
+}
+
+fn default_session_list_limit() -> usize {
+    50
+}
+
+/// Filters and page of `list_sessions`; every filter is optional and they all
+/// have to match
+/// 会话列表查询：按时间区间、类型、跳过状态、时长、结束原因与文字筛选后分页。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionListQuery {
+    /// Sessions overlapping `[start_date, end_date]`. Keep `end_date` fixed
+    /// while paging so sessions recorded in between don't shift the pages.
+    #[serde(default)]
+    pub start_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub end_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub session_type: Option<SessionType>,
+    /// Text the notes, segment label or end reason (e.g. `power-interrupt`)
+    /// contain (case-insensitive).
+    /// 在备注、分段名称与结束原因中查找的文字（不区分大小写）。
+    #[serde(default)]
+    pub search: Option<String>,
+    #[serde(default)]
+    pub is_skipped: Option<bool>,
+    #[serde(default)]
+    pub end_reason: Option<SessionEndReason>,
+    /// 实际时长范围（秒，含边界）。
+    #[serde(default)]
+    pub min_duration_seconds: Option<i64>,
+    #[serde(default)]
+    pub max_duration_seconds: Option<i64>,
+    #[serde(default)]
+    pub offset: usize,
+    #[serde(default = "default_session_list_limit")]
+    pub limit: usize,
+}
+
+/// One page of `list_sessions`, newest first, and how many sessions matched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionListPage {
+    pub sessions: Vec<Session>,
+    pub total: usize,
+}
+
+/// Analytics query parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticsQuery {
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+}
+
+/// Monitor information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    pub id: u32,
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub is_primary: bool,
+}
+
+/// Outcome of one part of the reminder output test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum OutputCheck {
+    Passed,
+    Skipped { reason: String },
+    Failed { error: String },
+}
+
+/// Result of `test_reminder_output`, one entry per output channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderOutputTest {
+    pub sound: OutputCheck,
+    pub notification: OutputCheck,
+    pub preview: OutputCheck,
+}
+
+/// Sound preference plus the transient mute flag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioStatus {
+    /// Saved `play_sound` preference
+    pub play_sound: bool,
+    /// Runtime mute ("I'm in a call"), cleared on restart
+    pub muted: bool,
+    /// Whether sounds and rest music may play right now
+    pub sound_enabled: bool,
+}
+
+/// Build information for bug reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppInfo {
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+    /// Short git commit embedded by build.rs (None when built outside a checkout)
+    pub commit: Option<String>,
+    /// When the binary was compiled (from build.rs)
+    pub build_timestamp: Option<DateTime<Utc>>,
+    /// Development builds (e.g. "0.0.0-dev") never auto-install updates
+    pub is_dev_build: bool,
+    /// A `portable` marker file sits next to the executable
+    pub is_portable: bool,
+    pub data_dir: String,
+}
+
+/// Which candidate the data directory was resolved from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageLocation {
+    /// The platform app data directory (the normal case)
+    AppData,
+    /// A `data` directory next to the executable
+    ExecutableDir,
+    /// A subdirectory of the OS temp dir; data may not survive a reboot
+    TempDir,
+}
+
+/// Where settings and sessions are stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageInfo {
+    pub data_dir: String,
+    pub location: StorageLocation,
+    /// Why the app data directory could not be used (None when it was)
+    pub primary_error: Option<String>,
+}
+
+/// Display power monitor health (watchdog state), for diagnostics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerMonitorHealth {
+    /// The monitor is supervised on this platform (Windows only)
+    pub supported: bool,
+    /// The message loop is running
+    pub running: bool,
+    /// Running with a recent heartbeat
+    pub healthy: bool,
+    pub last_heartbeat: Option<DateTime<Utc>>,
+    /// Times the watchdog restarted the monitor since launch
+    pub restarts: u32,
+    pub last_error: Option<String>,
+}
+
+/// Timer ticker liveness (`get_ticker_health`), for diagnostics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TickerHealth {
+    /// Ticked within the stall limit
+    pub healthy: bool,
+    pub last_tick: Option<DateTime<Utc>>,
+    /// Times the watchdog restarted a stalled ticker since launch
+    pub restarts: u32,
+    /// Ticks that panicked or failed since launch; the ticker kept going
+    pub panics: u32,
+    pub errors: u32,
+    pub last_error: Option<String>,
+}
+
+/// Countdown accuracy over the current phase (`get_timer_accuracy`), for diagnostics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerAccuracy {
+    pub phase: TimerPhase,
+    pub state: TimerState,
+    pub phase_end_time: Option<DateTime<Utc>>,
+    pub remaining_seconds: u32,
+    /// Ticks compared so far in this phase
+    pub samples: u32,
+    /// Largest gap between the shown and the expected remaining time
+    pub max_drift_ms: i64,
+    /// Gap measured by the latest tick
+    pub last_drift_ms: i64,
+}
+
+/// System status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemStatus {
+    pub is_fullscreen: bool,
+    pub is_do_not_disturb: bool,
+    /// Running inside a remote desktop (RDP) session
+    pub is_remote_session: bool,
+    /// False only once a connectivity probe found no network
+    pub is_online: bool,
+    /// False when no tray icon could be created (no status notifier host)
+    pub tray_available: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every cause, in declaration order; the match fails to compile when one is added.
+    fn all_causes() -> Vec<PhaseChangeCause> {
+        let causes = vec![
+            PhaseChangeCause::Startup,
+            PhaseChangeCause::UserStart,
+            PhaseChangeCause::UserSkip,
+            PhaseChangeCause::AutoCycle,
+            PhaseChangeCause::Suppression,
+            PhaseChangeCause::FlowMode,
+            PhaseChangeCause::ResumeFromIdle,
+            PhaseChangeCause::FirstActivity,
+            PhaseChangeCause::ReminderClosed,
+            PhaseChangeCause::Recovery,
+        ];
+        for cause in &causes {
+            match cause {
+                PhaseChangeCause::Startup
+                | PhaseChangeCause::UserStart
+                | PhaseChangeCause::UserSkip
+                | PhaseChangeCause::AutoCycle
+                | PhaseChangeCause::Suppression
+                | PhaseChangeCause::FlowMode
+                | PhaseChangeCause::ResumeFromIdle
+                | PhaseChangeCause::FirstActivity
+                | PhaseChangeCause::ReminderClosed
+                | PhaseChangeCause::Recovery => {}
+            }
+        }
+        causes
+    }
+
+    #[test]
+    fn phase_change_payloads_match_the_snapshot() {
+        let payloads: Vec<String> = all_causes()
+            .into_iter()
+            .zip([
+                TimerPhase::Work,
+                TimerPhase::Work,
+                TimerPhase::Break,
+                TimerPhase::Break,
+                TimerPhase::Work,
+                TimerPhase::Work,
+                TimerPhase::Work,
+                TimerPhase::Work,
+                TimerPhase::Work,
+                TimerPhase::Idle,
+            ])
+            .map(|(cause, phase)| serde_json::to_string(&PhaseChange { phase, cause }).unwrap())
+            .collect();
+
+        assert_eq!(
+            payloads,
+            [
+                r#"{"phase":"work","cause":"startup"}"#,
+                r#"{"phase":"work","cause":"user-start"}"#,
+                r#"{"phase":"break","cause":"user-skip"}"#,
+                r#"{"phase":"break","cause":"auto-cycle"}"#,
+                r#"{"phase":"work","cause":"suppression"}"#,
+                r#"{"phase":"work","cause":"flow-mode"}"#,
+                r#"{"phase":"work","cause":"resume-from-idle"}"#,
+                r#"{"phase":"work","cause":"first-activity"}"#,
+                r#"{"phase":"work","cause":"reminder-closed"}"#,
+                r#"{"phase":"idle","cause":"recovery"}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn phase_change_payloads_read_back() {
+        for cause in all_causes() {
+            let json = serde_json::to_string(&PhaseChange {
+                phase: TimerPhase::Transition,
+                cause,
+            })
+            .unwrap();
+            let back: PhaseChange = serde_json::from_str(&json).unwrap();
+            assert_eq!(back.cause, cause);
+            assert_eq!(back.phase, TimerPhase::Transition);
+        }
+    }
+
+    #[test]
+    fn app_info_serialization_matches_the_snapshot() {
+        let info = AppInfo {
+            version: "1.4.2".to_string(),
+            os: "windows".to_string(),
+            arch: "x86_64".to_string(),
+            commit: Some("1a2b3c4".to_string()),
+            build_timestamp: DateTime::from_timestamp(1_772_442_000, 0),
+            is_dev_build: false,
+            is_portable: true,
+            data_dir: "C:\\Users\\me\\AppData\\Roaming\\com.youtonghy.resty".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_string_pretty(&info).unwrap(),
+            r#"{
+  "version": "1.4.2",
+  "os": "windows",
+  "arch": "x86_64",
+  "commit": "1a2b3c4",
+  "buildTimestamp": "2026-03-02T09:00:00Z",
+  "isDevBuild": false,
+  "isPortable": true,
+  "dataDir": "C:\\Users\\me\\AppData\\Roaming\\com.youtonghy.resty"
+}"#
+        );
+
+        // Built outside a checkout: the unknown fields stay present as null
+        let bare = AppInfo {
+            commit: None,
+            build_timestamp: None,
+            is_dev_build: true,
+            ..info
+        };
+        assert_eq!(
+            serde_json::to_value(&bare).unwrap(),
+            serde_json::json!({
+                "version": "1.4.2",
+                "os": "windows",
+                "arch": "x86_64",
+                "commit": null,
+                "buildTimestamp": null,
+                "isDevBuild": true,
+                "isPortable": true,
+                "dataDir": "C:\\Users\\me\\AppData\\Roaming\\com.youtonghy.resty",
+            })
+        );
+    }
+}
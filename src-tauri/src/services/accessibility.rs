@@ -0,0 +1,165 @@
+use crate::models::AccessibilityPrefs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long an OS detection result is reused before querying again.
+const DETECTION_TTL: Duration = Duration::from_secs(30);
+
+static DETECTION_CACHE: Mutex<Option<(Instant, AccessibilityPrefs)>> = Mutex::new(None);
+static FORCE_HIGH_CONTRAST: AtomicBool = AtomicBool::new(false);
+static FORCE_REDUCED_MOTION: AtomicBool = AtomicBool::new(false);
+
+/// Remember the settings overrides (`force_high_contrast` / `force_reduced_motion`).
+/// 记录设置中的强制开关，开启时忽略系统检测结果。
+pub fn set_overrides(force_high_contrast: bool, force_reduced_motion: bool) {
+    FORCE_HIGH_CONTRAST.store(force_high_contrast, Ordering::SeqCst);
+    FORCE_REDUCED_MOTION.store(force_reduced_motion, Ordering::SeqCst);
+}
+
+/// Effective preferences: OS detection combined with the settings overrides.
+/// 返回系统检测结果与设置强制开关合并后的无障碍偏好。
+pub fn current_prefs() -> AccessibilityPrefs {
+    let detected = detect_cached();
+    AccessibilityPrefs {
+        high_contrast: FORCE_HIGH_CONTRAST.load(Ordering::SeqCst) || detected.high_contrast,
+        reduced_motion: FORCE_REDUCED_MOTION.load(Ordering::SeqCst) || detected.reduced_motion,
+    }
+}
+
+/// OS detection, cached for `DETECTION_TTL` so reminder creation stays fast.
+fn detect_cached() -> AccessibilityPrefs {
+    if let Ok(cache) = DETECTION_CACHE.lock() {
+        if let Some((at, prefs)) = cache.as_ref() {
+            if at.elapsed() < DETECTION_TTL {
+                return prefs.clone();
+            }
+        }
+    }
+
+    let prefs = detect();
+    if let Ok(mut cache) = DETECTION_CACHE.lock() {
+        *cache = Some((Instant::now(), prefs.clone()));
+    }
+    prefs
+}
+
+#[cfg(windows)]
+use windows_impl::detect;
+
+#[cfg(target_os = "macos")]
+use macos_impl::detect;
+
+#[cfg(target_os = "linux")]
+use linux_impl::detect;
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+fn detect() -> AccessibilityPrefs {
+    AccessibilityPrefs::default()
+}
+
+#[cfg(windows)]
+mod windows_impl {
+    use super::*;
+    use std::ffi::c_void;
+
+    use windows::Win32::UI::Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION, SPI_GETHIGHCONTRAST,
+        SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+    };
+
+    pub(super) fn detect() -> AccessibilityPrefs {
+        AccessibilityPrefs {
+            high_contrast: high_contrast_enabled(),
+            reduced_motion: !client_area_animation_enabled(),
+        }
+    }
+
+    fn high_contrast_enabled() -> bool {
+        let mut info = HIGHCONTRASTW {
+            cbSize: std::mem::size_of::<HIGHCONTRASTW>() as u32,
+            ..Default::default()
+        };
+        unsafe {
+            SystemParametersInfoW(
+                SPI_GETHIGHCONTRAST,
+                info.cbSize,
+                Some(&mut info as *mut HIGHCONTRASTW as *mut c_void),
+                SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS::default(),
+            )
+            .is_ok()
+                && info.dwFlags.0 & HCF_HIGHCONTRASTON.0 != 0
+        }
+    }
+
+    /// "Show animations in Windows" (Settings > Accessibility > Visual effects).
+    fn client_area_animation_enabled() -> bool {
+        let mut enabled: i32 = 1;
+        unsafe {
+            if SystemParametersInfoW(
+                SPI_GETCLIENTAREAANIMATION,
+                0,
+                Some(&mut enabled as *mut i32 as *mut c_void),
+                SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS::default(),
+            )
+            .is_err()
+            {
+                return true;
+            }
+        }
+        enabled != 0
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos_impl {
+    use super::*;
+    use cocoa::base::{id, BOOL, NO};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    pub(super) fn detect() -> AccessibilityPrefs {
+        unsafe {
+            let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let high_contrast: BOOL =
+                msg_send![workspace, accessibilityDisplayShouldIncreaseContrast];
+            let reduced_motion: BOOL = msg_send![workspace, accessibilityDisplayShouldReduceMotion];
+            AccessibilityPrefs {
+                high_contrast: high_contrast != NO,
+                reduced_motion: reduced_motion != NO,
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux_impl {
+    use super::*;
+    use std::process::Command;
+
+    /// Best effort: GNOME settings via `gsettings`, plus the GTK_THEME override.
+    pub(super) fn detect() -> AccessibilityPrefs {
+        let theme_is_high_contrast = std::env::var("GTK_THEME")
+            .map(|theme| theme.to_lowercase().contains("highcontrast"))
+            .unwrap_or(false);
+        AccessibilityPrefs {
+            high_contrast: theme_is_high_contrast
+                || gsettings("org.gnome.desktop.a11y.interface", "high-contrast").as_deref()
+                    == Some("true"),
+            reduced_motion: gsettings("org.gnome.desktop.interface", "enable-animations")
+                .as_deref()
+                == Some("false"),
+        }
+    }
+
+    fn gsettings(schema: &str, key: &str) -> Option<String> {
+        let output = Command::new("gsettings")
+            .args(["get", schema, key])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
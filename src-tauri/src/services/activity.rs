@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use super::{DatabaseService, TimerService};
+use crate::models::PhaseChangeCause;
+use tauri::AppHandle;
+use tokio::time::{self, Duration as TokioDuration, MissedTickBehavior};
+
+/// How often the input idle time is sampled while waiting for the day's first activity.
+const POLL_INTERVAL_SECONDS: u64 = 5;
+/// Continuous input needed before the first work session of the day starts.
+/// 持续使用多少秒后才视为当天开始工作（避免碰一下鼠标就开始计时）。
+const FIRST_ACTIVITY_SECONDS: u64 = 60;
+/// How often the time at the computer is brought up to date.
+const SCREEN_TIME_SAMPLE_SECONDS: u64 = 15;
+/// Every this many samples the screen time is saved and shown in the tray tooltip.
+const SCREEN_TIME_SAVE_EVERY: u32 = 4;
+
+/// Watch for the first sustained user activity of the day and start work then
+/// (settings `auto_start_on_first_activity`). Input is sampled only while the
+/// timer waits for it; where the idle time cannot be read, work starts right away.
+/// 监听当天首次持续的键鼠活动并开始工作；无法读取空闲时间的平台上直接开始。
+pub fn start_first_activity_monitor(timer: Arc<TimerService>) {
+    super::panics::spawn_guarded("firstActivity", async move {
+        let mut interval = time::interval(TokioDuration::from_secs(POLL_INTERVAL_SECONDS));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        // Seconds of uninterrupted activity observed so far
+        let mut active_seconds = 0;
+        loop {
+            interval.tick().await;
+            if !timer.awaiting_first_activity() {
+                active_seconds = 0;
+                continue;
+            }
+
+            let sustained = match seconds_since_last_input() {
+                // Input since the previous sample keeps the streak going
+                Some(idle) if idle <= POLL_INTERVAL_SECONDS => {
+                    active_seconds += POLL_INTERVAL_SECONDS;
+                    active_seconds >= FIRST_ACTIVITY_SECONDS
+                }
+                Some(_) => {
+                    active_seconds = 0;
+                    false
+                }
+                None => true,
+            };
+            if sustained {
+                active_seconds = 0;
+                if let Err(e) = timer.start_work(PhaseChangeCause::FirstActivity) {
+                    eprintln!("Failed to start work on first activity: {}", e);
+                }
+            }
+        }
+    });
+}
+
+/// Track how long the user has been at the computer since their last real break:
+/// the time is sampled against the input idle time, saved so a restart on the
+/// same day continues it, and shown in the tray tooltip.
+/// 定期更新连续使用电脑的时长，保存到磁盘并刷新托盘提示。
+pub fn start_screen_time_monitor(
+    app: AppHandle,
+    timer: Arc<TimerService>,
+    database: Arc<tokio::sync::Mutex<DatabaseService>>,
+) {
+    super::panics::spawn_guarded("screenTime", async move {
+        let mut interval = time::interval(TokioDuration::from_secs(SCREEN_TIME_SAMPLE_SECONDS));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut samples: u32 = 0;
+        loop {
+            interval.tick().await;
+            let continuity = timer.sample_screen_time(seconds_since_last_input());
+            samples = samples.wrapping_add(1);
+            if !samples.is_multiple_of(SCREEN_TIME_SAVE_EVERY) {
+                continue;
+            }
+            let db = database.lock().await;
+            if let Err(e) = db.save_screen_continuity(&continuity) {
+                eprintln!("Failed to save screen time: {}", e);
+            }
+            let language = db.load_settings().await.unwrap_or_default().language;
+            drop(db);
+            crate::refresh_tray_tooltip(&app, &language);
+        }
+    });
+}
+
+/// Sample the input idle time so work sessions record how long the user was
+/// actually at the keyboard (settings `activity_sample_seconds` /
+/// `activity_idle_threshold_seconds`). Only the time since the last input is
+/// read, never the input itself.
+/// 定期采样键鼠空闲时间，累计工作会话的实际活跃时长（只读取距上次输入的时间）。
+pub fn start_active_time_monitor(timer: Arc<TimerService>) {
+    super::panics::spawn_guarded("activeTime", async move {
+        loop {
+            // Re-read each round so a changed interval applies without a restart
+            let sample_seconds = timer.activity_sample_seconds().max(1);
+            time::sleep(TokioDuration::from_secs(u64::from(sample_seconds))).await;
+            timer.sample_active_time(seconds_since_last_input(), sample_seconds);
+        }
+    });
+}
+
+/// Seconds since the last keyboard or mouse input, if the platform reports it.
+fn seconds_since_last_input() -> Option<u64> {
+    #[cfg(windows)]
+    {
+        windows_impl::seconds_since_last_input()
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        macos_impl::seconds_since_last_input()
+    }
+
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        None
+    }
+}
+
+#[cfg(windows)]
+mod windows_impl {
+    use windows::Win32::System::SystemInformation::GetTickCount;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    pub(super) fn seconds_since_last_input() -> Option<u64> {
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        unsafe {
+            if !GetLastInputInfo(&mut info).as_bool() {
+                return None;
+            }
+            // Both are 32-bit millisecond tick counts; wrapping covers the 49.7-day rollover
+            let idle_millis = GetTickCount().wrapping_sub(info.dwTime);
+            Some(u64::from(idle_millis) / 1000)
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos_impl {
+    /// `kCGEventSourceStateCombinedSessionState`
+    const COMBINED_SESSION_STATE: i32 = 0;
+    /// `kCGAnyInputEventType`
+    const ANY_INPUT_EVENT_TYPE: u32 = !0;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(source_state: i32, event_type: u32) -> f64;
+    }
+
+    pub(super) fn seconds_since_last_input() -> Option<u64> {
+        let seconds = unsafe {
+            CGEventSourceSecondsSinceLastEventType(COMBINED_SESSION_STATE, ANY_INPUT_EVENT_TYPE)
+        };
+        seconds.is_finite().then(|| seconds.max(0.0) as u64)
+    }
+}
@@ -0,0 +1,145 @@
+use crate::models::{AnomalyKind, AnomalySettings, Session, SessionAnomaly, SessionType};
+
+/// The anomaly `session` shows under `thresholds`, if any. Sessions still in
+/// progress or already dismissed are never flagged; when several thresholds
+/// match, the first of zero duration, break overrun and long work wins.
+/// 按阈值检测异常会话：进行中或已忽略的会话不参与检测，每个会话最多报告一种异常。
+pub fn detect(session: &Session, thresholds: &AnomalySettings) -> Option<SessionAnomaly> {
+    if session.in_progress || session.anomaly_dismissed {
+        return None;
+    }
+    let (kind, detail) = if session.duration <= 0 {
+        if !thresholds.flag_zero_duration {
+            return None;
+        }
+        (
+            AnomalyKind::ZeroDuration,
+            "session has no duration".to_string(),
+        )
+    } else {
+        match session.session_type {
+            SessionType::Break => {
+                // `planned_duration` already includes any extensions
+                let planned = session.planned_duration;
+                if planned <= 0 {
+                    return None;
+                }
+                let ratio = session.duration as f64 / planned as f64;
+                if ratio < thresholds.break_overrun_ratio {
+                    return None;
+                }
+                (
+                    AnomalyKind::BreakOverrun,
+                    format!("break ran {:.1}× its planned {} min", ratio, planned / 60),
+                )
+            }
+            SessionType::Work => {
+                let max_seconds = thresholds.max_work_minutes as i64 * 60;
+                if session.duration <= max_seconds {
+                    return None;
+                }
+                (
+                    AnomalyKind::LongWork,
+                    format!(
+                        "work session ran {} min (limit {} min)",
+                        session.duration / 60,
+                        thresholds.max_work_minutes
+                    ),
+                )
+            }
+        }
+    };
+    Some(SessionAnomaly {
+        session_id: session.id.clone(),
+        kind,
+        detail,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Finished, running and dismissed sessions with known anomalies.
+    const SESSIONS_FIXTURE: &str = include_str!("../../tests/fixtures/anomalies/sessions.json");
+
+    fn sessions() -> Vec<Session> {
+        serde_json::from_str(SESSIONS_FIXTURE).unwrap()
+    }
+
+    fn detect_all(thresholds: &AnomalySettings) -> Vec<SessionAnomaly> {
+        sessions()
+            .iter()
+            .filter_map(|session| detect(session, thresholds))
+            .collect()
+    }
+
+    fn anomaly(session_id: &str, kind: AnomalyKind, detail: &str) -> SessionAnomaly {
+        SessionAnomaly {
+            session_id: session_id.to_string(),
+            kind,
+            detail: detail.to_string(),
+        }
+    }
+
+    #[test]
+    fn default_thresholds_flag_the_known_anomalies() {
+        assert_eq!(
+            detect_all(&AnomalySettings::default()),
+            [
+                anomaly(
+                    "break-laptop-open",
+                    AnomalyKind::BreakOverrun,
+                    "break ran 3.2× its planned 5 min"
+                ),
+                anomaly(
+                    "work-forgot-to-stop",
+                    AnomalyKind::LongWork,
+                    "work session ran 570 min (limit 540 min)"
+                ),
+                anomaly(
+                    "work-zero",
+                    AnomalyKind::ZeroDuration,
+                    "session has no duration"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn thresholds_change_what_is_flagged() {
+        let thresholds = AnomalySettings {
+            break_overrun_ratio: 2.0,
+            max_work_minutes: 8 * 60,
+            flag_zero_duration: false,
+        };
+        let flagged: Vec<(String, AnomalyKind)> = detect_all(&thresholds)
+            .into_iter()
+            .map(|anomaly| (anomaly.session_id, anomaly.kind))
+            .collect();
+
+        assert_eq!(
+            flagged,
+            [
+                ("break-laptop-open".to_string(), AnomalyKind::BreakOverrun),
+                ("break-just-under".to_string(), AnomalyKind::BreakOverrun),
+                ("break-extended".to_string(), AnomalyKind::BreakOverrun),
+                ("work-forgot-to-stop".to_string(), AnomalyKind::LongWork),
+                ("work-at-limit".to_string(), AnomalyKind::LongWork),
+            ]
+        );
+    }
+
+    #[test]
+    fn dismissing_a_session_stops_its_anomaly() {
+        let thresholds = AnomalySettings::default();
+        let mut session = sessions()
+            .into_iter()
+            .find(|session| session.id == "break-laptop-open")
+            .unwrap();
+        assert!(detect(&session, &thresholds).is_some());
+
+        session.anomaly_dismissed = true;
+        assert_eq!(detect(&session, &thresholds), None);
+    }
+}
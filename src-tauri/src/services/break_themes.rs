@@ -0,0 +1,27 @@
+use crate::models::{BreakTheme, Settings};
+use chrono::{Datelike, Local, NaiveDate};
+
+/// Theme of `day`: themes take turns by calendar day, so the choice is the
+/// same all day long and changes at local midnight.
+/// 按日期轮换的休息主题，同一天内保持不变。
+pub fn theme_for(themes: &[BreakTheme], day: NaiveDate) -> Option<&BreakTheme> {
+    if themes.is_empty() {
+        return None;
+    }
+    let index = day.num_days_from_ce().rem_euclid(themes.len() as i32) as usize;
+    themes.get(index)
+}
+
+/// Theme of today (local date), if any themes are configured.
+pub fn today(themes: &[BreakTheme]) -> Option<&BreakTheme> {
+    theme_for(themes, Local::now().date_naive())
+}
+
+/// Rest music folder for today: the theme's folder when it sets one, else the
+/// `rest_music_directory` setting.
+pub fn music_directory(settings: &Settings) -> &str {
+    today(&settings.break_themes)
+        .and_then(|theme| theme.music_directory.as_deref())
+        .filter(|dir| !dir.trim().is_empty())
+        .unwrap_or(&settings.rest_music_directory)
+}
@@ -0,0 +1,140 @@
+use std::sync::Arc;
+
+use super::{DatabaseService, TimerService};
+use tauri::AppHandle;
+use tokio::time::{self, Duration as TokioDuration, MissedTickBehavior};
+
+/// How often microphone use is checked while breaks wait for calls.
+const POLL_INTERVAL_SECONDS: u64 = 15;
+
+/// Defer breaks while an audio/video call is running (settings
+/// `defer_breaks_during_calls`). A call is assumed while any app captures the
+/// microphone; the check reads what Windows already records for its privacy
+/// settings, so it needs no microphone permission. On other platforms nothing
+/// is detected and breaks are never held.
+/// 检测通话（麦克风正在被使用）并推迟休息；仅 Windows 可检测，且无需麦克风权限。
+pub fn start_call_monitor(
+    app: AppHandle,
+    timer: Arc<TimerService>,
+    database: Arc<tokio::sync::Mutex<DatabaseService>>,
+) {
+    if !cfg!(windows) {
+        return;
+    }
+    super::panics::spawn_guarded("callMonitor", async move {
+        let mut interval = time::interval(TokioDuration::from_secs(POLL_INTERVAL_SECONDS));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut in_call = false;
+        loop {
+            interval.tick().await;
+            let active = timer.defers_breaks_during_calls() && microphone_in_use();
+            if active == in_call {
+                continue;
+            }
+            in_call = active;
+            if let Err(e) = timer.set_call_in_progress(active) {
+                eprintln!("Failed to update call state: {}", e);
+            }
+            let language = database
+                .lock()
+                .await
+                .load_settings()
+                .await
+                .unwrap_or_default()
+                .language;
+            crate::refresh_tray_tooltip(&app, &language);
+        }
+    });
+}
+
+/// Whether any app is capturing the microphone right now.
+fn microphone_in_use() -> bool {
+    #[cfg(windows)]
+    {
+        windows_impl::microphone_in_use()
+    }
+
+    #[cfg(not(windows))]
+    {
+        false
+    }
+}
+
+#[cfg(windows)]
+mod windows_impl {
+    use std::ffi::c_void;
+    use windows::core::{HSTRING, PCWSTR, PWSTR};
+    use windows::Win32::Foundation::{ERROR_NO_MORE_ITEMS, ERROR_SUCCESS};
+    use windows::Win32::System::Registry::{
+        RegCloseKey, RegEnumKeyExW, RegGetValueW, RegOpenKeyExW, HKEY, HKEY_CURRENT_USER, KEY_READ,
+        RRF_RT_REG_QWORD,
+    };
+
+    /// Per-app microphone use as recorded by the privacy settings: each app has
+    /// a subkey whose `LastUsedTimeStop` is 0 while it is capturing. Desktop
+    /// (non-packaged) apps sit one level deeper under `NonPackaged`.
+    const MICROPHONE_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore\microphone";
+
+    pub(super) fn microphone_in_use() -> bool {
+        any_app_capturing(MICROPHONE_KEY)
+            || any_app_capturing(&format!(r"{}\NonPackaged", MICROPHONE_KEY))
+    }
+
+    fn any_app_capturing(path: &str) -> bool {
+        let mut key = HKEY::default();
+        let path = HSTRING::from(path);
+        unsafe {
+            if RegOpenKeyExW(HKEY_CURRENT_USER, &path, None, KEY_READ, &mut key) != ERROR_SUCCESS {
+                return false;
+            }
+            let mut capturing = false;
+            let mut index = 0;
+            loop {
+                let mut name = [0u16; 512];
+                let mut len = name.len() as u32;
+                let result = RegEnumKeyExW(
+                    key,
+                    index,
+                    Some(PWSTR(name.as_mut_ptr())),
+                    &mut len,
+                    None,
+                    None,
+                    None,
+                    None,
+                );
+                if result == ERROR_NO_MORE_ITEMS {
+                    break;
+                }
+                index += 1;
+                if result != ERROR_SUCCESS {
+                    continue;
+                }
+                let subkey = PCWSTR(name.as_ptr());
+                let started = read_qword(key, subkey, "LastUsedTimeStart");
+                let stopped = read_qword(key, subkey, "LastUsedTimeStop");
+                if matches!((started, stopped), (Some(start), Some(0)) if start > 0) {
+                    capturing = true;
+                    break;
+                }
+            }
+            let _ = RegCloseKey(key);
+            capturing
+        }
+    }
+
+    unsafe fn read_qword(key: HKEY, subkey: PCWSTR, value: &str) -> Option<u64> {
+        let value = HSTRING::from(value);
+        let mut data = 0u64;
+        let mut size = std::mem::size_of::<u64>() as u32;
+        let result = RegGetValueW(
+            key,
+            subkey,
+            &value,
+            RRF_RT_REG_QWORD,
+            None,
+            Some(&mut data as *mut u64 as *mut c_void),
+            Some(&mut size),
+        );
+        (result == ERROR_SUCCESS).then_some(data)
+    }
+}
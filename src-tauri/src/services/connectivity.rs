@@ -0,0 +1,72 @@
+use crate::models::ConnectivityChanged;
+use anyhow::{anyhow, Result};
+use std::sync::atomic::{AtomicU8, Ordering};
+use tauri::{AppHandle, Emitter};
+use tokio::net::TcpStream;
+use tokio::time::{self, Duration as TokioDuration, MissedTickBehavior};
+
+/// How often connectivity is re-checked.
+const PROBE_INTERVAL_SECONDS: u64 = 60;
+/// Per-endpoint connect timeout of a probe.
+const PROBE_TIMEOUT_SECONDS: u64 = 3;
+/// Endpoints tried in order; reaching any one counts as online. Plain
+/// addresses keep the probe independent of DNS.
+/// 依次尝试的探测地址（AliDNS、Cloudflare），任一可连接即视为在线。
+const PROBE_ENDPOINTS: &[&str] = &["223.5.5.5:53", "1.1.1.1:443"];
+
+const UNKNOWN: u8 = 0;
+const ONLINE: u8 = 1;
+const OFFLINE: u8 = 2;
+
+/// Last probe result; unknown until the first probe finishes.
+static STATE: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+/// Whether the last probe found no network. Unknown counts as online, so
+/// requests are still attempted before the first probe has finished.
+pub fn is_known_offline() -> bool {
+    STATE.load(Ordering::SeqCst) == OFFLINE
+}
+
+/// Fail fast instead of waiting out a request timeout when known offline.
+/// 已知离线时立即返回错误，避免网络请求等待超时。
+pub fn ensure_online() -> Result<()> {
+    if is_known_offline() {
+        return Err(anyhow!("Offline; network request skipped"));
+    }
+    Ok(())
+}
+
+/// Probe connectivity now and then every minute, emitting
+/// `connectivity-changed` whenever the result changes.
+/// 定期探测网络连通性，状态变化时发送 `connectivity-changed` 事件。
+pub fn start_connectivity_monitor(app: AppHandle) {
+    super::panics::spawn_guarded("connectivity", async move {
+        let mut interval = time::interval(TokioDuration::from_secs(PROBE_INTERVAL_SECONDS));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let online = probe().await;
+            let next = if online { ONLINE } else { OFFLINE };
+            if STATE.swap(next, Ordering::SeqCst) == next {
+                continue;
+            }
+            eprintln!(
+                "[Connectivity] {}",
+                if online { "online" } else { "offline" }
+            );
+            let _ = app.emit("connectivity-changed", ConnectivityChanged { online });
+        }
+    });
+}
+
+async fn probe() -> bool {
+    for endpoint in PROBE_ENDPOINTS {
+        let connect = TcpStream::connect(*endpoint);
+        if let Ok(Ok(_)) =
+            time::timeout(TokioDuration::from_secs(PROBE_TIMEOUT_SECONDS), connect).await
+        {
+            return true;
+        }
+    }
+    false
+}
@@ -1,10 +1,20 @@
+use super::focus_score;
+use super::lifetime_stats::LifetimeRollup;
+use super::rest_debt;
 use crate::models::{
     default_work_segments, rest_music_directory_default, AchievementUnlock, AnalyticsData,
-    AnalyticsQuery, Session, SessionType, SessionsBounds, Settings,
+    AnalyticsDelta, AnalyticsQuery, DailyActivity, DurationBucket, DurationHistogram,
+    ExerciseRotation, FocusScore, HydrationLog, HydrationProgress, LifetimeStats, RatingBucket,
+    RatingStats, ScreenContinuity, Session, SessionEndReason, SessionListPage, SessionListQuery,
+    SessionType, SessionsBounds, Settings, StorageFailure, StorageFormat, StorageInfo,
+    StorageLocation, WorkSegment,
 };
+use crate::utils::encryption::{self, DataKey, EncryptionHeader};
 use crate::utils::{AppError, AppResult};
-use chrono::Utc;
-use std::path::PathBuf;
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveDate, NaiveTime, Utc};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::Mutex;
 
@@ -17,6 +27,152 @@ const POWER_INTERRUPT_WORK_NOTE: &str = "power-interrupt-work";
 
 const SECONDS_PER_HOUR: i64 = 3600;
 
+/// Keys missing from settings files written before segmented work existed.
+const PRE_SEGMENT_SETTINGS_KEYS: &[&str] =
+    &["segmentedWorkEnabled", "workSegments", "silentAutostart"];
+
+/// Largest histogram bucket accepted by `get_duration_histogram` (one day)
+const MAX_HISTOGRAM_BUCKET_MINUTES: u32 = 24 * 60;
+
+/// Pause before retrying a sessions write that failed transiently.
+const WRITE_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// A break counts as completed only if it reached the configured share of its plan.
+/// 仅当实际时长达到计划时长的指定比例时才计为完成，过短的休息计入跳过。
+pub(crate) fn is_completed_break(session: &Session, min_completion_ratio: f64) -> bool {
+    !session.is_skipped
+        && (session.planned_duration <= 0
+            || session.duration as f64 >= session.planned_duration as f64 * min_completion_ratio)
+}
+
+/// Errors worth one more try: interrupted or timed-out writes, and on Windows
+/// a file briefly held open by another process (antivirus, backup, indexer).
+/// Full disks and missing permissions are not retried.
+/// 可重试一次的暂时性写入错误。
+fn is_transient_io_error(err: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+
+    if matches!(
+        err.kind(),
+        ErrorKind::Interrupted | ErrorKind::WouldBlock | ErrorKind::TimedOut
+    ) {
+        return true;
+    }
+    // ERROR_SHARING_VIOLATION, ERROR_LOCK_VIOLATION
+    cfg!(windows) && matches!(err.raw_os_error(), Some(32) | Some(33))
+}
+
+/// Whether `session` passes every filter of `query`; `search` is the query's
+/// search text, trimmed and lowercased, found in the notes, the segment label
+/// or the end reason.
+fn session_matches(session: &Session, query: &SessionListQuery, search: Option<&str>) -> bool {
+    let excluded = matches!(query.start_date, Some(start) if session.end_time < start)
+        || matches!(query.end_date, Some(end) if session.start_time > end)
+        || matches!(query.session_type, Some(ref kind) if *kind != session.session_type)
+        || matches!(query.is_skipped, Some(skipped) if skipped != session.is_skipped)
+        || matches!(query.end_reason, Some(reason) if Some(reason) != session.end_reason)
+        || matches!(query.min_duration_seconds, Some(min) if session.duration < min)
+        || matches!(query.max_duration_seconds, Some(max) if session.duration > max);
+    let text_found = search.is_none_or(|text| {
+        [
+            session.notes.as_deref(),
+            session.segment_label.as_deref(),
+            session.end_reason.map(SessionEndReason::as_str),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|field| field.to_lowercase().contains(text))
+    });
+    !excluded && text_found
+}
+
+/// The page of `sessions` that `query` selects, newest first (ties by id).
+fn page_sessions(sessions: Vec<Session>, query: &SessionListQuery) -> SessionListPage {
+    let search = query
+        .search
+        .as_deref()
+        .map(|text| text.trim().to_lowercase())
+        .filter(|text| !text.is_empty());
+    let mut matches: Vec<Session> = sessions
+        .into_iter()
+        .filter(|s| session_matches(s, query, search.as_deref()))
+        .collect();
+    matches.sort_by(|a, b| {
+        b.start_time
+            .cmp(&a.start_time)
+            .then_with(|| b.id.cmp(&a.id))
+    });
+
+    let total = matches.len();
+    let sessions = matches
+        .into_iter()
+        .skip(query.offset)
+        .take(query.limit)
+        .collect();
+    SessionListPage { sessions, total }
+}
+
+/// How replacing `previous` with `current` changes the analytics totals.
+/// 以同一会话的旧版本为基准计算增量，避免占位记录多次写入时重复累加。
+fn analytics_delta(
+    previous: Option<&Session>,
+    current: &Session,
+    min_completion_ratio: f64,
+) -> AnalyticsDelta {
+    // [work seconds, break seconds, breaks, completed, skipped]
+    let contribution = |s: &Session| -> [i64; 5] {
+        match s.session_type {
+            SessionType::Work => [s.duration, 0, 0, 0, 0],
+            SessionType::Break => {
+                let completed = is_completed_break(s, min_completion_ratio) as i64;
+                [0, s.duration, 1, completed, 1 - completed]
+            }
+        }
+    };
+    let new = contribution(current);
+    let old = previous.map(contribution).unwrap_or_default();
+
+    AnalyticsDelta {
+        session_id: current.id.clone(),
+        date: current.start_time.with_timezone(&Local).date_naive(),
+        work_seconds: new[0] - old[0],
+        break_seconds: new[1] - old[1],
+        break_count: new[2] - old[2],
+        completed_breaks: new[3] - old[3],
+        skipped_breaks: new[4] - old[4],
+    }
+}
+
+/// Active against elapsed work time per local day (of the session start).
+/// Sessions without `active_seconds` (recorded before it was tracked) are left
+/// out of both sums rather than counted as idle.
+/// 按本地日期汇总活跃时长与对应会话的工作时长；未记录活跃时长的旧会话不参与比例。
+fn daily_activity(sessions: &[&Session]) -> Vec<DailyActivity> {
+    let mut days: BTreeMap<NaiveDate, (i64, i64)> = BTreeMap::new();
+    for session in sessions {
+        let Some(active) = session.active_seconds else {
+            continue;
+        };
+        if session.session_type != SessionType::Work {
+            continue;
+        }
+        let day = days
+            .entry(session.start_time.with_timezone(&Local).date_naive())
+            .or_default();
+        day.0 += active;
+        day.1 += session.duration.max(0);
+    }
+    days.into_iter()
+        .map(|(date, (active_seconds, tracked_seconds))| DailyActivity {
+            date,
+            active_seconds,
+            tracked_seconds,
+            activity_ratio: (tracked_seconds > 0)
+                .then(|| (active_seconds as f64 / tracked_seconds as f64).min(1.0)),
+        })
+        .collect()
+}
+
 /// Work milestones: 10, 50, 100, 500, 1000, then +500 infinitely.
 fn work_hour_thresholds(total_seconds: i64) -> Vec<i64> {
     let total_hours = total_seconds / SECONDS_PER_HOUR;
@@ -63,32 +219,161 @@ fn break_hour_thresholds(total_seconds: i64) -> Vec<i64> {
     thresholds
 }
 
+/// Serialize sessions in the given on-disk format.
+fn encode_sessions(sessions: &[Session], format: StorageFormat) -> AppResult<String> {
+    let serialize_error = |e: serde_json::Error| {
+        AppError::DatabaseError(format!("Failed to serialize sessions: {}", e))
+    };
+    match format {
+        StorageFormat::Json => serde_json::to_string_pretty(sessions).map_err(serialize_error),
+        StorageFormat::Ndjson => {
+            let mut out = String::new();
+            for session in sessions {
+                out.push_str(&serde_json::to_string(session).map_err(serialize_error)?);
+                out.push('\n');
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// Parse sessions written in the given on-disk format.
+fn decode_sessions(content: &str, format: StorageFormat) -> AppResult<Vec<Session>> {
+    match format {
+        StorageFormat::Json => serde_json::from_str(content)
+            .map_err(|e| AppError::DatabaseError(format!("Failed to parse sessions: {}", e))),
+        StorageFormat::Ndjson => content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line).map_err(|e| {
+                    AppError::DatabaseError(format!(
+                        "Failed to parse sessions line {}: {}",
+                        index + 1,
+                        e
+                    ))
+                })
+            })
+            .collect(),
+    }
+}
+
+/// Resolve the data directory: the app data dir, then `data` next to the executable,
+/// then a temp subdirectory. A candidate is used only if it can be created.
+/// 依次尝试应用数据目录、可执行文件旁的 data 目录与系统临时目录，返回首个可创建的目录。
+fn resolve_data_dir(app: &AppHandle) -> (PathBuf, StorageLocation, Option<String>) {
+    let primary_error = match app.path().app_data_dir() {
+        Ok(dir) => match std::fs::create_dir_all(&dir) {
+            Ok(()) => return (dir, StorageLocation::AppData, None),
+            Err(e) => format!("{}: {}", dir.display(), e),
+        },
+        Err(e) => e.to_string(),
+    };
+
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join("data")));
+    let temp_dir = std::env::temp_dir().join(&app.config().identifier);
+    let mut fallbacks: Vec<(PathBuf, StorageLocation)> = Vec::new();
+    if let Some(dir) = exe_dir {
+        fallbacks.push((dir, StorageLocation::ExecutableDir));
+    }
+    fallbacks.push((temp_dir.clone(), StorageLocation::TempDir));
+
+    for (dir, location) in fallbacks {
+        match std::fs::create_dir_all(&dir) {
+            Ok(()) => {
+                eprintln!(
+                    "WARNING: app data directory unavailable ({}); storing data in {}. \
+                     Settings may not persist across launches.",
+                    primary_error,
+                    dir.display()
+                );
+                return (dir, location, Some(primary_error));
+            }
+            Err(e) => eprintln!("Data directory fallback {} unusable: {}", dir.display(), e),
+        }
+    }
+
+    // Nothing was writable; keep the temp dir so `initialize` reports the failure
+    eprintln!(
+        "WARNING: no writable data directory found (app data: {}); settings will not be saved.",
+        primary_error
+    );
+    (temp_dir, StorageLocation::TempDir, Some(primary_error))
+}
+
 /// Database service for managing persistent data.
 /// 使用本地 JSON 文件持久化设置与会话历史。
 pub struct DatabaseService {
     app: AppHandle,
     settings: Mutex<Settings>,
     sessions: Mutex<Vec<Session>>,
+    // Sessions that ended before this were set aside by the load window
+    // (settings `analytics_load_window_days`); None = all are in `sessions`
+    archive_before: std::sync::Mutex<Option<DateTime<Utc>>>,
+    // The sessions set aside, kept as loaded so writes need not re-read the file
+    archived: std::sync::Mutex<Vec<Session>>,
     achievements: Mutex<Vec<AchievementUnlock>>,
+    // All-time totals (settings `lifetime_stats_enabled`)
+    lifetime: Mutex<LifetimeRollup>,
+    // Start of each local day's first work (settings `auto_start_on_first_activity`)
+    day_starts: Mutex<Vec<DateTime<Utc>>>,
     data_dir: PathBuf,
+    // Format of the session file (settings `storage_format`)
+    storage_format: std::sync::Mutex<StorageFormat>,
+    storage_location: StorageLocation,
+    // Why the app data directory was not used
+    primary_storage_error: Option<String>,
+    // Set once loading adjusted the settings file (migration or normalization)
+    settings_normalized: AtomicBool,
+    // Key for the encrypted settings and sessions files, once the passphrase
+    // was entered; None while locked or when encryption is off
+    data_key: std::sync::Mutex<Option<DataKey>>,
+    // Set while the data files are rewritten as plaintext before the
+    // encryption header is removed
+    writing_plaintext: AtomicBool,
 }
 
 impl DatabaseService {
     /// Create a new database service instance
     /// 计算数据目录并初始化内存缓存。
     pub fn new(app: AppHandle) -> Self {
-        // Get app data directory
-        let data_dir = app
-            .path()
-            .app_data_dir()
-            .unwrap_or_else(|_| PathBuf::from("."));
+        let (data_dir, storage_location, primary_storage_error) = resolve_data_dir(&app);
 
         Self {
             app,
             settings: Mutex::new(Settings::default()),
             sessions: Mutex::new(Vec::new()),
+            archive_before: std::sync::Mutex::new(None),
+            archived: std::sync::Mutex::new(Vec::new()),
             achievements: Mutex::new(Vec::new()),
+            lifetime: Mutex::new(LifetimeRollup::default()),
+            day_starts: Mutex::new(Vec::new()),
             data_dir,
+            storage_format: std::sync::Mutex::new(StorageFormat::Json),
+            storage_location,
+            primary_storage_error,
+            settings_normalized: AtomicBool::new(false),
+            data_key: std::sync::Mutex::new(None),
+            writing_plaintext: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether loading changed the settings file since startup: an old file was
+    /// migrated or values were normalized (e.g. tray behavior, music folder,
+    /// silent autostart without autostart, an empty segment list).
+    pub fn is_settings_normalized(&self) -> bool {
+        self.settings_normalized.load(Ordering::SeqCst)
+    }
+
+    /// Data directory in use and which fallback it came from
+    pub fn storage_info(&self) -> StorageInfo {
+        StorageInfo {
+            data_dir: self.data_dir.display().to_string(),
+            location: self.storage_location,
+            primary_error: self.primary_storage_error.clone(),
         }
     }
 
@@ -102,21 +387,203 @@ impl DatabaseService {
             })?;
         }
 
-        // Load settings from file
-        self.load_settings_from_file().await?;
+        if self.is_locked() {
+            // Settings, sessions and the lifetime rollup load once `unlock`
+            // gets the passphrase
+            eprintln!("[Database] Data is encrypted; waiting for the passphrase");
+        } else {
+            // Load settings from file
+            self.load_settings_from_file().await?;
 
-        // Load sessions from file
-        self.load_sessions_from_file().await?;
+            // Load sessions from file
+            self.load_sessions_from_file().await?;
+
+            // Load the lifetime rollup before recovery finalizes sessions into it
+            self.load_lifetime_stats_from_file().await?;
+
+            // Sessions left running by a crash or forced quit
+            self.recover_interrupted_sessions().await?;
+        }
 
         // Load achievements from file
         self.load_achievements_from_file().await?;
 
+        // Load recorded day starts
+        self.load_day_starts_from_file().await?;
+
         // Reconcile achievements for existing data
-        self.reconcile_achievements().await?;
+        if !self.is_locked() {
+            self.reconcile_achievements().await?;
+        }
 
         Ok(())
     }
 
+    /// Get encryption header file path
+    fn encryption_file(&self) -> PathBuf {
+        self.data_dir.join("encryption.json")
+    }
+
+    /// Whether settings and sessions are stored encrypted (`encryption.json` exists).
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption_file().exists()
+    }
+
+    /// Encrypted and the passphrase not entered yet: settings and sessions can be
+    /// neither read nor written, so nothing overwrites the encrypted files.
+    pub fn is_locked(&self) -> bool {
+        self.data_key.lock().unwrap().is_none() && self.is_encrypted()
+    }
+
+    fn read_encryption_header(&self) -> AppResult<EncryptionHeader> {
+        let content = std::fs::read_to_string(self.encryption_file()).map_err(|e| {
+            AppError::DatabaseError(format!("Failed to read encryption file: {}", e))
+        })?;
+        serde_json::from_str(&content)
+            .map_err(|e| AppError::DatabaseError(format!("Failed to parse encryption file: {}", e)))
+    }
+
+    fn write_encryption_header(&self, header: &EncryptionHeader) -> AppResult<()> {
+        let json = serde_json::to_string_pretty(header).map_err(|e| {
+            AppError::DatabaseError(format!("Failed to serialize encryption file: {}", e))
+        })?;
+        std::fs::write(self.encryption_file(), json)
+            .map_err(|e| AppError::DatabaseError(format!("Failed to write encryption file: {}", e)))
+    }
+
+    /// Read a settings or sessions file, decrypting it when it was written encrypted.
+    fn read_data_file(&self, path: &Path, what: &str) -> AppResult<String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| AppError::DatabaseError(format!("Failed to read {} file: {}", what, e)))?;
+        if !encryption::is_encrypted(&content) {
+            return Ok(content);
+        }
+        match &*self.data_key.lock().unwrap() {
+            Some(key) => key.decrypt(&content),
+            None => Err(AppError::DataLocked),
+        }
+    }
+
+    /// Text to write to a settings or sessions file: encrypted while a key is set.
+    fn seal(&self, content: String) -> AppResult<String> {
+        if self.writing_plaintext.load(Ordering::SeqCst) {
+            return Ok(content);
+        }
+        match &*self.data_key.lock().unwrap() {
+            Some(key) => key.encrypt(&content),
+            None if self.is_encrypted() => Err(AppError::DataLocked),
+            None => Ok(content),
+        }
+    }
+
+    /// Enter the passphrase of encrypted data and load the settings and
+    /// sessions. A wrong passphrase is `WrongPassphrase` and changes nothing.
+    /// 输入密码解锁加密数据并加载设置与会话；密码错误时返回 WrongPassphrase。
+    pub async fn unlock(&self, passphrase: &str) -> AppResult<()> {
+        if !self.is_locked() {
+            return Ok(());
+        }
+        let header = self.read_encryption_header()?;
+        let key = DataKey::unlock(passphrase, &header)?;
+        *self.data_key.lock().unwrap() = Some(key);
+        self.load_settings_from_file().await?;
+        self.load_sessions_from_file().await?;
+        self.load_lifetime_stats_from_file().await?;
+        self.recover_interrupted_sessions().await?;
+        self.reconcile_achievements().await
+    }
+
+    /// Encrypt `settings.json` and the session file with a key derived from
+    /// `passphrase` (Argon2id, AES-256-GCM). The passphrase is not stored; it is
+    /// asked for at every launch and cannot be recovered.
+    /// 开启数据加密：用密码派生的密钥加密设置与会话文件（密码不保存，遗失无法恢复）。
+    pub async fn enable_encryption(&self, passphrase: &str) -> AppResult<()> {
+        if self.is_encrypted() {
+            return Err(AppError::ValidationError(
+                "data encryption is already on".to_string(),
+            ));
+        }
+        let (key, header) = DataKey::create(passphrase)?;
+        self.write_encryption_header(&header)?;
+        *self.data_key.lock().unwrap() = Some(key);
+        if let Err(e) = self.rewrite_data_files().await {
+            // Back to plaintext; files already written encrypted are rewritten
+            if let Err(rollback) = self.decrypt_data_files().await {
+                return Err(Self::rollback_failed(e, rollback));
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Store settings and sessions as plaintext again, after checking `passphrase`.
+    /// 关闭数据加密：校验密码后以明文重新写入设置与会话文件。
+    pub async fn disable_encryption(&self, passphrase: &str) -> AppResult<()> {
+        if !self.is_encrypted() {
+            return Ok(());
+        }
+        // Checks the passphrase; the unlocked key stays in use until the end
+        DataKey::unlock(passphrase, &self.read_encryption_header()?)?;
+        if self.is_locked() {
+            return Err(AppError::DataLocked);
+        }
+        if let Err(e) = self.decrypt_data_files().await {
+            // Stay encrypted; files already written as plaintext are encrypted again
+            if let Err(rollback) = self.rewrite_data_files().await {
+                return Err(Self::rollback_failed(e, rollback));
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Rewrite the data files as plaintext, then remove the encryption header
+    /// and forget the key. The header goes last: if this stops halfway, the
+    /// rewritten files read as plaintext and the others still decrypt.
+    async fn decrypt_data_files(&self) -> AppResult<()> {
+        self.writing_plaintext.store(true, Ordering::SeqCst);
+        let result = match self.rewrite_data_files().await {
+            Ok(()) => std::fs::remove_file(self.encryption_file()).map_err(|e| {
+                AppError::DatabaseError(format!("Failed to remove encryption file: {}", e))
+            }),
+            Err(e) => Err(e),
+        };
+        if result.is_ok() {
+            *self.data_key.lock().unwrap() = None;
+        }
+        self.writing_plaintext.store(false, Ordering::SeqCst);
+        result
+    }
+
+    /// `error`, noting that undoing the partial change failed as well.
+    fn rollback_failed(error: AppError, rollback: AppError) -> AppError {
+        eprintln!(
+            "[Database] Failed to restore the data files after an encryption change: {}",
+            rollback
+        );
+        AppError::DatabaseError(format!(
+            "{}; restoring the previous files also failed: {}",
+            error, rollback
+        ))
+    }
+
+    /// Write the settings and session files again in the current mode.
+    async fn rewrite_data_files(&self) -> AppResult<()> {
+        {
+            let sessions = self.sessions.lock().await;
+            self.write_sessions_file(&sessions)?;
+        }
+        let settings = self.settings.lock().await.clone();
+        self.write_settings_file(&settings)
+    }
+
+    fn write_settings_file(&self, settings: &Settings) -> AppResult<()> {
+        let json = serde_json::to_string_pretty(settings)
+            .map_err(|e| AppError::DatabaseError(format!("Failed to serialize settings: {}", e)))?;
+        std::fs::write(self.settings_file(), self.seal(json)?)
+            .map_err(|e| AppError::DatabaseError(format!("Failed to write settings file: {}", e)))
+    }
+
     /// Get settings file path
     fn settings_file(&self) -> PathBuf {
         self.data_dir.join("settings.json")
@@ -124,7 +591,44 @@ impl DatabaseService {
 
     /// Get sessions file path
     fn sessions_file(&self) -> PathBuf {
-        self.data_dir.join("sessions.json")
+        self.sessions_file_for(*self.storage_format.lock().unwrap())
+    }
+
+    fn sessions_file_for(&self, format: StorageFormat) -> PathBuf {
+        match format {
+            StorageFormat::Json => self.data_dir.join("sessions.json"),
+            StorageFormat::Ndjson => self.data_dir.join("sessions.ndjson"),
+        }
+    }
+
+    /// The session file on disk: the configured format first, then the other one
+    /// (left behind before a format switch was migrated).
+    fn existing_sessions_file(&self) -> Option<(PathBuf, StorageFormat)> {
+        let current = *self.storage_format.lock().unwrap();
+        let other = match current {
+            StorageFormat::Json => StorageFormat::Ndjson,
+            StorageFormat::Ndjson => StorageFormat::Json,
+        };
+        [current, other]
+            .into_iter()
+            .map(|format| (self.sessions_file_for(format), format))
+            .find(|(path, _)| path.exists())
+    }
+
+    /// Rewrite the session history in `format` and remove the previous file.
+    /// 切换存储格式：按新格式写出全部会话后删除旧文件。
+    fn migrate_sessions_file(&self, sessions: &[Session], format: StorageFormat) -> AppResult<()> {
+        let previous = std::mem::replace(&mut *self.storage_format.lock().unwrap(), format);
+        self.write_sessions_file(sessions)?;
+        if previous != format {
+            let old_file = self.sessions_file_for(previous);
+            if old_file.exists() {
+                std::fs::remove_file(&old_file).map_err(|e| {
+                    AppError::DatabaseError(format!("Failed to remove old sessions file: {}", e))
+                })?;
+            }
+        }
+        Ok(())
     }
 
     /// Get achievements file path
@@ -137,13 +641,16 @@ impl DatabaseService {
         let file_path = self.settings_file();
 
         if file_path.exists() {
-            let content = std::fs::read_to_string(&file_path).map_err(|e| {
-                AppError::DatabaseError(format!("Failed to read settings file: {}", e))
-            })?;
+            let content = self.read_data_file(&file_path, "settings")?;
+            let (loaded_settings, pre_segment) = Self::parse_settings_file(&content)?;
 
-            let loaded_settings: Settings = serde_json::from_str(&content)
-                .map_err(|e| AppError::DatabaseError(format!("Failed to parse settings: {}", e)))?;
+            if pre_segment {
+                self.write_settings_file(&loaded_settings)?;
+                eprintln!("Settings file migrated: segmented work fields added");
+                self.settings_normalized.store(true, Ordering::SeqCst);
+            }
 
+            *self.storage_format.lock().unwrap() = loaded_settings.storage_format;
             let mut settings = self.settings.lock().await;
             *settings = loaded_settings;
         }
@@ -151,25 +658,354 @@ impl DatabaseService {
         Ok(())
     }
 
-    /// Load sessions from file
-    async fn load_sessions_from_file(&self) -> AppResult<()> {
-        let file_path = self.sessions_file();
+    /// Parse the settings file `content`, migrating a file written before
+    /// segmented work. The flag tells whether it was migrated and needs rewriting.
+    fn parse_settings_file(content: &str) -> AppResult<(Settings, bool)> {
+        let raw: serde_json::Value = serde_json::from_str(content)
+            .map_err(|e| AppError::DatabaseError(format!("Failed to parse settings: {}", e)))?;
+        let pre_segment = raw.as_object().is_some_and(|fields| {
+            PRE_SEGMENT_SETTINGS_KEYS
+                .iter()
+                .any(|key| !fields.contains_key(*key))
+        });
+        let mut settings: Settings = serde_json::from_value(raw)
+            .map_err(|e| AppError::DatabaseError(format!("Failed to parse settings: {}", e)))?;
+        if pre_segment {
+            Self::migrate_pre_segment_settings(&mut settings);
+        }
+        Ok((settings, pre_segment))
+    }
 
-        if file_path.exists() {
-            let content = std::fs::read_to_string(&file_path).map_err(|e| {
-                AppError::DatabaseError(format!("Failed to read sessions file: {}", e))
-            })?;
+    /// Settings from a file written before segmented work: the absent fields
+    /// already hold their serde defaults; segments outside the accepted bounds
+    /// are replaced by the default program, switched off.
+    /// 旧版（无分段工作）设置文件的迁移：缺失字段取默认值，越界的分段恢复为默认并关闭分段。
+    fn migrate_pre_segment_settings(settings: &mut Settings) {
+        let segments_valid = !settings.work_segments.is_empty()
+            && settings
+                .work_segments
+                .iter()
+                .all(WorkSegment::is_within_bounds);
+        if !segments_valid {
+            settings.work_segments = default_work_segments();
+            settings.segmented_work_enabled = false;
+        }
+        if settings.silent_autostart && !settings.autostart {
+            settings.silent_autostart = false;
+        }
+    }
 
-            let loaded_sessions: Vec<Session> = serde_json::from_str(&content)
-                .map_err(|e| AppError::DatabaseError(format!("Failed to parse sessions: {}", e)))?;
+    /// Load sessions from file, migrating a file in the other format. With a load
+    /// window only its sessions are queried by default; older ones are set aside
+    /// and joined in when a query reaches back that far.
+    /// 加载会话；设置了加载窗口时窗口外的会话单独存放，仅在查询范围覆盖时参与计算。
+    async fn load_sessions_from_file(&self) -> AppResult<()> {
+        self.clear_archive();
+        if let Some((file_path, format)) = self.existing_sessions_file() {
+            let content = self.read_data_file(&file_path, "sessions")?;
+
+            let mut loaded_sessions = decode_sessions(&content, format)?;
+            let window_days = self.settings.lock().await.analytics_load_window_days;
+            if window_days > 0 {
+                let cutoff = Utc::now() - ChronoDuration::days(i64::from(window_days));
+                let (archived, recent): (Vec<Session>, Vec<Session>) = loaded_sessions
+                    .into_iter()
+                    .partition(|s| Self::is_archived(s, cutoff));
+                loaded_sessions = recent;
+                if !archived.is_empty() {
+                    *self.archive_before.lock().unwrap() = Some(cutoff);
+                    *self.archived.lock().unwrap() = archived;
+                }
+            }
 
             let mut sessions = self.sessions.lock().await;
             *sessions = loaded_sessions;
+
+            let configured = *self.storage_format.lock().unwrap();
+            if format != configured {
+                // Written before `storage_format` changed (e.g. first run after enabling it)
+                *self.storage_format.lock().unwrap() = format;
+                self.migrate_sessions_file(&sessions, configured)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether `session` is set aside under a load window ending at `cutoff`.
+    fn is_archived(session: &Session, cutoff: DateTime<Utc>) -> bool {
+        !session.in_progress && session.end_time < cutoff
+    }
+
+    /// Forget the sessions set aside by the load window (history replaced or cleared).
+    fn clear_archive(&self) {
+        *self.archive_before.lock().unwrap() = None;
+        self.archived.lock().unwrap().clear();
+    }
+
+    /// Sessions a query reaching back to `since` (None = all history) needs: the
+    /// recent ones, plus the archived ones when `since` falls before the load
+    /// window.
+    async fn sessions_since(&self, since: Option<DateTime<Utc>>) -> AppResult<Vec<Session>> {
+        let cutoff = *self.archive_before.lock().unwrap();
+        let reaches_archive = match (cutoff, since) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(cutoff), Some(since)) => since < cutoff,
+        };
+        let mut sessions = if reaches_archive {
+            self.archived.lock().unwrap().clone()
+        } else {
+            Vec::new()
+        };
+        sessions.extend(self.sessions.lock().await.iter().cloned());
+        Ok(sessions)
+    }
+
+    /// Finalize sessions still marked in progress from a previous run: checkpointed ones
+    /// keep the duration up to their last checkpoint, bare placeholders are removed.
+    /// 启动时处理上次未正常结束的会话：保留已有检查点的记录，删除零时长占位记录。
+    async fn recover_interrupted_sessions(&self) -> AppResult<()> {
+        let mut sessions = self.sessions.lock().await;
+        if !sessions.iter().any(|s| s.in_progress) {
+            return Ok(());
+        }
+
+        sessions.retain(|s| !s.in_progress || s.duration > 0);
+        let mut recovered = Vec::new();
+        for session in sessions.iter_mut().filter(|s| s.in_progress) {
+            session.in_progress = false;
+            session.end_reason = Some(SessionEndReason::Interrupted);
+            recovered.push(session.clone());
+        }
+        self.write_sessions_file(&sessions)?;
+        drop(sessions);
+
+        for session in &recovered {
+            self.record_lifetime_stats(None, session).await?;
+        }
+        Ok(())
+    }
+
+    /// Get lifetime statistics file path
+    fn lifetime_stats_file(&self) -> PathBuf {
+        self.data_dir.join("lifetime_stats.json")
+    }
+
+    /// Load the lifetime rollup, rebuilding it from the sessions when missing or unreadable
+    async fn load_lifetime_stats_from_file(&self) -> AppResult<()> {
+        if !self.settings.lock().await.lifetime_stats_enabled {
+            return Ok(());
+        }
+
+        let file_path = self.lifetime_stats_file();
+        let loaded = if file_path.exists() {
+            let content = std::fs::read_to_string(&file_path).map_err(|e| {
+                AppError::DatabaseError(format!("Failed to read lifetime stats file: {}", e))
+            })?;
+            match serde_json::from_str::<LifetimeRollup>(&content) {
+                Ok(rollup) => Some(rollup),
+                Err(e) => {
+                    eprintln!("Failed to parse lifetime stats, rebuilding: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        match loaded {
+            Some(rollup) => *self.lifetime.lock().await = rollup,
+            None => {
+                self.rebuild_lifetime_stats().await?;
+            }
         }
+        Ok(())
+    }
+
+    fn write_lifetime_stats_file(&self, rollup: &LifetimeRollup) -> AppResult<()> {
+        let json = serde_json::to_string_pretty(rollup).map_err(|e| {
+            AppError::DatabaseError(format!("Failed to serialize lifetime stats: {}", e))
+        })?;
+        std::fs::write(self.lifetime_stats_file(), json).map_err(|e| {
+            AppError::DatabaseError(format!("Failed to write lifetime stats file: {}", e))
+        })
+    }
+
+    /// Fold one session write into the lifetime rollup when it is enabled.
+    /// Deleting sessions never touches the rollup, so totals keep the full history.
+    /// 会话写入时增量更新全部历史汇总；删除会话不影响汇总。
+    async fn record_lifetime_stats(
+        &self,
+        previous: Option<&Session>,
+        current: &Session,
+    ) -> AppResult<()> {
+        let min_completion_ratio = {
+            let settings = self.settings.lock().await;
+            if !settings.lifetime_stats_enabled {
+                return Ok(());
+            }
+            settings.min_break_completion_ratio.clamp(0.0, 1.0)
+        };
+        let mut rollup = self.lifetime.lock().await;
+        rollup.apply(previous, current, min_completion_ratio);
+        self.write_lifetime_stats_file(&rollup)
+    }
+
+    /// Get day starts file path
+    fn day_starts_file(&self) -> PathBuf {
+        self.data_dir.join("day_starts.json")
+    }
 
+    /// Load recorded day starts; an unreadable file only loses that history
+    async fn load_day_starts_from_file(&self) -> AppResult<()> {
+        let file_path = self.day_starts_file();
+        if !file_path.exists() {
+            return Ok(());
+        }
+        let content = std::fs::read_to_string(&file_path).map_err(|e| {
+            AppError::DatabaseError(format!("Failed to read day starts file: {}", e))
+        })?;
+        match serde_json::from_str::<Vec<DateTime<Utc>>>(&content) {
+            Ok(loaded) => *self.day_starts.lock().await = loaded,
+            Err(e) => eprintln!("Failed to parse day starts, ignoring: {}", e),
+        }
         Ok(())
     }
 
+    /// Record when the first work session of a local day started; later starts
+    /// on an already recorded day are ignored.
+    /// 记录当天第一个工作阶段的开始时间，同一天只记录一次。
+    pub async fn record_day_start(&self, started_at: DateTime<Utc>) -> AppResult<()> {
+        let mut day_starts = self.day_starts.lock().await;
+        let day = started_at.with_timezone(&Local).date_naive();
+        if day_starts
+            .iter()
+            .any(|start| start.with_timezone(&Local).date_naive() == day)
+        {
+            return Ok(());
+        }
+        day_starts.push(started_at);
+        day_starts.sort();
+        let json = serde_json::to_string_pretty(&*day_starts).map_err(|e| {
+            AppError::DatabaseError(format!("Failed to serialize day starts: {}", e))
+        })?;
+        std::fs::write(self.day_starts_file(), json)
+            .map_err(|e| AppError::DatabaseError(format!("Failed to write day starts file: {}", e)))
+    }
+
+    /// Every recorded day start, oldest first
+    pub async fn get_day_starts(&self) -> Vec<DateTime<Utc>> {
+        self.day_starts.lock().await.clone()
+    }
+
+    /// Most recent recorded day start
+    pub async fn last_day_start(&self) -> Option<DateTime<Utc>> {
+        self.day_starts.lock().await.last().copied()
+    }
+
+    /// Get screen continuity file path
+    fn screen_continuity_file(&self) -> PathBuf {
+        self.data_dir.join("screen_continuity.json")
+    }
+
+    /// Screen time saved by the previous run; a missing or unreadable file starts over
+    pub fn load_screen_continuity(&self) -> ScreenContinuity {
+        let Ok(content) = std::fs::read_to_string(self.screen_continuity_file()) else {
+            return ScreenContinuity::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("Failed to parse screen continuity, ignoring: {}", e);
+            ScreenContinuity::default()
+        })
+    }
+
+    /// Get exercise rotation file path
+    fn exercise_rotation_file(&self) -> PathBuf {
+        self.data_dir.join("exercise_rotation.json")
+    }
+
+    /// Exercises suggested so far; a missing or unreadable file starts the rotation over
+    pub fn load_exercise_rotation(&self) -> ExerciseRotation {
+        let Ok(content) = std::fs::read_to_string(self.exercise_rotation_file()) else {
+            return ExerciseRotation::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("Failed to parse exercise rotation, ignoring: {}", e);
+            ExerciseRotation::default()
+        })
+    }
+
+    /// Save which exercises were suggested so the rotation survives restarts.
+    pub fn save_exercise_rotation(&self, rotation: &ExerciseRotation) -> AppResult<()> {
+        let json = serde_json::to_string(rotation).map_err(|e| {
+            AppError::DatabaseError(format!("Failed to serialize exercise rotation: {}", e))
+        })?;
+        std::fs::write(self.exercise_rotation_file(), json).map_err(|e| {
+            AppError::DatabaseError(format!("Failed to write exercise rotation file: {}", e))
+        })
+    }
+
+    /// Get hydration log file path
+    fn hydration_file(&self) -> PathBuf {
+        self.data_dir.join("hydration.json")
+    }
+
+    /// Glasses logged on `today`; a log from an earlier day counts as zero.
+    /// 今天的饮水进度，跨过本地日期后自动归零。
+    pub async fn get_hydration_progress(&self, today: NaiveDate) -> AppResult<HydrationProgress> {
+        let goal = self.settings.lock().await.hydration.daily_goal_glasses;
+        let log = self.load_hydration_log();
+        let count = if log.date == Some(today) {
+            log.count
+        } else {
+            0
+        };
+        Ok(HydrationProgress {
+            date: today,
+            count,
+            goal,
+        })
+    }
+
+    /// Log one more glass on `today`, starting the count over on a new day.
+    pub async fn increment_hydration(&self, today: NaiveDate) -> AppResult<HydrationProgress> {
+        let mut progress = self.get_hydration_progress(today).await?;
+        progress.count = progress.count.saturating_add(1);
+        let log = HydrationLog {
+            date: Some(today),
+            count: progress.count,
+        };
+        let json = serde_json::to_string(&log).map_err(|e| {
+            AppError::DatabaseError(format!("Failed to serialize hydration log: {}", e))
+        })?;
+        std::fs::write(self.hydration_file(), json).map_err(|e| {
+            AppError::DatabaseError(format!("Failed to write hydration file: {}", e))
+        })?;
+        Ok(progress)
+    }
+
+    pub fn load_hydration_log(&self) -> HydrationLog {
+        let Ok(content) = std::fs::read_to_string(self.hydration_file()) else {
+            return HydrationLog::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("Failed to parse hydration log, ignoring: {}", e);
+            HydrationLog::default()
+        })
+    }
+
+    /// Save the screen time so a restart on the same day continues it.
+    /// 保存连续使用电脑的时长，同一天内重启后继续计时。
+    pub fn save_screen_continuity(&self, continuity: &ScreenContinuity) -> AppResult<()> {
+        let json = serde_json::to_string(continuity).map_err(|e| {
+            AppError::DatabaseError(format!("Failed to serialize screen continuity: {}", e))
+        })?;
+        std::fs::write(self.screen_continuity_file(), json).map_err(|e| {
+            AppError::DatabaseError(format!("Failed to write screen continuity file: {}", e))
+        })
+    }
+
     /// Load achievements from file
     async fn load_achievements_from_file(&self) -> AppResult<()> {
         let file_path = self.achievements_file();
@@ -179,8 +1015,9 @@ impl DatabaseService {
                 AppError::DatabaseError(format!("Failed to read achievements file: {}", e))
             })?;
 
-            let loaded: Vec<AchievementUnlock> = serde_json::from_str(&content)
-                .map_err(|e| AppError::DatabaseError(format!("Failed to parse achievements: {}", e)))?;
+            let loaded: Vec<AchievementUnlock> = serde_json::from_str(&content).map_err(|e| {
+                AppError::DatabaseError(format!("Failed to parse achievements: {}", e))
+            })?;
 
             let mut achievements = self.achievements.lock().await;
             *achievements = loaded;
@@ -248,11 +1085,10 @@ impl DatabaseService {
                 continue;
             }
             let prev_note = prev.notes.as_deref();
-            let should_fill =
-                (matches!(prev.session_type, SessionType::Work)
-                    && matches!(next.session_type, SessionType::Work)
-                    && prev_note != Some(POWER_INTERRUPT_WORK_NOTE))
-                    || prev_note == Some(POWER_INTERRUPT_BREAK_NOTE);
+            let should_fill = (matches!(prev.session_type, SessionType::Work)
+                && matches!(next.session_type, SessionType::Work)
+                && prev_note != Some(POWER_INTERRUPT_WORK_NOTE))
+                || prev_note == Some(POWER_INTERRUPT_BREAK_NOTE);
             if !should_fill {
                 continue;
             }
@@ -263,8 +1099,9 @@ impl DatabaseService {
     }
 
     fn persist_achievements(&self, achievements: &[AchievementUnlock]) -> AppResult<()> {
-        let json = serde_json::to_string_pretty(achievements)
-            .map_err(|e| AppError::DatabaseError(format!("Failed to serialize achievements: {}", e)))?;
+        let json = serde_json::to_string_pretty(achievements).map_err(|e| {
+            AppError::DatabaseError(format!("Failed to serialize achievements: {}", e))
+        })?;
 
         std::fs::write(self.achievements_file(), json).map_err(|e| {
             AppError::DatabaseError(format!("Failed to write achievements file: {}", e))
@@ -339,7 +1176,9 @@ impl DatabaseService {
         };
 
         if settings_snapshot.autostart {
-            let _ = self.unlock_achievement(ACHIEVEMENT_ENABLE_AUTOSTART).await?;
+            let _ = self
+                .unlock_achievement(ACHIEVEMENT_ENABLE_AUTOSTART)
+                .await?;
         }
 
         if sessions_snapshot.iter().any(Self::is_completed_work) {
@@ -350,11 +1189,8 @@ impl DatabaseService {
             let _ = self.unlock_achievement(ACHIEVEMENT_FIRST_BREAK).await?;
         }
 
-        self.unlock_duration_achievements(
-            &sessions_snapshot,
-            settings_snapshot.more_rest_enabled,
-        )
-        .await?;
+        self.unlock_duration_achievements(&sessions_snapshot, settings_snapshot.more_rest_enabled)
+            .await?;
 
         Ok(())
     }
@@ -364,29 +1200,30 @@ impl DatabaseService {
         Ok(achievements.clone())
     }
 
+    /// Every stored session, including those set aside by the load window.
     pub async fn get_sessions(&self) -> AppResult<Vec<Session>> {
-        let sessions = self.sessions.lock().await;
-        Ok(sessions.clone())
+        self.sessions_since(None).await
     }
 
     pub async fn replace_sessions(&self, sessions: Vec<Session>) -> AppResult<()> {
-        let json = serde_json::to_string_pretty(&sessions).map_err(|e| {
-            AppError::DatabaseError(format!("Failed to serialize sessions: {}", e))
-        })?;
-
         {
             let mut stored = self.sessions.lock().await;
-            *stored = sessions.clone();
+            *stored = sessions;
+            // The imported history replaces the archived sessions too
+            self.clear_archive();
+            self.write_sessions_file(&stored)?;
+        }
+        // Imported history replaces the old one, totals included
+        if self.settings.lock().await.lifetime_stats_enabled {
+            self.rebuild_lifetime_stats().await?;
         }
-
-        std::fs::write(self.sessions_file(), json).map_err(|e| {
-            AppError::DatabaseError(format!("Failed to write sessions file: {}", e))
-        })?;
-
         Ok(())
     }
 
-    pub async fn replace_achievements(&self, achievements: Vec<AchievementUnlock>) -> AppResult<()> {
+    pub async fn replace_achievements(
+        &self,
+        achievements: Vec<AchievementUnlock>,
+    ) -> AppResult<()> {
         {
             let mut stored = self.achievements.lock().await;
             *stored = achievements.clone();
@@ -403,7 +1240,9 @@ impl DatabaseService {
         let normalized = self.persist_settings(settings).await?;
 
         if normalized.autostart {
-            let _ = self.unlock_achievement(ACHIEVEMENT_ENABLE_AUTOSTART).await?;
+            let _ = self
+                .unlock_achievement(ACHIEVEMENT_ENABLE_AUTOSTART)
+                .await?;
         }
 
         let sessions_snapshot = {
@@ -423,6 +1262,9 @@ impl DatabaseService {
     }
 
     async fn persist_settings(&self, settings: &Settings) -> AppResult<Settings> {
+        if self.is_locked() {
+            return Err(AppError::DataLocked);
+        }
         let mut normalized = settings.clone();
         normalized.minimize_to_tray = true;
         normalized.close_to_tray = true;
@@ -435,18 +1277,25 @@ impl DatabaseService {
         }
 
         // Update in-memory settings
-        {
+        let lifetime_stats_enabled_now = {
             let mut stored_settings = self.settings.lock().await;
+            let was_enabled = stored_settings.lifetime_stats_enabled;
             *stored_settings = normalized.clone();
+            normalized.lifetime_stats_enabled && !was_enabled
+        };
+
+        let current_format = *self.storage_format.lock().unwrap();
+        if normalized.storage_format != current_format {
+            let sessions = self.sessions.lock().await;
+            self.migrate_sessions_file(&sessions, normalized.storage_format)?;
         }
 
         // Persist to file
-        let json = serde_json::to_string_pretty(&normalized)
-            .map_err(|e| AppError::DatabaseError(format!("Failed to serialize settings: {}", e)))?;
+        self.write_settings_file(&normalized)?;
 
-        std::fs::write(self.settings_file(), json).map_err(|e| {
-            AppError::DatabaseError(format!("Failed to write settings file: {}", e))
-        })?;
+        if lifetime_stats_enabled_now {
+            self.rebuild_lifetime_stats().await?;
+        }
 
         // Ensure rest music directory exists when settings change
         if !normalized.rest_music_directory.trim().is_empty() {
@@ -467,6 +1316,7 @@ impl DatabaseService {
     pub async fn load_settings(&self) -> AppResult<Settings> {
         let (snapshot, needs_persist) = {
             let mut settings = self.settings.lock().await;
+            let before = serde_json::to_value(&*settings).ok();
 
             let mut persist_flag = false;
             if !settings.minimize_to_tray {
@@ -507,6 +1357,9 @@ impl DatabaseService {
                 persist_flag = true;
             }
 
+            if serde_json::to_value(&*settings).ok() != before {
+                self.settings_normalized.store(true, Ordering::SeqCst);
+            }
             (settings.clone(), persist_flag)
         };
 
@@ -517,21 +1370,36 @@ impl DatabaseService {
         Ok(snapshot)
     }
 
-    /// Save a completed session
-    /// 追加会话记录并写入 `sessions.json`。
-    pub async fn save_session(&self, session: &Session) -> AppResult<()> {
-        let sessions_snapshot = {
+    /// Insert or update a session by `id`.
+    /// 如果已存在相同 `id` 的会话，则更新其字段；否则追加。
+    pub async fn save_or_update_session(&self, session: &Session) -> AppResult<()> {
+        let (sessions_snapshot, previous) = {
             let mut sessions = self.sessions.lock().await;
-            sessions.push(session.clone());
 
-            let json = serde_json::to_string_pretty(&*sessions)
-                .map_err(|e| AppError::DatabaseError(format!("Failed to serialize sessions: {}", e)))?;
-
-            std::fs::write(self.sessions_file(), json).map_err(|e| {
-                AppError::DatabaseError(format!("Failed to write sessions file: {}", e))
-            })?;
+            let existing = sessions.iter_mut().find(|s| s.id == session.id);
+            let previous = if let Some(existing) = existing {
+                Some(std::mem::replace(existing, session.clone()))
+            } else {
+                sessions.push(session.clone());
+                None
+            };
+            if let Err(err) = self.write_sessions_file(&sessions) {
+                // Undo the change so memory keeps matching the file
+                match &previous {
+                    Some(previous) => {
+                        if let Some(existing) = sessions.iter_mut().find(|s| s.id == session.id) {
+                            *existing = previous.clone();
+                        }
+                    }
+                    None => {
+                        sessions.pop();
+                    }
+                }
+                self.report_storage_error("saveSession", &err);
+                return Err(err);
+            }
 
-            sessions.clone()
+            (sessions.clone(), previous)
         };
         let settings_snapshot = {
             let settings = self.settings.lock().await;
@@ -541,6 +1409,27 @@ impl DatabaseService {
         // Notify frontend listeners for real-time updates
         let _ = self.app.emit("session-upserted", session.clone());
 
+        // Checkpoints are written quietly; listeners last saw the zero-duration placeholder
+        let baseline = previous.clone().map(|p| {
+            if p.in_progress {
+                Session {
+                    end_time: p.start_time,
+                    duration: 0,
+                    ..p
+                }
+            } else {
+                p
+            }
+        });
+        let delta = analytics_delta(
+            baseline.as_ref(),
+            session,
+            settings_snapshot.min_break_completion_ratio.clamp(0.0, 1.0),
+        );
+        let _ = self.app.emit("analytics-delta", delta);
+
+        self.record_lifetime_stats(previous.as_ref(), session)
+            .await?;
         self.unlock_for_session(session).await?;
         self.unlock_duration_achievements(&sessions_snapshot, settings_snapshot.more_rest_enabled)
             .await?;
@@ -548,64 +1437,368 @@ impl DatabaseService {
         Ok(())
     }
 
-    /// Insert or update a session by `id`.
-    /// 如果已存在相同 `id` 的会话，则更新其字段；否则追加。
-    pub async fn save_or_update_session(&self, session: &Session) -> AppResult<()> {
-        let sessions_snapshot = {
-            let mut sessions = self.sessions.lock().await;
-
+    /// Update the record of a running session without notifying listeners.
+    /// Only an existing in-progress record is touched, so a late checkpoint cannot
+    /// overwrite the finished session or revive a discarded one.
+    /// 静默写入进行中会话的检查点，不发送事件也不检查成就。
+    pub async fn checkpoint_session(&self, session: &Session) -> AppResult<()> {
+        let mut sessions = self.sessions.lock().await;
+        let Some(existing) = sessions
+            .iter_mut()
+            .find(|s| s.id == session.id && s.in_progress)
+        else {
+            return Ok(());
+        };
+        let previous = std::mem::replace(existing, session.clone());
+        if let Err(err) = self.write_sessions_file(&sessions) {
             if let Some(existing) = sessions.iter_mut().find(|s| s.id == session.id) {
-                *existing = session.clone();
-            } else {
-                sessions.push(session.clone());
+                *existing = previous;
             }
+            self.report_storage_error("checkpointSession", &err);
+            return Err(err);
+        }
+        Ok(())
+    }
 
-            let json = serde_json::to_string_pretty(&*sessions)
-                .map_err(|e| AppError::DatabaseError(format!("Failed to serialize sessions: {}", e)))?;
+    /// Tell the UI that history is not being saved (`storage-error`).
+    /// 会话写入失败时通知前端（如磁盘已满、无写入权限）。
+    fn report_storage_error(&self, operation: &str, err: &AppError) {
+        eprintln!("[Database] {} failed: {}", operation, err);
+        if matches!(err, AppError::DataLocked) {
+            // Nothing is wrong with the storage; the UI already asks for the passphrase
+            return;
+        }
+        let _ = self.app.emit(
+            "storage-error",
+            StorageFailure {
+                operation: operation.to_string(),
+                error: err.to_string(),
+                failed_at: Utc::now(),
+            },
+        );
+    }
+
+    /// Write `sessions` together with the archived sessions.
+    fn write_sessions_file(&self, sessions: &[Session]) -> AppResult<()> {
+        let archived = self.archived.lock().unwrap();
+        self.write_all_sessions(&archived, sessions)
+    }
 
-            std::fs::write(self.sessions_file(), json).map_err(|e| {
+    fn write_all_sessions(&self, archived: &[Session], sessions: &[Session]) -> AppResult<()> {
+        let format = *self.storage_format.lock().unwrap();
+        let content = if archived.is_empty() {
+            encode_sessions(sessions, format)?
+        } else {
+            let all: Vec<Session> = archived.iter().chain(sessions).cloned().collect();
+            encode_sessions(&all, format)?
+        };
+        let content = self.seal(content)?;
+
+        let path = self.sessions_file();
+        if let Err(err) = std::fs::write(&path, &content) {
+            if !is_transient_io_error(&err) {
+                return Err(AppError::DatabaseError(format!(
+                    "Failed to write sessions file: {}",
+                    err
+                )));
+            }
+            eprintln!("[Database] Sessions write failed ({}), retrying", err);
+            std::thread::sleep(WRITE_RETRY_DELAY);
+            std::fs::write(&path, &content).map_err(|e| {
                 AppError::DatabaseError(format!("Failed to write sessions file: {}", e))
             })?;
+        }
 
-            sessions.clone()
+        Ok(())
+    }
+
+    /// Change a stored session, recent or archived, write the file and notify
+    /// listeners (`session-upserted`).
+    /// 按 `id` 修改会话（含加载窗口外的旧会话）并持久化，同时通知前端刷新。
+    async fn edit_session(&self, id: &str, edit: impl FnOnce(&mut Session)) -> AppResult<Session> {
+        let updated = {
+            let mut sessions = self.sessions.lock().await;
+            if let Some(session) = sessions.iter_mut().find(|s| s.id == id) {
+                edit(session);
+                let updated = session.clone();
+                self.write_sessions_file(&sessions)?;
+                updated
+            } else {
+                let mut archived = self.archived.lock().unwrap();
+                let session = archived
+                    .iter_mut()
+                    .find(|s| s.id == id)
+                    .ok_or_else(|| AppError::SessionNotFound(id.to_string()))?;
+                edit(session);
+                let updated = session.clone();
+                self.write_all_sessions(&archived, &sessions)?;
+                updated
+            }
         };
-        let settings_snapshot = {
+
+        let _ = self.app.emit("session-upserted", updated.clone());
+
+        Ok(updated)
+    }
+
+    /// Attach a 1-5 rating to a session (or clear it with `None`).
+    /// 更新会话评分并持久化，同时通知前端刷新。
+    pub async fn rate_session(&self, id: &str, rating: Option<u8>) -> AppResult<Session> {
+        if let Some(value) = rating {
+            if !(1..=5).contains(&value) {
+                return Err(AppError::InvalidRating);
+            }
+        }
+
+        self.edit_session(id, |session| session.rating = rating)
+            .await
+    }
+
+    /// Stop reporting the anomaly of a session once the user reviewed it.
+    /// 将会话的异常标记为已查看，之后不再出现在分析结果中。
+    pub async fn dismiss_anomaly(&self, id: &str) -> AppResult<Session> {
+        self.edit_session(id, |session| session.anomaly_dismissed = true)
+            .await
+    }
+
+    /// Set (or clear) the free-form note of a session.
+    /// 更新会话备注并持久化，同时通知前端刷新。
+    pub async fn update_session_note(&self, id: &str, note: Option<String>) -> AppResult<Session> {
+        let note = note
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty());
+
+        self.edit_session(id, |session| session.notes = note).await
+    }
+
+    /// Correlate work session duration with self-reported ratings.
+    /// 按评分分组统计工作会话数量与平均时长。
+    pub async fn get_rating_stats(&self, query: &AnalyticsQuery) -> AppResult<RatingStats> {
+        let sessions = self.sessions_since(Some(query.start_date)).await?;
+        let rated: Vec<(&Session, u8)> = sessions
+            .iter()
+            .filter(|s| s.end_time >= query.start_date && s.start_time <= query.end_date)
+            .filter(|s| matches!(s.session_type, SessionType::Work))
+            .filter_map(|s| s.rating.map(|rating| (s, rating)))
+            .collect();
+
+        let buckets = (1..=5u8)
+            .filter_map(|rating| {
+                let durations: Vec<i64> = rated
+                    .iter()
+                    .filter(|(_, value)| *value == rating)
+                    .map(|(session, _)| session.duration)
+                    .collect();
+                if durations.is_empty() {
+                    return None;
+                }
+                Some(RatingBucket {
+                    rating,
+                    session_count: durations.len(),
+                    average_work_seconds: durations.iter().sum::<i64>() / durations.len() as i64,
+                })
+            })
+            .collect();
+
+        let average_rating = if rated.is_empty() {
+            None
+        } else {
+            let total: u32 = rated.iter().map(|(_, rating)| *rating as u32).sum();
+            Some(total as f64 / rated.len() as f64)
+        };
+
+        Ok(RatingStats {
+            rated_sessions: rated.len(),
+            average_rating,
+            buckets,
+        })
+    }
+
+    /// Focus score of the local day `date` (see `focus_score::compute`).
+    pub async fn get_focus_score(&self, date: NaiveDate) -> AppResult<FocusScore> {
+        let (focus_settings, min_completion_ratio) = {
             let settings = self.settings.lock().await;
-            settings.clone()
+            (
+                settings.focus_score.clone(),
+                settings.min_break_completion_ratio.clamp(0.0, 1.0),
+            )
         };
+        // Local midnight is at most a day away from UTC midnight
+        let since = date.and_time(NaiveTime::MIN).and_utc() - ChronoDuration::days(1);
+        let sessions = self.sessions_since(Some(since)).await?;
+        Ok(focus_score::compute(
+            &sessions,
+            date,
+            &focus_settings,
+            min_completion_ratio,
+        ))
+    }
 
-        // Notify frontend listeners for real-time updates
-        let _ = self.app.emit("session-upserted", session.clone());
+    /// Work seconds of the finished work sessions that started on the local day
+    /// `date`; the running session's record is left out.
+    pub async fn finished_work_seconds_on(&self, date: NaiveDate) -> AppResult<i64> {
+        let since = date.and_time(NaiveTime::MIN).and_utc() - ChronoDuration::days(1);
+        let sessions = self.sessions_since(Some(since)).await?;
+        Ok(sessions
+            .iter()
+            .filter(|s| s.session_type == SessionType::Work && !s.in_progress)
+            .filter(|s| s.start_time.with_timezone(&Local).date_naive() == date)
+            .map(|s| s.duration.max(0))
+            .sum())
+    }
 
-        self.unlock_for_session(session).await?;
-        self.unlock_duration_achievements(&sessions_snapshot, settings_snapshot.more_rest_enabled)
-            .await?;
+    /// Rest owed at `now` in minutes (see `rest_debt::debt_minutes`).
+    pub async fn get_rest_debt_minutes(&self, now: DateTime<Utc>) -> AppResult<f64> {
+        let break_minutes = self.settings.lock().await.break_duration;
+        let sessions = self.sessions.lock().await;
+        Ok(rest_debt::debt_minutes(&sessions, now, break_minutes))
+    }
 
-        Ok(())
+    /// Count finished work and break sessions per `bucket_minutes` of duration.
+    /// 按时长分桶统计区间内已结束的工作与休息会话数量。
+    pub async fn get_duration_histogram(
+        &self,
+        query: &AnalyticsQuery,
+        bucket_minutes: u32,
+    ) -> AppResult<DurationHistogram> {
+        if !(1..=MAX_HISTOGRAM_BUCKET_MINUTES).contains(&bucket_minutes) {
+            return Err(AppError::ValidationError(format!(
+                "Bucket size must be between 1 and {} minutes",
+                MAX_HISTOGRAM_BUCKET_MINUTES
+            )));
+        }
+        let sessions = self.sessions_since(Some(query.start_date)).await?;
+        let filtered: Vec<&Session> = sessions
+            .iter()
+            .filter(|s| s.end_time >= query.start_date && s.start_time <= query.end_date)
+            .filter(|s| !s.in_progress)
+            .collect();
+
+        let histogram = |session_type: SessionType| {
+            let bucket_seconds = i64::from(bucket_minutes) * 60;
+            let mut counts: Vec<usize> = Vec::new();
+            for session in filtered.iter().filter(|s| s.session_type == session_type) {
+                let index = (session.duration.max(0) / bucket_seconds) as usize;
+                if counts.len() <= index {
+                    counts.resize(index + 1, 0);
+                }
+                counts[index] += 1;
+            }
+            counts
+                .into_iter()
+                .enumerate()
+                .map(|(index, count)| DurationBucket {
+                    start_minutes: index as u32 * bucket_minutes,
+                    end_minutes: (index as u32 + 1) * bucket_minutes,
+                    count,
+                })
+                .collect()
+        };
+
+        Ok(DurationHistogram {
+            bucket_minutes,
+            work: histogram(SessionType::Work),
+            breaks: histogram(SessionType::Break),
+        })
     }
 
-    /// Clear all session records and persist empty sessions.json
-    pub async fn clear_sessions(&self) -> AppResult<()> {
-        let empty: Vec<Session> = Vec::new();
-        {
-            let mut sessions = self.sessions.lock().await;
-            *sessions = empty.clone();
+    /// Remove a single session by `id`; missing ids are ignored.
+    /// 按 `id` 删除单条会话记录并写回 `sessions.json`。
+    pub async fn delete_session(&self, id: &str) -> AppResult<()> {
+        let mut sessions = self.sessions.lock().await;
+        let before = sessions.len();
+        sessions.retain(|s| s.id != id);
+        if sessions.len() == before {
+            // Not among the recent ones: it may be one of the archived sessions
+            let mut archived = self.archived.lock().unwrap();
+            let archived_before = archived.len();
+            archived.retain(|s| s.id != id);
+            if archived.len() == archived_before {
+                return Ok(());
+            }
+            return self.write_all_sessions(&archived, &sessions);
+        }
+        self.write_sessions_file(&sessions)
+    }
+
+    /// Re-read the session file after external edits and re-check achievements.
+    /// 从磁盘重新加载会话（文件不存在时清空），重新核对成就并发送 `sessions-reloaded`。
+    pub async fn reload_sessions(&self) -> AppResult<usize> {
+        if self.existing_sessions_file().is_some() {
+            self.load_sessions_from_file().await?;
+        } else {
+            self.clear_archive();
+            self.sessions.lock().await.clear();
         }
+        self.reconcile_achievements().await?;
 
-        let json = serde_json::to_string_pretty(&empty)
-            .map_err(|e| AppError::DatabaseError(format!("Failed to serialize sessions: {}", e)))?;
+        let count = self.sessions.lock().await.len();
+        let _ = self.app.emit("sessions-reloaded", count);
+        Ok(count)
+    }
 
-        std::fs::write(self.sessions_file(), json).map_err(|e| {
-            AppError::DatabaseError(format!("Failed to write sessions file: {}", e))
-        })?;
+    /// All-time statistics from the lifetime rollup.
+    pub async fn get_lifetime_stats(&self) -> AppResult<LifetimeStats> {
+        self.ensure_lifetime_stats_enabled().await?;
+        Ok(self.lifetime.lock().await.stats())
+    }
 
-        Ok(())
+    /// Recompute the lifetime rollup from the stored sessions, dropping totals of
+    /// sessions deleted since.
+    /// 根据现有会话重新计算全部历史汇总（已删除会话的数据会丢失）。
+    pub async fn rebuild_lifetime_stats(&self) -> AppResult<LifetimeStats> {
+        self.ensure_lifetime_stats_enabled().await?;
+        let min_completion_ratio = {
+            let settings = self.settings.lock().await;
+            settings.min_break_completion_ratio.clamp(0.0, 1.0)
+        };
+        let rollup = {
+            let sessions = self.sessions_since(None).await?;
+            LifetimeRollup::rebuild(&sessions, min_completion_ratio)
+        };
+        self.write_lifetime_stats_file(&rollup)?;
+        let stats = rollup.stats();
+        *self.lifetime.lock().await = rollup;
+        Ok(stats)
+    }
+
+    async fn ensure_lifetime_stats_enabled(&self) -> AppResult<()> {
+        if self.settings.lock().await.lifetime_stats_enabled {
+            Ok(())
+        } else {
+            Err(AppError::ValidationError(
+                "Lifetime statistics are disabled".to_string(),
+            ))
+        }
+    }
+
+    /// Clear all session records and persist an empty session file
+    pub async fn clear_sessions(&self) -> AppResult<()> {
+        let mut sessions = self.sessions.lock().await;
+        sessions.clear();
+        self.clear_archive();
+        self.write_sessions_file(&sessions)
     }
 
     /// Get analytics data for a date range
     /// 按时间区间筛选会话，计算统计指标。
     pub async fn get_analytics(&self, query: &AnalyticsQuery) -> AppResult<AnalyticsData> {
-        let sessions = self.sessions.lock().await;
+        let (min_completion_ratio, anomaly_thresholds) = {
+            let settings = self.settings.lock().await;
+            (
+                settings.min_break_completion_ratio.clamp(0.0, 1.0),
+                settings.anomaly_detection.clone(),
+            )
+        };
+        let day_starts = self
+            .day_starts
+            .lock()
+            .await
+            .iter()
+            .filter(|start| **start >= query.start_date && **start <= query.end_date)
+            .copied()
+            .collect();
+        let sessions = self.sessions_since(Some(query.start_date)).await?;
 
         // Filter sessions by overlap with date range [start_date, end_date]
         // 选择与区间有任意重叠的会话（而非仅按开始时间落在区间内）
@@ -635,29 +1828,72 @@ impl DatabaseService {
         let completed_breaks = filtered
             .iter()
             .filter(|s| {
-                matches!(s.session_type, crate::models::SessionType::Break) && !s.is_skipped
+                matches!(s.session_type, crate::models::SessionType::Break)
+                    && is_completed_break(s, min_completion_ratio)
             })
             .count();
 
         let skipped_breaks = filtered
             .iter()
-            .filter(|s| matches!(s.session_type, crate::models::SessionType::Break) && s.is_skipped)
+            .filter(|s| {
+                matches!(s.session_type, crate::models::SessionType::Break)
+                    && !is_completed_break(s, min_completion_ratio)
+            })
+            .count();
+
+        let total_extended_seconds: i64 = filtered.iter().map(|s| s.extended_seconds).sum();
+
+        let acknowledged_breaks = filtered
+            .iter()
+            .filter(|s| s.acknowledged == Some(true))
+            .count();
+        let unacknowledged_breaks = filtered
+            .iter()
+            .filter(|s| s.acknowledged == Some(false))
             .count();
 
+        let anomalies = filtered
+            .iter()
+            .filter_map(|s| super::anomalies::detect(s, &anomaly_thresholds))
+            .collect();
+
+        let total_active_seconds: i64 = filtered
+            .iter()
+            .filter(|s| matches!(s.session_type, crate::models::SessionType::Work))
+            .filter_map(|s| s.active_seconds)
+            .sum();
+        let daily_activity = daily_activity(&filtered);
+
         Ok(AnalyticsData {
             total_work_seconds,
             total_break_seconds,
             break_count,
             completed_breaks,
             skipped_breaks,
+            total_extended_seconds,
+            acknowledged_breaks,
+            unacknowledged_breaks,
+            anomalies,
+            day_starts,
+            total_active_seconds,
+            daily_activity,
             sessions: filtered.iter().map(|s| (*s).clone()).collect(),
         })
     }
 
+    /// Sessions matching `query`, newest first (by start time, then id), one
+    /// page at a time. Filters are applied in one pass before paging; `total`
+    /// counts every match.
+    /// 一次遍历筛选会话，再按开始时间与 id 倒序分页，并返回匹配总数。
+    pub async fn list_sessions(&self, query: &SessionListQuery) -> AppResult<SessionListPage> {
+        let sessions = self.sessions_since(query.start_date).await?;
+        Ok(page_sessions(sessions, query))
+    }
+
     /// Get sessions time bounds
     /// 获取会话数据的时间范围（最早开始/最晚结束）。
     pub async fn get_sessions_bounds(&self) -> AppResult<SessionsBounds> {
-        let sessions = self.sessions.lock().await;
+        let sessions = self.sessions_since(None).await?;
         let earliest_start = sessions.iter().map(|s| s.start_time).min();
         let latest_end = sessions.iter().map(|s| s.end_time).max();
         Ok(SessionsBounds {
@@ -666,3 +1902,366 @@ impl DatabaseService {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, hour, minute, 0).unwrap()
+    }
+
+    fn session(id: &str, session_type: SessionType, start: DateTime<Utc>, minutes: i64) -> Session {
+        Session {
+            id: id.to_string(),
+            session_type,
+            start_time: start,
+            end_time: start + ChronoDuration::minutes(minutes),
+            duration: minutes * 60,
+            planned_duration: minutes * 60,
+            is_skipped: false,
+            extended_seconds: 0,
+            notes: None,
+            rating: None,
+            in_progress: false,
+            anomaly_dismissed: false,
+            acknowledged: None,
+            active_seconds: None,
+            segment_label: None,
+            end_reason: None,
+        }
+    }
+
+    fn query() -> SessionListQuery {
+        serde_json::from_str("{}").unwrap()
+    }
+
+    fn ids(page: &SessionListPage) -> Vec<&str> {
+        page.sessions.iter().map(|s| s.id.as_str()).collect()
+    }
+
+    #[test]
+    fn combined_filters_must_all_match() {
+        let mut noted_break = session("noted-break", SessionType::Break, at(9, 25), 5);
+        noted_break.notes = Some("Walked to the Park".to_string());
+        let mut skipped_break = session("skipped-break", SessionType::Break, at(10, 25), 1);
+        skipped_break.notes = Some("park bench".to_string());
+        skipped_break.is_skipped = true;
+        let mut long_break = session("long-break", SessionType::Break, at(11, 25), 20);
+        long_break.notes = Some("park".to_string());
+        let mut noted_work = session("noted-work", SessionType::Work, at(12, 0), 25);
+        noted_work.notes = Some("park the idea".to_string());
+        let mut early_break = session("early-break", SessionType::Break, at(7, 0), 5);
+        early_break.notes = Some("park".to_string());
+        let sessions = vec![
+            noted_break,
+            skipped_break,
+            long_break,
+            noted_work,
+            early_break,
+        ];
+
+        let filtered = SessionListQuery {
+            start_date: Some(at(8, 0)),
+            end_date: Some(at(13, 0)),
+            session_type: Some(SessionType::Break),
+            search: Some("  PARK ".to_string()),
+            is_skipped: Some(false),
+            min_duration_seconds: Some(60),
+            max_duration_seconds: Some(10 * 60),
+            ..query()
+        };
+        let page = page_sessions(sessions.clone(), &filtered);
+        assert_eq!(ids(&page), ["noted-break"]);
+        assert_eq!(page.total, 1);
+
+        // Dropping one filter lets exactly the sessions it excluded back in
+        let page = page_sessions(
+            sessions.clone(),
+            &SessionListQuery {
+                is_skipped: None,
+                ..filtered.clone()
+            },
+        );
+        assert_eq!(ids(&page), ["skipped-break", "noted-break"]);
+        let page = page_sessions(
+            sessions,
+            &SessionListQuery {
+                max_duration_seconds: None,
+                ..filtered
+            },
+        );
+        assert_eq!(ids(&page), ["long-break", "noted-break"]);
+    }
+
+    #[test]
+    fn search_and_end_reason_look_past_the_notes() {
+        let mut labelled = session("labelled", SessionType::Work, at(9, 0), 25);
+        labelled.segment_label = Some("Deep Work".to_string());
+        labelled.end_reason = Some(SessionEndReason::Completed);
+        let mut interrupted = session("interrupted", SessionType::Work, at(10, 0), 10);
+        interrupted.end_reason = Some(SessionEndReason::PowerInterrupt);
+        let mut noted = session("noted", SessionType::Break, at(11, 0), 5);
+        noted.notes = Some("deep breaths".to_string());
+        noted.end_reason = Some(SessionEndReason::Skipped);
+        let older = session("older", SessionType::Work, at(12, 0), 25);
+        let sessions = vec![labelled, interrupted, noted, older];
+
+        let search = |text: &str| {
+            let page = page_sessions(
+                sessions.clone(),
+                &SessionListQuery {
+                    search: Some(text.to_string()),
+                    ..query()
+                },
+            );
+            ids(&page)
+                .into_iter()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(search("deep"), ["noted", "labelled"]);
+        assert_eq!(search("POWER"), ["interrupted"]);
+        assert_eq!(search("skipped"), ["noted"]);
+
+        let page = page_sessions(
+            sessions.clone(),
+            &SessionListQuery {
+                end_reason: Some(SessionEndReason::Completed),
+                ..query()
+            },
+        );
+        assert_eq!(ids(&page), ["labelled"]);
+        // Sessions recorded before end reasons were stored match no reason
+        let page = page_sessions(
+            sessions,
+            &SessionListQuery {
+                end_reason: Some(SessionEndReason::Interrupted),
+                ..query()
+            },
+        );
+        assert_eq!(page.total, 0);
+    }
+
+    #[test]
+    fn pages_stay_stable_when_sessions_arrive_between_them() {
+        let mut sessions: Vec<Session> = (0..7)
+            .map(|index| {
+                session(
+                    &format!("s{index}"),
+                    SessionType::Work,
+                    at(8 + index, 0),
+                    25,
+                )
+            })
+            .collect();
+        // Same start: the id breaks the tie
+        sessions.push(session("s6b", SessionType::Work, at(14, 0), 25));
+        let paging = SessionListQuery {
+            end_date: Some(at(15, 0)),
+            limit: 3,
+            ..query()
+        };
+
+        let mut seen = Vec::new();
+        for page_index in 0..3 {
+            let page = page_sessions(
+                sessions.clone(),
+                &SessionListQuery {
+                    offset: page_index * 3,
+                    ..paging.clone()
+                },
+            );
+            assert_eq!(page.total, 8);
+            seen.extend(page.sessions.into_iter().map(|s| s.id));
+            // New sessions start after the fixed end date
+            sessions.push(session(
+                &format!("new{page_index}"),
+                SessionType::Break,
+                at(15, 5 + page_index as u32 * 10),
+                5,
+            ));
+        }
+
+        assert_eq!(seen, ["s6b", "s6", "s5", "s4", "s3", "s2", "s1", "s0"]);
+    }
+
+    fn history(count: usize) -> Vec<Session> {
+        (0..count)
+            .map(|index| {
+                let kind = if index % 2 == 0 {
+                    SessionType::Work
+                } else {
+                    SessionType::Break
+                };
+                let mut s = session(
+                    &format!("session-{index}"),
+                    kind,
+                    at(0, 0) - ChronoDuration::minutes(30 * index as i64),
+                    if index % 2 == 0 { 25 } else { 5 },
+                );
+                s.notes = (index % 7 == 0).then(|| format!("note \"{index}\"\nline two"));
+                s.rating = (index % 5 == 0).then_some(4);
+                s.active_seconds = (index % 2 == 0).then_some(1200);
+                s
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sessions_round_trip_between_json_and_ndjson() {
+        let sessions = history(500);
+        let json = encode_sessions(&sessions, StorageFormat::Json).unwrap();
+        let from_json = decode_sessions(&json, StorageFormat::Json).unwrap();
+        let ndjson = encode_sessions(&from_json, StorageFormat::Ndjson).unwrap();
+        assert_eq!(ndjson.lines().count(), sessions.len());
+        let from_ndjson = decode_sessions(&ndjson, StorageFormat::Ndjson).unwrap();
+        let back = encode_sessions(&from_ndjson, StorageFormat::Json).unwrap();
+
+        assert_eq!(back, json);
+        // Blank lines (e.g. a trailing newline added by an editor) are skipped
+        let padded = format!("\n{}\n\n", ndjson);
+        assert_eq!(
+            decode_sessions(&padded, StorageFormat::Ndjson)
+                .unwrap()
+                .len(),
+            sessions.len()
+        );
+    }
+
+    #[test]
+    fn a_bad_ndjson_line_is_named() {
+        let mut ndjson = encode_sessions(&history(3), StorageFormat::Ndjson).unwrap();
+        ndjson.push_str("{not json}\n");
+
+        let err = decode_sessions(&ndjson, StorageFormat::Ndjson).unwrap_err();
+        assert!(err.to_string().contains("line 4"), "{err}");
+    }
+
+    /// A 50k-session history loads completely and in order in both formats.
+    #[test]
+    fn fifty_thousand_sessions_load_in_both_formats() {
+        let sessions = history(50_000);
+        for format in [StorageFormat::Json, StorageFormat::Ndjson] {
+            let content = encode_sessions(&sessions, format).unwrap();
+            let loaded = decode_sessions(&content, format).unwrap();
+
+            assert_eq!(loaded.len(), sessions.len(), "{format:?}");
+            assert!(
+                loaded.iter().zip(&sessions).all(|(a, b)| a.id == b.id),
+                "{format:?}"
+            );
+        }
+    }
+
+    /// Load time of a 50k-session history in both formats. A benchmark, not run
+    /// by default: `cargo test --release -- --ignored fifty_thousand`.
+    #[test]
+    #[ignore = "benchmark"]
+    fn fifty_thousand_sessions_load_quickly() {
+        let sessions = history(50_000);
+        for format in [StorageFormat::Json, StorageFormat::Ndjson] {
+            let content = encode_sessions(&sessions, format).unwrap();
+            let started = std::time::Instant::now();
+            decode_sessions(&content, format).unwrap();
+            let elapsed = started.elapsed();
+            assert!(
+                elapsed < std::time::Duration::from_secs(10),
+                "{format:?} took {elapsed:?}"
+            );
+        }
+    }
+
+    /// settings.json as written before segmented work existed.
+    const PRE_SEGMENT_SETTINGS: &str =
+        include_str!("../../tests/fixtures/settings/pre-segment.json");
+
+    fn legacy_settings_json() -> serde_json::Map<String, serde_json::Value> {
+        serde_json::from_str(PRE_SEGMENT_SETTINGS).unwrap()
+    }
+
+    fn parse_settings(fields: &serde_json::Map<String, serde_json::Value>) -> (Settings, bool) {
+        let content = serde_json::to_string(fields).unwrap();
+        DatabaseService::parse_settings_file(&content).unwrap()
+    }
+
+    #[test]
+    fn a_legacy_settings_file_keeps_every_field() {
+        let legacy = legacy_settings_json();
+        let (settings, migrated) =
+            DatabaseService::parse_settings_file(PRE_SEGMENT_SETTINGS).unwrap();
+        assert!(migrated);
+
+        let saved = serde_json::to_value(&settings).unwrap();
+        for (key, value) in &legacy {
+            assert_eq!(&saved[key], value, "{key}");
+        }
+        assert!(!settings.segmented_work_enabled);
+        assert_eq!(
+            serde_json::to_value(&settings.work_segments).unwrap(),
+            serde_json::to_value(default_work_segments()).unwrap()
+        );
+        assert!(!settings.silent_autostart);
+        for key in PRE_SEGMENT_SETTINGS_KEYS {
+            assert!(saved.get(*key).is_some(), "{key}");
+        }
+    }
+
+    #[test]
+    fn a_migrated_settings_file_is_rewritten_once() {
+        let (migrated, _) = DatabaseService::parse_settings_file(PRE_SEGMENT_SETTINGS).unwrap();
+        let rewritten = serde_json::to_string_pretty(&migrated).unwrap();
+
+        let (reloaded, migrated_again) = DatabaseService::parse_settings_file(&rewritten).unwrap();
+        assert!(!migrated_again);
+        assert_eq!(serde_json::to_string_pretty(&reloaded).unwrap(), rewritten);
+    }
+
+    #[test]
+    fn legacy_segments_out_of_bounds_fall_back_to_the_default_program() {
+        let mut legacy = legacy_settings_json();
+        legacy.insert("segmentedWorkEnabled".to_string(), true.into());
+        legacy.insert(
+            "workSegments".to_string(),
+            serde_json::json!([{ "workMinutes": 0, "breakMinutes": 5 }]),
+        );
+        let (settings, migrated) = parse_settings(&legacy);
+        assert!(migrated);
+        assert!(!settings.segmented_work_enabled);
+        assert_eq!(settings.work_segments.len(), 1);
+        assert!(settings.work_segments[0].is_within_bounds());
+
+        // Valid segments survive, with the repeat count defaulted
+        legacy.insert(
+            "workSegments".to_string(),
+            serde_json::json!([
+                { "workMinutes": 50, "breakMinutes": 10 },
+                { "workMinutes": 25, "breakMinutes": 5 }
+            ]),
+        );
+        let (settings, _) = parse_settings(&legacy);
+        assert!(settings.segmented_work_enabled);
+        let segments: Vec<(u32, u32, u32)> = settings
+            .work_segments
+            .iter()
+            .map(|segment| (segment.work_minutes, segment.break_minutes, segment.repeat))
+            .collect();
+        assert_eq!(segments, [(50, 10, 1), (25, 5, 1)]);
+    }
+
+    #[test]
+    fn legacy_silent_autostart_needs_autostart() {
+        let mut legacy = legacy_settings_json();
+        legacy.insert("silentAutostart".to_string(), true.into());
+        legacy.insert("autostart".to_string(), false.into());
+        let (settings, migrated) = parse_settings(&legacy);
+        // workSegments is still missing, so this is a legacy file
+        assert!(migrated);
+        assert!(!settings.silent_autostart);
+
+        legacy.insert("autostart".to_string(), true.into());
+        let (settings, _) = parse_settings(&legacy);
+        assert!(settings.silent_autostart);
+    }
+}
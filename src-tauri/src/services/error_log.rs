@@ -0,0 +1,55 @@
+use chrono::Local;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// File name of the error log, in the app log directory.
+const ERROR_LOG_FILE: &str = "error.log";
+
+static LOG_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Put the error log in the app log directory. Until this has run, entries
+/// only go to stderr, which release builds on Windows do not show.
+pub fn init(app: &AppHandle) {
+    let dir = match app.path().app_log_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            eprintln!("Failed to resolve log directory: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        eprintln!("Failed to create log directory {}: {}", dir.display(), e);
+        return;
+    }
+    if let Ok(mut path) = LOG_PATH.lock() {
+        *path = Some(dir.join(ERROR_LOG_FILE));
+    }
+}
+
+/// Write `message` to stderr and append it, timestamped and tagged with
+/// `source`, to the error log.
+/// 记录到错误日志（应用日志目录下的 error.log），同时输出到标准错误。
+pub fn record(source: &str, message: &str) {
+    eprintln!("[{}] {}", source, message);
+    let Some(path) = LOG_PATH.lock().ok().and_then(|path| path.clone()) else {
+        return;
+    };
+    let appended = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| {
+            writeln!(
+                file,
+                "[{}] [{}] {}",
+                Local::now().to_rfc3339(),
+                source,
+                message
+            )
+        });
+    if let Err(e) = appended {
+        eprintln!("Failed to write error log {}: {}", path.display(), e);
+    }
+}
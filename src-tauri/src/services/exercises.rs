@@ -0,0 +1,169 @@
+use crate::models::{BreakExercise, ExerciseRotation, Settings};
+use chrono::Utc;
+use serde_json::Value;
+
+/// Translation section holding the built-in exercise texts, keyed by id.
+const TRANSLATION_POINTER: &str = "/breakExercises";
+/// At most this many shown ids are remembered.
+const MAX_RECENT: usize = 200;
+
+/// A bundled exercise; `text` is the English fallback when the translation
+/// bundle has no entry for it.
+struct BuiltinExercise {
+    id: &'static str,
+    text: &'static str,
+    duration_seconds: u32,
+    category: &'static str,
+}
+
+const BUILTIN_EXERCISES: &[BuiltinExercise] = &[
+    BuiltinExercise {
+        id: "shoulderRoll",
+        text: "Roll your shoulders slowly backwards ten times.",
+        duration_seconds: 30,
+        category: "stretch",
+    },
+    BuiltinExercise {
+        id: "neckStretch",
+        text: "Tilt your head gently to each side and hold for a few breaths.",
+        duration_seconds: 30,
+        category: "stretch",
+    },
+    BuiltinExercise {
+        id: "wristStretch",
+        text: "Stretch each wrist by gently pulling your fingers back.",
+        duration_seconds: 30,
+        category: "stretch",
+    },
+    BuiltinExercise {
+        id: "eyes202020",
+        text: "Look at something about 6 meters (20 feet) away for 20 seconds.",
+        duration_seconds: 20,
+        category: "eyes",
+    },
+    BuiltinExercise {
+        id: "palming",
+        text: "Rub your palms warm and rest them over your closed eyes.",
+        duration_seconds: 30,
+        category: "eyes",
+    },
+    BuiltinExercise {
+        id: "slowBlinks",
+        text: "Blink slowly fifteen times to refresh your eyes.",
+        duration_seconds: 15,
+        category: "eyes",
+    },
+    BuiltinExercise {
+        id: "walkToWindow",
+        text: "Walk to a window and look outside for a minute.",
+        duration_seconds: 60,
+        category: "movement",
+    },
+    BuiltinExercise {
+        id: "standAndReach",
+        text: "Stand up, reach your arms overhead and take a few steps.",
+        duration_seconds: 45,
+        category: "movement",
+    },
+    BuiltinExercise {
+        id: "boxBreathing",
+        text: "Breathe in for 4, hold for 4, out for 4, hold for 4. Repeat four times.",
+        duration_seconds: 60,
+        category: "breathing",
+    },
+];
+
+/// Whether `id` names a bundled exercise (custom exercises may not reuse it).
+pub fn is_builtin_id(id: &str) -> bool {
+    BUILTIN_EXERCISES.iter().any(|exercise| exercise.id == id)
+}
+
+/// Every exercise available under `settings`: the bundled ones with their text
+/// from `translation`, then the custom ones.
+fn catalog(settings: &Settings, translation: Option<&Value>) -> Vec<BreakExercise> {
+    let texts = translation.and_then(|t| t.pointer(TRANSLATION_POINTER));
+    let builtin = BUILTIN_EXERCISES.iter().map(|exercise| BreakExercise {
+        id: exercise.id.to_string(),
+        text: texts
+            .and_then(|t| t.get(exercise.id))
+            .and_then(Value::as_str)
+            .unwrap_or(exercise.text)
+            .to_string(),
+        duration_seconds: exercise.duration_seconds,
+        category: exercise.category.to_string(),
+        custom: false,
+    });
+    let custom = settings
+        .custom_exercises
+        .iter()
+        .map(|exercise| BreakExercise {
+            id: exercise.id.clone(),
+            text: exercise.text.clone(),
+            duration_seconds: exercise.duration_seconds,
+            category: exercise.category.clone(),
+            custom: true,
+        });
+    builtin.chain(custom).collect()
+}
+
+/// Pick the next exercise and record it in `rotation`. Only the settings'
+/// `preferred_exercise_categories` are suggested (all when none match); an
+/// exercise repeats only once every other one in the pool has been shown.
+/// Asking again during the same break (`session_id`, e.g. from the reminder
+/// window of another monitor) returns the exercise already picked.
+/// 选出下一个休息练习：按偏好类别筛选，在练习池轮换完之前不重复；同一次休息内返回同一个练习。
+pub fn next_exercise(
+    settings: &Settings,
+    rotation: &mut ExerciseRotation,
+    translation: Option<&Value>,
+    session_id: Option<&str>,
+) -> Option<BreakExercise> {
+    let all = catalog(settings, translation);
+    if session_id.is_some() && rotation.session_id.as_deref() == session_id {
+        let last = rotation.recent.last();
+        if let Some(exercise) = all.iter().find(|exercise| Some(&exercise.id) == last) {
+            return Some(exercise.clone());
+        }
+    }
+
+    let preferred: Vec<BreakExercise> = all
+        .iter()
+        .filter(|exercise| {
+            settings
+                .preferred_exercise_categories
+                .iter()
+                .any(|category| category.eq_ignore_ascii_case(&exercise.category))
+        })
+        .cloned()
+        .collect();
+    let pool = if preferred.is_empty() { all } else { preferred };
+
+    let mut candidates: Vec<&BreakExercise> = pool
+        .iter()
+        .filter(|exercise| !rotation.recent.contains(&exercise.id))
+        .collect();
+    if candidates.is_empty() {
+        // Pool exhausted: start over, but not with the exercise just shown
+        let last = rotation.recent.last().cloned();
+        rotation
+            .recent
+            .retain(|id| !pool.iter().any(|exercise| &exercise.id == id));
+        candidates = pool
+            .iter()
+            .filter(|exercise| pool.len() == 1 || Some(&exercise.id) != last.as_ref())
+            .collect();
+    }
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let index = Utc::now().timestamp_subsec_nanos() as usize % candidates.len();
+    let picked = candidates[index].clone();
+    rotation.recent.push(picked.id.clone());
+    rotation.session_id = session_id.map(str::to_string);
+    if rotation.recent.len() > MAX_RECENT {
+        let excess = rotation.recent.len() - MAX_RECENT;
+        rotation.recent.drain(..excess);
+    }
+    Some(picked)
+}
@@ -0,0 +1,87 @@
+use super::database::is_completed_break;
+use crate::models::{FocusScore, FocusScoreComponent, FocusScoreSettings, Session, SessionType};
+use chrono::{Local, NaiveDate};
+
+/// Skipped breaks that use up the whole "low skips" part of the score.
+const SKIPS_FOR_ZERO: usize = 4;
+
+/// Focus score of the local day `date`, from the sessions that started on it.
+///
+/// Each part is a ratio between 0 and 1:
+/// - work goal: work time ÷ `daily_work_goal_minutes`, capped at 1
+/// - break completion: completed breaks ÷ finished breaks (1 on a day without breaks)
+/// - low skips: 1 − skipped breaks ÷ 4, at least 0
+///
+/// score = Σ ratio × weight ÷ Σ weight × 100, rounded. A break counts as
+/// completed as in analytics (`min_completion_ratio`); breaks still running are
+/// left out.
+/// 专注评分 = 各项比例按权重加权平均 × 100：工作时长达成目标的比例、休息完成率、跳过次数（跳过 4 次该项为 0）。
+pub fn compute(
+    sessions: &[Session],
+    date: NaiveDate,
+    settings: &FocusScoreSettings,
+    min_completion_ratio: f64,
+) -> FocusScore {
+    let of_day: Vec<&Session> = sessions
+        .iter()
+        .filter(|s| s.start_time.with_timezone(&Local).date_naive() == date)
+        .collect();
+    let work_seconds: i64 = of_day
+        .iter()
+        .filter(|s| s.session_type == SessionType::Work)
+        .map(|s| s.duration.max(0))
+        .sum();
+    let breaks: Vec<&&Session> = of_day
+        .iter()
+        .filter(|s| s.session_type == SessionType::Break && !s.in_progress)
+        .collect();
+    let completed_breaks = breaks
+        .iter()
+        .filter(|s| is_completed_break(s, min_completion_ratio))
+        .count();
+    let skipped_breaks = breaks.len() - completed_breaks;
+
+    let goal_seconds = i64::from(settings.daily_work_goal_minutes) * 60;
+    let work_ratio = if goal_seconds > 0 {
+        (work_seconds as f64 / goal_seconds as f64).min(1.0)
+    } else {
+        1.0
+    };
+    let completion_ratio = if breaks.is_empty() {
+        1.0
+    } else {
+        completed_breaks as f64 / breaks.len() as f64
+    };
+    let skips_ratio = 1.0 - (skipped_breaks.min(SKIPS_FOR_ZERO) as f64 / SKIPS_FOR_ZERO as f64);
+
+    let total_weight =
+        settings.work_goal_weight + settings.break_completion_weight + settings.low_skips_weight;
+    let component = |ratio: f64, weight: u32| FocusScoreComponent {
+        ratio,
+        weight,
+        points: if total_weight == 0 {
+            0.0
+        } else {
+            ratio * f64::from(weight) / f64::from(total_weight) * 100.0
+        },
+    };
+    let work_goal = component(work_ratio, settings.work_goal_weight);
+    let break_completion = component(completion_ratio, settings.break_completion_weight);
+    let low_skips = component(skips_ratio, settings.low_skips_weight);
+    let score = (work_goal.points + break_completion.points + low_skips.points)
+        .round()
+        .clamp(0.0, 100.0) as u8;
+
+    FocusScore {
+        date,
+        score,
+        work_seconds,
+        goal_seconds,
+        break_count: breaks.len(),
+        completed_breaks,
+        skipped_breaks,
+        work_goal,
+        break_completion,
+        low_skips,
+    }
+}
@@ -0,0 +1,49 @@
+use super::error_log;
+use std::process::{Command, Output};
+
+/// Run a user-configured hook command through the platform shell on a background
+/// thread; its exit status and output go to the error log.
+/// 在后台线程中通过系统 shell 执行用户配置的钩子命令，退出码与输出写入错误日志。
+pub fn run_hook(name: &'static str, command: &str) {
+    let command = command.trim().to_string();
+    if command.is_empty() {
+        return;
+    }
+
+    std::thread::spawn(move || match shell_command(&command).output() {
+        Ok(output) => log_output(name, &command, &output),
+        Err(e) => error_log::record(
+            "Hook",
+            &format!("{} failed to start `{}`: {}", name, command, e),
+        ),
+    });
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Command {
+    use std::os::windows::process::CommandExt;
+    // Keep a console window from flashing up for every hook
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let mut shell = Command::new("cmd");
+    shell.args(["/C", command]).creation_flags(CREATE_NO_WINDOW);
+    shell
+}
+
+#[cfg(not(windows))]
+fn shell_command(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.args(["-c", command]);
+    shell
+}
+
+fn log_output(name: &str, command: &str, output: &Output) {
+    let mut entry = format!("{} `{}` exited with {}", name, command, output.status);
+    for (stream, bytes) in [("stdout", &output.stdout), ("stderr", &output.stderr)] {
+        let text = String::from_utf8_lossy(bytes);
+        if !text.trim().is_empty() {
+            entry.push_str(&format!("\n{}:\n{}", stream, text.trim_end()));
+        }
+    }
+    error_log::record("Hook", &entry);
+}
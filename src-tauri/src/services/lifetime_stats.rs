@@ -0,0 +1,281 @@
+use super::database::is_completed_break;
+use crate::models::{LifetimeStats, LifetimeTotals, Session, SessionType, YearStats};
+use chrono::{DateTime, Datelike, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Incrementally maintained all-time counters, stored as `lifetime_stats.json`.
+/// Only additive counters are kept so applying every session once gives the same
+/// rollup as `rebuild`, whatever the order; streaks and per-year figures are
+/// derived in `stats`.
+/// 可增量维护的全部历史汇总：只保存可加计数，派生指标在查询时计算。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LifetimeRollup {
+    /// Start of the earliest session ever recorded
+    first_use: Option<DateTime<Utc>>,
+    /// Totals per local month, keyed "YYYY-MM"
+    months: BTreeMap<String, LifetimeTotals>,
+    /// Completed breaks per local day (days without any are left out)
+    completed_break_days: BTreeMap<NaiveDate, i64>,
+}
+
+impl LifetimeRollup {
+    /// Rollup of `sessions` computed from scratch.
+    pub fn rebuild(sessions: &[Session], min_completion_ratio: f64) -> Self {
+        let mut rollup = Self::default();
+        for session in sessions {
+            rollup.apply(None, session, min_completion_ratio);
+        }
+        rollup
+    }
+
+    /// Replace `previous` (the stored version of the same session, if any) with
+    /// `current`. Sessions still in progress are not counted.
+    pub fn apply(
+        &mut self,
+        previous: Option<&Session>,
+        current: &Session,
+        min_completion_ratio: f64,
+    ) {
+        if let Some(previous) = previous {
+            self.add(previous, -1, min_completion_ratio);
+        }
+        self.add(current, 1, min_completion_ratio);
+    }
+
+    fn add(&mut self, session: &Session, sign: i64, min_completion_ratio: f64) {
+        if session.in_progress {
+            return;
+        }
+        let local = session.start_time.with_timezone(&Local);
+        let month = format!("{:04}-{:02}", local.year(), local.month());
+        let totals = self.months.entry(month.clone()).or_default();
+        match session.session_type {
+            SessionType::Work => {
+                totals.work_sessions += sign;
+                totals.work_seconds += sign * session.duration;
+            }
+            SessionType::Break => {
+                totals.breaks += sign;
+                totals.break_seconds += sign * session.duration;
+                if is_completed_break(session, min_completion_ratio) {
+                    totals.completed_breaks += sign;
+                    let day = self
+                        .completed_break_days
+                        .entry(local.date_naive())
+                        .or_default();
+                    *day += sign;
+                    if *day <= 0 {
+                        self.completed_break_days.remove(&local.date_naive());
+                    }
+                } else {
+                    totals.skipped_breaks += sign;
+                }
+            }
+        }
+        // Keep emptied months out so the incremental rollup matches a rebuild
+        if self.months.get(&month) == Some(&LifetimeTotals::default()) {
+            self.months.remove(&month);
+        }
+        let earliest = !matches!(self.first_use, Some(first) if first <= session.start_time);
+        if sign > 0 && earliest {
+            self.first_use = Some(session.start_time);
+        }
+    }
+
+    /// Derive the all-time statistics.
+    pub fn stats(&self) -> LifetimeStats {
+        let mut totals = LifetimeTotals::default();
+        let mut years: BTreeMap<i32, (LifetimeTotals, Option<(u32, LifetimeTotals)>)> =
+            BTreeMap::new();
+        let mut best_month: Option<(&str, LifetimeTotals)> = None;
+
+        for (key, month) in &self.months {
+            add_totals(&mut totals, month);
+            let Some((year, month_number)) = parse_month(key) else {
+                continue;
+            };
+            let (year_totals, year_best) = years.entry(year).or_default();
+            add_totals(year_totals, month);
+            if more_disciplined(month, year_best.as_ref().map(|(_, best)| best)) {
+                *year_best = Some((month_number, *month));
+            }
+            if more_disciplined(month, best_month.as_ref().map(|(_, best)| best)) {
+                best_month = Some((key.as_str(), *month));
+            }
+        }
+
+        LifetimeStats {
+            first_use_date: self
+                .first_use
+                .map(|first| first.with_timezone(&Local).date_naive()),
+            totals,
+            longest_streak_days: self.longest_streak_days(),
+            most_disciplined_month: best_month.map(|(key, _)| key.to_string()),
+            years: years
+                .into_iter()
+                .map(|(year, (totals, best))| YearStats {
+                    year,
+                    totals,
+                    most_disciplined_month: best.map(|(month, _)| month),
+                })
+                .collect(),
+        }
+    }
+
+    fn longest_streak_days(&self) -> u32 {
+        let mut longest = 0;
+        let mut current = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for day in self.completed_break_days.keys() {
+            current = match previous {
+                Some(prev) if prev.succ_opt() == Some(*day) => current + 1,
+                _ => 1,
+            };
+            longest = longest.max(current);
+            previous = Some(*day);
+        }
+        longest
+    }
+}
+
+fn add_totals(into: &mut LifetimeTotals, other: &LifetimeTotals) {
+    into.work_sessions += other.work_sessions;
+    into.work_seconds += other.work_seconds;
+    into.breaks += other.breaks;
+    into.completed_breaks += other.completed_breaks;
+    into.skipped_breaks += other.skipped_breaks;
+    into.break_seconds += other.break_seconds;
+}
+
+fn parse_month(key: &str) -> Option<(i32, u32)> {
+    let (year, month) = key.split_once('-')?;
+    Some((year.parse().ok()?, month.parse().ok()?))
+}
+
+/// Higher share of completed breaks wins, then more completed breaks; months
+/// without breaks never qualify and earlier months win ties.
+fn more_disciplined(candidate: &LifetimeTotals, best: Option<&LifetimeTotals>) -> bool {
+    if candidate.breaks <= 0 {
+        return false;
+    }
+    let Some(best) = best else {
+        return true;
+    };
+    let lhs = candidate.completed_breaks * best.breaks;
+    let rhs = best.completed_breaks * candidate.breaks;
+    lhs > rhs || (lhs == rhs && candidate.completed_breaks > best.completed_breaks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    const RATIO: f64 = 0.8;
+
+    /// Small deterministic generator (xorshift) so every run checks the same cases.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, bound: u64) -> i64 {
+            (self.next() % bound) as i64
+        }
+    }
+
+    fn session(rng: &mut Rng, index: usize) -> Session {
+        let start = Utc.with_ymd_and_hms(2025, 11, 20, 8, 0, 0).unwrap()
+            + Duration::minutes(rng.below(90 * 24 * 60));
+        let planned = 60 * (1 + rng.below(30));
+        Session {
+            id: format!("session-{index}"),
+            session_type: if rng.below(2) == 0 {
+                SessionType::Work
+            } else {
+                SessionType::Break
+            },
+            start_time: start,
+            end_time: start,
+            duration: 0,
+            planned_duration: planned,
+            is_skipped: false,
+            extended_seconds: 0,
+            notes: None,
+            rating: None,
+            in_progress: true,
+            anomaly_dismissed: false,
+            acknowledged: None,
+            active_seconds: None,
+            segment_label: None,
+            end_reason: None,
+        }
+    }
+
+    /// The next stored version of `session`: a checkpoint, the finished record,
+    /// or an edit of a finished one.
+    fn next_version(rng: &mut Rng, session: &Session) -> Session {
+        let mut next = session.clone();
+        next.duration = rng.below(next.planned_duration as u64 * 2);
+        next.end_time = next.start_time + Duration::seconds(next.duration);
+        next.in_progress = session.in_progress && rng.below(3) == 0;
+        next.is_skipped = next.session_type == SessionType::Break && rng.below(4) == 0;
+        next
+    }
+
+    #[test]
+    fn incremental_updates_match_a_rebuild() {
+        for seed in 1..=50u64 {
+            let mut rng = Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+            let mut stored: Vec<Session> = Vec::new();
+            let mut rollup = LifetimeRollup::default();
+            for step in 0..200 {
+                if stored.is_empty() || rng.below(3) == 0 {
+                    let placeholder = session(&mut rng, step);
+                    rollup.apply(None, &placeholder, RATIO);
+                    stored.push(placeholder);
+                } else {
+                    let index = rng.below(stored.len() as u64) as usize;
+                    let next = next_version(&mut rng, &stored[index]);
+                    rollup.apply(Some(&stored[index]), &next, RATIO);
+                    stored[index] = next;
+                }
+            }
+
+            assert_eq!(
+                rollup,
+                LifetimeRollup::rebuild(&stored, RATIO),
+                "seed {seed}"
+            );
+            assert_eq!(
+                rollup.stats(),
+                LifetimeRollup::rebuild(&stored, RATIO).stats(),
+                "seed {seed}"
+            );
+        }
+    }
+
+    #[test]
+    fn rebuild_does_not_depend_on_the_order() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        let mut sessions: Vec<Session> = (0..300)
+            .map(|index| {
+                let placeholder = session(&mut rng, index);
+                let mut finished = next_version(&mut rng, &placeholder);
+                finished.in_progress = false;
+                finished
+            })
+            .collect();
+        let forward = LifetimeRollup::rebuild(&sessions, RATIO);
+        sessions.reverse();
+
+        assert_eq!(forward, LifetimeRollup::rebuild(&sessions, RATIO));
+    }
+}
@@ -1,8 +1,29 @@
+pub mod accessibility;
+pub mod activity;
+pub mod anomalies;
+pub mod break_themes;
+pub mod calls;
+pub mod connectivity;
 pub mod database;
+pub mod error_log;
+pub mod exercises;
+pub mod focus_score;
+pub mod hooks;
+pub mod lifetime_stats;
+pub mod network_policy;
+pub mod notifications;
+pub mod panics;
 pub mod power;
 pub mod remote;
+pub mod rest_debt;
+pub mod screen_time;
+pub mod session_ids;
+pub mod system_status;
 pub mod timer;
+pub mod timer_deps;
 pub mod updater;
+pub mod webhook;
 
 pub use database::DatabaseService;
 pub use timer::TimerService;
+pub use timer_deps::{Clock, EventSink, SessionStore, SystemClock};
@@ -0,0 +1,254 @@
+use crate::models::{NetworkQuietHours, Settings};
+use chrono::{DateTime, Datelike, Local, NaiveTime};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// How long a metered-connection detection result is reused.
+const METERED_DETECTION_TTL: Duration = Duration::from_secs(60);
+
+/// Settings deciding when background network calls may run.
+#[derive(Debug, Clone, Default)]
+struct NetworkPolicy {
+    quiet_hours: Vec<NetworkQuietHours>,
+    metered_mode: bool,
+    auto_detect_metered: bool,
+}
+
+static POLICY: Mutex<Option<NetworkPolicy>> = Mutex::new(None);
+static METERED_CACHE: Mutex<Option<(Instant, bool)>> = Mutex::new(None);
+
+/// Why a background network call was not made now.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum NetworkDeferred {
+    #[error("Network deferred: metered connection")]
+    Metered,
+    #[error("Network deferred: quiet hours until {until}")]
+    QuietHours { until: DateTime<Local> },
+}
+
+/// Remember the network settings (`network_quiet_hours`, `metered_mode`,
+/// `auto_detect_metered`).
+/// 记录网络静默时段与按流量计费设置，供后台网络请求前检查。
+pub fn set_policy(settings: &Settings) {
+    if let Ok(mut policy) = POLICY.lock() {
+        *policy = Some(NetworkPolicy {
+            quiet_hours: settings.network_quiet_hours.clone(),
+            metered_mode: settings.metered_mode,
+            auto_detect_metered: settings.auto_detect_metered,
+        });
+    }
+}
+
+/// Whether a background network call (quote fetch, update check) may run now.
+/// Calls the user asks for explicitly are not gated.
+/// 后台网络请求前调用：按流量计费或处于静默时段时返回推迟原因。
+pub fn ensure_allowed() -> Result<(), NetworkDeferred> {
+    let policy = POLICY
+        .lock()
+        .ok()
+        .and_then(|policy| policy.clone())
+        .unwrap_or_default();
+    if policy.metered_mode || (policy.auto_detect_metered && is_metered_cached()) {
+        return Err(NetworkDeferred::Metered);
+    }
+    match quiet_until(&policy.quiet_hours, Local::now()) {
+        Some(until) => Err(NetworkDeferred::QuietHours { until }),
+        None => Ok(()),
+    }
+}
+
+/// End of the quiet range covering `now`, if any. A range whose end is not
+/// after its start runs past midnight and belongs to the day it starts on, so
+/// "22:00"–"06:00" on Fridays covers Friday night until Saturday 06:00. Ranges
+/// with unparsable times are ignored.
+/// 当前时刻所在静默时段的结束时间；跨午夜的时段归属于开始的那一天。
+pub fn quiet_until(ranges: &[NetworkQuietHours], now: DateTime<Local>) -> Option<DateTime<Local>> {
+    let time = now.time();
+    let today = now.date_naive();
+    let yesterday = today.pred_opt()?;
+    ranges.iter().find_map(|range| {
+        let start = parse_time(&range.start)?;
+        let end = parse_time(&range.end)?;
+        let applies = |weekday: chrono::Weekday| {
+            range.days.is_empty() || range.days.contains(&weekday.number_from_monday())
+        };
+        let until_end = || {
+            let end_date = if time < end { today } else { today.succ_opt()? };
+            end_date.and_time(end).and_local_timezone(Local).earliest()
+        };
+        let covered = if start < end {
+            applies(today.weekday()) && start <= time && time < end
+        } else if start > end {
+            let tonight = applies(today.weekday()) && time >= start;
+            let last_night = applies(yesterday.weekday()) && time < end;
+            tonight || last_night
+        } else {
+            false
+        };
+        covered.then(until_end).flatten()
+    })
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// OS detection, cached for `METERED_DETECTION_TTL`.
+fn is_metered_cached() -> bool {
+    if let Ok(cache) = METERED_CACHE.lock() {
+        if let Some((at, metered)) = *cache {
+            if at.elapsed() < METERED_DETECTION_TTL {
+                return metered;
+            }
+        }
+    }
+    let metered = detect_metered();
+    if let Ok(mut cache) = METERED_CACHE.lock() {
+        *cache = Some((Instant::now(), metered));
+    }
+    metered
+}
+
+/// Whether the current internet connection is metered (fixed or variable cost,
+/// roaming, or over its data limit).
+/// 通过系统 API 判断当前连接是否按流量计费（仅 Windows 可检测）。
+#[cfg(windows)]
+fn detect_metered() -> bool {
+    use windows::Networking::Connectivity::{NetworkCostType, NetworkInformation};
+
+    let Ok(profile) = NetworkInformation::GetInternetConnectionProfile() else {
+        return false;
+    };
+    let Ok(cost) = profile.GetConnectionCost() else {
+        return false;
+    };
+    let cost_type = cost
+        .NetworkCostType()
+        .unwrap_or(NetworkCostType::Unrestricted);
+    matches!(
+        cost_type,
+        NetworkCostType::Fixed | NetworkCostType::Variable
+    ) || cost.Roaming().unwrap_or(false)
+        || cost.OverDataLimit().unwrap_or(false)
+}
+
+#[cfg(not(windows))]
+fn detect_metered() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn quiet(start: &str, end: &str, days: &[u32]) -> NetworkQuietHours {
+        NetworkQuietHours {
+            start: start.to_string(),
+            end: end.to_string(),
+            days: days.to_vec(),
+        }
+    }
+
+    /// `hour:minute` on January `day`, 2026; the 16th is a Friday.
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 1, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    /// Check `(now, quiet until)` pairs against `ranges`.
+    fn assert_quiet(
+        ranges: &[NetworkQuietHours],
+        cases: &[(DateTime<Local>, Option<DateTime<Local>>)],
+    ) {
+        for (now, until) in cases {
+            assert_eq!(quiet_until(ranges, *now), *until, "at {now}");
+        }
+    }
+
+    #[test]
+    fn a_nightly_range_covers_both_sides_of_midnight() {
+        let saturday_morning = Some(at(17, 6, 0));
+        assert_quiet(
+            &[quiet("22:00", "06:00", &[])],
+            &[
+                (at(16, 21, 59), None),
+                (at(16, 22, 0), saturday_morning),
+                (at(16, 23, 59), saturday_morning),
+                (at(17, 0, 0), saturday_morning),
+                (at(17, 5, 59), saturday_morning),
+                (at(17, 6, 0), None),
+                (at(17, 12, 0), None),
+            ],
+        );
+    }
+
+    #[test]
+    fn a_range_past_midnight_belongs_to_the_day_it_starts() {
+        // Friday nights only
+        assert_quiet(
+            &[quiet("22:00", "06:00", &[5])],
+            &[
+                (at(16, 1, 0), None),
+                (at(16, 23, 0), Some(at(17, 6, 0))),
+                (at(17, 1, 0), Some(at(17, 6, 0))),
+                (at(17, 23, 0), None),
+                (at(18, 1, 0), None),
+            ],
+        );
+    }
+
+    #[test]
+    fn ranges_ending_or_starting_at_midnight() {
+        assert_quiet(
+            &[quiet("20:00", "00:00", &[5])],
+            &[
+                (at(16, 19, 59), None),
+                (at(16, 23, 30), Some(at(17, 0, 0))),
+                (at(17, 0, 0), None),
+            ],
+        );
+        assert_quiet(
+            &[quiet("00:00", "06:00", &[6])],
+            &[
+                (at(16, 23, 59), None),
+                (at(17, 0, 0), Some(at(17, 6, 0))),
+                (at(18, 0, 0), None),
+            ],
+        );
+    }
+
+    #[test]
+    fn a_daytime_range_only_applies_on_its_days() {
+        let weekdays = [quiet("09:00", "17:00", &[1, 2, 3, 4, 5])];
+        assert_quiet(
+            &weekdays,
+            &[
+                (at(16, 8, 59), None),
+                (at(16, 9, 0), Some(at(16, 17, 0))),
+                (at(16, 16, 59), Some(at(16, 17, 0))),
+                (at(16, 17, 0), None),
+                (at(17, 10, 0), None),
+            ],
+        );
+    }
+
+    #[test]
+    fn empty_and_unparsable_ranges_are_ignored() {
+        let ranges = [
+            quiet("10:00", "10:00", &[]),
+            quiet("late", "06:00", &[]),
+            quiet("23:00", "07:00", &[]),
+        ];
+        assert_quiet(
+            &ranges,
+            &[
+                (at(16, 10, 0), None),
+                (at(16, 23, 0), Some(at(17, 7, 0))),
+                (at(17, 6, 59), Some(at(17, 7, 0))),
+            ],
+        );
+    }
+}
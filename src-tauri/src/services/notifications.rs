@@ -0,0 +1,197 @@
+use super::{DatabaseService, TimerService};
+use crate::models::{PhaseChangeCause, TimerPhase};
+use crate::utils::AppResult;
+use std::sync::{Arc, OnceLock};
+use tauri::{AppHandle, Emitter};
+
+/// Break-end notification: end the break now.
+pub const ACTION_START_WORK: &str = "start-work";
+/// Break-end notification: add `EXTEND_BREAK_MINUTES` to the break.
+pub const ACTION_EXTEND_BREAK: &str = "extend-5";
+const EXTEND_BREAK_MINUTES: u32 = 5;
+/// Suppression-expiring notification: end the suppression now.
+pub const ACTION_RESUME_BREAKS: &str = "resume-breaks";
+/// Suppression-expiring notification: add `EXTEND_SUPPRESSION_MINUTES` to it.
+pub const ACTION_EXTEND_SUPPRESSION: &str = "extend-suppression";
+const EXTEND_SUPPRESSION_MINUTES: i64 = 60;
+
+/// A notification button; `id` is reported back when it is clicked.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub struct NotificationAction<'a> {
+    pub id: &'a str,
+    pub label: &'a str,
+}
+
+/// What button activations act on, set once by `init`.
+#[cfg_attr(not(windows), allow(dead_code))]
+struct ActionContext {
+    app: AppHandle,
+    timer: Arc<TimerService>,
+    database: Arc<tokio::sync::Mutex<DatabaseService>>,
+}
+
+static ACTION_CONTEXT: OnceLock<ActionContext> = OnceLock::new();
+
+/// Register the app's notification identity and the activation handler.
+/// 启动时注册通知所属的 AppUserModelID 与按钮回调，需在发送带按钮的通知前调用。
+pub fn init(
+    app: &AppHandle,
+    timer: Arc<TimerService>,
+    database: Arc<tokio::sync::Mutex<DatabaseService>>,
+) {
+    #[cfg(windows)]
+    windows_impl::register_app_user_model_id(&app.config().identifier);
+
+    let _ = ACTION_CONTEXT.set(ActionContext {
+        app: app.clone(),
+        timer,
+        database,
+    });
+}
+
+/// Show a notification with `actions` as buttons, falling back to a plain
+/// notification where buttons are unsupported (non-Windows, old Windows builds).
+/// 优先发送带按钮的通知，平台不支持时退回普通通知。
+pub fn show(app: &AppHandle, title: &str, body: &str, actions: &[NotificationAction]) {
+    if !actions.is_empty() {
+        match show_with_actions(app, title, body, actions) {
+            Ok(()) => return,
+            Err(e) => eprintln!(
+                "Action notification unavailable, sending a plain one: {}",
+                e
+            ),
+        }
+    }
+    show_plain(app, title, body);
+}
+
+/// Show a notification with buttons; errors when the platform cannot.
+#[cfg(windows)]
+pub fn show_with_actions(
+    app: &AppHandle,
+    title: &str,
+    body: &str,
+    actions: &[NotificationAction],
+) -> Result<(), String> {
+    windows_impl::show_toast(app, title, body, actions)
+}
+
+/// Show a notification with buttons; errors when the platform cannot.
+#[cfg(not(windows))]
+pub fn show_with_actions(
+    _app: &AppHandle,
+    _title: &str,
+    _body: &str,
+    _actions: &[NotificationAction],
+) -> Result<(), String> {
+    Err("Notification actions are only supported on Windows".to_string())
+}
+
+fn show_plain(app: &AppHandle, title: &str, body: &str) {
+    use tauri_plugin_notification::NotificationExt;
+
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        eprintln!("Failed to show notification: {}", e);
+    }
+}
+
+/// Route a clicked button. Break-end and suppression-expiring buttons drive the
+/// timer here; the pre-break buttons are forwarded to the frontend as
+/// `pre-break-action`.
+/// 处理通知按钮：休息结束与暂不休息到期按钮直接操作计时器，其余按钮转发给前端。
+#[cfg_attr(not(windows), allow(dead_code))]
+fn dispatch_action(id: String) {
+    let Some(context) = ACTION_CONTEXT.get() else {
+        eprintln!("Notification action {} before init", id);
+        return;
+    };
+    let result = match id.as_str() {
+        ACTION_RESUME_BREAKS => context.timer.resume_breaks(),
+        ACTION_EXTEND_SUPPRESSION => context.timer.extend_suppression(EXTEND_SUPPRESSION_MINUTES),
+        ACTION_START_WORK | ACTION_EXTEND_BREAK => {
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = handle_break_end_action(context, &id).await {
+                    eprintln!("Failed to handle notification action {}: {}", id, e);
+                }
+            });
+            return;
+        }
+        _ => {
+            let _ = context.app.emit("pre-break-action", id);
+            return;
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("Failed to handle notification action {}: {}", id, e);
+    }
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+async fn handle_break_end_action(context: &ActionContext, id: &str) -> AppResult<()> {
+    // A button clicked after the break already ended is stale
+    if context.timer.get_info().phase != TimerPhase::Break {
+        return Ok(());
+    }
+    match id {
+        ACTION_START_WORK => {
+            let skipped = context.timer.skip(true, PhaseChangeCause::UserSkip)?;
+            if let Some((Some(session), _)) = skipped {
+                let db = context.database.lock().await;
+                db.save_or_update_session(&session).await?;
+            }
+        }
+        ACTION_EXTEND_BREAK => context.timer.extend(EXTEND_BREAK_MINUTES)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+mod windows_impl {
+    use super::{dispatch_action, NotificationAction};
+    use tauri::AppHandle;
+    use tauri_winrt_notification::{Duration, Toast};
+    use windows::core::HSTRING;
+    use windows::Win32::UI::Shell::SetCurrentProcessExplicitAppUserModelID;
+
+    /// Toasts are attributed to, and activate, the process with this AUMID.
+    pub(super) fn register_app_user_model_id(app_id: &str) {
+        if let Err(e) = unsafe { SetCurrentProcessExplicitAppUserModelID(&HSTRING::from(app_id)) } {
+            eprintln!("Failed to set AppUserModelID: {}", e);
+        }
+    }
+
+    pub(super) fn show_toast(
+        app: &AppHandle,
+        title: &str,
+        body: &str,
+        actions: &[NotificationAction],
+    ) -> Result<(), String> {
+        let app_id = app.config().identifier.clone();
+        build_toast(&app_id, title, body, actions)
+            .show()
+            .or_else(|err| {
+                // Fallback to the powershell workaround app id (useful in dev where
+                // the AppUserModelID may not be registered yet).
+                build_toast(Toast::POWERSHELL_APP_ID, title, body, actions)
+                    .show()
+                    .map_err(|e| format!("{err}; fallback: {e}"))
+            })
+    }
+
+    fn build_toast(app_id: &str, title: &str, body: &str, actions: &[NotificationAction]) -> Toast {
+        let mut toast = Toast::new(app_id)
+            .title(title)
+            .text1(body)
+            .duration(Duration::Short);
+        for action in actions {
+            toast = toast.add_button(action.label, action.id);
+        }
+        toast.on_activated(|action| {
+            if let Some(id) = action {
+                dispatch_action(id);
+            }
+            Ok(())
+        })
+    }
+}
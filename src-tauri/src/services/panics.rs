@@ -0,0 +1,118 @@
+use crate::models::BackendPanic;
+use chrono::Local;
+use std::future::Future;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// File name of the panic log, in the app log directory once it is known.
+const PANIC_LOG_FILE: &str = "panic.log";
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+static LOG_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+// Message of the most recent panic, for the `backend-panic` event
+static LAST_PANIC: Mutex<Option<String>> = Mutex::new(None);
+
+/// Log every panic (location and message) to stderr and the panic log.
+/// 安装 panic 钩子：记录 panic 位置与信息到标准错误输出和 panic.log。
+pub fn install_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        let msg = match info.payload().downcast_ref::<&str>() {
+            Some(s) => *s,
+            None => match info.payload().downcast_ref::<String>() {
+                Some(s) => &s[..],
+                None => "Box<Any>",
+            },
+        };
+        let location = info
+            .location()
+            .map(|l| l.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        let log = format!("Panic occurred at {}: {}", location, msg);
+        eprintln!("{}", log);
+        if let Ok(mut last) = LAST_PANIC.lock() {
+            *last = Some(log.clone());
+        }
+        let appended = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_path())
+            .and_then(|mut file| writeln!(file, "[{}] {}", Local::now().to_rfc3339(), log));
+        if let Err(e) = appended {
+            eprintln!("Failed to write panic log: {}", e);
+        }
+    }));
+}
+
+/// Keep the app handle for `backend-panic` events and move the panic log into
+/// the app log directory (it stays in the working directory if that fails).
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    let dir = match app.path().app_log_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            eprintln!("Failed to resolve log directory: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        eprintln!("Failed to create log directory {}: {}", dir.display(), e);
+        return;
+    }
+    if let Ok(mut path) = LOG_PATH.lock() {
+        *path = Some(dir.join(PANIC_LOG_FILE));
+    }
+}
+
+/// Where panics are logged.
+pub fn log_path() -> PathBuf {
+    LOG_PATH
+        .lock()
+        .ok()
+        .and_then(|path| path.clone())
+        .unwrap_or_else(|| {
+            std::env::current_dir()
+                .map(|dir| dir.join(PANIC_LOG_FILE))
+                .unwrap_or_else(|_| PathBuf::from(PANIC_LOG_FILE))
+        })
+}
+
+/// Payload of `backend-panic` for a panic caught in `task`.
+pub fn backend_panic(task: &str, recovered: bool) -> BackendPanic {
+    BackendPanic {
+        task: task.to_string(),
+        message: LAST_PANIC
+            .lock()
+            .ok()
+            .and_then(|last| last.clone())
+            .unwrap_or_default(),
+        log_path: log_path().display().to_string(),
+        recovered,
+    }
+}
+
+/// Tell the UI that a background task panicked (`backend-panic`), so it can
+/// show where the log was saved.
+/// 后台任务 panic 时发送 `backend-panic` 事件，界面据此提示并显示日志位置。
+pub fn report(task: &str, recovered: bool) {
+    eprintln!("Background task '{}' panicked", task);
+    if let Some(app) = APP.get() {
+        let _ = app.emit("backend-panic", backend_panic(task, recovered));
+    }
+}
+
+/// Spawn a long-running background task; if it panics the panic is reported
+/// instead of the task ending silently.
+pub fn spawn_guarded<F>(task: &'static str, future: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = tokio::spawn(future).await {
+            if e.is_panic() {
+                report(task, false);
+            }
+        }
+    });
+}
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use super::TimerService;
+use crate::models::PowerMonitorHealth;
 
 /// Start monitoring power state changes (display off, system suspend/resume).
 /// This ensures the timer pauses when the screen turns off or system hibernates,
@@ -21,11 +22,29 @@ pub fn start_display_power_monitor(timer: Arc<TimerService>) {
     }
 }
 
+/// Health of the power monitor, for diagnostics. Only the Windows monitor is
+/// supervised; elsewhere `supported` is false.
+/// 电源监听的健康状态（仅 Windows 有心跳与自动重启）。
+pub fn monitor_health() -> PowerMonitorHealth {
+    #[cfg(windows)]
+    {
+        windows_impl::health()
+    }
+
+    #[cfg(not(windows))]
+    {
+        PowerMonitorHealth::default()
+    }
+}
+
 #[cfg(windows)]
 mod windows_impl {
     use super::*;
-    use std::sync::OnceLock;
+    use chrono::{DateTime, Utc};
+    use std::sync::atomic::{AtomicBool, AtomicI64, AtomicIsize, AtomicU32, Ordering};
+    use std::sync::{Mutex, RwLock};
     use std::thread;
+    use std::time::Duration;
 
     use windows::core::{w, Error, Result as WinResult, GUID};
     use windows::Win32::Foundation::{
@@ -38,32 +57,119 @@ mod windows_impl {
     };
     use windows::Win32::System::SystemServices::GUID_CONSOLE_DISPLAY_STATE;
     use windows::Win32::UI::WindowsAndMessaging::{
-        CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetMessageW,
-        PostQuitMessage, RegisterClassW, TranslateMessage, DEVICE_NOTIFY_WINDOW_HANDLE,
+        CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetMessageW, KillTimer,
+        PostQuitMessage, RegisterClassW, SetTimer, TranslateMessage, DEVICE_NOTIFY_WINDOW_HANDLE,
         HWND_MESSAGE, MSG, PBT_APMRESUMEAUTOMATIC, PBT_APMRESUMESUSPEND, PBT_APMSUSPEND,
         PBT_POWERSETTINGCHANGE, WINDOW_EX_STYLE, WINDOW_STYLE, WM_DESTROY, WM_POWERBROADCAST,
-        WNDCLASSW,
+        WM_TIMER, WNDCLASSW,
     };
 
     // GUID for system suspend/resume power setting notifications
     // {5d3e9a59-e9D5-4b00-a6bd-ff34ff516548}
     const GUID_SYSTEM_AWAYMODE: GUID = GUID::from_u128(0x98a7f580_01f7_48aa_9c0f_44352c29e5c0);
 
-    static TIMER_INSTANCE: OnceLock<Arc<TimerService>> = OnceLock::new();
+    /// The message loop refreshes its heartbeat this often (WM_TIMER)
+    const HEARTBEAT_TIMER_ID: usize = 1;
+    const HEARTBEAT_INTERVAL_MS: u32 = 20_000;
+    /// A heartbeat older than this means the message loop is stuck
+    const HEARTBEAT_STALE_SECONDS: i64 = 90;
+    const SUPERVISOR_INTERVAL: Duration = Duration::from_secs(60);
+
+    // Kept in a slot rather than a OnceLock so a restarted loop (and a repeated
+    // `start`) reuses the same slot instead of being rejected
+    static TIMER_INSTANCE: RwLock<Option<Arc<TimerService>>> = RwLock::new(None);
+    static SUPERVISOR_STARTED: AtomicBool = AtomicBool::new(false);
+    /// Message window of the current loop; windows of abandoned loops ignore
+    /// power events and close themselves
+    static ACTIVE_HWND: AtomicIsize = AtomicIsize::new(0);
+    static RUNNING: AtomicBool = AtomicBool::new(false);
+    /// Unix seconds of the last heartbeat, 0 before the first one
+    static LAST_HEARTBEAT: AtomicI64 = AtomicI64::new(0);
+    static RESTARTS: AtomicU32 = AtomicU32::new(0);
+    static LAST_ERROR: Mutex<Option<String>> = Mutex::new(None);
 
     pub(super) fn start(timer: Arc<TimerService>) {
-        if TIMER_INSTANCE.set(timer).is_err() {
-            // Already started; ignore duplicate registrations.
+        *TIMER_INSTANCE.write().unwrap() = Some(timer);
+        if SUPERVISOR_STARTED.swap(true, Ordering::SeqCst) {
+            // Already running; the loop picks up the new timer from the slot.
             return;
         }
 
+        spawn_loop();
+        thread::spawn(supervise);
+    }
+
+    pub(super) fn health() -> PowerMonitorHealth {
+        let last_heartbeat = LAST_HEARTBEAT.load(Ordering::SeqCst);
+        PowerMonitorHealth {
+            supported: true,
+            running: RUNNING.load(Ordering::SeqCst),
+            healthy: is_healthy(),
+            last_heartbeat: (last_heartbeat > 0)
+                .then(|| DateTime::<Utc>::from_timestamp(last_heartbeat, 0))
+                .flatten(),
+            restarts: RESTARTS.load(Ordering::SeqCst),
+            last_error: LAST_ERROR.lock().ok().and_then(|error| error.clone()),
+        }
+    }
+
+    fn is_healthy() -> bool {
+        let last = LAST_HEARTBEAT.load(Ordering::SeqCst);
+        RUNNING.load(Ordering::SeqCst)
+            && last > 0
+            && Utc::now().timestamp() - last <= HEARTBEAT_STALE_SECONDS
+    }
+
+    fn spawn_loop() {
         thread::spawn(|| {
             if let Err(err) = run_loop() {
                 eprintln!("Display power monitor failed: {:?}", err);
+                if let Ok(mut last_error) = LAST_ERROR.lock() {
+                    *last_error = Some(err.to_string());
+                }
             }
         });
     }
 
+    /// Restart the message loop whenever it exited or stopped beating.
+    /// 每分钟检查一次心跳，消息循环退出或卡住时重新启动。
+    fn supervise() {
+        loop {
+            thread::sleep(SUPERVISOR_INTERVAL);
+            if is_healthy() {
+                continue;
+            }
+            let restarts = RESTARTS.fetch_add(1, Ordering::SeqCst) + 1;
+            let status = if RUNNING.load(Ordering::SeqCst) {
+                "stalled"
+            } else {
+                "stopped"
+            };
+            eprintln!(
+                "[Power] Display power monitor {} (heartbeat {}s ago), restarting (#{})",
+                status,
+                Utc::now().timestamp() - LAST_HEARTBEAT.load(Ordering::SeqCst),
+                restarts
+            );
+            // A stuck loop that wakes up later must not act alongside the new one
+            ACTIVE_HWND.store(0, Ordering::SeqCst);
+            RUNNING.store(false, Ordering::SeqCst);
+            spawn_loop();
+        }
+    }
+
+    fn current_timer() -> Option<Arc<TimerService>> {
+        TIMER_INSTANCE.read().ok().and_then(|timer| timer.clone())
+    }
+
+    fn beat() {
+        LAST_HEARTBEAT.store(Utc::now().timestamp(), Ordering::SeqCst);
+    }
+
+    fn is_active(hwnd: HWND) -> bool {
+        ACTIVE_HWND.load(Ordering::SeqCst) == hwnd.0 as isize
+    }
+
     fn run_loop() -> WinResult<()> {
         unsafe {
             let module = GetModuleHandleW(None)?;
@@ -113,10 +219,16 @@ mod windows_impl {
                 DEVICE_NOTIFY_WINDOW_HANDLE,
             );
 
+            ACTIVE_HWND.store(hwnd.0 as isize, Ordering::SeqCst);
+            RUNNING.store(true, Ordering::SeqCst);
+            beat();
+            SetTimer(Some(hwnd), HEARTBEAT_TIMER_ID, HEARTBEAT_INTERVAL_MS, None);
+
             let mut msg = MSG::default();
             loop {
                 let result = GetMessageW(&mut msg, None, 0, 0);
                 if result.0 == -1 {
+                    mark_stopped(hwnd);
                     UnregisterPowerSettingNotification(notify)?;
                     if let Ok(h) = notify_away {
                         let _ = UnregisterPowerSettingNotification(h);
@@ -131,15 +243,27 @@ mod windows_impl {
                 DispatchMessageW(&msg);
             }
 
+            mark_stopped(hwnd);
+            eprintln!("[Power] Display power monitor message loop exited");
             UnregisterPowerSettingNotification(notify)?;
             if let Ok(h) = notify_away {
                 let _ = UnregisterPowerSettingNotification(h);
             }
-            DestroyWindow(hwnd)?;
+            // Already destroyed when the loop ended through WM_DESTROY
+            let _ = DestroyWindow(hwnd);
         }
         Ok(())
     }
 
+    fn mark_stopped(hwnd: HWND) {
+        if ACTIVE_HWND
+            .compare_exchange(hwnd.0 as isize, 0, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+        {
+            RUNNING.store(false, Ordering::SeqCst);
+        }
+    }
+
     unsafe extern "system" fn window_proc(
         hwnd: HWND,
         msg: u32,
@@ -147,12 +271,22 @@ mod windows_impl {
         lparam: LPARAM,
     ) -> LRESULT {
         match msg {
+            WM_TIMER if wparam.0 == HEARTBEAT_TIMER_ID => {
+                if is_active(hwnd) {
+                    beat();
+                } else {
+                    // Superseded by a restarted loop
+                    let _ = KillTimer(Some(hwnd), HEARTBEAT_TIMER_ID);
+                    let _ = DestroyWindow(hwnd);
+                }
+                LRESULT(0)
+            }
             WM_POWERBROADCAST => {
-                if let Some(timer) = TIMER_INSTANCE.get() {
+                if let Some(timer) = current_timer().filter(|_| is_active(hwnd)) {
                     let wparam_val = wparam.0 as u32;
                     match wparam_val {
                         PBT_POWERSETTINGCHANGE => {
-                            handle_power_setting(lparam, timer);
+                            handle_power_setting(lparam, &timer);
                         }
                         PBT_APMSUSPEND => {
                             // System is about to suspend/hibernate
@@ -296,7 +430,8 @@ mod macos_impl {
 
     unsafe fn add_observer(center: id, observer: id, selector: Sel, name: &str) {
         let ns_name = NSString::alloc(nil).init_str(name);
-        let _: () = msg_send![center, addObserver: observer selector: selector name: ns_name object: nil];
+        let _: () =
+            msg_send![center, addObserver: observer selector: selector name: ns_name object: nil];
     }
 
     extern "C" fn screen_did_sleep(_: &Object, _: Sel, _: id) {
@@ -409,10 +544,7 @@ mod linux_impl {
         Ok(())
     }
 
-    fn handle_sleep_signal(
-        timer: &Arc<TimerService>,
-        msg: zbus::Message,
-    ) -> zbus::Result<()> {
+    fn handle_sleep_signal(timer: &Arc<TimerService>, msg: zbus::Message) -> zbus::Result<()> {
         if let Ok((sleeping,)) = msg.body().deserialize::<(bool,)>() {
             if sleeping {
                 eprintln!("[Power] System suspending, pausing timer");
@@ -429,10 +561,7 @@ mod linux_impl {
         Ok(())
     }
 
-    fn handle_screen_signal(
-        timer: &Arc<TimerService>,
-        msg: zbus::Message,
-    ) -> zbus::Result<()> {
+    fn handle_screen_signal(timer: &Arc<TimerService>, msg: zbus::Message) -> zbus::Result<()> {
         if let Ok((active,)) = msg.body().deserialize::<(bool,)>() {
             if active {
                 eprintln!("[Power] Screen saver active, pausing timer");
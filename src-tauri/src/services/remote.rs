@@ -1,12 +1,55 @@
+use super::{connectivity, network_policy};
+use crate::models::{BreakContent, BreakContentSource, BreakTheme, QuoteProvider};
 use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
 use std::time::Duration;
 use tauri::AppHandle;
+use thiserror::Error;
 
-const HITOKOTO_URL: &str = "https://v1.hitokoto.cn/?encode=json";
-const VIEWBITS_URL: &str = "https://api.viewbits.com/v1/zenquotes?mode=random";
 const QUOTE_USER_AGENT: &str = "RESTY-Quote";
+const HTTP_USER_AGENT: &str = "RESTY";
+const LOCAL_EYE_CARE_TIP_POINTER: &str = "/dashboard/tips/library/eyeCare";
+const LOCAL_HEALTH_TIP_POINTER: &str = "/dashboard/tips/library/health";
+const LOCAL_BREAK_MESSAGE_POINTER: &str = "/reminder/message/break";
+const BUILTIN_BREAK_MESSAGE: &str = "Time to rest your eyes for a moment.";
+
+/// Process-wide HTTP client; callers set per-request timeouts.
+/// 进程内共享的 HTTP 客户端，复用连接池，超时由各请求单独设置。
+pub fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .user_agent(HTTP_USER_AGENT)
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+/// 最近一次成功获取的名言，按语言分桶缓存，用于离线回退。
+fn quote_cache() -> &'static Mutex<HashMap<&'static str, String>> {
+    static QUOTE_CACHE: OnceLock<Mutex<HashMap<&'static str, String>>> = OnceLock::new();
+    QUOTE_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn quote_bucket(language: &str) -> &'static str {
+    if language.to_lowercase().starts_with("zh") {
+        "zh"
+    } else {
+        "en"
+    }
+}
+
+/// Return the last successfully fetched quote for the language, if any.
+pub fn cached_tip_quote(language: &str) -> Option<String> {
+    quote_cache()
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(quote_bucket(language)).cloned())
+}
 
 fn normalize_quote(value: Option<&str>) -> Option<String> {
     let text = value?.trim();
@@ -17,29 +60,36 @@ fn normalize_quote(value: Option<&str>) -> Option<String> {
     }
 }
 
-fn extract_viewbits_quote(payload: &Value) -> Option<String> {
-    if let Some(value) = payload.get("q").and_then(|item| item.as_str()) {
-        return normalize_quote(Some(value));
-    }
-    if let Some(items) = payload.get("data").and_then(|item| item.as_array()) {
-        if let Some(value) = items
-            .get(0)
-            .and_then(|item| item.get("q"))
-            .and_then(|item| item.as_str())
-        {
-            return normalize_quote(Some(value));
-        }
-    }
-    if let Some(items) = payload.as_array() {
-        if let Some(value) = items
-            .get(0)
-            .and_then(|item| item.get("q"))
-            .and_then(|item| item.as_str())
-        {
-            return normalize_quote(Some(value));
-        }
-    }
-    None
+/// Quote text at the first of `pointers` that holds a non-empty string.
+fn extract_quote(payload: &Value, pointers: &[String]) -> Option<String> {
+    pointers
+        .iter()
+        .find_map(|pointer| normalize_quote(payload.pointer(pointer).and_then(Value::as_str)))
+}
+
+/// Whether `provider` serves `language` (a code such as "zh-CN").
+fn provider_serves(provider: &QuoteProvider, language: &str) -> bool {
+    provider.language.as_deref().is_none_or(|prefix| {
+        language
+            .to_lowercase()
+            .starts_with(&prefix.trim().to_lowercase())
+    })
+}
+
+/// Why a quote provider produced no quote.
+#[derive(Error, Debug)]
+pub enum QuoteSourceError {
+    #[error("Network error: {0}")]
+    Network(String),
+
+    #[error("Quote API returned {0}")]
+    Status(reqwest::StatusCode),
+
+    #[error("Response is not valid JSON: {0}")]
+    Parse(String),
+
+    #[error("No quote text at {0}")]
+    PathMiss(String),
 }
 
 fn resolve_local_asset_path(asset_path: &str) -> Option<PathBuf> {
@@ -53,40 +103,191 @@ fn resolve_local_asset_path(asset_path: &str) -> Option<PathBuf> {
     candidates.into_iter().find(|path| path.exists())
 }
 
-async fn fetch_json(url: &str) -> Result<Value> {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(6))
-        .user_agent(QUOTE_USER_AGENT)
-        .build()
-        .context("Failed to build quote HTTP client")?;
-    let response = client
+async fn fetch_json(url: &str) -> std::result::Result<Value, QuoteSourceError> {
+    let response = http_client()
         .get(url)
+        .timeout(Duration::from_secs(6))
+        .header(reqwest::header::USER_AGENT, QUOTE_USER_AGENT)
         .send()
         .await
-        .context("Failed to request quote API")?;
+        .map_err(|e| QuoteSourceError::Network(e.to_string()))?;
 
     if !response.status().is_success() {
-        return Err(anyhow!("Quote API returned {}", response.status()));
+        return Err(QuoteSourceError::Status(response.status()));
     }
 
-    response
-        .json::<Value>()
+    let body = response
+        .bytes()
         .await
-        .context("Failed to parse quote payload")
+        .map_err(|e| QuoteSourceError::Network(e.to_string()))?;
+    serde_json::from_slice(&body).map_err(|e| QuoteSourceError::Parse(e.to_string()))
 }
 
-pub async fn fetch_tip_quote(language: &str) -> Result<Option<String>> {
-    let is_zh = language.to_lowercase().starts_with("zh");
-    let payload = if is_zh {
-        fetch_json(HITOKOTO_URL).await?
-    } else {
-        fetch_json(VIEWBITS_URL).await?
+/// Request `provider` once and extract its quote, exactly as the break
+/// content does (used directly by `test_quote_source`).
+/// 请求单个名言来源并提取名言，与休息文案使用同一套逻辑。
+pub async fn fetch_provider_quote(
+    provider: &QuoteProvider,
+) -> std::result::Result<String, QuoteSourceError> {
+    let payload = fetch_json(&provider.url).await?;
+    extract_quote(&payload, &provider.pointers)
+        .ok_or_else(|| QuoteSourceError::PathMiss(provider.pointers.join(", ")))
+}
+
+/// Fetch a quote from the `providers` serving `language`, in order: a provider
+/// that fails or answers without a quote falls through to the next. The error of
+/// the last failing provider is returned when none produced a quote, and right
+/// away when known offline. While network calls are deferred (metered
+/// connection or quiet hours) the cached quote is returned instead.
+/// 按顺序尝试名言来源，失败时回退到下一个；已知离线时直接返回错误，网络推迟时返回缓存。
+pub async fn fetch_tip_quote(
+    language: &str,
+    providers: &[QuoteProvider],
+) -> Result<Option<String>> {
+    connectivity::ensure_online()?;
+    if let Err(deferred) = network_policy::ensure_allowed() {
+        eprintln!("[Quote] {}; using cached quote", deferred);
+        return Ok(cached_tip_quote(language));
+    }
+    let mut last_error: Option<anyhow::Error> = None;
+    for provider in providers.iter().filter(|p| provider_serves(p, language)) {
+        let text = match fetch_provider_quote(provider).await {
+            Ok(text) => text,
+            Err(QuoteSourceError::PathMiss(_)) => {
+                eprintln!("[Quote] {} returned no quote", provider.name);
+                continue;
+            }
+            Err(err) => {
+                eprintln!("[Quote] {} unavailable: {}", provider.name, err);
+                last_error = Some(err.into());
+                continue;
+            }
+        };
+        if let Ok(mut cache) = quote_cache().lock() {
+            cache.insert(quote_bucket(language), text.clone());
+        }
+        return Ok(Some(text));
+    }
+
+    match last_error {
+        Some(err) => Err(err),
+        None => Ok(None),
+    }
+}
+
+/// A random non-blank message of `messages`.
+fn pick_message(messages: &[&str]) -> Option<String> {
+    let pool: Vec<&str> = messages
+        .iter()
+        .copied()
+        .filter(|text| !text.trim().is_empty())
+        .collect();
+    if pool.is_empty() {
+        return None;
+    }
+    let index = Utc::now().timestamp_subsec_nanos() as usize % pool.len();
+    normalize_quote(Some(pool[index]))
+}
+
+fn pick_local_tip(translation: &Value, pointers: &[&str]) -> Option<String> {
+    let pool: Vec<&str> = pointers
+        .iter()
+        .filter_map(|pointer| translation.pointer(pointer))
+        .filter_map(|value| value.as_array())
+        .flatten()
+        .filter_map(|item| item.as_str())
+        .collect();
+    pick_message(&pool).or_else(|| {
+        translation
+            .pointer(LOCAL_BREAK_MESSAGE_POINTER)
+            .and_then(|value| normalize_quote(value.as_str()))
+    })
+}
+
+/// Resolve break text: a message of today's `theme` when it has any, else
+/// remote quote → cached quote → bundled local tip. The theme itself is
+/// returned along with the text.
+/// 优先使用当日主题的文案，否则依次尝试远程名言、缓存与本地贴士，保证休息提醒始终有文案。
+///
+/// `source` mirrors the tips card setting: `"local"`/`"health"` skip the
+/// network and go straight to the bundled pools. `providers` are the quote
+/// sources from settings. When known offline the cached quote is used at once.
+pub async fn get_break_content(
+    app: &AppHandle,
+    language: &str,
+    source: Option<&str>,
+    providers: &[QuoteProvider],
+    theme: Option<&BreakTheme>,
+) -> BreakContent {
+    let themed = theme.and_then(|theme| {
+        let messages: Vec<&str> = theme.messages.iter().map(String::as_str).collect();
+        pick_message(&messages)
+    });
+    let mut content = match themed {
+        Some(text) => BreakContent {
+            text,
+            source: BreakContentSource::Theme,
+            theme: None,
+            hydration: None,
+        },
+        None => resolve_break_text(app, language, source, providers).await,
     };
+    content.theme = theme.cloned();
+    content
+}
 
-    if is_zh {
-        Ok(normalize_quote(payload.get("hitokoto").and_then(|item| item.as_str())))
-    } else {
-        Ok(extract_viewbits_quote(&payload))
+async fn resolve_break_text(
+    app: &AppHandle,
+    language: &str,
+    source: Option<&str>,
+    providers: &[QuoteProvider],
+) -> BreakContent {
+    let local_pointers: &[&str] = match source {
+        Some("local") => &[LOCAL_EYE_CARE_TIP_POINTER],
+        Some("health") => &[LOCAL_HEALTH_TIP_POINTER],
+        _ => &[LOCAL_EYE_CARE_TIP_POINTER, LOCAL_HEALTH_TIP_POINTER],
+    };
+    let try_remote = !matches!(source, Some("local") | Some("health"));
+
+    if try_remote {
+        if !connectivity::is_known_offline() && network_policy::ensure_allowed().is_ok() {
+            match fetch_tip_quote(language, providers).await {
+                Ok(Some(text)) => {
+                    return BreakContent {
+                        text,
+                        source: BreakContentSource::Remote,
+                        theme: None,
+                        hydration: None,
+                    }
+                }
+                Ok(None) => {}
+                Err(err) => eprintln!("[Quote] Remote quote unavailable: {:#}", err),
+            }
+        }
+
+        if let Some(text) = cached_tip_quote(language) {
+            return BreakContent {
+                text,
+                source: BreakContentSource::Cache,
+                theme: None,
+                hydration: None,
+            };
+        }
+    }
+
+    let local = match load_translation(app, language).await {
+        Ok(translation) => pick_local_tip(&translation, local_pointers),
+        Err(err) => {
+            eprintln!("[Quote] Failed to load local messages: {:#}", err);
+            None
+        }
+    };
+
+    BreakContent {
+        text: local.unwrap_or_else(|| BUILTIN_BREAK_MESSAGE.to_string()),
+        source: BreakContentSource::Local,
+        theme: None,
+        hydration: None,
     }
 }
 
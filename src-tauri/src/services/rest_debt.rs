@@ -0,0 +1,214 @@
+use crate::models::{Session, SessionType};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+
+/// Only sessions that ended within this many hours count toward the debt.
+pub const WINDOW_HOURS: i64 = 8;
+/// Missed rest counts half as much after this many minutes, a quarter after
+/// twice as long, and so on.
+pub const HALF_LIFE_MINUTES: f64 = 120.0;
+/// The next break is lengthened only once the debt reaches this many minutes.
+pub const COMPENSATION_THRESHOLD_MINUTES: f64 = 5.0;
+/// At most this many minutes are added to one break.
+pub const MAX_COMPENSATION_MINUTES: u32 = 10;
+
+/// Weight of rest missed `age_minutes` ago: 0.5^(age / half-life).
+pub fn decay(age_minutes: f64) -> f64 {
+    0.5_f64.powf(age_minutes.max(0.0) / HALF_LIFE_MINUTES)
+}
+
+/// Rest owed at `now`, in minutes: planned minus taken break time over the last
+/// `WINDOW_HOURS`, each break weighted by `decay` of its age. A break cut short
+/// adds what was left of it; one taken longer than planned pays earlier debt
+/// back. Two work sessions less than `break_minutes` apart (a break skipped
+/// while breaks were suppressed or in flow mode) add the part of a
+/// `break_minutes` break that the gap did not cover. Never negative.
+/// 休息欠债（分钟）：近 8 小时内计划休息与实际休息之差，按时间衰减（半衰期 2 小时）。
+pub fn debt_minutes(sessions: &[Session], now: DateTime<Utc>, break_minutes: u32) -> f64 {
+    let window_start = now - ChronoDuration::hours(WINDOW_HOURS);
+    let mut finished: Vec<&Session> = sessions
+        .iter()
+        .filter(|s| !s.in_progress && s.end_time >= window_start && s.end_time <= now)
+        .collect();
+    finished.sort_by_key(|s| s.start_time);
+
+    let age_minutes = |at: DateTime<Utc>| (now - at).num_seconds() as f64 / 60.0;
+    let mut debt = 0.0;
+    for (index, session) in finished.iter().enumerate() {
+        match session.session_type {
+            SessionType::Break => {
+                let missed = (session.planned_duration - session.duration.max(0)) as f64 / 60.0;
+                debt += missed * decay(age_minutes(session.end_time));
+            }
+            SessionType::Work => {
+                let Some(next) = finished.get(index + 1) else {
+                    continue;
+                };
+                if next.session_type != SessionType::Work {
+                    continue;
+                }
+                let gap_minutes = (next.start_time - session.end_time).num_seconds() as f64 / 60.0;
+                let missed = f64::from(break_minutes) - gap_minutes.max(0.0);
+                if missed > 0.0 {
+                    debt += missed * decay(age_minutes(session.end_time));
+                }
+            }
+        }
+    }
+    debt.max(0.0)
+}
+
+/// Minutes to add to the next break for `debt` minutes owed: `percent` of the
+/// debt, rounded, up to `MAX_COMPENSATION_MINUTES`; none below the threshold.
+/// 按欠债比例延长下一次休息，欠债低于阈值时不延长。
+pub fn compensation_minutes(debt: f64, percent: u32) -> u32 {
+    if percent == 0 || debt < COMPENSATION_THRESHOLD_MINUTES {
+        return 0;
+    }
+    let extra = (debt * f64::from(percent.min(100)) / 100.0).round() as u32;
+    extra.min(MAX_COMPENSATION_MINUTES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, 18, 0, 0).unwrap()
+    }
+
+    /// A session of `session_type` ending `ended_minutes_ago`, `taken` of
+    /// `planned` minutes long.
+    fn session(
+        session_type: SessionType,
+        ended_minutes_ago: i64,
+        planned: i64,
+        taken: i64,
+    ) -> Session {
+        let end_time = now() - ChronoDuration::minutes(ended_minutes_ago);
+        Session {
+            id: format!("{:?}-{}", session_type, ended_minutes_ago),
+            session_type,
+            start_time: end_time - ChronoDuration::minutes(taken),
+            end_time,
+            duration: taken * 60,
+            planned_duration: planned * 60,
+            is_skipped: taken < planned,
+            extended_seconds: 0,
+            notes: None,
+            rating: None,
+            in_progress: false,
+            anomaly_dismissed: false,
+            acknowledged: None,
+            active_seconds: None,
+            segment_label: None,
+            end_reason: None,
+        }
+    }
+
+    fn assert_minutes(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {expected} min, got {actual}"
+        );
+    }
+
+    #[test]
+    fn decay_halves_every_half_life() {
+        assert_eq!(HALF_LIFE_MINUTES, 120.0);
+        assert_minutes(decay(0.0), 1.0);
+        assert_minutes(decay(60.0), 0.5_f64.sqrt());
+        assert_minutes(decay(120.0), 0.5);
+        assert_minutes(decay(240.0), 0.25);
+        assert_minutes(decay(WINDOW_HOURS as f64 * 60.0), 0.0625);
+        // Clock skew never weighs a break more than fully
+        assert_minutes(decay(-30.0), 1.0);
+    }
+
+    #[test]
+    fn skipped_breaks_decay_with_their_age() {
+        let just_now = [session(SessionType::Break, 0, 5, 0)];
+        assert_minutes(debt_minutes(&just_now, now(), 5), 5.0);
+
+        let two_hours_ago = [session(SessionType::Break, 120, 5, 0)];
+        assert_minutes(debt_minutes(&two_hours_ago, now(), 5), 2.5);
+
+        let both = [just_now[0].clone(), two_hours_ago[0].clone()];
+        assert_minutes(debt_minutes(&both, now(), 5), 7.5);
+    }
+
+    #[test]
+    fn a_break_cut_short_owes_what_was_left() {
+        let sessions = [session(SessionType::Break, 240, 10, 4)];
+        assert_minutes(debt_minutes(&sessions, now(), 5), 6.0 * 0.25);
+    }
+
+    #[test]
+    fn a_longer_break_pays_earlier_debt_back() {
+        let sessions = [
+            session(SessionType::Break, 120, 5, 0),
+            // 4 extra minutes now outweigh the 2.5 still owed
+            session(SessionType::Break, 0, 5, 9),
+        ];
+        assert_minutes(debt_minutes(&sessions, now(), 5), 0.0);
+
+        let partly = [
+            session(SessionType::Break, 0, 10, 0),
+            session(SessionType::Break, 60, 5, 7),
+        ];
+        assert_minutes(debt_minutes(&partly, now(), 5), 10.0 - 2.0 * 0.5_f64.sqrt());
+    }
+
+    #[test]
+    fn back_to_back_work_owes_the_uncovered_part_of_a_break() {
+        let first = session(SessionType::Work, 60, 25, 25);
+        let mut second = session(SessionType::Work, 33, 25, 25);
+        // Two minutes between the two work sessions
+        second.start_time = first.end_time + ChronoDuration::minutes(2);
+        second.end_time = second.start_time + ChronoDuration::minutes(25);
+
+        let sessions = [second, first];
+        assert_minutes(debt_minutes(&sessions, now(), 5), 3.0 * 0.5_f64.sqrt());
+        // A gap as long as the break owes nothing
+        assert_minutes(debt_minutes(&sessions, now(), 2), 0.0);
+    }
+
+    #[test]
+    fn old_running_and_future_sessions_are_left_out() {
+        let mut running = session(SessionType::Break, 0, 5, 0);
+        running.in_progress = true;
+        let sessions = [
+            session(SessionType::Break, WINDOW_HOURS * 60 + 1, 5, 0),
+            session(SessionType::Break, -10, 5, 0),
+            running,
+        ];
+        assert_minutes(debt_minutes(&sessions, now(), 5), 0.0);
+
+        let at_window_start = [session(SessionType::Break, WINDOW_HOURS * 60, 16, 0)];
+        assert_minutes(debt_minutes(&at_window_start, now(), 5), 1.0);
+    }
+
+    #[test]
+    fn compensation_is_a_capped_share_of_the_debt() {
+        assert_eq!(COMPENSATION_THRESHOLD_MINUTES, 5.0);
+        assert_eq!(MAX_COMPENSATION_MINUTES, 10);
+        // (debt, percent, extra minutes)
+        let cases = [
+            (4.9, 100, 0),
+            (5.0, 100, 5),
+            (10.0, 50, 5),
+            (7.0, 50, 4),
+            (6.8, 25, 2),
+            (30.0, 50, 10),
+            (8.0, 250, 8),
+            (20.0, 0, 0),
+        ];
+        for (debt, percent, extra) in cases {
+            assert_eq!(
+                compensation_minutes(debt, percent),
+                extra,
+                "{debt} min owed at {percent}%"
+            );
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::models::ScreenContinuity;
+use chrono::{DateTime, Local, Utc};
+
+/// Tracks how long the user has been at the computer since their last real
+/// break. Time accumulates between samples while input is present; a break of
+/// at least `min_real_break_minutes`, an equally long absence or a new local
+/// day starts the count over.
+/// 记录距上次真正休息后连续使用电脑的时长：足够长的休息、离开或跨天都会清零。
+#[derive(Debug, Clone, Default)]
+pub struct ScreenTimeTracker {
+    continuity: ScreenContinuity,
+    threshold_seconds: u64,
+}
+
+impl ScreenTimeTracker {
+    /// Set the shortest break or absence that resets the count.
+    pub fn set_min_real_break_minutes(&mut self, minutes: u32) {
+        self.threshold_seconds = u64::from(minutes.max(1)) * 60;
+    }
+
+    /// Whether a break lasting `seconds` counts as a real one.
+    pub fn is_real_break(&self, seconds: i64) -> bool {
+        seconds >= 0 && seconds as u64 >= self.threshold_seconds
+    }
+
+    /// Continue from a value saved by a previous run; the time the app was not
+    /// running counts as an absence.
+    pub fn restore(&mut self, saved: ScreenContinuity, now: DateTime<Utc>) {
+        self.continuity = saved;
+        self.sample(now, None);
+    }
+
+    /// Account for the time since the previous sample. `idle_seconds` is the
+    /// time since the last keyboard or mouse input, if the platform reports it.
+    pub fn sample(&mut self, now: DateTime<Utc>, idle_seconds: Option<u64>) {
+        let Some(previous) = self.continuity.updated_at else {
+            self.continuity.updated_at = Some(now);
+            return;
+        };
+        let elapsed = (now - previous).num_seconds().max(0) as u64;
+        let new_day =
+            previous.with_timezone(&Local).date_naive() != now.with_timezone(&Local).date_naive();
+        let away = elapsed.max(idle_seconds.unwrap_or(0));
+        if new_day || away >= self.threshold_seconds {
+            self.continuity.seconds = 0;
+        } else if idle_seconds.is_none_or(|idle| idle < elapsed) {
+            // Input since the previous sample: the whole interval was spent here
+            self.continuity.seconds += elapsed;
+        }
+        self.continuity.updated_at = Some(now);
+    }
+
+    /// Start over after a real break.
+    pub fn reset(&mut self, now: DateTime<Utc>) {
+        self.continuity = ScreenContinuity {
+            seconds: 0,
+            updated_at: Some(now),
+        };
+    }
+
+    /// Seconds at the computer since the last real break.
+    pub fn seconds(&self) -> u64 {
+        self.continuity.seconds
+    }
+
+    /// Current value, as persisted across restarts.
+    pub fn snapshot(&self) -> ScreenContinuity {
+        self.continuity
+    }
+}
@@ -0,0 +1,63 @@
+use crate::models::SessionIdScheme;
+use chrono::NaiveDate;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Hands out session IDs in the configured scheme (settings `session_id_scheme`
+/// and `session_id_prefix`). IDs already in the history are skipped, so the
+/// id-based upsert never merges two sessions.
+/// 按配置的方案生成会话 ID，并跳过历史中已存在的 ID，保证按 ID 更新不会合并不同会话。
+#[derive(Debug, Clone, Default)]
+pub struct SessionIdGenerator {
+    scheme: SessionIdScheme,
+    prefix: String,
+    /// Last number tried by the sequential scheme
+    sequence: u64,
+    /// Day and last counter tried by the daily scheme
+    daily: Option<(NaiveDate, u32)>,
+    taken: HashSet<String>,
+}
+
+impl SessionIdGenerator {
+    /// Switch scheme or prefix; counters restart and skip taken IDs.
+    pub fn configure(&mut self, scheme: SessionIdScheme, prefix: &str) {
+        if self.scheme == scheme && self.prefix == prefix {
+            return;
+        }
+        self.scheme = scheme;
+        self.prefix = prefix.to_string();
+        self.sequence = 0;
+        self.daily = None;
+    }
+
+    /// Record IDs already in use (the stored history).
+    pub fn seed<I: IntoIterator<Item = String>>(&mut self, ids: I) {
+        self.taken.extend(ids);
+    }
+
+    /// Next unused ID; `today` is the local date the session starts on.
+    pub fn next(&mut self, today: NaiveDate) -> String {
+        let id = loop {
+            let candidate = match self.scheme {
+                SessionIdScheme::Uuid => format!("{}{}", self.prefix, Uuid::new_v4()),
+                SessionIdScheme::Sequential => {
+                    self.sequence += 1;
+                    format!("{}{:06}", self.prefix, self.sequence)
+                }
+                SessionIdScheme::Daily => {
+                    let counter = match self.daily {
+                        Some((day, counter)) if day == today => counter + 1,
+                        _ => 1,
+                    };
+                    self.daily = Some((today, counter));
+                    format!("{}{}-{:03}", self.prefix, today.format("%Y%m%d"), counter)
+                }
+            };
+            if !self.taken.contains(&candidate) {
+                break candidate;
+            }
+        };
+        self.taken.insert(id.clone());
+        id
+    }
+}
@@ -0,0 +1,160 @@
+use super::connectivity;
+use crate::models::{ReminderMode, RemoteSessionBehavior, Settings, SystemStatus};
+
+/// How the reminder for one break is presented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakPresentation {
+    /// Reminder window, fullscreen or floating
+    Window { fullscreen: bool },
+    /// System notification only
+    Notification,
+    /// Nothing is shown; the break still runs
+    Hidden,
+}
+
+/// Current system status. Nothing is cached: a session can move between the
+/// console and a remote desktop at any time.
+pub fn current() -> SystemStatus {
+    SystemStatus {
+        // Not detected yet
+        is_fullscreen: false,
+        is_do_not_disturb: false,
+        is_remote_session: is_remote_session(),
+        is_online: !connectivity::is_known_offline(),
+        // Filled in from the app state by the command
+        tray_available: true,
+    }
+}
+
+/// Whether the app runs inside a remote desktop (RDP) session.
+/// 是否运行在远程桌面会话中（仅 Windows 可检测）。
+pub fn is_remote_session() -> bool {
+    #[cfg(windows)]
+    {
+        use windows::Win32::UI::WindowsAndMessaging::{GetSystemMetrics, SM_REMOTESESSION};
+        unsafe { GetSystemMetrics(SM_REMOTESESSION) != 0 }
+    }
+
+    #[cfg(not(windows))]
+    {
+        false
+    }
+}
+
+/// Presentation of the next break reminder. `is_remote_session` is checked on
+/// every call, so each break sees where the session is now.
+/// 按提醒模式与远程会话设置决定本次休息提醒的呈现方式。
+pub fn break_presentation(settings: &Settings) -> BreakPresentation {
+    presentation_for(settings, is_remote_session)
+}
+
+/// `break_presentation` with the remote session check passed in.
+fn presentation_for(settings: &Settings, is_remote: impl FnOnce() -> bool) -> BreakPresentation {
+    let fullscreen = matches!(settings.reminder_mode, ReminderMode::Fullscreen);
+    if !is_remote() {
+        return BreakPresentation::Window { fullscreen };
+    }
+    match settings.remote_session_behavior {
+        RemoteSessionBehavior::Normal => BreakPresentation::Window { fullscreen },
+        RemoteSessionBehavior::FloatingOnly => BreakPresentation::Window { fullscreen: false },
+        RemoteSessionBehavior::NotificationsOnly => BreakPresentation::Notification,
+        RemoteSessionBehavior::Suppress => BreakPresentation::Hidden,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    const ALL_BEHAVIORS: [RemoteSessionBehavior; 4] = [
+        RemoteSessionBehavior::Normal,
+        RemoteSessionBehavior::FloatingOnly,
+        RemoteSessionBehavior::NotificationsOnly,
+        RemoteSessionBehavior::Suppress,
+    ];
+
+    fn settings(mode: ReminderMode, behavior: RemoteSessionBehavior) -> Settings {
+        Settings {
+            reminder_mode: mode,
+            remote_session_behavior: behavior,
+            ..Settings::default()
+        }
+    }
+
+    #[test]
+    fn the_console_always_gets_the_configured_window() {
+        for behavior in ALL_BEHAVIORS {
+            assert_eq!(
+                presentation_for(&settings(ReminderMode::Fullscreen, behavior), || false),
+                BreakPresentation::Window { fullscreen: true }
+            );
+            assert_eq!(
+                presentation_for(&settings(ReminderMode::Floating, behavior), || false),
+                BreakPresentation::Window { fullscreen: false }
+            );
+        }
+    }
+
+    #[test]
+    fn a_remote_session_applies_the_remote_behavior() {
+        let cases = [
+            (
+                RemoteSessionBehavior::Normal,
+                BreakPresentation::Window { fullscreen: true },
+            ),
+            (
+                RemoteSessionBehavior::FloatingOnly,
+                BreakPresentation::Window { fullscreen: false },
+            ),
+            (
+                RemoteSessionBehavior::NotificationsOnly,
+                BreakPresentation::Notification,
+            ),
+            (RemoteSessionBehavior::Suppress, BreakPresentation::Hidden),
+        ];
+        for (behavior, expected) in cases {
+            assert_eq!(
+                presentation_for(&settings(ReminderMode::Fullscreen, behavior), || true),
+                expected,
+                "{behavior:?}"
+            );
+        }
+        assert_eq!(
+            presentation_for(
+                &settings(ReminderMode::Floating, RemoteSessionBehavior::Normal),
+                || true
+            ),
+            BreakPresentation::Window { fullscreen: false }
+        );
+    }
+
+    #[test]
+    fn the_session_is_checked_again_for_every_break() {
+        let settings = settings(
+            ReminderMode::Fullscreen,
+            RemoteSessionBehavior::NotificationsOnly,
+        );
+        let checks = Cell::new(0);
+        // Console, then RDP, then back on the console
+        let presentations: Vec<BreakPresentation> = [false, true, false]
+            .into_iter()
+            .map(|remote| {
+                presentation_for(&settings, || {
+                    checks.set(checks.get() + 1);
+                    remote
+                })
+            })
+            .collect();
+
+        assert_eq!(checks.get(), 3);
+        assert_eq!(
+            presentations,
+            [
+                BreakPresentation::Window { fullscreen: true },
+                BreakPresentation::Notification,
+                BreakPresentation::Window { fullscreen: true },
+            ]
+        );
+    }
+}
@@ -1,23 +1,83 @@
-use crate::models::{Session, SessionType, TimerInfo, TimerPhase, TimerState, WorkSegment};
-use crate::services::DatabaseService;
-use crate::utils::AppResult;
-use chrono::{Duration as ChronoDuration, Local, TimeZone, Utc};
-use std::sync::{Arc, Mutex};
-use tauri::{AppHandle, Emitter};
+use crate::models::{
+    BreakAcknowledged, ClockChanged, DayStarted, EffectiveTimerMode, HydrationProgress,
+    HydrationSettings, PhaseChange, PhaseChangeCause, ScheduleCompleteAction, ScheduledPhase,
+    ScreenContinuity, Session, SessionEndReason, SessionType, Settings, SuppressionExpiring,
+    TickerHealth, TimeAnchoredSegment, TimerAccuracy, TimerInfo, TimerPhase, TimerState,
+    WindDownSettings, WorkNotePrompt, WorkSegment,
+};
+use crate::services::panics;
+use crate::services::rest_debt;
+use crate::services::screen_time::ScreenTimeTracker;
+use crate::services::session_ids::SessionIdGenerator;
+use crate::services::{Clock, DatabaseService, EventSink, SessionStore, SystemClock};
+use crate::utils::{AppError, AppResult};
+use chrono::{Duration as ChronoDuration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::Serialize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Instant;
+use tauri::AppHandle;
 use tokio::time::{self, Duration as TokioDuration, MissedTickBehavior};
 use uuid::Uuid;
 
-const POWER_INTERRUPT_BREAK_NOTE: &str = "power-interrupt-break";
 const POWER_INTERRUPT_WORK_NOTE: &str = "power-interrupt-work";
+/// 补偿休息：每抑制 1 小时额外增加的休息分钟数。
+const CATCH_UP_EXTRA_MINUTES_PER_HOUR: i64 = 2;
+/// 补偿休息的最长时长（分钟）。
+const CATCH_UP_MAX_MINUTES: u32 = 30;
+/// 阶段开始后多少秒内的跳过请求会被忽略（防止双击连跳两个阶段）。
+const MIN_PHASE_AGE_FOR_SKIP_SECONDS: i64 = 2;
+/// 用户在该时间窗口（毫秒）内的连续跳过只生效一次。
+const SKIP_DEBOUNCE_MILLIS: i64 = 1000;
+/// 短于该时长（秒）的工作阶段结束时不弹出工作记录窗口。
+const WORK_NOTE_MIN_SESSION_SECONDS: i64 = 5 * 60;
+/// 运行中的阶段每隔多少秒静默写入一次进度，崩溃后可恢复到最近的检查点。
+const SESSION_CHECKPOINT_INTERVAL_SECONDS: i64 = 60;
+/// 休息剩余多少秒时发送一次 `break-ending` 事件（休息即将结束通知）。
+const BREAK_END_NOTICE_SECONDS: u32 = 30;
+/// 日程预览最长可模拟的小时数。
+const MAX_SIMULATION_HOURS: u32 = 48;
+/// 两次 tick 之间系统时间与单调时钟的偏差超过该毫秒数即视为时钟跳变。
+const CLOCK_JUMP_TOLERANCE_MILLIS: i64 = 5_000;
+/// 单调时钟在系统睡眠期间是否继续计时：Windows（QueryPerformanceCounter）继续，
+/// Linux（CLOCK_MONOTONIC）与 macOS 停止，此时系统时间多走的部分可能只是睡眠。
+const MONOTONIC_COUNTS_SUSPEND: bool = cfg!(windows);
+/// 休息欠债的刷新间隔（秒）。
+const REST_DEBT_REFRESH_SECONDS: u64 = 60;
+/// 饮水提醒的检查间隔（秒），每次检查计入一分钟工作时间。
+const HYDRATION_CHECK_SECONDS: u64 = 60;
+/// 通话结束后，推迟的休息再等待的秒数。
+const CALL_GRACE_SECONDS: i64 = 60;
+/// 计时循环看门狗的检查间隔（秒）。
+const TICKER_WATCHDOG_SECONDS: u64 = 5;
+/// 超过该秒数没有 tick 即视为计时循环停滞并重新启动。
+const TICKER_STALL_SECONDS: u64 = 10;
 
 /// Timer service for managing work/break cycles.
 /// 负责管理工作/休息阶段状态与事件广播。
 pub struct TimerService {
     state: Arc<Mutex<TimerServiceState>>,
-    app: AppHandle,
-    db: Arc<tokio::sync::Mutex<DatabaseService>>, // database handle for persisting sessions
+    events: Arc<dyn EventSink>,
+    store: Arc<dyn SessionStore>, // persistence for finished sessions
+    clock: Arc<dyn Clock>,
+    // Kept apart from `state` so a stuck state lock does not hide a stalled ticker
+    ticker: Mutex<TickerMonitor>,
 }
 
+/// Liveness of the ticker loop, watched by the ticker watchdog.
+#[derive(Default)]
+struct TickerMonitor {
+    last_tick: Option<Instant>,
+    last_tick_at: Option<chrono::DateTime<Utc>>,
+    // Loop currently in charge; a replaced loop sees a newer value and exits
+    generation: u64,
+    restarts: u32,
+    panics: u32,
+    errors: u32,
+    last_error: Option<String>,
+}
+
+#[derive(Clone)]
 struct TimerServiceState {
     phase: TimerPhase,
     state: TimerState,
@@ -32,16 +92,258 @@ struct TimerServiceState {
     segments: Vec<WorkSegment>,
     segment_index: usize,
     segment_iteration: u32,
+    // Durations by local time of day (settings `time_anchored_segments`)
+    time_anchored: Vec<TimeAnchoredRange>,
+    // Passes over the whole segment list; None = loop forever
+    program_repeat: Option<u32>,
+    program_iteration: u32,
+    stop_on_schedule_complete: bool,
     phase_end_time: Option<chrono::DateTime<Utc>>,
     current_session_id: Option<String>,
     current_session_start: Option<chrono::DateTime<Utc>>,
+    // Last time the running session's progress was written to disk
+    last_checkpoint_at: Option<chrono::DateTime<Utc>>,
+    // `break-ending` was already emitted for the current break
+    break_end_notified: bool,
     auto_cycle: bool, // Auto cycle between work and break
     // When set, automatically skip breaks until this time
     suppress_breaks_until: Option<chrono::DateTime<Utc>>,
+    // When the current suppression window started (used for catch-up breaks)
+    suppress_breaks_since: Option<chrono::DateTime<Utc>>,
+    catch_up_break_enabled: bool,
+    force_break_enabled: bool,
+    max_reminder_minutes: u32,
+    // Keep the reminder up after a break until the user acknowledges it
+    require_break_acknowledgment: bool,
+    // Finished break still waiting for `acknowledge_break`
+    pending_acknowledgment: Option<Session>,
+    // Longest allowed break suppression (minutes); None = unlimited
+    max_suppression_minutes: Option<u32>,
+    // Lead (minutes) of the `suppression-expiring` warning; 0 = off
+    suppression_expiry_warning_minutes: u32,
+    // `suppression-expiring` was already emitted for the current window
+    suppression_expiry_warned: bool,
+    prompt_work_note: bool,
+    work_note_in_flow_mode: bool,
+    // One-shot override applied to the next break (in minutes)
+    next_break_override_minutes: Option<u32>,
     paused_due_to_display_off: bool,
     paused_due_to_system_suspend: bool,
     last_power_restart_at: Option<chrono::DateTime<Utc>>,
     pending_power_restart: bool,
+    // When display-off/suspend paused the current break
+    power_paused_at: Option<chrono::DateTime<Utc>>,
+    // Absence (minutes) after which a power-paused break counts as done
+    long_absence_minutes: Option<u32>,
+    // Travel mode: display-off/suspend events are ignored (pure wall clock)
+    travel_mode: bool,
+    // Hold breaks back while the microphone is in use (settings `defer_breaks_during_calls`)
+    defer_breaks_during_calls: bool,
+    call_in_progress: bool,
+    // Breaks stay deferred until this time after a call ended
+    call_grace_until: Option<chrono::DateTime<Utc>>,
+    // Extend caps per phase (settings `max_extends_per_phase` / `max_total_extend_minutes`)
+    max_extends_per_phase: Option<u32>,
+    max_total_extend_minutes: Option<u32>,
+    // Countdown (seconds) between an auto-cycled break and the next work session
+    work_start_countdown_seconds: u32,
+    // Extends used in the current phase; reset at each phase start
+    extends_this_phase: u32,
+    extended_minutes_this_phase: u32,
+    // Work blocks vary by up to ± this many minutes (0 = off)
+    break_jitter_minutes: u32,
+    // Seed the jitter generator was last reset to (settings `random_seed`)
+    jitter_seed: Option<u64>,
+    jitter_rng: u64,
+    // Monotonic and wall-clock time of the previous running tick (clock-jump detection)
+    clock_anchor: Option<(Instant, chrono::DateTime<Utc>)>,
+    // Countdown drift observed by the ticks of the current phase (`get_timer_accuracy`)
+    tick_drift: TickDrift,
+    // Source of new session IDs (settings `session_id_scheme` / `session_id_prefix`)
+    session_ids: SessionIdGenerator,
+    // Hold work back until the day's first activity (settings `auto_start_on_first_activity`)
+    auto_start_on_first_activity: bool,
+    // Local day the first work session last started on
+    day_started_on: Option<NaiveDate>,
+    // Sessions shorter than this are not persisted (0 = keep all)
+    min_session_seconds: u32,
+    // Time at the computer since the last real break (settings `min_real_break_minutes`)
+    screen_time: ScreenTimeTracker,
+    // Input sampling for active time (settings `activity_sample_seconds` /
+    // `activity_idle_threshold_seconds`)
+    activity_sample_seconds: u32,
+    activity_idle_threshold_seconds: u32,
+    // Active seconds of the running work session; None until it is first sampled
+    current_active_seconds: Option<i64>,
+    // Rest owed (minutes, decayed), refreshed by `start_rest_debt_ticker`
+    rest_debt_minutes: f64,
+    // Share of the debt added to the next break (settings `rest_debt_compensation_percent`)
+    rest_debt_compensation_percent: u32,
+    // Minutes the current break was lengthened by to pay the debt back
+    rest_debt_extra_minutes: u32,
+    last_user_skip_at: Option<chrono::DateTime<Utc>>,
+    // Name of the preset the durations came from (settings `active_preset`)
+    active_preset: Option<String>,
+    wind_down: Option<WindDownWindow>,
+    // Whether the current phase was started inside the wind-down window
+    wind_down_active: bool,
+    // Reminder cadence and daily goal (settings `hydration`)
+    hydration: HydrationSettings,
+    // Glasses logged on a local day; None until read from the hydration file
+    hydration_count: Option<(NaiveDate, u32)>,
+    // Minutes of running work since the last glass or hydration reminder
+    hydration_worked_minutes: u32,
+}
+
+/// Drift between the countdown shown and the time left until `phase_end_time`,
+/// collected by the ticks of one phase.
+#[derive(Clone, Default)]
+struct TickDrift {
+    // Session the samples belong to; a new phase starts over
+    session_id: Option<String>,
+    samples: u32,
+    max_millis: i64,
+    last_millis: i64,
+}
+
+/// Parsed `wind_down` settings: the last minutes before the end of the work day.
+#[derive(Clone)]
+struct WindDownWindow {
+    end_of_day: NaiveTime,
+    lead: ChronoDuration,
+    work_minutes: u32,
+    break_minutes: u32,
+}
+
+impl WindDownWindow {
+    fn from_settings(settings: &WindDownSettings) -> Option<Self> {
+        if !settings.enabled {
+            return None;
+        }
+        let end_of_day = NaiveTime::parse_from_str(settings.end_of_day.trim(), "%H:%M").ok()?;
+        Some(Self {
+            end_of_day,
+            lead: ChronoDuration::minutes(settings.starts_before_end_minutes.max(1) as i64),
+            work_minutes: settings.work_minutes.max(1),
+            break_minutes: settings.break_minutes.max(1),
+        })
+    }
+
+    /// Whether `at` falls in `[end_of_day - lead, end_of_day)` local time.
+    fn contains(&self, at: chrono::DateTime<Utc>) -> bool {
+        let local = at.with_timezone(&Local).naive_local();
+        // The window may start the evening before an end of day shortly after midnight
+        [local.date(), local.date() + ChronoDuration::days(1)]
+            .iter()
+            .any(|day| {
+                let end = day.and_time(self.end_of_day);
+                local >= end - self.lead && local < end
+            })
+    }
+}
+
+/// Parsed `time_anchored_segments` entry: durations for a range of local time.
+#[derive(Clone)]
+struct TimeAnchoredRange {
+    start: NaiveTime,
+    end: NaiveTime,
+    work_minutes: u32,
+    break_minutes: u32,
+}
+
+impl TimeAnchoredRange {
+    /// Parse the settings ranges, dropping malformed or empty ones.
+    fn from_settings(segments: &[TimeAnchoredSegment]) -> Vec<Self> {
+        segments
+            .iter()
+            .filter_map(|segment| {
+                let start = NaiveTime::parse_from_str(segment.start.trim(), "%H:%M").ok()?;
+                let end = NaiveTime::parse_from_str(segment.end.trim(), "%H:%M").ok()?;
+                if start == end {
+                    return None;
+                }
+                Some(Self {
+                    start,
+                    end,
+                    work_minutes: segment.work_minutes.clamp(1, 120),
+                    break_minutes: segment.break_minutes.clamp(1, 120),
+                })
+            })
+            .collect()
+    }
+
+    /// Whether `at` falls in `[start, end)` local time; a range ending before it
+    /// starts runs across midnight.
+    fn contains(&self, at: chrono::DateTime<Utc>) -> bool {
+        let time = at.with_timezone(&Local).time();
+        if self.start < self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// Something currently holding back breaks and reminders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InterruptionBlock {
+    /// "No breaks for X hours" / "until tomorrow morning"
+    Suppression,
+    /// Flow mode chains work sessions without breaks
+    FlowMode,
+    /// The microphone is in use (a call); the break waits until it ends
+    Call,
+}
+
+impl InterruptionBlock {
+    fn as_str(self) -> &'static str {
+        match self {
+            InterruptionBlock::Suppression => "suppression",
+            InterruptionBlock::FlowMode => "flowMode",
+            InterruptionBlock::Call => "call",
+        }
+    }
+
+    /// Whether the break is postponed rather than skipped.
+    fn defers(self) -> bool {
+        matches!(self, InterruptionBlock::Call)
+    }
+}
+
+/// Single answer to "may we interrupt the user right now?".
+/// Every path that would start a break or raise a reminder asks the gate,
+/// so a new blocking condition only has to be added in `reminder_gate`.
+/// 统一判断当前是否允许打断用户（进入休息、弹出提醒），并记录阻止原因。
+struct ReminderGate {
+    blocked_by: Vec<InterruptionBlock>,
+}
+
+impl ReminderGate {
+    fn allows_interruptions(&self) -> bool {
+        self.blocked_by.is_empty()
+    }
+
+    fn is_blocked_by(&self, reason: InterruptionBlock) -> bool {
+        self.blocked_by.contains(&reason)
+    }
+
+    /// A work phase ending now goes on without a break.
+    fn skips_breaks(&self) -> bool {
+        self.blocked_by.iter().any(|reason| !reason.defers())
+    }
+
+    /// Only postponing conditions apply: the ending work phase is held until
+    /// they clear, then the break starts.
+    fn only_defers(&self) -> bool {
+        !self.allows_interruptions() && !self.skips_breaks()
+    }
+
+    fn reasons(&self) -> Vec<String> {
+        self.blocked_by
+            .iter()
+            .map(|reason| reason.as_str().to_string())
+            .collect()
+    }
 }
 
 impl TimerServiceState {
@@ -49,7 +351,38 @@ impl TimerServiceState {
         self.segmented_enabled && !self.segments.is_empty()
     }
 
-    fn apply_current_segment(&mut self) {
+    /// Flow mode takes precedence over segments: the segment position still
+    /// advances, but no break is taken.
+    fn effective_mode(&self) -> EffectiveTimerMode {
+        if self.flow_mode {
+            EffectiveTimerMode::Flow
+        } else if self.has_segments() {
+            EffectiveTimerMode::Segmented
+        } else {
+            EffectiveTimerMode::Simple
+        }
+    }
+
+    /// Clock-anchored mode is only used while the sequential segments are off.
+    fn has_time_anchored(&self) -> bool {
+        !self.has_segments() && !self.time_anchored.is_empty()
+    }
+
+    /// The clock-anchored range covering `at`, if that mode is active.
+    fn time_anchored_at(&self, at: chrono::DateTime<Utc>) -> Option<&TimeAnchoredRange> {
+        if !self.has_time_anchored() {
+            return None;
+        }
+        self.time_anchored.iter().find(|range| range.contains(at))
+    }
+
+    /// Set the durations for `at`: the clock-anchored range covering it, or the
+    /// current segment in sequence; gaps and plain mode use the base durations.
+    fn apply_current_segment(&mut self, at: chrono::DateTime<Utc>) {
+        if let Some(range) = self.time_anchored_at(at) {
+            (self.work_duration, self.break_duration) = (range.work_minutes, range.break_minutes);
+            return;
+        }
         if self.has_segments() {
             let idx = self
                 .segment_index
@@ -64,39 +397,247 @@ impl TimerServiceState {
         self.break_duration = self.base_break_duration.max(1);
     }
 
-    fn reset_segment_progress(&mut self) {
+    /// Name of the segment the running phase belongs to, while segmented work runs.
+    fn current_segment_label(&self) -> Option<String> {
+        if !self.has_segments() {
+            return None;
+        }
+        let idx = self
+            .segment_index
+            .min(self.segments.len().saturating_sub(1));
+        self.segments
+            .get(idx)
+            .and_then(|segment| segment.label.clone())
+    }
+
+    /// Planned length of the running phase as recorded in its session. Minutes
+    /// added to pay the rest debt back are left out, so taking them reduces the debt.
+    fn planned_seconds(&self) -> i64 {
+        let mut planned = i64::from(self.total_seconds);
+        if self.phase == TimerPhase::Break {
+            planned -= i64::from(self.rest_debt_extra_minutes) * 60;
+        }
+        planned
+    }
+
+    /// Evaluate the reminder gate at `now`. Call `expire_suppression` first.
+    /// 计算当前的打断许可；调用前应先清理已过期的抑制。
+    fn reminder_gate(&self, now: chrono::DateTime<Utc>) -> ReminderGate {
+        let mut blocked_by = Vec::new();
+        if matches!(self.suppress_breaks_until, Some(until) if now < until) {
+            blocked_by.push(InterruptionBlock::Suppression);
+        }
+        if self.flow_mode {
+            blocked_by.push(InterruptionBlock::FlowMode);
+        }
+        if self.call_in_progress || matches!(self.call_grace_until, Some(until) if now < until) {
+            blocked_by.push(InterruptionBlock::Call);
+        }
+        ReminderGate { blocked_by }
+    }
+
+    /// Clear a suppression window that ended by `now`, queueing the catch-up break.
+    /// 清理已到期的休息抑制，并按需设置补偿休息时长。
+    fn expire_suppression(&mut self, now: chrono::DateTime<Utc>) {
+        let Some(until) = self.suppress_breaks_until else {
+            return;
+        };
+        if now < until {
+            return;
+        }
+        self.suppress_breaks_until = None;
+        let since = self.suppress_breaks_since.take();
+        if let (true, Some(since)) = (self.catch_up_break_enabled, since) {
+            let minutes =
+                TimerService::catch_up_break_minutes(self.base_break_duration, until - since);
+            self.next_break_override_minutes = Some(minutes);
+        }
+    }
+
+    /// Compare the countdown left by the previous tick with the time actually
+    /// remaining until `end_time`, before this tick refreshes it.
+    fn record_tick_drift(&mut self, end_time: chrono::DateTime<Utc>, now: chrono::DateTime<Utc>) {
+        if self.tick_drift.session_id != self.current_session_id {
+            self.tick_drift = TickDrift {
+                session_id: self.current_session_id.clone(),
+                ..TickDrift::default()
+            };
+        }
+        let expected_millis = (end_time - now).num_milliseconds().max(0);
+        let drift_millis = (i64::from(self.remaining_seconds) * 1000 - expected_millis).abs();
+        let drift = &mut self.tick_drift;
+        drift.samples += 1;
+        drift.last_millis = drift_millis;
+        drift.max_millis = drift.max_millis.max(drift_millis);
+    }
+
+    /// Whether the first-activity gate is armed: the setting is on, the timer is
+    /// stopped and no work started yet on the local day of `now`.
+    fn awaiting_first_activity(&self, now: chrono::DateTime<Utc>) -> bool {
+        self.auto_start_on_first_activity
+            && self.phase == TimerPhase::Idle
+            && self.day_started_on != Some(now.with_timezone(&Local).date_naive())
+    }
+
+    /// Compare the wall-clock time since the previous tick with the monotonic
+    /// time. When they disagree by more than `CLOCK_JUMP_TOLERANCE_MILLIS` (NTP
+    /// sync, manual change) the running phase is shifted by the difference so its
+    /// remaining time is unchanged, and the jump (seconds) is returned. Forward
+    /// jumps are kept in travel mode, and wherever the monotonic clock stops
+    /// during suspend: there they look the same as a sleep the power monitor
+    /// missed, and that time must count.
+    /// 检测系统时间跳变：与单调时钟比较，超出容差时按剩余时间重新锚定当前阶段。
+    fn correct_clock_jump(&mut self, now: chrono::DateTime<Utc>, instant: Instant) -> Option<i64> {
+        let (previous_instant, previous_now) = self.clock_anchor.replace((instant, now))?;
+        let wall_millis = (now - previous_now).num_milliseconds();
+        let monotonic_millis = instant.duration_since(previous_instant).as_millis() as i64;
+        let drift = wall_millis - monotonic_millis;
+        let may_be_sleep = self.travel_mode || !MONOTONIC_COUNTS_SUSPEND;
+        if drift.abs() <= CLOCK_JUMP_TOLERANCE_MILLIS || (drift > 0 && may_be_sleep) {
+            return None;
+        }
+        let shift = ChronoDuration::milliseconds(drift);
+        self.phase_end_time = self.phase_end_time.map(|t| t + shift);
+        self.current_session_start = self.current_session_start.map(|t| t + shift);
+        self.last_checkpoint_at = self.last_checkpoint_at.map(|t| t + shift);
+        Some(drift / 1000)
+    }
+
+    /// The suppression window due for its `suppression-expiring` warning at `now`,
+    /// at most once per window. Windows no longer than the lead never warn.
+    /// 抑制即将结束时返回其结束时间（每个抑制窗口仅一次）。
+    fn take_suppression_expiry_warning(
+        &mut self,
+        now: chrono::DateTime<Utc>,
+    ) -> Option<chrono::DateTime<Utc>> {
+        if self.suppression_expiry_warning_minutes == 0 || self.suppression_expiry_warned {
+            return None;
+        }
+        let until = self.suppress_breaks_until?;
+        let lead = ChronoDuration::minutes(self.suppression_expiry_warning_minutes as i64);
+        if until - now > lead {
+            return None;
+        }
+        if matches!(self.suppress_breaks_since, Some(since) if until - since <= lead) {
+            return None;
+        }
+        self.suppression_expiry_warned = true;
+        Some(until)
+    }
+
+    /// Set the durations for a phase starting at `at`: the wind-down durations
+    /// (bypassing segments) inside the wind-down window, the segment/base ones otherwise.
+    /// Returns whether the wind-down window applies.
+    /// 按阶段开始时间选择时长：收尾时段内使用收尾时长，否则使用分段/基础时长。
+    fn apply_phase_durations(&mut self, at: chrono::DateTime<Utc>) -> bool {
+        self.apply_current_segment(at);
+        match self.wind_down.as_ref().filter(|w| w.contains(at)) {
+            Some(window) => {
+                self.work_duration = window.work_minutes;
+                self.break_duration = window.break_minutes;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Work minutes of segment `index` for a work block starting at `at`.
+    fn work_minutes_at(&self, index: usize, at: chrono::DateTime<Utc>) -> u32 {
+        if let Some(window) = self.wind_down.as_ref().filter(|w| w.contains(at)) {
+            return window.work_minutes;
+        }
+        match self.time_anchored_at(at) {
+            Some(range) => range.work_minutes,
+            None => self.cycle_work_minutes(index),
+        }
+    }
+
+    /// Break minutes of segment `index` for a break starting at `at`.
+    fn break_minutes_at(&self, index: usize, at: chrono::DateTime<Utc>) -> u32 {
+        if let Some(window) = self.wind_down.as_ref().filter(|w| w.contains(at)) {
+            return window.break_minutes;
+        }
+        match self.time_anchored_at(at) {
+            Some(range) => range.break_minutes,
+            None => self.cycle_break_minutes(index),
+        }
+    }
+
+    /// Next value of the jitter generator (SplitMix64).
+    fn next_jitter_random(&mut self) -> u64 {
+        self.jitter_rng = self.jitter_rng.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.jitter_rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Length of the next work block in seconds, randomized by ±`break_jitter_minutes`
+    /// so breaks of people starting together drift apart. Never below one minute.
+    /// 工作时长按 ±抖动分钟数随机化，使同时开始的团队成员错开休息。
+    fn jittered_work_seconds(&mut self) -> u32 {
+        let base = self.work_duration * 60;
+        if self.break_jitter_minutes == 0 {
+            return base;
+        }
+        let span = self.break_jitter_minutes as i64 * 60;
+        let offset = (self.next_jitter_random() % (2 * span as u64 + 1)) as i64 - span;
+        (base as i64 + offset).max(60) as u32
+    }
+
+    fn reset_segment_progress(&mut self, at: chrono::DateTime<Utc>) {
         self.segment_index = 0;
         self.segment_iteration = 0;
-        self.apply_current_segment();
+        self.program_iteration = 0;
+        self.apply_current_segment(at);
     }
 
-    fn advance_segment_cycle(&mut self) {
+    /// Step to the next segment position.
+    /// Returns true when this finished the last program pass and the schedule stops.
+    fn advance_segment_cycle(&mut self, at: chrono::DateTime<Utc>) -> bool {
         if !self.has_segments() {
             self.segment_index = 0;
             self.segment_iteration = 0;
-            self.apply_current_segment();
-            return;
+            self.apply_current_segment(at);
+            return false;
         }
 
         let len = self.segments.len();
         if len == 0 {
             self.segment_index = 0;
             self.segment_iteration = 0;
-            self.apply_current_segment();
-            return;
+            self.apply_current_segment(at);
+            return false;
         }
 
         let idx = self.segment_index.min(len - 1);
         let repeat = self.segments[idx].repeat.max(1);
 
+        let mut schedule_finished = false;
         if self.segment_iteration + 1 < repeat {
             self.segment_iteration += 1;
         } else {
             self.segment_iteration = 0;
             self.segment_index = (idx + 1) % len;
+            if self.segment_index == 0 {
+                let (passes, finished) = self.complete_program_pass(self.program_iteration);
+                self.program_iteration = passes;
+                schedule_finished = finished;
+            }
         }
 
-        self.apply_current_segment();
+        self.apply_current_segment(at);
+        schedule_finished
+    }
+
+    /// Count one full pass over the segment list.
+    /// Returns the updated pass count and whether the schedule stops here.
+    fn complete_program_pass(&self, passes: u32) -> (u32, bool) {
+        match self.program_repeat {
+            Some(limit) if passes + 1 >= limit.max(1) => (0, self.stop_on_schedule_complete),
+            Some(_) => (passes + 1, false),
+            None => (0, false),
+        }
     }
 
     fn normalized_segment_index(&self, index: usize) -> usize {
@@ -131,6 +672,87 @@ impl TimerServiceState {
         }
     }
 
+    /// Step the cycle forward on this (copied) state until `horizon`.
+    /// 复用真实的阶段推进逻辑，在状态副本上推演未来的工作/休息安排。
+    fn project_phases(
+        &mut self,
+        now: chrono::DateTime<Utc>,
+        horizon: chrono::DateTime<Utc>,
+    ) -> Vec<ScheduledPhase> {
+        let mut phases = Vec::new();
+
+        let (mut phase, mut end) = match self.phase {
+            TimerPhase::Idle => {
+                self.apply_phase_durations(now);
+                let work_end = now + ChronoDuration::seconds((self.work_duration * 60) as i64);
+                (TimerPhase::Work, work_end)
+            }
+            ref phase => {
+                let end = match (&self.state, self.phase_end_time) {
+                    (TimerState::Running, Some(end)) => end,
+                    _ => now + ChronoDuration::seconds(self.remaining_seconds as i64),
+                };
+                (phase.clone(), end)
+            }
+        };
+        let mut start = self.current_session_start.unwrap_or(now).min(end);
+
+        while start < horizon {
+            phases.push(ScheduledPhase {
+                phase: phase.clone(),
+                start,
+                end,
+            });
+            start = end;
+
+            self.expire_suppression(start);
+            // A deferred break still comes, so the projection keeps it
+            let next_is_break =
+                phase == TimerPhase::Work && !self.reminder_gate(start).skips_breaks();
+            if next_is_break {
+                self.apply_phase_durations(start);
+                if let Some(minutes) = self.next_break_override_minutes.take() {
+                    self.break_duration = minutes.max(1);
+                }
+                phase = TimerPhase::Break;
+                end = start + ChronoDuration::seconds((self.break_duration * 60) as i64);
+            } else {
+                // The program already advanced when the break before a countdown ended
+                if phase != TimerPhase::Transition
+                    && self.has_segments()
+                    && self.advance_segment_cycle(start)
+                {
+                    // The program ends here and the timer stops
+                    break;
+                }
+                self.apply_phase_durations(start);
+                phase = TimerPhase::Work;
+                end = start + ChronoDuration::seconds((self.work_duration * 60) as i64);
+            }
+        }
+
+        phases
+    }
+
+    /// Like `next_cycle_position`, also tracking program passes.
+    /// Returns None when the schedule stops at this transition.
+    fn next_program_position(
+        &self,
+        index: usize,
+        iteration: u32,
+        passes: u32,
+    ) -> Option<(usize, u32, u32)> {
+        let (next_index, next_iteration) = self.next_cycle_position(index, iteration);
+        // Landing on the first position again means the whole list has run once
+        if !self.has_segments() || (next_index, next_iteration) != (0, 0) {
+            return Some((next_index, next_iteration, passes));
+        }
+        match self.complete_program_pass(passes) {
+            (_, true) => None,
+            (passes, false) => Some((next_index, next_iteration, passes)),
+        }
+    }
+
     fn next_cycle_position(&self, index: usize, iteration: u32) -> (usize, u32) {
         if !self.has_segments() || self.segments.is_empty() {
             return (0, 0);
@@ -172,26 +794,47 @@ impl TimerService {
             .collect()
     }
 
-    fn advance_segment_if_needed(&self, segmented_active: bool) {
+    /// Returns true when the segmented program finished and the timer should stop.
+    fn advance_segment_if_needed(&self, segmented_active: bool) -> bool {
         if !segmented_active {
-            return;
+            return false;
         }
+        let now = self.clock.now();
         let mut state = self.state.lock().unwrap();
-        state.advance_segment_cycle();
+        state.advance_segment_cycle(now)
     }
 
-    /// Create a new timer service
-    /// 初始化服务，记录工作/休息时长并保留 AppHandle。
-    pub fn new(
-        app: AppHandle,
-        db: Arc<tokio::sync::Mutex<DatabaseService>>,
-        work_duration: u32,
-        break_duration: u32,
-        flow_mode: bool,
-        segmented_enabled: bool,
-        segments: Vec<WorkSegment>,
+    /// Stop the timer after the segmented program ran its configured passes.
+    /// 分段程序运行满设定轮数且配置为停止时，停止计时并回到空闲状态。
+    fn complete_schedule(&self, cause: PhaseChangeCause) -> AppResult<()> {
+        println!("TimerService: segmented program complete, stopping");
+        self.stop()?;
+        self.state
+            .lock()
+            .unwrap()
+            .reset_segment_progress(self.clock.now());
+        self.emit_phase_change(TimerPhase::Idle, cause)?;
+        Ok(())
+    }
+
+    /// Create a new timer service wired to the app handle and database.
+    /// Durations and modes come from `apply_settings`, which the caller runs next.
+    /// 初始化服务，以 AppHandle 与数据库作为事件与持久化后端；时长等配置由随后调用的 `apply_settings` 设置。
+    pub fn new(app: AppHandle, db: Arc<tokio::sync::Mutex<DatabaseService>>) -> Arc<Self> {
+        Self::with_dependencies(Arc::new(app), Arc::new(db), Arc::new(SystemClock))
+    }
+
+    /// Create a timer service with explicit event, persistence and clock dependencies.
+    /// It starts with the default durations until `apply_settings` is called.
+    /// 使用自定义的事件、持久化与时钟依赖创建计时服务（便于在无 Tauri 环境下驱动）。
+    pub fn with_dependencies(
+        events: Arc<dyn EventSink>,
+        store: Arc<dyn SessionStore>,
+        clock: Arc<dyn Clock>,
     ) -> Arc<Self> {
-        let sanitized_segments = Self::sanitize_segments(segments);
+        let defaults = Settings::default();
+        let work_duration = defaults.work_duration;
+        let break_duration = defaults.break_duration;
         let mut state = TimerServiceState {
             phase: TimerPhase::Idle,
             state: TimerState::Stopped,
@@ -201,85 +844,203 @@ impl TimerService {
             break_duration,
             base_work_duration: work_duration,
             base_break_duration: break_duration,
-            flow_mode,
-            segmented_enabled: segmented_enabled && !sanitized_segments.is_empty(),
-            segments: sanitized_segments,
+            flow_mode: false,
+            segmented_enabled: false,
+            segments: Vec::new(),
             segment_index: 0,
             segment_iteration: 0,
+            time_anchored: Vec::new(),
+            program_repeat: None,
+            program_iteration: 0,
+            stop_on_schedule_complete: false,
             phase_end_time: None,
             current_session_id: None,
             current_session_start: None,
+            last_checkpoint_at: None,
+            break_end_notified: false,
             auto_cycle: true, // Enable auto cycle by default
             suppress_breaks_until: None,
+            suppress_breaks_since: None,
+            catch_up_break_enabled: false,
+            force_break_enabled: false,
+            max_reminder_minutes: 0,
+            require_break_acknowledgment: false,
+            pending_acknowledgment: None,
+            max_suppression_minutes: None,
+            suppression_expiry_warning_minutes: 0,
+            suppression_expiry_warned: false,
+            prompt_work_note: false,
+            work_note_in_flow_mode: false,
+            next_break_override_minutes: None,
             paused_due_to_display_off: false,
             paused_due_to_system_suspend: false,
             last_power_restart_at: None,
             pending_power_restart: false,
+            power_paused_at: None,
+            long_absence_minutes: None,
+            travel_mode: false,
+            defer_breaks_during_calls: false,
+            call_in_progress: false,
+            call_grace_until: None,
+            max_extends_per_phase: None,
+            max_total_extend_minutes: None,
+            work_start_countdown_seconds: 0,
+            extends_this_phase: 0,
+            extended_minutes_this_phase: 0,
+            break_jitter_minutes: 0,
+            jitter_seed: None,
+            jitter_rng: Uuid::new_v4().as_u64_pair().0,
+            clock_anchor: None,
+            tick_drift: TickDrift::default(),
+            session_ids: SessionIdGenerator::default(),
+            auto_start_on_first_activity: false,
+            day_started_on: None,
+            min_session_seconds: 0,
+            screen_time: ScreenTimeTracker::default(),
+            activity_sample_seconds: 15,
+            activity_idle_threshold_seconds: 60,
+            current_active_seconds: None,
+            rest_debt_minutes: 0.0,
+            rest_debt_compensation_percent: 0,
+            rest_debt_extra_minutes: 0,
+            last_user_skip_at: None,
+            active_preset: None,
+            wind_down: None,
+            wind_down_active: false,
+            hydration: HydrationSettings::default(),
+            hydration_count: None,
+            hydration_worked_minutes: 0,
         };
-        state.reset_segment_progress();
+        state.reset_segment_progress(clock.now());
 
         Arc::new(Self {
             state: Arc::new(Mutex::new(state)),
-            app,
-            db,
+            events,
+            store,
+            clock,
+            ticker: Mutex::new(TickerMonitor::default()),
         })
     }
 
     /// Start work session
     /// 切换到工作阶段并重置计时。
-    pub fn start_work(&self) -> AppResult<()> {
+    pub fn start_work(&self, cause: PhaseChangeCause) -> AppResult<()> {
         let mut state = self.state.lock().unwrap();
-        state.apply_current_segment();
+        let start_time = self.clock.now();
+        let wind_down_started = Self::enter_phase_durations(&mut state, start_time);
+        // Any work start (first activity or a manual one) opens a gated day
+        let opens_day = state.awaiting_first_activity(start_time);
+        if opens_day {
+            state.day_started_on = Some(start_time.with_timezone(&Local).date_naive());
+        }
         state.phase = TimerPhase::Work;
         state.state = TimerState::Running;
-        let work_seconds = state.work_duration * 60;
+        let work_seconds = state.jittered_work_seconds();
         state.total_seconds = work_seconds;
         state.remaining_seconds = work_seconds;
-        let start_time = Utc::now();
         state.phase_end_time = Some(start_time + ChronoDuration::seconds(work_seconds as i64));
-        state.current_session_id = Some(Uuid::new_v4().to_string());
+        state.current_session_id = Some(Self::next_session_id(&mut state, start_time));
         state.current_session_start = Some(start_time);
+        state.current_active_seconds = None;
+        state.last_checkpoint_at = Some(start_time);
         state.paused_due_to_display_off = false;
         state.paused_due_to_system_suspend = false;
+        state.extends_this_phase = 0;
+        state.extended_minutes_this_phase = 0;
         drop(state);
 
         self.emit_timer_update()?;
-        self.emit_phase_change("work")?;
+        self.emit_phase_change(TimerPhase::Work, cause)?;
+        if opens_day {
+            self.emit(
+                "day-started",
+                DayStarted {
+                    started_at: start_time,
+                },
+            )?;
+        }
+        if wind_down_started {
+            self.emit("wind-down-started", ())?;
+        }
         self.persist_session_start();
         Ok(())
     }
 
     /// Start break session
     /// 切换到休息阶段并重置计时。
-    pub fn start_break(&self) -> AppResult<()> {
+    pub fn start_break(&self, cause: PhaseChangeCause) -> AppResult<()> {
         let mut state = self.state.lock().unwrap();
-        state.apply_current_segment();
+        let start_time = self.clock.now();
+        let wind_down_started = Self::enter_phase_durations(&mut state, start_time);
+        state.rest_debt_extra_minutes = 0;
+        if let Some(minutes) = state.next_break_override_minutes.take() {
+            state.break_duration = minutes.max(1);
+        } else {
+            // A catch-up break already makes up for the suppressed ones
+            let extra = rest_debt::compensation_minutes(
+                state.rest_debt_minutes,
+                state.rest_debt_compensation_percent,
+            );
+            state.break_duration += extra;
+            state.rest_debt_extra_minutes = extra;
+        }
         state.phase = TimerPhase::Break;
+        state.break_end_notified = false;
+        // A new break replaces one never acknowledged; that one stays auto-dismissed
+        state.pending_acknowledgment = None;
         state.state = TimerState::Running;
         let break_seconds = state.break_duration * 60;
         state.total_seconds = break_seconds;
         state.remaining_seconds = break_seconds;
-        let start_time = Utc::now();
         state.phase_end_time = Some(start_time + ChronoDuration::seconds(break_seconds as i64));
-        state.current_session_id = Some(Uuid::new_v4().to_string());
+        state.current_session_id = Some(Self::next_session_id(&mut state, start_time));
         state.current_session_start = Some(start_time);
+        state.current_active_seconds = None;
+        state.last_checkpoint_at = Some(start_time);
         state.paused_due_to_display_off = false;
         state.paused_due_to_system_suspend = false;
+        state.extends_this_phase = 0;
+        state.extended_minutes_this_phase = 0;
         drop(state);
 
         self.emit_timer_update()?;
-        self.emit_phase_change("break")?;
+        self.emit_phase_change(TimerPhase::Break, cause)?;
+        if wind_down_started {
+            self.emit("wind-down-started", ())?;
+        }
         self.persist_session_start();
         Ok(())
     }
 
+    /// Count down `seconds` before the work session that follows a break, so the
+    /// reminder can show "back to work in N…". No session is recorded for it.
+    /// 休息结束后进入返回工作倒计时，不产生会话记录，倒计时结束后才开始工作。
+    fn start_transition(&self, seconds: u32) -> AppResult<()> {
+        let mut state = self.state.lock().unwrap();
+        let now = self.clock.now();
+        state.phase = TimerPhase::Transition;
+        state.state = TimerState::Running;
+        state.total_seconds = seconds;
+        state.remaining_seconds = seconds;
+        state.phase_end_time = Some(now + ChronoDuration::seconds(seconds as i64));
+        state.current_session_id = None;
+        state.current_session_start = None;
+        state.current_active_seconds = None;
+        state.last_checkpoint_at = None;
+        drop(state);
+
+        self.emit_timer_update()?;
+        self.emit("transition-countdown", seconds)?;
+        Ok(())
+    }
+
     /// Pause timer
     /// 将计时器状态标记为暂停，不再递减剩余时间。
     pub fn pause(&self) -> AppResult<()> {
         let mut state = self.state.lock().unwrap();
         if state.state == TimerState::Running {
             state.state = TimerState::Paused;
-            Self::update_remaining_seconds(&mut state);
+            Self::update_remaining_seconds(&mut state, self.clock.now());
             state.phase_end_time = None;
             drop(state);
             self.emit_timer_update()?;
@@ -293,13 +1054,14 @@ impl TimerService {
         let mut state = self.state.lock().unwrap();
         if state.state == TimerState::Paused {
             state.state = TimerState::Running;
-            let start = Utc::now();
+            let start = self.clock.now();
             if state.remaining_seconds > 0 {
                 state.phase_end_time =
                     Some(start + ChronoDuration::seconds(state.remaining_seconds as i64));
             } else {
                 state.phase_end_time = Some(start);
             }
+            state.power_paused_at = None;
             state.paused_due_to_display_off = false;
             state.paused_due_to_system_suspend = false;
             drop(state);
@@ -310,30 +1072,76 @@ impl TimerService {
 
     /// Skip current phase
     /// 终止当前阶段并生成会话记录，返回给上层持久化。
-    /// Returns (Session, should_show_break_reminder)
-    pub fn skip(&self) -> AppResult<Option<(Session, bool)>> {
+    /// Returns (Session, should_show_break_reminder); the session is None when it was
+    /// shorter than `min_session_seconds` and has been dropped instead of saved.
+    ///
+    /// A phase younger than two seconds is not skipped unless `force` is set,
+    /// so a double click cannot skip both the work phase and the new break.
+    /// User skips within `SKIP_DEBOUNCE_MILLIS` of the previous one are ignored
+    /// even when forced. A force break cannot be skipped before
+    /// `max_reminder_minutes` (`AppError::ForceBreakLocked`), whoever asks.
+    /// `cause` is reported on the resulting `phase-change` event.
+    pub fn skip(
+        &self,
+        force: bool,
+        cause: PhaseChangeCause,
+    ) -> AppResult<Option<(Option<Session>, bool)>> {
         println!("TimerService: skip called");
         let (previous_phase, session, segmented_active) = {
-            let state = self.state.lock().unwrap();
+            let mut state = self.state.lock().unwrap();
             if state.phase == TimerPhase::Idle {
                 println!("TimerService: skip ignored (Idle)");
                 return Ok(None);
             }
+            if state.phase == TimerPhase::Transition {
+                // Skipping the countdown starts work right away
+                drop(state);
+                self.start_work(cause)?;
+                return Ok(Some((None, false)));
+            }
+            let now = self.clock.now();
+            if Self::break_dismissible_at(&state).is_some_and(|at| now < at) {
+                return Err(AppError::ForceBreakLocked);
+            }
+            let user_skip = matches!(cause, PhaseChangeCause::UserSkip);
+            if user_skip {
+                if let Some(last) = state.last_user_skip_at {
+                    if (now - last).num_milliseconds() < SKIP_DEBOUNCE_MILLIS {
+                        println!("TimerService: skip ignored (debounced)");
+                        return Ok(None);
+                    }
+                }
+            }
+            if !force {
+                if let Some(start) = state.current_session_start {
+                    if (self.clock.now() - start).num_seconds() < MIN_PHASE_AGE_FOR_SKIP_SECONDS {
+                        println!("TimerService: skip ignored (phase just started)");
+                        return Ok(None);
+                    }
+                }
+            }
+            if user_skip {
+                state.last_user_skip_at = Some(now);
+            }
             (
                 state.phase.clone(),
-                self.create_session_record(&state, true, None),
+                self.create_session_record(&state, true, None, Some(SessionEndReason::Skipped)),
                 state.has_segments(),
             )
         };
 
         // println!("TimerService: skip - stopping current timer");
         self.stop()?;
+        let session = self.keep_session(session);
 
         let should_show_reminder = match previous_phase {
             TimerPhase::Work => {
+                if let Some(session) = &session {
+                    self.prompt_work_note(session, false);
+                }
                 // println!("TimerService: skip - switching to break");
                 // Skipping work should immediately begin the break phase
-                self.start_break()?;
+                self.start_break(cause)?;
                 // Fix: Do NOT return true here. The `start_break` inside `tick` handles the emission.
                 // But `skip` is manual.
                 // If I return true, the command emits it.
@@ -344,11 +1152,14 @@ impl TimerService {
             TimerPhase::Break => {
                 println!("TimerService: skip - switching to work");
                 // Skipping break returns to the next work session
-                self.advance_segment_if_needed(segmented_active);
-                self.start_work()?;
+                if self.advance_segment_if_needed(segmented_active) {
+                    self.complete_schedule(cause)?;
+                } else {
+                    self.start_work(cause)?;
+                }
                 false
             }
-            TimerPhase::Idle => false,
+            TimerPhase::Idle | TimerPhase::Transition => false,
         };
 
         println!("TimerService: skip completed");
@@ -359,7 +1170,28 @@ impl TimerService {
     /// 延长当前阶段剩余时长，并通知前端刷新进度。
     pub fn extend(&self, minutes: u32) -> AppResult<()> {
         let mut state = self.state.lock().unwrap();
-        let additional_seconds = minutes.max(1) * 60;
+        let minutes = minutes.max(1);
+        if let Some(max) = state.max_extends_per_phase {
+            if state.extends_this_phase >= max {
+                return Err(AppError::ExtendLimitReached(format!(
+                    "at most {} extensions per phase",
+                    max
+                )));
+            }
+        }
+        if let Some(max) = state.max_total_extend_minutes {
+            if state.extended_minutes_this_phase + minutes > max {
+                return Err(AppError::ExtendLimitReached(format!(
+                    "at most {} extra minutes per phase",
+                    max
+                )));
+            }
+        }
+        state.extends_this_phase += 1;
+        state.extended_minutes_this_phase += minutes;
+        let additional_seconds = minutes * 60;
+        // The break-end notice fires again for the extended break
+        state.break_end_notified = false;
         state.remaining_seconds += additional_seconds;
         state.total_seconds += additional_seconds;
         if let Some(end_time) = state.phase_end_time {
@@ -382,62 +1214,223 @@ impl TimerService {
         state.phase_end_time = None;
         state.current_session_id = None;
         state.current_session_start = None;
+        state.current_active_seconds = None;
+        state.last_checkpoint_at = None;
+        state.power_paused_at = None;
         state.paused_due_to_display_off = false;
         state.paused_due_to_system_suspend = false;
+        state.pending_acknowledgment = None;
         drop(state);
         self.emit_timer_update()?;
         Ok(())
     }
 
+    /// End the running phase because the app exits: the timer stops and the
+    /// session so far is returned for the caller to save, since it must reach the
+    /// disk before the process ends. None when nothing ran or it was too short to keep.
+    /// 退出前结束当前阶段：停止计时并返回已进行部分的会话记录，由调用方等待写入。
+    pub fn finish_for_exit(&self) -> AppResult<Option<Session>> {
+        let session = {
+            let state = self.state.lock().unwrap();
+            let running = matches!(state.phase, TimerPhase::Work | TimerPhase::Break)
+                && state.current_session_id.is_some();
+            running.then(|| {
+                self.create_session_record(&state, false, None, Some(SessionEndReason::Exit))
+            })
+        };
+        self.stop()?;
+        Ok(session.and_then(|session| self.keep_session(session)))
+    }
+
+    /// Recovery escape hatch: end whatever runs now and put the timer into a
+    /// fresh `phase` with `minutes` left (Idle just stops). Pending overrides,
+    /// extensions and acknowledgments are dropped. Returns the interrupted
+    /// session for the caller to save.
+    /// 恢复用：结束当前阶段，强制进入指定阶段并设定剩余分钟数，清除残留的临时状态。
+    pub fn force_phase(&self, phase: TimerPhase, minutes: u32) -> AppResult<Option<Session>> {
+        if phase == TimerPhase::Transition {
+            return Err(AppError::ValidationError(
+                "Cannot force the transition countdown".to_string(),
+            ));
+        }
+        let session = {
+            let state = self.state.lock().unwrap();
+            let running = matches!(state.phase, TimerPhase::Work | TimerPhase::Break)
+                && state.current_session_id.is_some();
+            running.then(|| {
+                self.create_session_record(&state, true, None, Some(SessionEndReason::Recovery))
+            })
+        };
+        self.stop()?;
+        let session = session.and_then(|session| self.keep_session(session));
+        {
+            let mut state = self.state.lock().unwrap();
+            state.next_break_override_minutes = None;
+            state.rest_debt_extra_minutes = 0;
+            state.break_end_notified = false;
+            state.extends_this_phase = 0;
+            state.extended_minutes_this_phase = 0;
+            if phase == TimerPhase::Idle {
+                return Ok(session);
+            }
+            let start_time = self.clock.now();
+            let seconds = minutes.max(1) * 60;
+            state.phase = phase.clone();
+            state.state = TimerState::Running;
+            state.total_seconds = seconds;
+            state.remaining_seconds = seconds;
+            state.phase_end_time = Some(start_time + ChronoDuration::seconds(seconds as i64));
+            state.current_session_id = Some(Self::next_session_id(&mut state, start_time));
+            state.current_session_start = Some(start_time);
+            state.current_active_seconds = None;
+            state.last_checkpoint_at = Some(start_time);
+        }
+        println!("TimerService: forced {:?} for {} min", phase, minutes);
+        self.emit_timer_update()?;
+        self.emit_phase_change(phase, PhaseChangeCause::Recovery)?;
+        self.persist_session_start();
+        Ok(session)
+    }
+
+    /// Pretend `seconds` have elapsed: move the running phase and any suppression
+    /// window back in time, then tick once so transitions, reminders and session
+    /// persistence run exactly as they would naturally. At most one transition
+    /// happens per call, however far past the phase end it advances.
+    /// 将当前阶段与抑制窗口的时间戳前移并立即 tick 一次（仅供测试使用）。
+    #[cfg(feature = "dev-tools")]
+    pub fn debug_advance(&self, seconds: u32) -> AppResult<Option<Session>> {
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.state != TimerState::Running {
+                return Err(AppError::ValidationError(
+                    "Timer must be running to advance".to_string(),
+                ));
+            }
+            let offset = chrono::Duration::seconds(seconds as i64);
+            state.phase_end_time = state.phase_end_time.map(|t| t - offset);
+            state.current_session_start = state.current_session_start.map(|t| t - offset);
+            state.last_checkpoint_at = state.last_checkpoint_at.map(|t| t - offset);
+            state.suppress_breaks_until = state.suppress_breaks_until.map(|t| t - offset);
+            state.suppress_breaks_since = state.suppress_breaks_since.map(|t| t - offset);
+        }
+        self.tick()
+    }
+
     /// Tick timer (call every second)
     /// 定时器后台循环调用，每秒递减并在阶段结束时自动轮换。
     pub fn tick(&self) -> AppResult<Option<Session>> {
         let mut state = self.state.lock().unwrap();
 
         if state.state != TimerState::Running {
+            // Time spent paused or stopped is not compared against the wall clock
+            state.clock_anchor = None;
+            return Ok(None);
+        }
+        if let Some(drift_seconds) =
+            state.correct_clock_jump(self.clock.now(), self.clock.monotonic())
+        {
+            drop(state);
+            eprintln!(
+                "TimerService: system clock jumped {}s, phase re-anchored",
+                drift_seconds
+            );
+            self.emit("clock-changed", ClockChanged { drift_seconds })?;
+            state = self.state.lock().unwrap();
+        }
+        if state.phase == TimerPhase::Transition {
+            drop(state);
+            self.tick_transition()?;
             return Ok(None);
         }
 
         let mut timer_finished = false;
         let next_phase = state.phase.clone();
         let mut session = None;
+        let mut checkpoint = None;
+        let mut break_ending = None;
 
         if let Some(end_time) = state.phase_end_time {
-            let now = Utc::now();
-            if now >= end_time {
+            let now = self.clock.now();
+            state.record_tick_drift(end_time, now);
+            // Work past its end while a call defers the break; remaining stays 0
+            let held = state.phase == TimerPhase::Work && state.reminder_gate(now).only_defers();
+            if now >= end_time && !held {
                 state.remaining_seconds = 0;
                 timer_finished = true;
-                session = Some(self.create_session_record(&state, false, None));
+                session = Some(self.create_session_record(
+                    &state,
+                    false,
+                    None,
+                    Some(SessionEndReason::Completed),
+                ));
                 state.phase_end_time = None;
             } else {
                 let diff = (end_time - now).num_seconds();
                 state.remaining_seconds = diff.max(0) as u32;
+                if state.phase == TimerPhase::Break
+                    && !state.break_end_notified
+                    && state.remaining_seconds <= BREAK_END_NOTICE_SECONDS
+                {
+                    state.break_end_notified = true;
+                    break_ending = Some(state.remaining_seconds);
+                }
+                let checkpoint_due = state.last_checkpoint_at.is_some_and(|at| {
+                    (now - at).num_seconds() >= SESSION_CHECKPOINT_INTERVAL_SECONDS
+                });
+                if checkpoint_due {
+                    state.last_checkpoint_at = Some(now);
+                    let mut record = self.create_session_record(&state, false, None, None);
+                    record.in_progress = true;
+                    checkpoint = Some(record);
+                }
             }
         }
 
         let flow_mode = state.flow_mode;
+        let work_start_countdown = state.work_start_countdown_seconds;
         let segmented_active = state.has_segments();
         let should_auto_cycle = timer_finished && state.auto_cycle;
-        // Evaluate whether break suppression is active; clear if expired
-        let suppress_breaks_active = if let Some(until) = state.suppress_breaks_until {
-            if Utc::now() < until {
-                true
-            } else {
-                // Clear expired suppression
-                state.suppress_breaks_until = None;
-                false
-            }
-        } else {
-            false
-        };
+        let require_acknowledgment =
+            next_phase == TimerPhase::Break && state.require_break_acknowledgment;
+        let now = self.clock.now();
+        state.expire_suppression(now);
+        let suppression_expiring = state.take_suppression_expiry_warning(now);
+        let gate = state.reminder_gate(now);
+        let suppress_breaks_active = gate.is_blocked_by(InterruptionBlock::Suppression);
         drop(state);
+        if let Some(record) = checkpoint {
+            self.store.checkpoint_session(record);
+        }
         self.emit_timer_update()?;
+        if let Some(remaining) = break_ending {
+            self.emit("break-ending", remaining)?;
+        }
+        if let Some(until) = suppression_expiring {
+            self.emit(
+                "suppression-expiring",
+                SuppressionExpiring {
+                    until,
+                    remaining_seconds: (until - now).num_seconds().max(0),
+                },
+            )?;
+        }
 
         if timer_finished {
-            println!("TimerService: timer finished, auto_cycle={}", should_auto_cycle);
+            println!(
+                "TimerService: timer finished, auto_cycle={}",
+                should_auto_cycle
+            );
             self.emit_timer_finished()?;
-            if let Some(s) = session.clone() {
-                self.persist_session_finish(s);
+            if let Some(mut s) = session.clone().and_then(|s| self.keep_session(s)) {
+                if require_acknowledgment {
+                    // Recorded as auto-dismissed until `acknowledge_break` says otherwise
+                    s.acknowledged = Some(false);
+                    self.state.lock().unwrap().pending_acknowledgment = Some(s.clone());
+                }
+                // Flow mode chains work sessions back to back without a break
+                let chained = flow_mode && !suppress_breaks_active;
+                self.prompt_work_note(&s, chained);
+                self.store.persist_session(s);
             }
 
             // Auto-cycle to next phase
@@ -445,25 +1438,40 @@ impl TimerService {
                 match next_phase {
                     TimerPhase::Work => {
                         // Work finished
-                        if suppress_breaks_active || flow_mode {
+                        if !gate.allows_interruptions() {
                             println!("TimerService: Auto-cycling to work (suppressed/flow)");
-                            self.advance_segment_if_needed(segmented_active);
+                            let schedule_finished =
+                                self.advance_segment_if_needed(segmented_active);
                             // Skip break: immediately start another work session
-                            self.start_work()?;
+                            let cause = if suppress_breaks_active {
+                                PhaseChangeCause::Suppression
+                            } else {
+                                PhaseChangeCause::FlowMode
+                            };
+                            if schedule_finished {
+                                self.complete_schedule(cause)?;
+                            } else {
+                                self.start_work(cause)?;
+                            }
                         } else {
                             println!("TimerService: Auto-cycling to break");
                             // Start break and show reminder
-                            self.start_break()?;
+                            self.start_break(PhaseChangeCause::AutoCycle)?;
                             self.show_break_reminder()?;
                         }
                     }
                     TimerPhase::Break => {
                         println!("TimerService: Auto-cycling to work");
                         // Break finished, start work
-                        self.advance_segment_if_needed(segmented_active);
-                        self.start_work()?;
+                        if self.advance_segment_if_needed(segmented_active) {
+                            self.complete_schedule(PhaseChangeCause::AutoCycle)?;
+                        } else if work_start_countdown > 0 {
+                            self.start_transition(work_start_countdown)?;
+                        } else {
+                            self.start_work(PhaseChangeCause::AutoCycle)?;
+                        }
                     }
-                    TimerPhase::Idle => {}
+                    TimerPhase::Idle | TimerPhase::Transition => {}
                 }
             }
         }
@@ -471,12 +1479,33 @@ impl TimerService {
         Ok(session)
     }
 
-    /// Restart work session after a power-related resume event.
+    /// Count the post-break countdown down, then start work.
+    fn tick_transition(&self) -> AppResult<()> {
+        let remaining = {
+            let mut state = self.state.lock().unwrap();
+            let now = self.clock.now();
+            let remaining = state
+                .phase_end_time
+                .map_or(0, |end| (end - now).num_seconds().max(0) as u32);
+            state.remaining_seconds = remaining;
+            remaining
+        };
+        if remaining == 0 {
+            // Work starts only now so its recorded start time is accurate
+            return self.start_work(PhaseChangeCause::AutoCycle);
+        }
+        self.emit_timer_update()?;
+        self.emit("transition-countdown", remaining)?;
+        Ok(())
+    }
+
+    /// Restart work session after a power-related resume event; a break paused
+    /// by the event goes through `resume_break_after_absence` instead.
     fn restart_work_for_power_event(&self) -> AppResult<()> {
         const POWER_RESTART_DEBOUNCE_SECONDS: i64 = 3;
         let should_restart = {
             let mut state = self.state.lock().unwrap();
-            let now = Utc::now();
+            let now = self.clock.now();
             if !state.pending_power_restart {
                 return Ok(());
             }
@@ -495,32 +1524,178 @@ impl TimerService {
             return Ok(());
         }
 
-        self.start_work()?;
+        if self.state.lock().unwrap().phase == TimerPhase::Break {
+            return self.resume_break_after_absence();
+        }
+        self.start_work(PhaseChangeCause::ResumeFromIdle)?;
         Ok(())
     }
 
-    fn interrupt_for_power_event(&self) -> AppResult<()> {
-        let session = {
+    /// Continue a break paused by display-off/suspend. If the absence outlasted the
+    /// rest of the break (or `long_absence_minutes`), the break is saved as completed,
+    /// a fresh work session starts and `resumed-after-absence` carries the absence seconds.
+    /// 熄屏/睡眠暂停的休息：离开时间已覆盖剩余休息时直接记为完成并开始工作，否则继续休息。
+    fn resume_break_after_absence(&self) -> AppResult<()> {
+        let now = self.clock.now();
+        let (absence, session, segmented_active) = {
             let mut state = self.state.lock().unwrap();
-            if state.phase == TimerPhase::Idle {
+            let Some(paused_at) = state.power_paused_at.take() else {
+                // Paused by the user before the power event; leave it paused
                 return Ok(());
-            }
-            state.pending_power_restart = true;
-            let note = match state.phase {
-                TimerPhase::Break => POWER_INTERRUPT_BREAK_NOTE,
-                TimerPhase::Work => POWER_INTERRUPT_WORK_NOTE,
-                TimerPhase::Idle => POWER_INTERRUPT_WORK_NOTE,
             };
-            self.create_session_record(&state, false, Some(note))
+            let absence = now - paused_at;
+            if !Self::absence_covers_break(
+                absence,
+                state.remaining_seconds,
+                state.long_absence_minutes,
+            ) {
+                drop(state);
+                return self.resume();
+            }
+            // The absence counts as rest, so the record ends now
+            (
+                absence,
+                self.create_session_record(&state, false, None, Some(SessionEndReason::Absence)),
+                state.has_segments(),
+            )
         };
+
         self.persist_session_finish(session);
         self.stop()?;
-        Ok(())
+        if self.advance_segment_if_needed(segmented_active) {
+            self.complete_schedule(PhaseChangeCause::ResumeFromIdle)?;
+        } else {
+            self.start_work(PhaseChangeCause::ResumeFromIdle)?;
+        }
+        self.emit("resumed-after-absence", absence.num_seconds().max(0))?;
+        Ok(())
+    }
+
+    /// Whether being away for `absence` finished a break with `remaining_seconds` left.
+    fn absence_covers_break(
+        absence: ChronoDuration,
+        remaining_seconds: u32,
+        long_absence_minutes: Option<u32>,
+    ) -> bool {
+        absence.num_seconds() >= remaining_seconds as i64
+            || long_absence_minutes
+                .is_some_and(|minutes| absence >= ChronoDuration::minutes(minutes as i64))
+    }
+
+    /// Work sessions end at a power event; breaks pause so the resume can decide
+    /// whether the absence already covered them.
+    fn interrupt_for_power_event(&self) -> AppResult<()> {
+        let session = {
+            let mut state = self.state.lock().unwrap();
+            if state.phase == TimerPhase::Idle {
+                return Ok(());
+            }
+            state.pending_power_restart = true;
+            if state.phase == TimerPhase::Transition {
+                // Drop the countdown; work starts fresh on wake
+                drop(state);
+                return self.stop();
+            }
+            if state.phase == TimerPhase::Break {
+                // A break the user paused stays theirs to resume
+                if state.state == TimerState::Running {
+                    let now = self.clock.now();
+                    Self::update_remaining_seconds(&mut state, now);
+                    state.state = TimerState::Paused;
+                    state.phase_end_time = None;
+                    state.power_paused_at = Some(now);
+                }
+                None
+            } else {
+                Some(self.create_session_record(
+                    &state,
+                    false,
+                    Some(POWER_INTERRUPT_WORK_NOTE),
+                    Some(SessionEndReason::PowerInterrupt),
+                ))
+            }
+        };
+        match session {
+            Some(session) => {
+                self.persist_session_finish(session);
+                self.stop()?;
+            }
+            None => self.emit_timer_update()?,
+        }
+        Ok(())
+    }
+
+    /// Whether power events are being ignored because travel mode is on.
+    fn ignores_power_events(&self, event: &str) -> bool {
+        let travel_mode = self.state.lock().unwrap().travel_mode;
+        if travel_mode {
+            eprintln!("[Power] Travel mode on, ignoring {}", event);
+        }
+        travel_mode
+    }
+
+    /// Turn travel mode on or off. While on, display-off and suspend/resume never
+    /// pause or restart the timer; turning it on also drops a pending power restart
+    /// and resumes a break that a power event paused.
+    /// 出行模式：忽略熄屏与睡眠/唤醒事件，计时完全按真实时间进行。
+    pub fn set_travel_mode(&self, enabled: bool) -> AppResult<()> {
+        let resume_break = {
+            let mut state = self.state.lock().unwrap();
+            if state.travel_mode == enabled {
+                return Ok(());
+            }
+            state.travel_mode = enabled;
+            if enabled {
+                state.pending_power_restart = false;
+                state.paused_due_to_display_off = false;
+                state.paused_due_to_system_suspend = false;
+                state.power_paused_at.take().is_some()
+            } else {
+                false
+            }
+        };
+        if resume_break {
+            return self.resume();
+        }
+        self.emit_timer_update()
+    }
+
+    /// Whether breaks wait for calls to end (settings `defer_breaks_during_calls`).
+    pub fn defers_breaks_during_calls(&self) -> bool {
+        self.state.lock().unwrap().defer_breaks_during_calls
+    }
+
+    /// Report whether a call (microphone capture) is in progress. While it is,
+    /// an ending work phase runs on instead of starting the break; once it ends
+    /// the break follows after `CALL_GRACE_SECONDS`.
+    /// 通话状态变化：通话期间推迟休息，结束后经过短暂宽限再开始休息。
+    pub fn set_call_in_progress(&self, active: bool) -> AppResult<()> {
+        {
+            let mut state = self.state.lock().unwrap();
+            let active = active && state.defer_breaks_during_calls;
+            if state.call_in_progress == active {
+                return Ok(());
+            }
+            state.call_in_progress = active;
+            state.call_grace_until = if active {
+                None
+            } else {
+                Some(self.clock.now() + ChronoDuration::seconds(CALL_GRACE_SECONDS))
+            };
+        }
+        println!(
+            "TimerService: call {}",
+            if active { "started" } else { "ended" }
+        );
+        self.emit_timer_update()
     }
 
     /// React to system display power state changes.
     /// 当检测到显示器被熄灭/点亮时，自动暂停或重启工作计时。
     pub fn handle_display_power_state(&self, display_on: bool) -> AppResult<()> {
+        if self.ignores_power_events("display power change") {
+            return Ok(());
+        }
         if display_on {
             {
                 let mut state = self.state.lock().unwrap();
@@ -538,6 +1713,9 @@ impl TimerService {
     /// Handle system suspend event (hibernate/sleep).
     /// 系统即将进入休眠/睡眠状态时调用，暂停计时器以防止时间漂移。
     pub fn handle_system_suspend(&self) -> AppResult<()> {
+        if self.ignores_power_events("system suspend") {
+            return Ok(());
+        }
         self.interrupt_for_power_event()?;
         Ok(())
     }
@@ -545,6 +1723,9 @@ impl TimerService {
     /// Handle system resume event (wake from hibernate/sleep).
     /// 系统从休眠/睡眠状态恢复时调用，重启工作计时。
     pub fn handle_system_resume(&self) -> AppResult<()> {
+        if self.ignores_power_events("system resume") {
+            return Ok(());
+        }
         {
             let mut state = self.state.lock().unwrap();
             state.paused_due_to_system_suspend = false;
@@ -557,19 +1738,158 @@ impl TimerService {
     /// Get current timer info
     pub fn get_info(&self) -> TimerInfo {
         let state = self.state.lock().unwrap();
-        let next_break_time = Self::compute_next_break_time_from_state(&state);
+        let now = self.clock.now();
+        let next_break_time = Self::compute_next_break_time_from_state(&state, now);
+        let gate = state.reminder_gate(now);
         TimerInfo {
             phase: state.phase.clone(),
             state: state.state.clone(),
             remaining_seconds: state.remaining_seconds,
             total_seconds: state.total_seconds,
             next_transition_time: state.phase_end_time,
-            next_break_time: if state.flow_mode {
-                None
+            next_break_time,
+            break_dismissible_at: Self::break_dismissible_at(&state),
+            interruptions_blocked_by: gate.reasons(),
+            segmented: state.has_segments(),
+            active_preset: state.active_preset.clone(),
+            travel_mode: state.travel_mode,
+            awaiting_break_acknowledgment: state.pending_acknowledgment.is_some(),
+            continuous_screen_seconds: state.screen_time.seconds(),
+            rest_debt_minutes: state.rest_debt_minutes.round() as u32,
+            rest_debt_extra_minutes: if state.phase == TimerPhase::Break {
+                state.rest_debt_extra_minutes
             } else {
-                next_break_time
+                0
             },
+            effective_mode: state.effective_mode(),
+        }
+    }
+
+    /// ID of the session of the running phase, if any.
+    pub fn current_session_id(&self) -> Option<String> {
+        self.state.lock().unwrap().current_session_id.clone()
+    }
+
+    /// Name of the preset currently applied, if any.
+    pub fn active_preset(&self) -> Option<String> {
+        self.state.lock().unwrap().active_preset.clone()
+    }
+
+    /// Remember which preset the current durations came from.
+    /// 记录当前时长配置来自哪个预设（None 表示手动配置）。
+    pub fn set_active_preset(&self, name: Option<String>) -> AppResult<()> {
+        self.state.lock().unwrap().active_preset = name;
+        self.emit_timer_update()
+    }
+
+    /// Project the schedule for the next `hours` without touching live state.
+    /// 在状态副本上模拟未来若干小时的阶段安排，用于设置页预览。
+    pub fn simulate_schedule(&self, hours: u32) -> Vec<ScheduledPhase> {
+        let mut simulated = self.state.lock().unwrap().clone();
+        let now = self.clock.now();
+        let horizon = now + ChronoDuration::hours(hours.clamp(1, MAX_SIMULATION_HOURS) as i64);
+        simulated.project_phases(now, horizon)
+    }
+
+    /// When today's work reaches `goal_seconds` if the timer keeps its current
+    /// cycle: `done_seconds` of finished work, then the running work phase and
+    /// the work phases of the projected schedule. None once the goal is met or
+    /// when it would only be reached after `until`.
+    /// 按当前节奏预计达成每日工作目标的时间；已达成或在 `until` 前无法达成时返回 None。
+    pub fn project_goal_eta(
+        &self,
+        done_seconds: i64,
+        goal_seconds: i64,
+        until: chrono::DateTime<Utc>,
+    ) -> Option<chrono::DateTime<Utc>> {
+        let mut simulated = self.state.lock().unwrap().clone();
+        let now = self.clock.now();
+        let mut needed = goal_seconds - done_seconds;
+        if simulated.phase == TimerPhase::Work {
+            let elapsed = simulated
+                .total_seconds
+                .saturating_sub(simulated.remaining_seconds);
+            needed -= i64::from(elapsed);
+        }
+        if needed <= 0 {
+            return None;
         }
+
+        let horizon = until.min(now + ChronoDuration::hours(MAX_SIMULATION_HOURS as i64));
+        for scheduled in simulated.project_phases(now, horizon) {
+            if scheduled.phase != TimerPhase::Work {
+                continue;
+            }
+            // The running phase counts from now; its elapsed part is already in `needed`
+            let start = scheduled.start.max(now);
+            let available = (scheduled.end - start).num_seconds().max(0);
+            if available >= needed {
+                let eta = start + ChronoDuration::seconds(needed);
+                return (eta <= until).then_some(eta);
+            }
+            needed -= available;
+        }
+        None
+    }
+
+    /// Whether the `enable_force_break` setting is on.
+    pub fn is_force_break_enabled(&self) -> bool {
+        self.state.lock().unwrap().force_break_enabled
+    }
+
+    /// Whether a force break currently forbids closing the reminder or skipping.
+    /// 强制休息期间，在达到最长提醒时长前禁止关闭提醒/跳过休息。
+    pub fn is_break_dismissal_locked(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        Self::break_dismissible_at(&state)
+            .map(|at| self.clock.now() < at)
+            .unwrap_or(false)
+    }
+
+    /// Countdown accuracy over the current phase: how far the tick-updated
+    /// remaining time strayed from `phase_end_time`. With one tick per second a
+    /// healthy timer stays within about 1000 ms.
+    /// 当前阶段的计时精度：每次 tick 前显示的剩余时间与按结束时间计算的差值。
+    pub fn timer_accuracy(&self) -> TimerAccuracy {
+        let state = self.state.lock().unwrap();
+        let drift = if state.tick_drift.session_id == state.current_session_id {
+            state.tick_drift.clone()
+        } else {
+            TickDrift::default()
+        };
+        TimerAccuracy {
+            phase: state.phase.clone(),
+            state: state.state.clone(),
+            phase_end_time: state.phase_end_time,
+            remaining_seconds: state.remaining_seconds,
+            samples: drift.samples,
+            max_drift_ms: drift.max_millis,
+            last_drift_ms: drift.last_millis,
+        }
+    }
+
+    /// Whether a finished break keeps its reminder open until acknowledged.
+    pub fn is_break_acknowledgment_pending(&self) -> bool {
+        self.state.lock().unwrap().pending_acknowledgment.is_some()
+    }
+
+    /// Record that the user acknowledged the finished break and let its reminder
+    /// close; returns the updated session, or None when nothing was waiting.
+    /// 记录用户已确认刚结束的休息，更新会话并发送 `break-acknowledged` 事件。
+    pub fn acknowledge_break(&self) -> AppResult<Option<Session>> {
+        let Some(mut session) = self.state.lock().unwrap().pending_acknowledgment.take() else {
+            return Ok(None);
+        };
+        session.acknowledged = Some(true);
+        self.store.persist_session(session.clone());
+        self.emit_timer_update()?;
+        self.emit(
+            "break-acknowledged",
+            BreakAcknowledged {
+                session_id: session.id.clone(),
+            },
+        )?;
+        Ok(Some(session))
     }
 
     /// Update core timer configuration from settings.
@@ -589,6 +1909,7 @@ impl TimerService {
         if !state.segmented_enabled {
             state.segment_index = 0;
             state.segment_iteration = 0;
+            state.program_iteration = 0;
         } else {
             if state.segment_index >= state.segments.len() {
                 state.segment_index = 0;
@@ -601,7 +1922,73 @@ impl TimerService {
             }
         }
 
-        state.apply_current_segment();
+        state.apply_current_segment(self.clock.now());
+    }
+
+    /// Apply timer-related fields from settings.
+    /// 将设置中与计时相关的字段同步到计时服务。
+    pub fn apply_settings(&self, settings: &Settings) -> AppResult<()> {
+        // Set before the configuration update so it picks the durations by clock time
+        self.state.lock().unwrap().time_anchored =
+            TimeAnchoredRange::from_settings(&settings.time_anchored_segments);
+        self.update_timer_configuration(
+            settings.work_duration,
+            settings.break_duration,
+            settings.segmented_work_enabled,
+            settings.work_segments.clone(),
+        );
+        {
+            let mut state = self.state.lock().unwrap();
+            state.force_break_enabled = settings.enable_force_break;
+            state.max_reminder_minutes = settings.max_reminder_minutes;
+            state.require_break_acknowledgment = settings.require_break_acknowledgment;
+            if !state.require_break_acknowledgment {
+                state.pending_acknowledgment = None;
+            }
+            state.max_suppression_minutes = settings.max_suppression_minutes;
+            state.suppression_expiry_warning_minutes = settings.suppression_expiry_warning_minutes;
+            state.prompt_work_note = settings.prompt_work_note;
+            state.program_repeat = settings.program_repeat;
+            state.stop_on_schedule_complete =
+                settings.on_schedule_complete == ScheduleCompleteAction::Stop;
+            state.work_note_in_flow_mode = settings.work_note_in_flow_mode;
+            state.break_jitter_minutes = settings.break_jitter_minutes;
+            // Only reseed when the seed changes so saving settings doesn't replay the sequence
+            if settings.random_seed.is_some() && settings.random_seed != state.jitter_seed {
+                state.jitter_rng = settings.random_seed.unwrap_or_default();
+            }
+            state.jitter_seed = settings.random_seed;
+            state.min_session_seconds = settings.min_session_seconds;
+            state
+                .screen_time
+                .set_min_real_break_minutes(settings.min_real_break_minutes);
+            state.activity_sample_seconds = settings.activity_sample_seconds;
+            state.activity_idle_threshold_seconds = settings.activity_idle_threshold_seconds;
+            state.rest_debt_compensation_percent = settings.rest_debt_compensation_percent;
+            state.active_preset = settings.active_preset.clone();
+            state.hydration = settings.hydration.clone();
+            // Takes effect at the next phase boundary
+            state.wind_down = WindDownWindow::from_settings(&settings.wind_down);
+            state.catch_up_break_enabled = settings.catch_up_break_enabled;
+            state.long_absence_minutes = settings.long_absence_minutes;
+            state.max_extends_per_phase = settings.max_extends_per_phase;
+            state.max_total_extend_minutes = settings.max_total_extend_minutes;
+            state.work_start_countdown_seconds = settings.work_start_countdown_seconds;
+            state.auto_start_on_first_activity = settings.auto_start_on_first_activity;
+            state.defer_breaks_during_calls = settings.defer_breaks_during_calls;
+            if !state.defer_breaks_during_calls {
+                state.call_in_progress = false;
+                state.call_grace_until = None;
+            }
+            state
+                .session_ids
+                .configure(settings.session_id_scheme, &settings.session_id_prefix);
+            if !state.catch_up_break_enabled {
+                state.next_break_override_minutes = None;
+            }
+        }
+        self.set_travel_mode(settings.travel_mode)?;
+        self.update_flow_mode(settings.flow_mode_enabled)
     }
 
     /// Update flow mode toggle based on settings.
@@ -611,12 +1998,16 @@ impl TimerService {
             return Ok(());
         }
         state.flow_mode = enabled;
-        let should_switch_to_work = enabled && matches!(state.phase, TimerPhase::Break);
+        // A force break still runs up to `max_reminder_minutes`
+        let should_switch_to_work = enabled
+            && matches!(state.phase, TimerPhase::Break)
+            && Self::break_dismissible_at(&state).is_none_or(|at| self.clock.now() >= at);
         drop(state);
 
         if should_switch_to_work {
-            if let Some((session, _)) = self.skip()? {
-                self.persist_session_finish(session);
+            // `skip` already applied `keep_session`
+            if let Some((Some(session), _)) = self.skip(true, PhaseChangeCause::FlowMode)? {
+                self.store.persist_session(session);
             }
         } else {
             self.emit_timer_update()?;
@@ -626,19 +2017,80 @@ impl TimerService {
     }
 
     /// Do not take breaks for the specified number of hours from now.
-    pub fn suppress_breaks_for_hours(&self, hours: i64) {
+    pub fn suppress_breaks_for_hours(&self, hours: i64) -> AppResult<()> {
+        self.suppress_breaks_for_minutes(hours.max(1) * 60)
+    }
+
+    /// Do not take breaks for the specified number of minutes from now.
+    pub fn suppress_breaks_for_minutes(&self, minutes: i64) -> AppResult<()> {
+        let now = self.clock.now();
+        self.suppress_breaks_until(now, now + ChronoDuration::minutes(minutes.max(1)))
+    }
+
+    /// Push the active suppression window back by `minutes`.
+    /// 将当前的休息抑制再延长若干分钟。
+    pub fn extend_suppression(&self, minutes: i64) -> AppResult<()> {
+        let now = self.clock.now();
+        let until = self
+            .state
+            .lock()
+            .unwrap()
+            .suppress_breaks_until
+            .filter(|until| *until > now)
+            .ok_or_else(|| AppError::ValidationError("Breaks are not suppressed".to_string()))?;
+        self.suppress_breaks_until(now, until + ChronoDuration::minutes(minutes.max(1)))
+    }
+
+    /// End the suppression window now; breaks resume on their normal schedule,
+    /// with the catch-up break if enabled.
+    /// 立即结束休息抑制，恢复正常休息（开启补偿休息时照常补偿）。
+    pub fn resume_breaks(&self) -> AppResult<()> {
+        let mut state = self.state.lock().unwrap();
+        let now = self.clock.now();
+        if matches!(state.suppress_breaks_until, Some(until) if until > now) {
+            state.suppress_breaks_until = Some(now);
+        }
+        state.expire_suppression(now);
+        drop(state);
+        self.emit_timer_update()
+    }
+
+    /// Start a suppression window, enforcing `max_suppression_minutes`.
+    /// 超过上限时：强制休息开启则拒绝，否则截断到上限。
+    fn suppress_breaks_until(
+        &self,
+        now: chrono::DateTime<Utc>,
+        until: chrono::DateTime<Utc>,
+    ) -> AppResult<()> {
         let mut state = self.state.lock().unwrap();
-        let until = Utc::now() + ChronoDuration::hours(hours.max(1));
+        let mut until = until;
+        if let Some(cap) = state.max_suppression_minutes {
+            let cap_until = now + ChronoDuration::minutes(cap.max(1) as i64);
+            if until > cap_until {
+                if state.force_break_enabled {
+                    return Err(AppError::SuppressionTooLong(cap));
+                }
+                until = cap_until;
+            }
+        }
+        // Suppressing again while suppressed continues the same window, so a
+        // catch-up break still covers all of it
+        let active = matches!(state.suppress_breaks_until, Some(current) if current > now);
+        if !active {
+            state.suppress_breaks_since = Some(now);
+        }
         state.suppress_breaks_until = Some(until);
+        state.suppression_expiry_warned = false;
         drop(state);
         // 立即推送一次状态，确保前端的“下次休息时间”实时更新
         let _ = self.emit_timer_update();
+        Ok(())
     }
 
     /// Do not take breaks until tomorrow morning (08:00 local time).
-    pub fn suppress_breaks_until_tomorrow_morning(&self) {
+    pub fn suppress_breaks_until_tomorrow_morning(&self) -> AppResult<()> {
         // Compute tomorrow 08:00 in local time, convert to UTC
-        let now_local = Local::now();
+        let now_local = self.clock.now().with_timezone(&Local);
         let tomorrow_date = now_local.date_naive() + ChronoDuration::days(1);
         let morning = chrono::NaiveTime::from_hms_opt(8, 0, 0).unwrap();
         let naive_dt = chrono::NaiveDateTime::new(tomorrow_date, morning);
@@ -650,12 +2102,132 @@ impl TimerService {
             .or_else(|| local_result.earliest())
             .or_else(|| local_result.latest())
             .map(|dt| dt.with_timezone(&Utc))
-            .unwrap_or_else(|| Utc::now() + ChronoDuration::hours(24));
+            .unwrap_or_else(|| self.clock.now() + ChronoDuration::hours(24));
+        self.suppress_breaks_until(self.clock.now(), until_utc)
+    }
+
+    /// Continue the screen time saved by a previous run of the app.
+    pub fn restore_screen_time(&self, saved: ScreenContinuity) {
+        let now = self.clock.now();
+        self.state.lock().unwrap().screen_time.restore(saved, now);
+    }
+
+    /// Bring the screen time up to date; `idle_seconds` is the time since the
+    /// last keyboard or mouse input, if known. Returns the value to persist.
+    /// 更新连续使用电脑的时长，返回需要保存的值。
+    pub fn sample_screen_time(&self, idle_seconds: Option<u64>) -> ScreenContinuity {
+        let now = self.clock.now();
         let mut state = self.state.lock().unwrap();
-        state.suppress_breaks_until = Some(until_utc);
-        drop(state);
-        // 立即推送一次状态，确保前端的"下次休息时间"实时更新
-        let _ = self.emit_timer_update();
+        state.screen_time.sample(now, idle_seconds);
+        state.screen_time.snapshot()
+    }
+
+    /// Count one input sample toward the running work session's active time:
+    /// `sample_seconds` are added when the input idle time is within
+    /// `activity_idle_threshold_seconds`. Only a running work phase counts, so
+    /// nothing accrues while paused; an unknown idle time records nothing.
+    /// 工作阶段计时中按采样累计活跃时长，暂停、休息或无法读取空闲时间时不累计。
+    pub fn sample_active_time(&self, idle_seconds: Option<u64>, sample_seconds: u32) {
+        let Some(idle_seconds) = idle_seconds else {
+            return;
+        };
+        let mut state = self.state.lock().unwrap();
+        if state.phase != TimerPhase::Work || state.state != TimerState::Running {
+            return;
+        }
+        let active = idle_seconds <= u64::from(state.activity_idle_threshold_seconds);
+        let total = state.current_active_seconds.get_or_insert(0);
+        if active {
+            *total += i64::from(sample_seconds);
+        }
+    }
+
+    /// Seconds between input samples for active time (settings `activity_sample_seconds`).
+    pub fn activity_sample_seconds(&self) -> u32 {
+        self.state.lock().unwrap().activity_sample_seconds
+    }
+
+    /// Keep the glasses logged today (after `increment_hydration`); the wait for the
+    /// next hydration reminder starts over.
+    pub fn record_hydration(&self, progress: &HydrationProgress) {
+        let mut state = self.state.lock().unwrap();
+        state.hydration_count = Some((progress.date, progress.count));
+        state.hydration_worked_minutes = 0;
+    }
+
+    /// Whether hydration reminders are on but the count of `today` is not known
+    /// yet: at startup and once the day rolls over.
+    fn hydration_count_needed(&self, today: NaiveDate) -> bool {
+        let state = self.state.lock().unwrap();
+        state.hydration.enabled
+            && state.hydration.reminder_interval_minutes > 0
+            && !matches!(state.hydration_count, Some((date, _)) if date == today)
+    }
+
+    /// One minute of the hydration cadence: counts running work and returns the
+    /// progress to remind with once `reminder_interval_minutes` have passed and the
+    /// daily goal is not reached yet.
+    fn hydration_minute(&self, today: NaiveDate) -> Option<HydrationProgress> {
+        let mut state = self.state.lock().unwrap();
+        let interval = state.hydration.reminder_interval_minutes;
+        let count = match state.hydration_count {
+            Some((date, count)) if state.hydration.enabled && interval > 0 && date == today => {
+                count
+            }
+            _ => {
+                state.hydration_worked_minutes = 0;
+                return None;
+            }
+        };
+        if state.phase != TimerPhase::Work || state.state != TimerState::Running {
+            return None;
+        }
+        state.hydration_worked_minutes += 1;
+        if state.hydration_worked_minutes < interval {
+            return None;
+        }
+        state.hydration_worked_minutes = 0;
+        let goal = state.hydration.daily_goal_glasses;
+        (count < goal).then_some(HydrationProgress {
+            date: today,
+            count,
+            goal,
+        })
+    }
+
+    /// Set the rest debt (minutes) used for the next break and shown in `TimerInfo`.
+    pub fn set_rest_debt_minutes(&self, minutes: f64) {
+        self.state.lock().unwrap().rest_debt_minutes = minutes.max(0.0);
+    }
+
+    /// Register the IDs of stored sessions so new ones never reuse them.
+    /// 启动时登记已有会话 ID，避免顺序/按日方案生成重复 ID。
+    pub fn seed_session_ids<I: IntoIterator<Item = String>>(&self, ids: I) {
+        self.state.lock().unwrap().session_ids.seed(ids);
+    }
+
+    /// Restore the last recorded day start so a relaunch on the same day skips
+    /// the first-activity gate.
+    /// 启动时恢复最近一次的开工时间，同一天再次启动时不再等待首次活动。
+    pub fn seed_day_start(&self, started_at: Option<chrono::DateTime<Utc>>) {
+        self.state.lock().unwrap().day_started_on =
+            started_at.map(|at| at.with_timezone(&Local).date_naive());
+    }
+
+    /// Whether work is held back until the first sustained activity of the day.
+    /// It re-arms once the timer sits stopped into a new local day.
+    /// 是否正在等待当天的首次持续活动再开始工作；计时停止后跨日会重新生效。
+    pub fn awaiting_first_activity(&self) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .awaiting_first_activity(self.clock.now())
+    }
+
+    /// ID for a session starting at `start_time`, in the configured scheme.
+    fn next_session_id(state: &mut TimerServiceState, start_time: chrono::DateTime<Utc>) -> String {
+        let today = start_time.with_timezone(&Local).date_naive();
+        state.session_ids.next(today)
     }
 
     /// Create session record from current state
@@ -664,10 +2236,18 @@ impl TimerService {
         state: &TimerServiceState,
         is_skipped: bool,
         notes: Option<&str>,
+        end_reason: Option<SessionEndReason>,
     ) -> Session {
-        let end_time = Utc::now();
+        let end_time = self.clock.now();
         let start_time = state.current_session_start.unwrap_or(end_time);
         let actual_duration = (end_time - start_time).num_seconds();
+        // Samples are whole intervals, so the first one may overshoot a short session
+        let active_seconds = match state.phase {
+            TimerPhase::Work => state
+                .current_active_seconds
+                .map(|seconds| seconds.min(actual_duration.max(0))),
+            _ => None,
+        };
 
         Session {
             id: state
@@ -677,21 +2257,28 @@ impl TimerService {
             session_type: match state.phase {
                 TimerPhase::Work => SessionType::Work,
                 TimerPhase::Break => SessionType::Break,
-                TimerPhase::Idle => SessionType::Work,
+                TimerPhase::Idle | TimerPhase::Transition => SessionType::Work,
             },
             start_time,
             end_time,
             duration: actual_duration,
-            planned_duration: state.total_seconds as i64,
+            planned_duration: state.planned_seconds(),
             is_skipped,
-            extended_seconds: 0,
+            extended_seconds: state.extended_minutes_this_phase as i64 * 60,
             notes: notes.map(|note| note.to_string()),
+            rating: None,
+            in_progress: false,
+            anomaly_dismissed: false,
+            acknowledged: None,
+            active_seconds,
+            segment_label: state.current_segment_label(),
+            end_reason,
         }
     }
 
     /// Persist a zero-duration session record at phase start (for later updates).
     fn persist_session_start(&self) {
-        let (id, session_type, start_time, planned_secs) = {
+        let (id, session_type, start_time, planned_secs, segment_label) = {
             let state = self.state.lock().unwrap();
             (
                 state
@@ -699,8 +2286,11 @@ impl TimerService {
                     .clone()
                     .unwrap_or_else(|| Uuid::new_v4().to_string()),
                 state.phase.clone(),
-                state.current_session_start.unwrap_or_else(Utc::now),
-                state.total_seconds as i64,
+                state
+                    .current_session_start
+                    .unwrap_or_else(|| self.clock.now()),
+                state.planned_seconds(),
+                state.current_segment_label(),
             )
         };
 
@@ -710,7 +2300,7 @@ impl TimerService {
             session_type: match session_type {
                 TimerPhase::Work => SessionType::Work,
                 TimerPhase::Break => SessionType::Break,
-                TimerPhase::Idle => SessionType::Work,
+                TimerPhase::Idle | TimerPhase::Transition => SessionType::Work,
             },
             start_time,
             end_time: start_time,
@@ -719,83 +2309,272 @@ impl TimerService {
             is_skipped: false,
             extended_seconds: 0,
             notes: None,
+            rating: None,
+            in_progress: true,
+            anomaly_dismissed: false,
+            acknowledged: None,
+            active_seconds: None,
+            segment_label,
+            end_reason: None,
         };
 
-        let db = self.db.clone();
-        tauri::async_runtime::spawn(async move {
-            if let Ok(guard) = db.try_lock() {
-                let _ = guard.save_or_update_session(&session).await;
-            } else {
-                let db2 = db.lock().await;
-                let _ = db2.save_or_update_session(&session).await;
-            }
-        });
+        self.store.persist_session(session);
     }
 
     /// Persist finished session (auto or skipped) updating the previously created record.
     fn persist_session_finish(&self, session: Session) {
-        let db = self.db.clone();
-        tauri::async_runtime::spawn(async move {
-            if let Ok(guard) = db.try_lock() {
-                let _ = guard.save_or_update_session(&session).await;
-            } else {
-                let db2 = db.lock().await;
-                let _ = db2.save_or_update_session(&session).await;
+        if let Some(session) = self.keep_session(session) {
+            self.store.persist_session(session);
+        }
+    }
+
+    /// Apply `min_session_seconds`: a session shorter than the threshold is not kept,
+    /// and the placeholder written at phase start is removed. Every finished
+    /// session passes here, so a real break also starts the screen time over.
+    /// 过短的会话不保存，并删除阶段开始时写入的占位记录；足够长的休息同时清零连续使用时长。
+    fn keep_session(&self, session: Session) -> Option<Session> {
+        let min_seconds = {
+            let mut state = self.state.lock().unwrap();
+            if session.session_type == SessionType::Break
+                && state.screen_time.is_real_break(session.duration)
+            {
+                state.screen_time.reset(self.clock.now());
             }
-        });
+            state.min_session_seconds as i64
+        };
+        if session.duration >= min_seconds {
+            return Some(session);
+        }
+        println!(
+            "TimerService: dropping {}s session shorter than {}s",
+            session.duration, min_seconds
+        );
+        self.store.discard_session(&session.id);
+        None
+    }
+
+    fn emit<T: Serialize>(&self, event: &str, payload: T) -> AppResult<()> {
+        let value =
+            serde_json::to_value(payload).map_err(|e| AppError::TauriError(e.to_string()))?;
+        self.events.emit_event(event, value)
+    }
+
+    /// Ask the frontend to collect a note for a finished work session.
+    /// 工作阶段结束后按设置发出 `work-note-prompt`，过短或心流连续的阶段不提示。
+    fn prompt_work_note(&self, session: &Session, chained: bool) {
+        let (enabled, in_flow_mode) = {
+            let state = self.state.lock().unwrap();
+            (state.prompt_work_note, state.work_note_in_flow_mode)
+        };
+        if !enabled
+            || !matches!(session.session_type, SessionType::Work)
+            || session.duration < WORK_NOTE_MIN_SESSION_SECONDS
+            || (chained && !in_flow_mode)
+        {
+            return;
+        }
+        let _ = self.emit(
+            "work-note-prompt",
+            WorkNotePrompt {
+                session_id: session.id.clone(),
+            },
+        );
     }
 
     /// Emit timer update event
     /// 将计时器状态推送给前端，驱动 UI 更新。
     fn emit_timer_update(&self) -> AppResult<()> {
         let info = self.get_info();
-        self.app
-            .emit("timer-update", info)
-            .map_err(|e| crate::utils::AppError::TauriError(e.to_string()))?;
-        Ok(())
+        self.emit("timer-update", info)
     }
 
     /// Emit phase change event
     /// 通知前端阶段切换，用于弹窗或文案更新。
-    fn emit_phase_change(&self, phase: &str) -> AppResult<()> {
-        self.app
-            .emit("phase-change", phase)
-            .map_err(|e| crate::utils::AppError::TauriError(e.to_string()))?;
-        Ok(())
+    fn emit_phase_change(&self, phase: TimerPhase, cause: PhaseChangeCause) -> AppResult<()> {
+        self.emit("phase-change", PhaseChange { phase, cause })
     }
 
     /// Emit timer finished event
     /// 通知前端计时结束，可触发提示音或其他反馈。
     fn emit_timer_finished(&self) -> AppResult<()> {
-        self.app
-            .emit("timer-finished", ())
-            .map_err(|e| crate::utils::AppError::TauriError(e.to_string()))?;
-        Ok(())
+        self.emit("timer-finished", ())
     }
 
     /// Show break reminder window
     /// 触发前端或主进程创建休息提醒窗口。
     fn show_break_reminder(&self) -> AppResult<()> {
-        self.app
-            .emit("show-break-reminder", ())
-            .map_err(|e| crate::utils::AppError::TauriError(e.to_string()))?;
-        Ok(())
+        self.emit("show-break-reminder", ())
     }
 
-    /// Start background ticker
-    /// 在 Tokio 任务中启动秒级循环，持续驱动计时逻辑。
+    /// Start background ticker, plus a watchdog that restarts it when no tick
+    /// happened for `TICKER_STALL_SECONDS`.
+    /// 在 Tokio 任务中启动秒级循环，持续驱动计时逻辑；看门狗在循环停滞时重新启动它。
     pub fn start_ticker(self: Arc<Self>) {
-        let service = Arc::clone(&self);
+        Arc::clone(&self).spawn_tick_loop();
+        tokio::spawn(async move {
+            let mut interval = time::interval(TokioDuration::from_secs(TICKER_WATCHDOG_SECONDS));
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            let mut last_check = Instant::now();
+            loop {
+                interval.tick().await;
+                // A late check means the system was asleep; give the ticker a
+                // round to catch up before judging it
+                let asleep = last_check.elapsed().as_secs() > TICKER_WATCHDOG_SECONDS * 2;
+                last_check = Instant::now();
+                if asleep || !self.ticker_stalled() {
+                    continue;
+                }
+                {
+                    let mut ticker = self.ticker_monitor();
+                    ticker.restarts += 1;
+                    eprintln!(
+                        "TimerService: ticker stalled, restarting (restart #{})",
+                        ticker.restarts
+                    );
+                }
+                Arc::clone(&self).spawn_tick_loop();
+            }
+        });
+    }
+
+    /// The once-a-second loop driving `tick`. A failing or panicking tick is
+    /// logged and the loop carries on; a panic is also reported (`backend-panic`).
+    /// Starting a new loop retires the previous one.
+    fn spawn_tick_loop(self: Arc<Self>) {
+        let generation = {
+            let mut ticker = self.ticker_monitor();
+            ticker.generation += 1;
+            ticker.last_tick = Some(Instant::now());
+            ticker.generation
+        };
         tokio::spawn(async move {
             // Tick every second to ensure phase transitions happen on-time (00 seconds)
             let mut interval = time::interval(TokioDuration::from_secs(1));
             // Skip the backlog after system resume to avoid flooding the main thread.
             interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            // Report a run of panicking ticks once, not every second
+            let mut panicking = false;
             loop {
                 interval.tick().await;
-                if let Ok(session) = service.tick() {
-                    if let Some(_session) = session {
-                        // Session ended, could save to database here
+                if self.ticker_monitor().generation != generation {
+                    break;
+                }
+                let ticked = panic::catch_unwind(AssertUnwindSafe(|| self.tick()));
+                let panicked = ticked.is_err();
+                let error = match ticked {
+                    Ok(Ok(_)) => None,
+                    Ok(Err(e)) => Some(e.to_string()),
+                    Err(_) => {
+                        // The state lock may have been held by the panicking tick
+                        self.state.clear_poison();
+                        Some(panics::backend_panic("timer", true).message)
+                    }
+                };
+                let report_panic = panicked && !panicking;
+                panicking = panicked;
+                {
+                    let mut ticker = self.ticker_monitor();
+                    ticker.last_tick = Some(Instant::now());
+                    ticker.last_tick_at = Some(self.clock.now());
+                    if panicked {
+                        ticker.panics += 1;
+                    } else if error.is_some() {
+                        ticker.errors += 1;
+                    }
+                    if let Some(error) = error {
+                        // Log each distinct failure once rather than every second
+                        if ticker.last_error.as_deref() != Some(error.as_str()) {
+                            eprintln!("TimerService: tick failed: {}", error);
+                        }
+                        ticker.last_error = Some(error);
+                    }
+                }
+                if report_panic {
+                    let report = panics::backend_panic("timer", true);
+                    let _ = self.emit("backend-panic", report);
+                }
+            }
+        });
+    }
+
+    /// The ticker bookkeeping. A panic while it was held leaves nothing half
+    /// updated that matters, so a poisoned lock is used as is rather than
+    /// failing every later tick.
+    fn ticker_monitor(&self) -> MutexGuard<'_, TickerMonitor> {
+        self.ticker.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn ticker_stalled(&self) -> bool {
+        let ticker = self.ticker_monitor();
+        !matches!(ticker.last_tick, Some(at) if at.elapsed().as_secs() < TICKER_STALL_SECONDS)
+    }
+
+    /// Ticker liveness (last tick, watchdog restarts, failures), for diagnostics.
+    /// 计时循环的健康状态，用于诊断。
+    pub fn ticker_health(&self) -> TickerHealth {
+        let healthy = !self.ticker_stalled();
+        let ticker = self.ticker_monitor();
+        TickerHealth {
+            healthy,
+            last_tick: ticker.last_tick_at,
+            restarts: ticker.restarts,
+            panics: ticker.panics,
+            errors: ticker.errors,
+            last_error: ticker.last_error.clone(),
+        }
+    }
+
+    /// Recompute the rest debt from the stored sessions once a minute.
+    /// 每分钟根据会话记录重新计算休息欠债。
+    pub fn start_rest_debt_ticker(
+        self: Arc<Self>,
+        database: Arc<tokio::sync::Mutex<DatabaseService>>,
+    ) {
+        panics::spawn_guarded("restDebt", async move {
+            let mut interval = time::interval(TokioDuration::from_secs(REST_DEBT_REFRESH_SECONDS));
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                let now = self.clock.now();
+                let debt = database.lock().await.get_rest_debt_minutes(now).await;
+                match debt {
+                    Ok(minutes) => self.set_rest_debt_minutes(minutes),
+                    Err(e) => eprintln!("Failed to compute rest debt: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Hydration reminders on their own cadence: after
+    /// `hydration.reminder_interval_minutes` of running work a `hydration-reminder`
+    /// event carries today's progress. Logging a glass starts the wait over; no
+    /// reminder once the daily goal is reached.
+    /// 饮水提醒：工作计时达到间隔后发送 `hydration-reminder`，记录一杯后重新计时，达标后不再提醒。
+    pub fn start_hydration_reminders(
+        self: Arc<Self>,
+        database: Arc<tokio::sync::Mutex<DatabaseService>>,
+    ) {
+        panics::spawn_guarded("hydration", async move {
+            let mut interval = time::interval(TokioDuration::from_secs(HYDRATION_CHECK_SECONDS));
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                let today = self.clock.now().with_timezone(&Local).date_naive();
+                // Cadence and goal come from `apply_settings`; the file is read only
+                // when the count of today is not known
+                if self.hydration_count_needed(today) {
+                    let progress = database.lock().await.get_hydration_progress(today).await;
+                    match progress {
+                        Ok(progress) => self.record_hydration(&progress),
+                        Err(e) => {
+                            eprintln!("Failed to load hydration progress: {}", e);
+                            continue;
+                        }
+                    }
+                }
+                if let Some(progress) = self.hydration_minute(today) {
+                    if let Err(e) = self.emit("hydration-reminder", progress) {
+                        eprintln!("Failed to emit hydration reminder: {}", e);
                     }
                 }
             }
@@ -804,9 +2583,34 @@ impl TimerService {
 }
 
 impl TimerService {
-    fn update_remaining_seconds(state: &mut TimerServiceState) {
+    /// Pick the durations for a phase starting at `at` and track entering/leaving
+    /// the wind-down window. Returns true only for the first phase inside the window.
+    fn enter_phase_durations(state: &mut TimerServiceState, at: chrono::DateTime<Utc>) -> bool {
+        let in_wind_down = state.apply_phase_durations(at);
+        let started = in_wind_down && !state.wind_down_active;
+        state.wind_down_active = in_wind_down;
+        started
+    }
+
+    /// 强制休息时，提醒最多持续 `max_reminder_minutes`，之后即使未结束也允许关闭。
+    fn break_dismissible_at(state: &TimerServiceState) -> Option<chrono::DateTime<Utc>> {
+        if !state.force_break_enabled || state.phase != TimerPhase::Break {
+            return None;
+        }
+        let start = state.current_session_start?;
+        Some(start + ChronoDuration::minutes(state.max_reminder_minutes.max(1) as i64))
+    }
+
+    /// 根据抑制时长计算补偿休息分钟数：基础休息 + 每小时额外分钟，封顶。
+    fn catch_up_break_minutes(base_break_minutes: u32, suppressed: ChronoDuration) -> u32 {
+        let base = base_break_minutes.max(1);
+        let extra = suppressed.num_hours().max(0) * CATCH_UP_EXTRA_MINUTES_PER_HOUR;
+        let total = base as i64 + extra;
+        total.clamp(base as i64, CATCH_UP_MAX_MINUTES.max(base) as i64) as u32
+    }
+
+    fn update_remaining_seconds(state: &mut TimerServiceState, now: chrono::DateTime<Utc>) {
         if let Some(end_time) = state.phase_end_time {
-            let now = Utc::now();
             if now >= end_time {
                 state.remaining_seconds = 0;
             } else {
@@ -819,13 +2623,17 @@ impl TimerService {
     /// 根据当前状态与"抑制休息"设置，计算下一次真正开始休息的时间。
     fn compute_next_break_time_from_state(
         state: &TimerServiceState,
+        now: chrono::DateTime<Utc>,
     ) -> Option<chrono::DateTime<Utc>> {
         // Idle 阶段无法预测下一次休息时间
         if state.phase == TimerPhase::Idle {
             return None;
         }
+        // 心流模式优先于分段程序：不会有休息
+        if state.effective_mode() == EffectiveTimerMode::Flow {
+            return None;
+        }
 
-        let now = Utc::now();
         // 休息抑制截止时间（若存在且在未来，则以它为界）
         let allow_break_from = match state.suppress_breaks_until {
             Some(t) if t > now => t,
@@ -842,12 +2650,24 @@ impl TimerService {
                     let break_end = state.phase_end_time.unwrap_or_else(|| {
                         now + ChronoDuration::seconds(state.remaining_seconds.max(1) as i64)
                     });
-                    break_end + ChronoDuration::seconds(base_work_seconds)
+                    let work_seconds = (state.work_minutes_at(0, break_end) * 60) as i64;
+                    break_end + ChronoDuration::seconds(work_seconds)
+                }
+                TimerPhase::Transition => {
+                    let work_start = state.phase_end_time.unwrap_or(now);
+                    let work_seconds = (state.work_minutes_at(0, work_start) * 60) as i64;
+                    work_start + ChronoDuration::seconds(work_seconds)
                 }
                 TimerPhase::Idle => unreachable!(),
             };
 
-            if candidate < allow_break_from {
+            if state.wind_down.is_some() || state.has_time_anchored() {
+                // Work block lengths depend on when they start, step one at a time
+                while candidate < allow_break_from {
+                    candidate +=
+                        ChronoDuration::seconds((state.work_minutes_at(0, candidate) * 60) as i64);
+                }
+            } else if candidate < allow_break_from {
                 let diff_seconds = (allow_break_from - candidate).num_seconds();
                 let mut cycles = diff_seconds / base_work_seconds;
                 if diff_seconds % base_work_seconds != 0 {
@@ -862,7 +2682,9 @@ impl TimerService {
         let mut candidate;
         let mut idx;
         let mut iteration;
+        let mut passes = state.program_iteration;
 
+        // 分段程序在某次休息后结束并停止计时时，不再有下一次休息
         match state.phase {
             TimerPhase::Work => {
                 candidate = state.phase_end_time.unwrap_or_else(|| {
@@ -875,25 +2697,926 @@ impl TimerService {
                 let break_end = state.phase_end_time.unwrap_or_else(|| {
                     now + ChronoDuration::seconds(state.remaining_seconds.max(1) as i64)
                 });
-                let next_position =
-                    state.next_cycle_position(state.segment_index, state.segment_iteration);
-                let work_seconds = (state.cycle_work_minutes(next_position.0) * 60) as i64;
+                let next_position = state.next_program_position(
+                    state.segment_index,
+                    state.segment_iteration,
+                    passes,
+                )?;
+                let work_seconds = (state.work_minutes_at(next_position.0, break_end) * 60) as i64;
                 candidate = break_end + ChronoDuration::seconds(work_seconds);
                 idx = next_position.0;
                 iteration = next_position.1;
+                passes = next_position.2;
+            }
+            TimerPhase::Transition => {
+                // The program already moved on when the break ended
+                let work_start = state.phase_end_time.unwrap_or(now);
+                let work_seconds =
+                    (state.work_minutes_at(state.segment_index, work_start) * 60) as i64;
+                candidate = work_start + ChronoDuration::seconds(work_seconds);
+                idx = state.segment_index;
+                iteration = state.segment_iteration;
             }
             TimerPhase::Idle => unreachable!(),
         }
 
         while candidate < allow_break_from {
-            let break_len = (state.cycle_break_minutes(idx) * 60) as i64;
-            let next_position = state.next_cycle_position(idx, iteration);
-            let work_len = (state.cycle_work_minutes(next_position.0) * 60) as i64;
+            let break_len = (state.break_minutes_at(idx, candidate) * 60) as i64;
+            let next_position = state.next_program_position(idx, iteration, passes)?;
+            let work_start = candidate + ChronoDuration::seconds(break_len);
+            let work_len = (state.work_minutes_at(next_position.0, work_start) * 60) as i64;
             candidate += ChronoDuration::seconds(break_len + work_len);
             idx = next_position.0;
             iteration = next_position.1;
+            passes = next_position.2;
         }
 
         Some(candidate)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::timer_deps::fakes::{FakeClock, MemoryStore, RecordingEvents};
+
+    struct Harness {
+        timer: Arc<TimerService>,
+        events: Arc<RecordingEvents>,
+        store: Arc<MemoryStore>,
+        clock: Arc<FakeClock>,
+    }
+
+    impl Harness {
+        fn new(settings: &Settings) -> Self {
+            let events = Arc::new(RecordingEvents::default());
+            let store = Arc::new(MemoryStore::default());
+            let start = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+            let clock = Arc::new(FakeClock::at(start));
+            let timer =
+                TimerService::with_dependencies(events.clone(), store.clone(), clock.clone());
+            timer.apply_settings(settings).unwrap();
+            Self {
+                timer,
+                events,
+                store,
+                clock,
+            }
+        }
+
+        /// Let `seconds` pass, ticking once a second like the ticker loop.
+        fn run(&self, seconds: i64) {
+            for _ in 0..seconds {
+                self.clock.advance(1);
+                self.timer.tick().unwrap();
+            }
+        }
+
+        /// Like `run` for whole minutes, with input every minute.
+        fn work_at_computer(&self, minutes: i64) {
+            for _ in 0..minutes {
+                self.run(60);
+                self.timer.sample_screen_time(Some(0));
+            }
+        }
+
+        fn phase(&self) -> TimerPhase {
+            self.timer.get_info().phase
+        }
+
+        fn causes(&self) -> Vec<String> {
+            self.events
+                .payloads("phase-change")
+                .iter()
+                .map(|change| change["cause"].as_str().unwrap_or_default().to_string())
+                .collect()
+        }
+    }
+
+    fn settings() -> Settings {
+        Settings {
+            work_duration: 25,
+            break_duration: 5,
+            flow_mode_enabled: false,
+            segmented_work_enabled: false,
+            ..Settings::default()
+        }
+    }
+
+    #[test]
+    fn start_pause_resume_keeps_remaining_time() {
+        let h = Harness::new(&settings());
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        assert_eq!(h.phase(), TimerPhase::Work);
+        assert_eq!(h.timer.get_info().remaining_seconds, 25 * 60);
+
+        h.run(60);
+        assert_eq!(h.timer.get_info().remaining_seconds, 24 * 60);
+
+        h.timer.pause().unwrap();
+        h.run(300);
+        let info = h.timer.get_info();
+        assert_eq!(info.state, TimerState::Paused);
+        assert_eq!(info.remaining_seconds, 24 * 60);
+
+        h.timer.resume().unwrap();
+        h.run(30);
+        assert_eq!(h.timer.get_info().remaining_seconds, 24 * 60 - 30);
+        // Only the placeholder so far
+        assert!(h.store.finished().is_empty());
+    }
+
+    #[test]
+    fn skip_records_the_work_session_and_starts_the_break() {
+        let h = Harness::new(&settings());
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.run(120);
+
+        let (session, show_reminder) = h
+            .timer
+            .skip(false, PhaseChangeCause::UserSkip)
+            .unwrap()
+            .expect("skip applies");
+        let session = session.expect("session kept");
+        assert!(show_reminder);
+        assert!(session.is_skipped);
+        assert_eq!(session.end_reason, Some(SessionEndReason::Skipped));
+        assert_eq!(session.duration, 120);
+        assert_eq!(h.phase(), TimerPhase::Break);
+        assert_eq!(h.causes().last().map(String::as_str), Some("user-skip"));
+    }
+
+    #[test]
+    fn skip_right_after_a_phase_start_is_ignored() {
+        let h = Harness::new(&settings());
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.run(1);
+        assert!(h
+            .timer
+            .skip(false, PhaseChangeCause::UserSkip)
+            .unwrap()
+            .is_none());
+        assert_eq!(h.phase(), TimerPhase::Work);
+    }
+
+    #[test]
+    fn two_skips_100_ms_apart_skip_only_the_work_phase() {
+        let h = Harness::new(&settings());
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.run(10 * 60);
+
+        let (session, _) = h
+            .timer
+            .skip(false, PhaseChangeCause::UserSkip)
+            .unwrap()
+            .expect("first skip applies");
+        h.clock.advance_millis(100);
+        assert!(h
+            .timer
+            .skip(false, PhaseChangeCause::UserSkip)
+            .unwrap()
+            .is_none());
+
+        assert_eq!(session.unwrap().session_type, SessionType::Work);
+        assert_eq!(h.phase(), TimerPhase::Break);
+        assert_eq!(
+            h.causes()
+                .iter()
+                .filter(|cause| *cause == "user-skip")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn extend_respects_the_per_phase_limits() {
+        let h = Harness::new(&Settings {
+            max_extends_per_phase: Some(1),
+            ..settings()
+        });
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.timer.extend(5).unwrap();
+        let info = h.timer.get_info();
+        assert_eq!(info.remaining_seconds, 30 * 60);
+        assert_eq!(info.total_seconds, 30 * 60);
+        assert!(matches!(
+            h.timer.extend(5),
+            Err(AppError::ExtendLimitReached(_))
+        ));
+    }
+
+    #[test]
+    fn finished_work_auto_cycles_to_one_break() {
+        let h = Harness::new(&settings());
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.run(25 * 60);
+
+        assert_eq!(h.phase(), TimerPhase::Break);
+        assert_eq!(h.events.count("show-break-reminder"), 1);
+        assert_eq!(h.events.count("timer-finished"), 1);
+        let finished = h.store.finished();
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].session_type, SessionType::Work);
+        assert!(!finished[0].is_skipped);
+
+        h.run(5 * 60);
+        assert_eq!(h.phase(), TimerPhase::Work);
+        assert_eq!(h.causes(), ["user-start", "auto-cycle", "auto-cycle"]);
+    }
+
+    #[test]
+    fn segments_cycle_in_order_with_repeats() {
+        let h = Harness::new(&Settings {
+            segmented_work_enabled: true,
+            work_segments: vec![
+                WorkSegment {
+                    work_minutes: 10,
+                    break_minutes: 2,
+                    repeat: 2,
+                    label: Some("Warm-up".to_string()),
+                },
+                WorkSegment {
+                    work_minutes: 20,
+                    break_minutes: 4,
+                    repeat: 1,
+                    label: None,
+                },
+            ],
+            ..settings()
+        });
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+
+        let mut totals = Vec::new();
+        for _ in 0..6 {
+            let info = h.timer.get_info();
+            totals.push((info.phase.clone(), info.total_seconds / 60));
+            h.run(i64::from(info.total_seconds));
+        }
+        assert_eq!(
+            totals,
+            [
+                (TimerPhase::Work, 10),
+                (TimerPhase::Break, 2),
+                (TimerPhase::Work, 10),
+                (TimerPhase::Break, 2),
+                (TimerPhase::Work, 20),
+                (TimerPhase::Break, 4),
+            ]
+        );
+        // Back to the first segment
+        assert_eq!(h.timer.get_info().total_seconds, 10 * 60);
+        assert!(h
+            .store
+            .finished()
+            .iter()
+            .all(|session| session.end_reason == Some(SessionEndReason::Completed)));
+        // Each session carries its segment's name
+        let labels: Vec<Option<String>> = h
+            .store
+            .finished()
+            .into_iter()
+            .map(|session| session.segment_label)
+            .collect();
+        let warm_up = Some("Warm-up".to_string());
+        assert_eq!(
+            labels,
+            [
+                warm_up.clone(),
+                warm_up.clone(),
+                warm_up.clone(),
+                warm_up,
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn suppression_skips_breaks_until_it_expires() {
+        let h = Harness::new(&settings());
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.timer.suppress_breaks_for_minutes(30).unwrap();
+        assert_eq!(h.timer.get_info().interruptions_blocked_by, ["suppression"]);
+
+        h.run(25 * 60);
+        assert_eq!(h.phase(), TimerPhase::Work);
+        assert_eq!(h.causes().last().map(String::as_str), Some("suppression"));
+        assert_eq!(h.events.count("show-break-reminder"), 0);
+
+        // The window ended five minutes into this work block
+        h.run(25 * 60);
+        assert!(h.timer.get_info().interruptions_blocked_by.is_empty());
+        assert_eq!(h.phase(), TimerPhase::Break);
+        assert_eq!(h.events.count("show-break-reminder"), 1);
+    }
+
+    fn gate(blocked_by: &[InterruptionBlock]) -> ReminderGate {
+        ReminderGate {
+            blocked_by: blocked_by.to_vec(),
+        }
+    }
+
+    #[test]
+    fn gate_verdicts_for_each_combination_of_reasons() {
+        use InterruptionBlock::{Call, FlowMode, Suppression};
+
+        // (reasons, allows, skips breaks, only defers)
+        let cases: [(&[InterruptionBlock], bool, bool, bool); 6] = [
+            (&[], true, false, false),
+            (&[Suppression], false, true, false),
+            (&[FlowMode], false, true, false),
+            (&[Call], false, false, true),
+            (&[Suppression, Call], false, true, false),
+            (&[Suppression, FlowMode, Call], false, true, false),
+        ];
+        for (reasons, allows, skips, defers) in cases {
+            let gate = gate(reasons);
+            assert_eq!(gate.allows_interruptions(), allows, "{reasons:?}");
+            assert_eq!(gate.skips_breaks(), skips, "{reasons:?}");
+            assert_eq!(gate.only_defers(), defers, "{reasons:?}");
+        }
+        assert_eq!(
+            gate(&[Suppression, FlowMode, Call]).reasons(),
+            ["suppression", "flowMode", "call"]
+        );
+    }
+
+    #[test]
+    fn timer_info_lists_every_blocking_reason() {
+        let h = Harness::new(&Settings {
+            defer_breaks_during_calls: true,
+            ..settings()
+        });
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        assert!(h.timer.get_info().interruptions_blocked_by.is_empty());
+
+        h.timer.suppress_breaks_for_minutes(60).unwrap();
+        h.timer.update_flow_mode(true).unwrap();
+        h.timer.set_call_in_progress(true).unwrap();
+        assert_eq!(
+            h.timer.get_info().interruptions_blocked_by,
+            ["suppression", "flowMode", "call"]
+        );
+
+        // The call keeps blocking through its grace period
+        h.timer.set_call_in_progress(false).unwrap();
+        h.timer.update_flow_mode(false).unwrap();
+        assert_eq!(
+            h.timer.get_info().interruptions_blocked_by,
+            ["suppression", "call"]
+        );
+        h.run(CALL_GRACE_SECONDS);
+        assert_eq!(h.timer.get_info().interruptions_blocked_by, ["suppression"]);
+    }
+
+    #[test]
+    fn a_call_holds_the_break_until_its_grace_period_ends() {
+        let h = Harness::new(&Settings {
+            defer_breaks_during_calls: true,
+            ..settings()
+        });
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.run(20 * 60);
+        h.timer.set_call_in_progress(true).unwrap();
+
+        h.run(10 * 60);
+        assert_eq!(h.phase(), TimerPhase::Work);
+        assert_eq!(h.timer.get_info().remaining_seconds, 0);
+        assert_eq!(h.events.count("show-break-reminder"), 0);
+
+        h.timer.set_call_in_progress(false).unwrap();
+        h.run(CALL_GRACE_SECONDS - 1);
+        assert_eq!(h.phase(), TimerPhase::Work);
+        h.run(1);
+        assert_eq!(h.phase(), TimerPhase::Break);
+        assert_eq!(h.causes().last().map(String::as_str), Some("auto-cycle"));
+        assert_eq!(h.events.count("show-break-reminder"), 1);
+    }
+
+    #[test]
+    fn calls_do_not_block_when_deferring_is_off() {
+        let h = Harness::new(&settings());
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.timer.set_call_in_progress(true).unwrap();
+        assert!(h.timer.get_info().interruptions_blocked_by.is_empty());
+
+        h.run(25 * 60);
+        assert_eq!(h.phase(), TimerPhase::Break);
+    }
+
+    #[test]
+    fn flow_mode_skips_the_break() {
+        let h = Harness::new(&settings());
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.timer.update_flow_mode(true).unwrap();
+
+        h.run(25 * 60);
+        assert_eq!(h.phase(), TimerPhase::Work);
+        assert_eq!(h.causes().last().map(String::as_str), Some("flow-mode"));
+        assert_eq!(h.events.count("show-break-reminder"), 0);
+    }
+
+    #[test]
+    fn absence_covers_the_break_by_its_remainder_or_the_long_absence_limit() {
+        let minutes = ChronoDuration::minutes;
+        // (absence, remaining seconds, long absence minutes, covered)
+        let cases = [
+            (minutes(2), 240, None, false),
+            (minutes(4), 240, None, true),
+            (minutes(10), 240, None, true),
+            (ChronoDuration::seconds(239), 240, None, false),
+            (minutes(2), 240, Some(3), false),
+            (minutes(3), 240, Some(3), true),
+            (minutes(3), 3600, Some(3), true),
+            (minutes(0), 0, None, true),
+        ];
+        for (absence, remaining, long_absence, covered) in cases {
+            assert_eq!(
+                TimerService::absence_covers_break(absence, remaining, long_absence),
+                covered,
+                "{}s away, {remaining}s left, limit {long_absence:?}",
+                absence.num_seconds()
+            );
+        }
+    }
+
+    /// Work 25 minutes and one minute of the break, then turn the display off.
+    fn break_paused_by_display_off(settings: &Settings) -> Harness {
+        let h = Harness::new(settings);
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.run(25 * 60 + 60);
+        assert_eq!(h.phase(), TimerPhase::Break);
+        h.timer.handle_display_power_state(false).unwrap();
+        let info = h.timer.get_info();
+        assert_eq!(info.state, TimerState::Paused);
+        assert_eq!(info.remaining_seconds, 4 * 60);
+        h
+    }
+
+    #[test]
+    fn a_short_absence_resumes_the_paused_break() {
+        let h = break_paused_by_display_off(&settings());
+        h.clock.jump(2 * 60);
+        h.timer.handle_display_power_state(true).unwrap();
+
+        let info = h.timer.get_info();
+        assert_eq!(info.phase, TimerPhase::Break);
+        assert_eq!(info.state, TimerState::Running);
+        assert_eq!(info.remaining_seconds, 4 * 60);
+        assert_eq!(h.events.count("resumed-after-absence"), 0);
+        // Only the work session so far
+        assert_eq!(h.store.finished().len(), 1);
+    }
+
+    #[test]
+    fn an_absence_longer_than_the_break_completes_it() {
+        let h = break_paused_by_display_off(&settings());
+        h.clock.jump(4 * 60);
+        h.timer.handle_display_power_state(true).unwrap();
+
+        assert_eq!(h.phase(), TimerPhase::Work);
+        assert_eq!(
+            h.events.payloads("resumed-after-absence"),
+            vec![serde_json::json!(240)]
+        );
+        let finished = h.store.finished();
+        assert_eq!(finished.len(), 2);
+        assert_eq!(finished[1].session_type, SessionType::Break);
+        assert!(!finished[1].is_skipped);
+        assert_eq!(finished[1].end_reason, Some(SessionEndReason::Absence));
+        assert_eq!(h.causes().last().unwrap(), "resume-from-idle");
+    }
+
+    #[test]
+    fn the_long_absence_limit_completes_a_break_with_time_left() {
+        let h = break_paused_by_display_off(&Settings {
+            long_absence_minutes: Some(3),
+            ..settings()
+        });
+        h.clock.jump(3 * 60);
+        h.timer.handle_system_resume().unwrap();
+
+        assert_eq!(h.phase(), TimerPhase::Work);
+        assert_eq!(
+            h.events.payloads("resumed-after-absence"),
+            vec![serde_json::json!(180)]
+        );
+    }
+
+    #[test]
+    fn a_break_paused_by_the_user_stays_paused_after_a_power_event() {
+        let h = Harness::new(&settings());
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.run(25 * 60 + 60);
+        h.timer.pause().unwrap();
+        h.timer.handle_system_suspend().unwrap();
+        h.clock.jump(60 * 60);
+        h.timer.handle_system_resume().unwrap();
+
+        let info = h.timer.get_info();
+        assert_eq!(info.phase, TimerPhase::Break);
+        assert_eq!(info.state, TimerState::Paused);
+        assert_eq!(info.remaining_seconds, 4 * 60);
+        assert_eq!(h.events.count("resumed-after-absence"), 0);
+    }
+
+    #[test]
+    fn a_power_event_during_work_ends_the_session_and_wake_starts_fresh() {
+        let h = Harness::new(&settings());
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.run(10 * 60);
+        h.timer.handle_system_suspend().unwrap();
+        assert_eq!(h.phase(), TimerPhase::Idle);
+        let finished = h.store.finished();
+        assert_eq!(finished.len(), 1);
+        assert_eq!(
+            finished[0].notes.as_deref(),
+            Some(POWER_INTERRUPT_WORK_NOTE)
+        );
+        assert_eq!(
+            finished[0].end_reason,
+            Some(SessionEndReason::PowerInterrupt)
+        );
+
+        h.clock.jump(30 * 60);
+        h.timer.handle_system_resume().unwrap();
+        let info = h.timer.get_info();
+        assert_eq!(info.phase, TimerPhase::Work);
+        assert_eq!(info.remaining_seconds, 25 * 60);
+    }
+
+    #[test]
+    fn a_completed_real_break_resets_the_screen_time() {
+        let h = Harness::new(&Settings {
+            min_real_break_minutes: 5,
+            ..settings()
+        });
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.timer.sample_screen_time(Some(0));
+        h.work_at_computer(24);
+        assert_eq!(h.timer.get_info().continuous_screen_seconds, 24 * 60);
+        h.run(60);
+        assert_eq!(h.timer.get_info().continuous_screen_seconds, 24 * 60);
+
+        h.run(5 * 60);
+        assert_eq!(h.phase(), TimerPhase::Work);
+        assert_eq!(h.timer.get_info().continuous_screen_seconds, 0);
+    }
+
+    #[test]
+    fn a_skipped_break_resets_the_screen_time_only_when_long_enough() {
+        let h = Harness::new(&Settings {
+            min_real_break_minutes: 2,
+            ..settings()
+        });
+        h.timer.start_break(PhaseChangeCause::UserStart).unwrap();
+        h.timer.sample_screen_time(Some(0));
+        h.work_at_computer(1);
+        h.timer.skip(false, PhaseChangeCause::UserSkip).unwrap();
+        assert_eq!(h.timer.get_info().continuous_screen_seconds, 60);
+
+        h.timer.start_break(PhaseChangeCause::UserStart).unwrap();
+        h.run(120);
+        h.timer.skip(false, PhaseChangeCause::UserSkip).unwrap();
+        assert_eq!(h.timer.get_info().continuous_screen_seconds, 0);
+    }
+
+    #[test]
+    fn a_force_break_cannot_be_skipped_before_the_reminder_cap() {
+        let h = Harness::new(&Settings {
+            enable_force_break: true,
+            max_reminder_minutes: 3,
+            break_duration: 10,
+            ..settings()
+        });
+        h.timer.start_break(PhaseChangeCause::UserStart).unwrap();
+        h.run(60);
+        for cause in [PhaseChangeCause::UserSkip, PhaseChangeCause::ReminderClosed] {
+            assert!(matches!(
+                h.timer.skip(true, cause),
+                Err(AppError::ForceBreakLocked)
+            ));
+        }
+        // Turning flow mode on does not end it either
+        h.timer.update_flow_mode(true).unwrap();
+        assert_eq!(h.phase(), TimerPhase::Break);
+        h.timer.update_flow_mode(false).unwrap();
+
+        h.run(2 * 60);
+        assert!(!h.timer.is_break_dismissal_locked());
+        assert!(h
+            .timer
+            .skip(false, PhaseChangeCause::UserSkip)
+            .unwrap()
+            .is_some());
+        assert_eq!(h.phase(), TimerPhase::Work);
+    }
+
+    #[test]
+    fn a_poisoned_ticker_lock_does_not_break_the_health_check() {
+        let h = Harness::new(&settings());
+        let timer = h.timer.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = timer.ticker.lock().unwrap();
+            panic!("tick bookkeeping failed");
+        })
+        .join();
+        assert!(h.timer.ticker.is_poisoned());
+        assert_eq!(h.timer.ticker_health().panics, 0);
+    }
+
+    #[cfg(feature = "dev-tools")]
+    #[test]
+    fn advancing_past_the_phase_end_auto_cycles_exactly_once() {
+        let h = Harness::new(&settings());
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.run(60);
+
+        let session = h
+            .timer
+            .debug_advance(3 * 3600)
+            .unwrap()
+            .expect("work finished");
+        assert_eq!(session.session_type, SessionType::Work);
+        assert_eq!(session.duration, 3 * 3600 + 60);
+        assert_eq!(h.phase(), TimerPhase::Break);
+        assert_eq!(h.causes(), ["user-start", "auto-cycle"]);
+        assert_eq!(h.events.count("show-break-reminder"), 1);
+        assert_eq!(h.store.finished().len(), 1);
+        // The break starts whole; the overshoot is not carried into it
+        assert_eq!(h.timer.get_info().remaining_seconds, 5 * 60);
+
+        h.run(1);
+        assert_eq!(h.phase(), TimerPhase::Break);
+        assert_eq!(h.causes().len(), 2);
+    }
+
+    #[cfg(feature = "dev-tools")]
+    #[test]
+    fn advancing_moves_the_suppression_window_along() {
+        let h = Harness::new(&settings());
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.timer.suppress_breaks_for_minutes(30).unwrap();
+
+        h.timer.debug_advance(25 * 60).unwrap();
+        assert_eq!(h.phase(), TimerPhase::Work);
+        assert_eq!(h.causes().last().map(String::as_str), Some("suppression"));
+
+        // 25 more minutes end the window, then the work block
+        h.timer.debug_advance(25 * 60).unwrap();
+        assert_eq!(h.phase(), TimerPhase::Break);
+        assert!(h.timer.get_info().interruptions_blocked_by.is_empty());
+        assert_eq!(h.events.count("show-break-reminder"), 1);
+    }
+
+    #[cfg(feature = "dev-tools")]
+    #[test]
+    fn advancing_follows_the_segments() {
+        let h = Harness::new(&Settings {
+            segmented_work_enabled: true,
+            work_segments: vec![
+                WorkSegment {
+                    work_minutes: 10,
+                    break_minutes: 2,
+                    repeat: 1,
+                    label: None,
+                },
+                WorkSegment {
+                    work_minutes: 20,
+                    break_minutes: 4,
+                    repeat: 1,
+                    label: None,
+                },
+            ],
+            ..settings()
+        });
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+
+        let mut totals = Vec::new();
+        for _ in 0..4 {
+            h.timer.debug_advance(3600).unwrap();
+            let info = h.timer.get_info();
+            totals.push((info.phase, info.total_seconds / 60));
+        }
+        assert_eq!(
+            totals,
+            [
+                (TimerPhase::Break, 2),
+                (TimerPhase::Work, 20),
+                (TimerPhase::Break, 4),
+                (TimerPhase::Work, 10),
+            ]
+        );
+    }
+
+    #[cfg(feature = "dev-tools")]
+    #[test]
+    fn advancing_needs_a_running_timer() {
+        let h = Harness::new(&settings());
+        assert!(h.timer.debug_advance(60).is_err());
+
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.timer.pause().unwrap();
+        assert!(h.timer.debug_advance(60).is_err());
+        assert_eq!(h.timer.get_info().remaining_seconds, 25 * 60);
+    }
+
+    fn anchored(
+        start: &str,
+        end: &str,
+        work_minutes: u32,
+        break_minutes: u32,
+    ) -> TimeAnchoredSegment {
+        TimeAnchoredSegment {
+            start: start.to_string(),
+            end: end.to_string(),
+            work_minutes,
+            break_minutes,
+        }
+    }
+
+    /// `hour:minute` local time on a day without daylight saving changes.
+    fn local_at(hour: u32, minute: u32) -> chrono::DateTime<Utc> {
+        Local
+            .with_ymd_and_hms(2026, 1, 15, hour, minute, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn anchored_ranges_include_the_start_and_exclude_the_end() {
+        let ranges = TimeAnchoredRange::from_settings(&[
+            anchored("09:00", "12:00", 50, 10),
+            anchored("22:00", "02:00", 25, 5),
+            anchored("18:00", "00:00", 30, 5),
+            anchored("00:00", "06:00", 45, 15),
+        ]);
+        // (range, hour, minute, contained)
+        let cases = [
+            (0, 8, 59, false),
+            (0, 9, 0, true),
+            (0, 11, 59, true),
+            (0, 12, 0, false),
+            // Across midnight
+            (1, 21, 59, false),
+            (1, 22, 0, true),
+            (1, 23, 59, true),
+            (1, 0, 0, true),
+            (1, 1, 59, true),
+            (1, 2, 0, false),
+            // Ending at midnight
+            (2, 17, 59, false),
+            (2, 18, 0, true),
+            (2, 23, 59, true),
+            (2, 0, 0, false),
+            // Starting at midnight
+            (3, 23, 59, false),
+            (3, 0, 0, true),
+            (3, 5, 59, true),
+            (3, 6, 0, false),
+        ];
+        for (index, hour, minute, contained) in cases {
+            assert_eq!(
+                ranges[index].contains(local_at(hour, minute)),
+                contained,
+                "range {index} at {hour:02}:{minute:02}"
+            );
+        }
+    }
+
+    #[test]
+    fn malformed_and_empty_anchored_ranges_are_dropped() {
+        let ranges = TimeAnchoredRange::from_settings(&[
+            anchored("25:00", "26:00", 25, 5),
+            anchored("9am", "11am", 25, 5),
+            anchored("10:00", "10:00", 25, 5),
+            anchored(" 07:30 ", "08:15", 0, 500),
+        ]);
+
+        assert_eq!(ranges.len(), 1);
+        let range = &ranges[0];
+        assert_eq!(range.start, NaiveTime::from_hms_opt(7, 30, 0).unwrap());
+        assert_eq!((range.work_minutes, range.break_minutes), (1, 120));
+    }
+
+    #[test]
+    fn a_phase_starting_on_a_range_boundary_takes_the_new_durations() {
+        let start = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+        let hhmm = |offset_minutes: i64| {
+            (start + ChronoDuration::minutes(offset_minutes))
+                .with_timezone(&Local)
+                .format("%H:%M")
+                .to_string()
+        };
+        let h = Harness::new(&Settings {
+            time_anchored_segments: vec![
+                anchored(&hhmm(-60), &hhmm(25), 25, 5),
+                anchored(&hhmm(25), &hhmm(180), 50, 10),
+            ],
+            ..settings()
+        });
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        assert_eq!(h.timer.get_info().total_seconds, 25 * 60);
+
+        // The break starts exactly where the second range begins
+        h.run(25 * 60);
+        assert_eq!(h.phase(), TimerPhase::Break);
+        assert_eq!(h.timer.get_info().total_seconds, 10 * 60);
+
+        h.run(10 * 60);
+        assert_eq!(h.phase(), TimerPhase::Work);
+        assert_eq!(h.timer.get_info().total_seconds, 50 * 60);
+    }
+
+    #[test]
+    fn no_auto_cycle_while_stopped() {
+        let h = Harness::new(&settings());
+        h.run(60 * 60);
+        assert_eq!(h.phase(), TimerPhase::Idle);
+        assert!(h.store.sessions().is_empty());
+        assert_eq!(h.events.count("phase-change"), 0);
+    }
+
+    #[test]
+    fn a_backward_clock_jump_keeps_the_remaining_time() {
+        let h = Harness::new(&settings());
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.run(60);
+        h.clock.jump(-3600);
+        h.run(1);
+        assert_eq!(h.phase(), TimerPhase::Work);
+        assert_eq!(h.timer.get_info().remaining_seconds, 24 * 60 - 1);
+        assert_eq!(h.events.payloads("clock-changed")[0]["driftSeconds"], -3600);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn a_forward_clock_jump_keeps_the_remaining_time() {
+        let h = Harness::new(&settings());
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.run(60);
+        h.clock.jump(3600);
+        h.run(1);
+        assert_eq!(h.timer.get_info().remaining_seconds, 24 * 60 - 1);
+        assert_eq!(h.events.payloads("clock-changed")[0]["driftSeconds"], 3600);
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn a_sleep_the_power_monitor_missed_still_counts() {
+        let h = Harness::new(&settings());
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        h.run(60);
+        // Wall-clock time moves on, the monotonic clock stood still
+        h.clock.jump(10 * 60);
+        h.run(1);
+        assert_eq!(h.phase(), TimerPhase::Work);
+        assert_eq!(h.timer.get_info().remaining_seconds, 14 * 60 - 1);
+        assert_eq!(h.events.count("clock-changed"), 0);
+    }
+
+    #[test]
+    fn hydration_reminders_follow_the_cached_settings_and_count() {
+        let mut hydrating = settings();
+        hydrating.hydration = HydrationSettings {
+            enabled: true,
+            daily_goal_glasses: 2,
+            reminder_interval_minutes: 2,
+        };
+        let h = Harness::new(&hydrating);
+        let today = h.clock.now().with_timezone(&Local).date_naive();
+        assert!(h.timer.hydration_count_needed(today));
+        h.timer.record_hydration(&HydrationProgress {
+            date: today,
+            count: 1,
+            goal: 2,
+        });
+        assert!(!h.timer.hydration_count_needed(today));
+
+        // Only running work counts
+        assert_eq!(h.timer.hydration_minute(today), None);
+        h.timer.start_work(PhaseChangeCause::UserStart).unwrap();
+        assert_eq!(h.timer.hydration_minute(today), None);
+        let reminder = h.timer.hydration_minute(today).unwrap();
+        assert_eq!((reminder.count, reminder.goal), (1, 2));
+
+        // Logging a glass starts the wait over; the goal silences it
+        assert_eq!(h.timer.hydration_minute(today), None);
+        h.timer.record_hydration(&HydrationProgress {
+            date: today,
+            count: 2,
+            goal: 2,
+        });
+        assert_eq!(h.timer.hydration_minute(today), None);
+        assert_eq!(h.timer.hydration_minute(today), None);
+
+        // A new day needs the count read again
+        let tomorrow = today.succ_opt().unwrap();
+        assert!(h.timer.hydration_count_needed(tomorrow));
+        assert_eq!(h.timer.hydration_minute(tomorrow), None);
+
+        hydrating.hydration.enabled = false;
+        h.timer.apply_settings(&hydrating).unwrap();
+        assert!(!h.timer.hydration_count_needed(tomorrow));
+    }
+}
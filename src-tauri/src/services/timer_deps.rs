@@ -0,0 +1,214 @@
+use crate::models::Session;
+use crate::services::DatabaseService;
+use crate::utils::{AppError, AppResult};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+
+/// Destination for timer events (the Tauri app handle in production).
+/// 计时器事件的发送目标，生产环境中为 AppHandle。
+pub trait EventSink: Send + Sync {
+    fn emit_event(&self, event: &str, payload: serde_json::Value) -> AppResult<()>;
+}
+
+impl EventSink for AppHandle {
+    fn emit_event(&self, event: &str, payload: serde_json::Value) -> AppResult<()> {
+        self.emit(event, payload)
+            .map_err(|e| AppError::TauriError(e.to_string()))
+    }
+}
+
+/// Persistence for session records produced by the timer.
+/// 计时器产生的会话记录的持久化接口。
+pub trait SessionStore: Send + Sync {
+    /// Save or update a session; implementations may complete asynchronously.
+    /// 保存或更新会话，实现可以异步完成。
+    fn persist_session(&self, session: Session);
+
+    /// Quietly update a running session's duration; no events are emitted.
+    /// 静默更新进行中会话的时长（崩溃后保留进度），不发送事件。
+    fn checkpoint_session(&self, session: Session);
+
+    /// Drop a session record (e.g. the placeholder of a too-short session).
+    /// 删除会话记录，例如过短会话在开始时写入的占位记录。
+    fn discard_session(&self, id: &str);
+}
+
+impl SessionStore for Arc<tokio::sync::Mutex<DatabaseService>> {
+    fn persist_session(&self, session: Session) {
+        let db = self.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Ok(guard) = db.try_lock() {
+                let _ = guard.save_or_update_session(&session).await;
+            } else {
+                let db2 = db.lock().await;
+                let _ = db2.save_or_update_session(&session).await;
+            }
+        });
+    }
+
+    fn checkpoint_session(&self, session: Session) {
+        let db = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = db.lock().await.checkpoint_session(&session).await;
+        });
+    }
+
+    fn discard_session(&self, id: &str) {
+        let db = self.clone();
+        let id = id.to_string();
+        tauri::async_runtime::spawn(async move {
+            let _ = db.lock().await.delete_session(&id).await;
+        });
+    }
+}
+
+/// Source of the current time, replaceable for deterministic timer runs.
+/// 当前时间来源，可替换以便确定性地驱动计时器。
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Monotonic time, compared with `now` to notice system clock changes.
+    fn monotonic(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Wall clock backed by `Utc::now`.
+/// 基于系统时间的时钟。
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// In-memory stand-ins for the timer dependencies, for driving the timer in tests.
+/// 测试用的内存事件接收器、会话存储与可控时钟。
+#[cfg(test)]
+pub(crate) mod fakes {
+    use super::{Clock, EventSink, SessionStore};
+    use crate::models::Session;
+    use crate::utils::AppResult;
+    use chrono::{DateTime, Duration, Utc};
+    use std::sync::Mutex;
+    use std::time::Instant;
+
+    /// Records every emitted event in order.
+    #[derive(Default)]
+    pub struct RecordingEvents {
+        events: Mutex<Vec<(String, serde_json::Value)>>,
+    }
+
+    impl RecordingEvents {
+        /// Payloads of the events named `event`, oldest first.
+        pub fn payloads(&self, event: &str) -> Vec<serde_json::Value> {
+            self.events
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(name, _)| name == event)
+                .map(|(_, payload)| payload.clone())
+                .collect()
+        }
+
+        pub fn count(&self, event: &str) -> usize {
+            self.payloads(event).len()
+        }
+    }
+
+    impl EventSink for RecordingEvents {
+        fn emit_event(&self, event: &str, payload: serde_json::Value) -> AppResult<()> {
+            self.events
+                .lock()
+                .unwrap()
+                .push((event.to_string(), payload));
+            Ok(())
+        }
+    }
+
+    /// Keeps sessions in memory with the same upsert/checkpoint/discard rules
+    /// as the database.
+    #[derive(Default)]
+    pub struct MemoryStore {
+        sessions: Mutex<Vec<Session>>,
+    }
+
+    impl MemoryStore {
+        pub fn sessions(&self) -> Vec<Session> {
+            self.sessions.lock().unwrap().clone()
+        }
+
+        /// Sessions no longer running, oldest first.
+        pub fn finished(&self) -> Vec<Session> {
+            self.sessions()
+                .into_iter()
+                .filter(|session| !session.in_progress)
+                .collect()
+        }
+    }
+
+    impl SessionStore for MemoryStore {
+        fn persist_session(&self, session: Session) {
+            let mut sessions = self.sessions.lock().unwrap();
+            match sessions.iter_mut().find(|s| s.id == session.id) {
+                Some(existing) => *existing = session,
+                None => sessions.push(session),
+            }
+        }
+
+        fn checkpoint_session(&self, session: Session) {
+            let mut sessions = self.sessions.lock().unwrap();
+            if let Some(existing) = sessions
+                .iter_mut()
+                .find(|s| s.id == session.id && s.in_progress)
+            {
+                *existing = session;
+            }
+        }
+
+        fn discard_session(&self, id: &str) {
+            self.sessions.lock().unwrap().retain(|s| s.id != id);
+        }
+    }
+
+    /// A clock that only moves when told to. `advance` moves wall and monotonic
+    /// time together; `jump` changes the wall clock alone, like an NTP correction.
+    pub struct FakeClock {
+        inner: Mutex<(DateTime<Utc>, Instant)>,
+    }
+
+    impl FakeClock {
+        pub fn at(now: DateTime<Utc>) -> Self {
+            Self {
+                inner: Mutex::new((now, Instant::now())),
+            }
+        }
+
+        pub fn advance(&self, seconds: i64) {
+            self.advance_millis(seconds * 1000);
+        }
+
+        pub fn advance_millis(&self, millis: i64) {
+            let mut inner = self.inner.lock().unwrap();
+            inner.0 += Duration::milliseconds(millis);
+            inner.1 += std::time::Duration::from_millis(millis.max(0) as u64);
+        }
+
+        pub fn jump(&self, seconds: i64) {
+            self.inner.lock().unwrap().0 += Duration::seconds(seconds);
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.inner.lock().unwrap().0
+        }
+
+        fn monotonic(&self) -> Instant {
+            self.inner.lock().unwrap().1
+        }
+    }
+}
@@ -1,17 +1,18 @@
-use crate::services::{DatabaseService, TimerService};
-use anyhow::{anyhow, Context, Result};
-use serde::Serialize;
-use std::sync::Arc;
-use tauri::AppHandle;
-use tauri_plugin_updater::UpdaterExt;
 #[cfg(target_os = "windows")]
-use crate::models::TimerPhase;
+use crate::models::{Settings, TimerPhase};
 #[cfg(target_os = "windows")]
-use std::sync::OnceLock;
-#[cfg(target_os = "windows")]
-use tauri::Manager;
+use crate::services::network_policy;
+use crate::services::{connectivity, DatabaseService, TimerService};
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_updater::{Update, UpdaterExt};
 
 const RELEASES_PAGE_URL: &str = "https://github.com/youtonghy/RESTY/releases";
+/// Directory under the app cache where downloaded updates wait to be installed.
+const UPDATE_STAGING_DIR: &str = "updates";
 #[cfg(target_os = "windows")]
 const AUTO_UPDATE_POLL_INTERVAL_SECS: u64 = 30 * 60;
 #[cfg(target_os = "windows")]
@@ -25,15 +26,77 @@ pub struct UpdateManifest {
     pub notes: Option<String>,
 }
 
+/// Payload of `update-download-progress`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateDownloadProgress {
+    pub version: String,
+    /// Bytes received so far
+    pub downloaded: u64,
+    /// Size of the download, when the server reports it
+    pub total: Option<u64>,
+}
+
+/// A downloaded and verified update waiting on disk; payload of
+/// `update-ready-to-install`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StagedUpdate {
+    pub version: String,
+    pub notes: Option<String>,
+    pub path: PathBuf,
+}
+
+/// The staged update; the plugin's `Update` is needed again to install it.
+struct PendingInstall {
+    update: Update,
+    staged: StagedUpdate,
+}
+
+fn pending_install() -> &'static Mutex<Option<PendingInstall>> {
+    static PENDING_INSTALL: OnceLock<Mutex<Option<PendingInstall>>> = OnceLock::new();
+    PENDING_INSTALL.get_or_init(|| Mutex::new(None))
+}
+
+/// The update already staged for `version`, if its file is still there.
+fn staged_update(version: &str) -> Option<StagedUpdate> {
+    let pending = pending_install().lock().ok()?;
+    pending
+        .as_ref()
+        .map(|pending| &pending.staged)
+        .filter(|staged| staged.version == version && staged.path.is_file())
+        .cloned()
+}
+
+/// Version of the running app, from the Tauri package info.
+/// Shared by `get_app_info` and the update checks so both compare the same value.
+pub fn current_version(app: &AppHandle) -> String {
+    app.package_info().version.to_string()
+}
+
 /// Returns true if the running build is a development build (e.g. version "0.0.0-dev").
 /// Development builds only check for updates but never auto-download or install them.
 pub fn is_dev_build(app: &AppHandle) -> bool {
-    let version = app.package_info().version.to_string();
+    let version = current_version(app);
     version.starts_with("0.0.0") || version.contains("-dev")
 }
 
+/// Portable builds ship a `portable` marker file next to the executable.
+pub fn is_portable_build() -> bool {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join("portable").is_file()))
+        .unwrap_or(false)
+}
+
 pub async fn check_for_updates(app: &AppHandle) -> Result<Option<UpdateManifest>> {
-    let Some(update) = app.updater().context("Failed to create updater")?.check().await? else {
+    connectivity::ensure_online()?;
+    let Some(update) = app
+        .updater()
+        .context("Failed to create updater")?
+        .check()
+        .await?
+    else {
         return Ok(None);
     };
 
@@ -44,32 +107,113 @@ pub async fn check_for_updates(app: &AppHandle) -> Result<Option<UpdateManifest>
     }))
 }
 
+/// Download the latest update and install it right away (the update banner).
 pub async fn install_update(app: &AppHandle) -> Result<()> {
+    let staged = download_update(app).await?;
+    install_downloaded_update(app, &staged.path).await
+}
+
+/// Download and verify the latest update without installing it, emitting
+/// `update-download-progress`. An update already staged for the same version
+/// is reused.
+/// 下载并校验最新更新但不安装，返回暂存路径。
+pub async fn download_update(app: &AppHandle) -> Result<StagedUpdate> {
     if is_dev_build(app) {
         return Err(anyhow!(
             "Development builds do not install updates; please use an official release."
         ));
     }
+    connectivity::ensure_online()?;
 
-    let Some(update) = app.updater().context("Failed to create updater")?.check().await? else {
+    let Some(update) = app
+        .updater()
+        .context("Failed to create updater")?
+        .check()
+        .await?
+    else {
         return Err(anyhow!("No update available"));
     };
+    stage_update(app, update).await
+}
+
+async fn stage_update(app: &AppHandle, update: Update) -> Result<StagedUpdate> {
+    let version = update.version.to_string();
+    if let Some(staged) = staged_update(&version) {
+        return Ok(staged);
+    }
 
-    let target_version = update.version.to_string();
-    update
-        .download_and_install(
+    // The plugin checks the signature before handing the bytes over
+    let mut downloaded = 0u64;
+    let bytes = update
+        .download(
             |chunk_length, content_length| {
-                eprintln!(
-                    "[Updater] Downloaded {} bytes of {:?} for {}",
-                    chunk_length, content_length, target_version
+                downloaded += chunk_length as u64;
+                let _ = app.emit(
+                    "update-download-progress",
+                    UpdateDownloadProgress {
+                        version: version.clone(),
+                        downloaded,
+                        total: content_length,
+                    },
                 );
             },
             || {
-                eprintln!("[Updater] Finished downloading {}", target_version);
+                eprintln!("[Updater] Finished downloading {}", version);
             },
         )
         .await
-        .with_context(|| format!("Failed to install update {}", target_version))?;
+        .with_context(|| format!("Failed to download update {}", version))?;
+
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .context("Failed to resolve the cache directory")?
+        .join(UPDATE_STAGING_DIR);
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(format!("RESTY-{}.update", version));
+    std::fs::write(&path, &bytes).with_context(|| format!("Failed to stage update {}", version))?;
+
+    let staged = StagedUpdate {
+        version,
+        notes: normalize_notes(update.body.clone()),
+        path,
+    };
+    if let Ok(mut pending) = pending_install().lock() {
+        *pending = Some(PendingInstall {
+            update,
+            staged: staged.clone(),
+        });
+    }
+    Ok(staged)
+}
+
+/// Install the update staged at `path` by `download_update`. The running
+/// session is finished and saved first (see `prepare_for_exit`), then the
+/// installer takes over and the app restarts.
+/// 安装已下载的更新：先结束并保存当前会话，再启动安装程序。
+pub async fn install_downloaded_update(app: &AppHandle, path: &Path) -> Result<()> {
+    let pending = {
+        let mut pending = pending_install()
+            .lock()
+            .map_err(|_| anyhow!("Update state is unavailable"))?;
+        match pending.take() {
+            Some(install) if install.staged.path == path => install,
+            other => {
+                *pending = other;
+                return Err(anyhow!("{} is not a downloaded update", path.display()));
+            }
+        }
+    };
+    let version = pending.staged.version.clone();
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read staged update {}", version))?;
+
+    crate::prepare_for_exit(app).await;
+    pending
+        .update
+        .install(bytes)
+        .with_context(|| format!("Failed to install update {}", version))?;
+    let _ = std::fs::remove_file(path);
 
     app.restart();
 }
@@ -120,66 +264,77 @@ async fn try_auto_update_once(
         Err(_) => return Ok(()),
     };
 
-    if !is_auto_update_enabled(database_service).await? {
+    let settings = load_settings(database_service).await?;
+    // Try again at the next poll rather than waiting out the request timeout
+    if !settings.auto_silent_update_enabled || connectivity::is_known_offline() {
+        return Ok(());
+    }
+    // Metered connection or quiet hours: check again at a later poll
+    if let Err(deferred) = network_policy::ensure_allowed() {
+        eprintln!("[AutoUpdate] Update check postponed: {}", deferred);
         return Ok(());
     }
 
     if is_dev_build(app) {
         // Dev builds only check availability; they never download or install.
-        if let Some(update) = app.updater().context("Failed to create updater")?.check().await? {
+        if let Some(update) = app
+            .updater()
+            .context("Failed to create updater")?
+            .check()
+            .await?
+        {
             eprintln!(
                 "[AutoUpdate] Dev build {} detected update {}, skipping download.",
-                app.package_info().version,
+                current_version(app),
                 update.version
             );
         }
         return Ok(());
     }
 
-    let Some(update) = app.updater().context("Failed to create updater")?.check().await? else {
+    let Some(update) = app
+        .updater()
+        .context("Failed to create updater")?
+        .check()
+        .await?
+    else {
         return Ok(());
     };
 
     eprintln!(
         "[AutoUpdate] Found update {} -> {}",
-        app.package_info().version,
+        current_version(app),
         update.version
     );
 
-    if !wait_for_install_slot(app, timer_service, database_service).await? {
+    let already_staged = staged_update(&update.version).is_some();
+    let staged = stage_update(app, update).await?;
+
+    // Re-read: the download may have taken a while
+    let settings = load_settings(database_service).await?;
+    if !settings.auto_install_updates {
+        // Leave installing to the user; announce each staged version once
+        if !already_staged {
+            let _ = app.emit("update-ready-to-install", &staged);
+        }
         return Ok(());
     }
 
-    let target_version = update.version.to_string();
-    update
-        .download_and_install(
-            |chunk_length, content_length| {
-                eprintln!(
-                    "[AutoUpdate] Downloaded {} bytes of {:?} for {}",
-                    chunk_length, content_length, target_version
-                );
-            },
-            || {
-                eprintln!("[AutoUpdate] Finished downloading {}", target_version);
-            },
-        )
-        .await
-        .with_context(|| format!("Failed to install update {}", target_version))?;
+    if !wait_for_install_slot(app, timer_service, database_service).await? {
+        return Ok(());
+    }
 
-    app.restart();
+    install_downloaded_update(app, &staged.path).await
 }
 
 #[cfg(target_os = "windows")]
-async fn is_auto_update_enabled(
+async fn load_settings(
     database_service: &Arc<tokio::sync::Mutex<DatabaseService>>,
-) -> Result<bool> {
-    let settings = {
-        let db = database_service.lock().await;
-        db.load_settings()
-            .await
-            .map_err(|e| anyhow!("Failed to load settings: {}", e))?
-    };
-    Ok(settings.auto_silent_update_enabled)
+) -> Result<Settings> {
+    let db = database_service.lock().await;
+    db.load_settings()
+        .await
+        .map_err(|e| anyhow!("Failed to load settings: {}", e))
 }
 
 #[cfg(target_os = "windows")]
@@ -189,7 +344,8 @@ async fn wait_for_install_slot(
     database_service: &Arc<tokio::sync::Mutex<DatabaseService>>,
 ) -> Result<bool> {
     loop {
-        if !is_auto_update_enabled(database_service).await? {
+        let settings = load_settings(database_service).await?;
+        if !settings.auto_silent_update_enabled {
             return Ok(false);
         }
 
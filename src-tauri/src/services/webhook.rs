@@ -0,0 +1,56 @@
+use super::remote;
+use crate::models::{PhaseChangeCause, TimerPhase};
+use crate::utils::{AppError, AppResult};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::Duration;
+
+/// A webhook that takes longer than this is abandoned.
+const WEBHOOK_TIMEOUT_SECONDS: u64 = 5;
+
+/// JSON body POSTed to `webhook_url` on each phase transition.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPayload {
+    /// Phase that just started
+    pub phase: TimerPhase,
+    pub cause: PhaseChangeCause,
+    pub timestamp: DateTime<Utc>,
+    /// Planned length of the phase that just started (seconds)
+    pub planned_seconds: u32,
+    /// Wall-clock length of the phase that just ended (seconds), unknown for the first
+    pub actual_seconds: Option<i64>,
+}
+
+impl WebhookPayload {
+    /// Example transition sent by the `test_webhook` command.
+    pub fn sample() -> Self {
+        Self {
+            phase: TimerPhase::Break,
+            cause: PhaseChangeCause::AutoCycle,
+            timestamp: Utc::now(),
+            planned_seconds: 5 * 60,
+            actual_seconds: Some(25 * 60),
+        }
+    }
+}
+
+/// POST `payload` to `url` with the shared client and return the HTTP status.
+/// 使用共享 HTTP 客户端发送阶段切换通知，超时较短，返回 HTTP 状态码。
+pub async fn post(url: &str, payload: &WebhookPayload) -> AppResult<u16> {
+    let response = remote::http_client()
+        .post(url.trim())
+        .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECONDS))
+        .json(payload)
+        .send()
+        .await
+        .map_err(|e| AppError::WebhookFailed(e.to_string()))?;
+    Ok(response.status().as_u16())
+}
+
+/// Whether `url` is an absolute http(s) URL.
+pub fn is_valid_url(url: &str) -> bool {
+    reqwest::Url::parse(url.trim())
+        .map(|url| matches!(url.scheme(), "http" | "https"))
+        .unwrap_or(false)
+}
@@ -0,0 +1,241 @@
+use crate::models::{
+    AchievementUnlock, AppInfo, HydrationLog, LifetimeStats, ScreenContinuity, Session, Settings,
+};
+use crate::utils::{AppError, AppResult};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Value of the top-level `format` key.
+pub const FORMAT_ID: &str = "resty-data-export";
+/// Version of the file layout (top-level keys and section wrappers).
+pub const FORMAT_VERSION: u32 = 1;
+/// Versions of the data inside each section, raised when its shape changes.
+const APP_INFO_SCHEMA_VERSION: u32 = 1;
+const SETTINGS_SCHEMA_VERSION: u32 = 1;
+const SESSIONS_SCHEMA_VERSION: u32 = 1;
+const ACHIEVEMENTS_SCHEMA_VERSION: u32 = 1;
+const LIFETIME_STATS_SCHEMA_VERSION: u32 = 1;
+const DAY_STARTS_SCHEMA_VERSION: u32 = 1;
+const SCREEN_TIME_SCHEMA_VERSION: u32 = 1;
+const HYDRATION_SCHEMA_VERSION: u32 = 1;
+/// Progress is reported after every this many sessions.
+pub const PROGRESS_CHUNK: usize = 500;
+/// Sessions serialized to estimate the size of the rest.
+const ESTIMATE_SAMPLE: usize = 200;
+/// Replaces secrets in the exported settings.
+const REDACTED: &str = "[redacted]";
+
+/// Everything RESTY stores locally, gathered for `export_all_data`.
+pub struct DataExport {
+    pub app_id: &'static str,
+    pub app_info: AppInfo,
+    pub settings: Settings,
+    pub sessions: Vec<Session>,
+    pub achievements: Vec<AchievementUnlock>,
+    /// None while the lifetime rollup is turned off
+    pub lifetime_stats: Option<LifetimeStats>,
+    pub day_starts: Vec<DateTime<Utc>>,
+    pub screen_continuity: ScreenContinuity,
+    pub hydration: HydrationLog,
+}
+
+/// Settings with secrets replaced: the webhook URL (it usually carries a token)
+/// and the query strings of quote provider URLs (API keys).
+/// 导出用的设置：隐去 webhook 地址与名言来源地址中的查询参数。
+pub fn redacted_settings(settings: &Settings) -> AppResult<Value> {
+    let mut settings = settings.clone();
+    if settings
+        .webhook_url
+        .as_deref()
+        .is_some_and(|url| !url.trim().is_empty())
+    {
+        settings.webhook_url = Some(REDACTED.to_string());
+    }
+    for provider in &mut settings.quote_providers {
+        if let Some(index) = provider.url.find('?') {
+            provider.url = format!("{}?{}", &provider.url[..index], REDACTED);
+        }
+    }
+    serde_json::to_value(&settings).map_err(|e| AppError::ExportFailed(e.to_string()))
+}
+
+/// Rough size of the export in bytes: the small sections in full plus the
+/// average of the first sessions times the session count.
+pub fn estimate_bytes(export: &DataExport) -> AppResult<u64> {
+    let mut counter = CountingWriter::new(std::io::sink());
+    write_head(&mut counter, export)?;
+    let sample = &export.sessions[..export.sessions.len().min(ESTIMATE_SAMPLE)];
+    let mut sample_bytes = CountingWriter::new(std::io::sink());
+    for session in sample {
+        serde_json::to_writer(&mut sample_bytes, session)
+            .map_err(|e| AppError::ExportFailed(e.to_string()))?;
+    }
+    let per_session = if sample.is_empty() {
+        0
+    } else {
+        // +1 for the separating comma
+        sample_bytes.written / sample.len() as u64 + 1
+    };
+    Ok(counter.written + per_session * export.sessions.len() as u64)
+}
+
+/// Write `export` to `path` as one JSON object. Every section is wrapped as
+/// `{"schemaVersion": n, "data": ...}`; sessions are written one at a time
+/// so a long history is never held as one big string, and `on_progress` gets
+/// the number of sessions written after every `PROGRESS_CHUNK`. The file is
+/// written next to `path` and renamed into place at the end, so a failed
+/// export leaves no partial file. Returns the size in bytes.
+/// 将全部数据流式写入单个 JSON 文件（各部分带 schemaVersion），完成后再替换目标文件。
+pub fn write_export(
+    export: &DataExport,
+    path: &Path,
+    mut on_progress: impl FnMut(usize, u64),
+) -> AppResult<u64> {
+    let partial = partial_path(path);
+    match write_file(export, &partial, &mut on_progress) {
+        Ok(bytes) => {
+            std::fs::rename(&partial, path).map_err(|e| AppError::ExportFailed(e.to_string()))?;
+            Ok(bytes)
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&partial);
+            Err(e)
+        }
+    }
+}
+
+fn write_file(
+    export: &DataExport,
+    path: &Path,
+    on_progress: &mut impl FnMut(usize, u64),
+) -> AppResult<u64> {
+    let file = File::create(path).map_err(|e| AppError::ExportFailed(e.to_string()))?;
+    let mut out = CountingWriter::new(BufWriter::new(file));
+    write_head(&mut out, export)?;
+
+    let total = export.sessions.len();
+    write_raw(&mut out, ",\"sessions\":")?;
+    write_raw(
+        &mut out,
+        &format!(
+            "{{\"schemaVersion\":{},\"count\":{},\"data\":[",
+            SESSIONS_SCHEMA_VERSION, total
+        ),
+    )?;
+    for (index, session) in export.sessions.iter().enumerate() {
+        if index > 0 {
+            write_raw(&mut out, ",")?;
+        }
+        serde_json::to_writer(&mut out, session)
+            .map_err(|e| AppError::ExportFailed(e.to_string()))?;
+        if (index + 1) % PROGRESS_CHUNK == 0 {
+            on_progress(index + 1, out.written);
+        }
+    }
+    write_raw(&mut out, "]}}")?;
+    out.flush()
+        .map_err(|e| AppError::ExportFailed(e.to_string()))?;
+    on_progress(total, out.written);
+    Ok(out.written)
+}
+
+/// Opening brace, format keys and every section except the sessions.
+fn write_head(out: &mut impl Write, export: &DataExport) -> AppResult<()> {
+    write_raw(out, "{")?;
+    write_entry(out, "app", &export.app_id)?;
+    write_raw(out, ",")?;
+    write_entry(out, "format", &FORMAT_ID)?;
+    write_raw(out, ",")?;
+    write_entry(out, "formatVersion", &FORMAT_VERSION)?;
+    write_raw(out, ",")?;
+    write_entry(out, "exportedAt", &Utc::now())?;
+    write_section(out, "appInfo", APP_INFO_SCHEMA_VERSION, &export.app_info)?;
+    let settings = redacted_settings(&export.settings)?;
+    write_section(out, "settings", SETTINGS_SCHEMA_VERSION, &settings)?;
+    write_section(
+        out,
+        "achievements",
+        ACHIEVEMENTS_SCHEMA_VERSION,
+        &export.achievements,
+    )?;
+    write_section(
+        out,
+        "lifetimeStats",
+        LIFETIME_STATS_SCHEMA_VERSION,
+        &export.lifetime_stats,
+    )?;
+    write_section(
+        out,
+        "dayStarts",
+        DAY_STARTS_SCHEMA_VERSION,
+        &export.day_starts,
+    )?;
+    write_section(
+        out,
+        "screenTime",
+        SCREEN_TIME_SCHEMA_VERSION,
+        &export.screen_continuity,
+    )?;
+    write_section(
+        out,
+        "hydration",
+        HYDRATION_SCHEMA_VERSION,
+        &export.hydration,
+    )
+}
+
+fn write_section(
+    out: &mut impl Write,
+    key: &str,
+    schema_version: u32,
+    data: &impl Serialize,
+) -> AppResult<()> {
+    write_raw(out, ",")?;
+    let section = serde_json::json!({ "schemaVersion": schema_version, "data": data });
+    write_entry(out, key, &section)
+}
+
+fn write_entry(out: &mut impl Write, key: &str, value: &impl Serialize) -> AppResult<()> {
+    serde_json::to_writer(&mut *out, key).map_err(|e| AppError::ExportFailed(e.to_string()))?;
+    write_raw(out, ":")?;
+    serde_json::to_writer(&mut *out, value).map_err(|e| AppError::ExportFailed(e.to_string()))
+}
+
+fn write_raw(out: &mut impl Write, text: &str) -> AppResult<()> {
+    out.write_all(text.as_bytes())
+        .map_err(|e| AppError::ExportFailed(e.to_string()))
+}
+
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".partial");
+    path.with_file_name(name)
+}
+
+/// Passes writes through and counts the bytes.
+struct CountingWriter<W> {
+    inner: W,
+    written: u64,
+}
+
+impl<W> CountingWriter<W> {
+    fn new(inner: W) -> Self {
+        Self { inner, written: 0 }
+    }
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,116 @@
+use crate::utils::{AppError, AppResult};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// Prefix of an encrypted data file; the rest is base64 of nonce + ciphertext.
+const ENCRYPTED_PREFIX: &str = "RESTY-ENC1:";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+/// Plaintext of the check value, decrypted to tell a wrong passphrase apart
+/// from a damaged file.
+const CHECK_PLAINTEXT: &str = "resty-data-key";
+/// Shortest passphrase accepted when turning encryption on.
+pub const MIN_PASSPHRASE_CHARS: usize = 8;
+
+/// Contents of `encryption.json`: how the data key is derived from the
+/// passphrase. Its presence means the data files are encrypted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionHeader {
+    pub version: u32,
+    /// Argon2id salt, base64
+    pub salt: String,
+    /// `CHECK_PLAINTEXT` encrypted with the data key
+    pub check: String,
+}
+
+/// AES-256-GCM key derived from the passphrase; only ever kept in memory.
+#[derive(Clone)]
+pub struct DataKey(Key<Aes256Gcm>);
+
+impl DataKey {
+    /// New key with a fresh salt, and the header to store next to the data.
+    pub fn create(passphrase: &str) -> AppResult<(Self, EncryptionHeader)> {
+        if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+            return Err(AppError::ValidationError(format!(
+                "passphrase must be at least {} characters",
+                MIN_PASSPHRASE_CHARS
+            )));
+        }
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let key = Self::derive(passphrase, &salt)?;
+        let header = EncryptionHeader {
+            version: 1,
+            salt: BASE64.encode(salt),
+            check: key.encrypt(CHECK_PLAINTEXT)?,
+        };
+        Ok((key, header))
+    }
+
+    /// Key for `passphrase` under `header`; a passphrase that does not open the
+    /// check value is `WrongPassphrase`.
+    pub fn unlock(passphrase: &str, header: &EncryptionHeader) -> AppResult<Self> {
+        let salt = BASE64
+            .decode(&header.salt)
+            .map_err(|e| AppError::DatabaseError(format!("Invalid encryption salt: {}", e)))?;
+        let key = Self::derive(passphrase, &salt)?;
+        match key.decrypt(&header.check) {
+            Ok(check) if check == CHECK_PLAINTEXT => Ok(key),
+            _ => Err(AppError::WrongPassphrase),
+        }
+    }
+
+    fn derive(passphrase: &str, salt: &[u8]) -> AppResult<Self> {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| AppError::DatabaseError(format!("Failed to derive data key: {}", e)))?;
+        Ok(Self(key.into()))
+    }
+
+    /// Encrypt a data file's text.
+    pub fn encrypt(&self, plaintext: &str) -> AppResult<String> {
+        let cipher = Aes256Gcm::new(&self.0);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|e| AppError::DatabaseError(format!("Failed to encrypt data: {}", e)))?;
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(payload)))
+    }
+
+    /// Decrypt a data file's text written by `encrypt`. Failing to decrypt with
+    /// a key that opened the check value means the file is damaged.
+    pub fn decrypt(&self, content: &str) -> AppResult<String> {
+        let encoded = content
+            .trim()
+            .strip_prefix(ENCRYPTED_PREFIX)
+            .ok_or_else(|| AppError::DatabaseError("Data file is not encrypted".to_string()))?;
+        let payload = BASE64
+            .decode(encoded)
+            .map_err(|e| AppError::DatabaseError(format!("Damaged encrypted file: {}", e)))?;
+        if payload.len() < NONCE_LEN {
+            return Err(AppError::DatabaseError(
+                "Damaged encrypted file: too short".to_string(),
+            ));
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let plaintext = Aes256Gcm::new(&self.0)
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| AppError::DatabaseError("Failed to decrypt data file".to_string()))?;
+        String::from_utf8(plaintext)
+            .map_err(|e| AppError::DatabaseError(format!("Damaged encrypted file: {}", e)))
+    }
+}
+
+/// Whether a data file's text was written encrypted.
+pub fn is_encrypted(content: &str) -> bool {
+    content.trim_start().starts_with(ENCRYPTED_PREFIX)
+}
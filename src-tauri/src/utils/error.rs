@@ -1,7 +1,7 @@
-use thiserror::Error;
-
-/// Application error types
-#[derive(Error, Debug)]
+use thiserror::Error;
+
+/// Application error types
+#[derive(Error, Debug)]
 pub enum AppError {
     #[error("Invalid duration: must be between 1 and 120 minutes")]
     InvalidDuration,
@@ -11,40 +11,70 @@ pub enum AppError {
 
     #[error("Invalid segmented schedule configuration")]
     InvalidWorkSegments,
-
-    #[error("Database error: {0}")]
-    DatabaseError(String),
-
-    #[error("IO error: {0}")]
-    IoError(#[from] std::io::Error),
-
-    #[error("JSON serialization error: {0}")]
-    SerializationError(#[from] serde_json::Error),
-
-    #[error("Tauri error: {0}")]
-    TauriError(String),
-
-    #[error("Failed to import configuration: {0}")]
-    ImportFailed(String),
-
-    #[error("Failed to export configuration: {0}")]
-    ExportFailed(String),
-
-    #[error("Configuration validation failed: {0}")]
-    ValidationError(String),
-
-    #[error("Audio error: {0}")]
-    AudioError(String),
-
-    #[error("Unknown error: {0}")]
-    Unknown(String),
-}
-
-impl From<AppError> for String {
-    fn from(error: AppError) -> Self {
-        error.to_string()
-    }
-}
-
-/// Result type alias for application operations
-pub type AppResult<T> = Result<T, AppError>;
+
+    #[error("Force break is active; the reminder cannot be dismissed yet")]
+    ForceBreakLocked,
+
+    #[error("Breaks can be suppressed for at most {0} minutes while force break is enabled")]
+    SuppressionTooLong(u32),
+
+    #[error("Extend limit reached: {0}")]
+    ExtendLimitReached(String),
+
+    #[error("Action not permitted: {0}")]
+    ActionNotPermitted(String),
+
+    #[error("Invalid rating: must be between 1 and 5")]
+    InvalidRating,
+
+    #[error("Session not found: {0}")]
+    SessionNotFound(String),
+
+    #[error("Timer preset not found: {0}")]
+    PresetNotFound(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+
+    #[error("Data is encrypted; enter the passphrase to unlock it")]
+    DataLocked,
+
+    #[error("Wrong passphrase")]
+    WrongPassphrase,
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("JSON serialization error: {0}")]
+    SerializationError(#[from] serde_json::Error),
+
+    #[error("Tauri error: {0}")]
+    TauriError(String),
+
+    #[error("Failed to import configuration: {0}")]
+    ImportFailed(String),
+
+    #[error("Failed to export configuration: {0}")]
+    ExportFailed(String),
+
+    #[error("Configuration validation failed: {0}")]
+    ValidationError(String),
+
+    #[error("Webhook request failed: {0}")]
+    WebhookFailed(String),
+
+    #[error("Audio error: {0}")]
+    AudioError(String),
+
+    #[error("Unknown error: {0}")]
+    Unknown(String),
+}
+
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Result type alias for application operations
+pub type AppResult<T> = Result<T, AppError>;
@@ -0,0 +1,348 @@
+use crate::models::Language;
+use chrono::{DateTime, Local, Timelike, Utc};
+
+/// How a duration is rendered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DurationStyle {
+    /// "2h 15m" / "2小时15分钟"
+    Short,
+    /// "2 hours 15 minutes" / "2 小时 15 分钟"
+    Long,
+    /// "2:15:00", identical in every language
+    Clock,
+}
+
+/// Per-language words used by the formatters.
+pub struct LocaleTable {
+    hour_short: &'static str,
+    minute_short: &'static str,
+    second_short: &'static str,
+    hour_long: (&'static str, &'static str),
+    minute_long: (&'static str, &'static str),
+    second_long: (&'static str, &'static str),
+    /// Separator between units in the short style
+    short_separator: &'static str,
+    /// Whether times use a 12-hour clock with AM/PM
+    twelve_hour: bool,
+    am: &'static str,
+    pm: &'static str,
+    /// Tray tooltip prefix for the next break time
+    pub next_break: &'static str,
+    /// Notification body when a suppression exceeds the configured cap
+    pub suppression_too_long: &'static str,
+    /// Body of the notification sent by the output test
+    pub test_notification: &'static str,
+    /// Notification body and tray tooltip when no reminder window could be opened
+    pub reminder_failed: &'static str,
+    /// Notification body replacing the reminder window in a remote session
+    pub break_started: &'static str,
+    /// Title of the notification shortly before a break ends
+    pub break_end_title: &'static str,
+    /// Body of the break-end notification; followed by the remaining time
+    pub break_end_body: &'static str,
+    /// Break-end notification buttons
+    pub start_work_action: &'static str,
+    pub extend_break_action: &'static str,
+    /// Tray tooltip line while travel mode is on
+    pub travel_mode: &'static str,
+    /// Tray tooltip line while a call defers the break
+    pub call_in_progress: &'static str,
+    /// Tray tooltip prefix for the time at the computer since the last real break
+    pub screen_time: &'static str,
+    /// Notification shortly before a break suppression ends; the body is
+    /// followed by the remaining time
+    pub suppression_expiring_title: &'static str,
+    pub suppression_expiring_body: &'static str,
+    /// Suppression-expiring notification buttons
+    pub resume_breaks_action: &'static str,
+    pub extend_suppression_action: &'static str,
+    /// Event title in the exported break schedule (.ics)
+    pub break_event_summary: &'static str,
+}
+
+const EN_US: LocaleTable = LocaleTable {
+    hour_short: "h",
+    minute_short: "m",
+    second_short: "s",
+    hour_long: (" hour", " hours"),
+    minute_long: (" minute", " minutes"),
+    second_long: (" second", " seconds"),
+    short_separator: " ",
+    twelve_hour: true,
+    am: "AM",
+    pm: "PM",
+    next_break: "Next break",
+    suppression_too_long: "Breaks can be postponed by at most",
+    test_notification: "This is how RESTY notifications will look.",
+    reminder_failed: "Your break has started, but the reminder window could not be opened.",
+    break_started: "Time for a break. Step away from the screen for a moment.",
+    break_end_title: "Break ends soon",
+    break_end_body: "Work resumes in",
+    start_work_action: "Start working",
+    extend_break_action: "5 more minutes",
+    travel_mode: "Travel mode: screen-off does not pause",
+    call_in_progress: "In a call: the break waits until it ends",
+    screen_time: "At the computer for",
+    suppression_expiring_title: "Still there? Breaks resume soon",
+    suppression_expiring_body: "Break suppression ends in",
+    resume_breaks_action: "Resume breaks now",
+    extend_suppression_action: "1 more hour",
+    break_event_summary: "RESTY break",
+};
+
+const EN_GB: LocaleTable = LocaleTable {
+    twelve_hour: false,
+    ..EN_US
+};
+
+const ZH_CN: LocaleTable = LocaleTable {
+    hour_short: "小时",
+    minute_short: "分钟",
+    second_short: "秒",
+    hour_long: (" 小时", " 小时"),
+    minute_long: (" 分钟", " 分钟"),
+    second_long: (" 秒", " 秒"),
+    short_separator: "",
+    twelve_hour: false,
+    am: "上午",
+    pm: "下午",
+    next_break: "下次休息",
+    suppression_too_long: "强制休息已开启，最多只能暂停休息",
+    test_notification: "这是 RESTY 的测试通知。",
+    reminder_failed: "休息已开始，但休息提醒窗口未能打开。",
+    break_started: "休息时间到了，离开屏幕放松一下吧。",
+    break_end_title: "休息即将结束",
+    break_end_body: "距离继续工作还有",
+    start_work_action: "开始工作",
+    extend_break_action: "再休息 5 分钟",
+    travel_mode: "出行模式：熄屏不暂停计时",
+    call_in_progress: "通话中：休息将在通话结束后开始",
+    screen_time: "已连续使用电脑",
+    suppression_expiring_title: "还在吗？即将恢复休息",
+    suppression_expiring_body: "暂不休息将在以下时间后结束：",
+    resume_breaks_action: "立即恢复休息",
+    extend_suppression_action: "再延长 1 小时",
+    break_event_summary: "RESTY 休息",
+};
+
+const ZH_TW: LocaleTable = LocaleTable {
+    hour_short: "小時",
+    minute_short: "分鐘",
+    hour_long: (" 小時", " 小時"),
+    minute_long: (" 分鐘", " 分鐘"),
+    next_break: "下次休息",
+    suppression_too_long: "強制休息已開啟，最多只能暫停休息",
+    test_notification: "這是 RESTY 的測試通知。",
+    reminder_failed: "休息已開始，但休息提醒視窗未能開啟。",
+    break_started: "休息時間到了，離開螢幕放鬆一下吧。",
+    break_end_title: "休息即將結束",
+    break_end_body: "距離繼續工作還有",
+    start_work_action: "開始工作",
+    extend_break_action: "再休息 5 分鐘",
+    travel_mode: "出行模式：熄屏不暫停計時",
+    call_in_progress: "通話中：休息將在通話結束後開始",
+    screen_time: "已連續使用電腦",
+    suppression_expiring_title: "還在嗎？即將恢復休息",
+    suppression_expiring_body: "暫不休息將在以下時間後結束：",
+    resume_breaks_action: "立即恢復休息",
+    extend_suppression_action: "再延長 1 小時",
+    break_event_summary: "RESTY 休息",
+    ..ZH_CN
+};
+
+/// Formatting table for a language.
+/// 获取指定语言的格式化词表。
+pub fn locale_table(language: &Language) -> &'static LocaleTable {
+    match language {
+        Language::EnglishUnitedStates => &EN_US,
+        Language::EnglishUnitedKingdom => &EN_GB,
+        Language::ChineseSimplified => &ZH_CN,
+        Language::ChineseTraditional => &ZH_TW,
+    }
+}
+
+/// Format a duration in seconds, e.g. "2h 15m" or "2小时15分钟".
+/// Seconds are only shown below one minute; hours are never folded into days.
+/// 按语言格式化时长；不足一分钟时显示秒，超过 24 小时仍以小时计。
+pub fn format_duration(seconds: i64, language: &Language, style: DurationStyle) -> String {
+    let total = seconds.max(0);
+    let hours = total / 3600;
+    let minutes = (total % 3600) / 60;
+    let secs = total % 60;
+
+    if style == DurationStyle::Clock {
+        return format!("{}:{:02}:{:02}", hours, minutes, secs);
+    }
+
+    let table = locale_table(language);
+    let unit = |value: i64, short: &str, long: (&str, &str)| match style {
+        DurationStyle::Long => {
+            let label = if value == 1 { long.0 } else { long.1 };
+            format!("{}{}", value, label)
+        }
+        _ => format!("{}{}", value, short),
+    };
+
+    if total < 60 {
+        return unit(secs, table.second_short, table.second_long);
+    }
+
+    let mut parts = Vec::new();
+    if hours > 0 {
+        parts.push(unit(hours, table.hour_short, table.hour_long));
+    }
+    if minutes > 0 || hours == 0 {
+        parts.push(unit(minutes, table.minute_short, table.minute_long));
+    }
+    let separator = match style {
+        DurationStyle::Long => " ",
+        _ => table.short_separator,
+    };
+    parts.join(separator)
+}
+
+/// Format a point in time as a local wall-clock time, e.g. "2:35 PM" or "14:35".
+/// 将时间转换为本地时间并按语言格式化。
+pub fn format_local_time(dt: DateTime<Utc>, language: &Language) -> String {
+    let local = dt.with_timezone(&Local);
+    let table = locale_table(language);
+    if !table.twelve_hour {
+        return format!("{:02}:{:02}", local.hour(), local.minute());
+    }
+
+    let (is_pm, hour) = local.hour12();
+    let suffix = if is_pm { table.pm } else { table.am };
+    format!("{}:{:02} {}", hour, local.minute(), suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const LANGUAGES: [Language; 4] = [
+        Language::EnglishUnitedStates,
+        Language::EnglishUnitedKingdom,
+        Language::ChineseSimplified,
+        Language::ChineseTraditional,
+    ];
+
+    /// `(seconds, [en-US, en-GB, zh-CN, zh-TW])`
+    fn assert_durations(style: DurationStyle, cases: &[(i64, [&str; 4])]) {
+        for (seconds, expected) in cases {
+            for (language, expected) in LANGUAGES.iter().zip(expected) {
+                assert_eq!(
+                    format_duration(*seconds, language, style),
+                    *expected,
+                    "{seconds}s, {language:?}, {style:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn short_durations() {
+        assert_durations(
+            DurationStyle::Short,
+            &[
+                (-5, ["0s", "0s", "0秒", "0秒"]),
+                (0, ["0s", "0s", "0秒", "0秒"]),
+                (1, ["1s", "1s", "1秒", "1秒"]),
+                (59, ["59s", "59s", "59秒", "59秒"]),
+                (60, ["1m", "1m", "1分钟", "1分鐘"]),
+                // Seconds are dropped from a minute on
+                (119, ["1m", "1m", "1分钟", "1分鐘"]),
+                (3599, ["59m", "59m", "59分钟", "59分鐘"]),
+                (3600, ["1h", "1h", "1小时", "1小時"]),
+                (3660, ["1h 1m", "1h 1m", "1小时1分钟", "1小時1分鐘"]),
+                (8100, ["2h 15m", "2h 15m", "2小时15分钟", "2小時15分鐘"]),
+                // Not folded into days
+                (86_400, ["24h", "24h", "24小时", "24小時"]),
+                (90_060, ["25h 1m", "25h 1m", "25小时1分钟", "25小時1分鐘"]),
+            ],
+        );
+    }
+
+    #[test]
+    fn long_durations() {
+        assert_durations(
+            DurationStyle::Long,
+            &[
+                (0, ["0 seconds", "0 seconds", "0 秒", "0 秒"]),
+                (1, ["1 second", "1 second", "1 秒", "1 秒"]),
+                (30, ["30 seconds", "30 seconds", "30 秒", "30 秒"]),
+                (60, ["1 minute", "1 minute", "1 分钟", "1 分鐘"]),
+                (120, ["2 minutes", "2 minutes", "2 分钟", "2 分鐘"]),
+                (3600, ["1 hour", "1 hour", "1 小时", "1 小時"]),
+                (
+                    7260,
+                    [
+                        "2 hours 1 minute",
+                        "2 hours 1 minute",
+                        "2 小时 1 分钟",
+                        "2 小時 1 分鐘",
+                    ],
+                ),
+                (
+                    8100,
+                    [
+                        "2 hours 15 minutes",
+                        "2 hours 15 minutes",
+                        "2 小时 15 分钟",
+                        "2 小時 15 分鐘",
+                    ],
+                ),
+                (100_800, ["28 hours", "28 hours", "28 小时", "28 小時"]),
+            ],
+        );
+    }
+
+    #[test]
+    fn clock_durations_are_the_same_in_every_language() {
+        for (seconds, expected) in [
+            (-1, "0:00:00"),
+            (0, "0:00:00"),
+            (59, "0:00:59"),
+            (3600, "1:00:00"),
+            (8105, "2:15:05"),
+            (90_000, "25:00:00"),
+        ] {
+            assert_durations(DurationStyle::Clock, &[(seconds, [expected; 4])]);
+        }
+    }
+
+    #[test]
+    fn local_times() {
+        // `(hour, minute, [en-US, en-GB, zh-CN, zh-TW])`
+        let cases = [
+            (0, 5, ["12:05 AM", "00:05", "00:05", "00:05"]),
+            (9, 7, ["9:07 AM", "09:07", "09:07", "09:07"]),
+            (12, 0, ["12:00 PM", "12:00", "12:00", "12:00"]),
+            (14, 35, ["2:35 PM", "14:35", "14:35", "14:35"]),
+            (23, 59, ["11:59 PM", "23:59", "23:59", "23:59"]),
+        ];
+        for (hour, minute, expected) in cases {
+            // Mid-January: no daylight saving change in any zone on that day
+            let at = Local
+                .with_ymd_and_hms(2026, 1, 15, hour, minute, 0)
+                .unwrap()
+                .with_timezone(&Utc);
+            for (language, expected) in LANGUAGES.iter().zip(expected) {
+                assert_eq!(format_local_time(at, language), expected, "{language:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn every_language_has_its_own_table() {
+        for language in &LANGUAGES {
+            let table = locale_table(language);
+            assert!(!table.next_break.is_empty(), "{language:?}");
+            assert!(!table.break_event_summary.is_empty(), "{language:?}");
+        }
+        assert_ne!(
+            locale_table(&Language::ChineseSimplified).break_end_title,
+            locale_table(&Language::ChineseTraditional).break_end_title
+        );
+    }
+}
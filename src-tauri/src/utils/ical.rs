@@ -0,0 +1,87 @@
+use crate::models::{ScheduledPhase, TimerPhase};
+use chrono::{DateTime, Utc};
+
+/// Product identifier written to exported calendars.
+const PRODUCT_ID: &str = "-//youtonghy//RESTY//EN";
+/// Domain part of event UIDs, so re-importing a schedule updates its events.
+const UID_DOMAIN: &str = "resty.youtonghy.com";
+/// Content lines longer than this many octets are folded (RFC 5545 §3.1).
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Render the breaks in `phases` as an iCalendar (RFC 5545) document with one
+/// VEVENT per break, all timestamps in UTC. Work phases are left out.
+/// 将预测的休息阶段导出为 iCalendar 文本，每次休息一个 VEVENT，时间均为 UTC。
+pub fn render_break_schedule(
+    phases: &[ScheduledPhase],
+    summary: &str,
+    generated_at: DateTime<Utc>,
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODUCT_ID),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+    ];
+    for phase in phases.iter().filter(|p| p.phase == TimerPhase::Break) {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!(
+            "UID:break-{}@{}",
+            format_timestamp(phase.start),
+            UID_DOMAIN
+        ));
+        lines.push(format!("DTSTAMP:{}", format_timestamp(generated_at)));
+        lines.push(format!("DTSTART:{}", format_timestamp(phase.start)));
+        lines.push(format!("DTEND:{}", format_timestamp(phase.end)));
+        lines.push(format!("SUMMARY:{}", escape_text(summary)));
+        lines.push("TRANSP:OPAQUE".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&fold_line(&line));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// UTC "form #2" date-time, e.g. `20240105T093000Z`.
+fn format_timestamp(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a TEXT value: backslash, semicolon, comma and line breaks.
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Fold a content line at `MAX_LINE_OCTETS`, never splitting a UTF-8 character;
+/// continuation lines start with a space.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if octets + len > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the continuation line
+            octets = 1;
+        }
+        folded.push(c);
+        octets += len;
+    }
+    folded
+}
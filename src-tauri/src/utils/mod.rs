@@ -1,3 +1,9 @@
+pub mod data_export;
+pub mod encryption;
 pub mod error;
+pub mod format;
+pub mod ical;
+pub mod qr;
+pub mod time_entries;
 
 pub use error::{AppError, AppResult};
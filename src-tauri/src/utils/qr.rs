@@ -0,0 +1,90 @@
+use crate::utils::{AppError, AppResult};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use image::{GrayImage, ImageFormat, Luma};
+use qrcode::{Color, EcLevel, QrCode};
+use std::io::{Cursor, Read, Write};
+
+/// Prefix identifying a RESTY configuration payload inside a QR code.
+const PAYLOAD_PREFIX: &str = "RESTY-CONFIG:";
+/// Marker for gzip + base64 compressed payloads.
+const COMPRESSED_MARKER: &str = "gz:";
+/// Payloads longer than this (bytes) are compressed before encoding.
+const COMPRESS_THRESHOLD: usize = 512;
+/// Pixel size of one QR module in the rendered PNG.
+const MODULE_PIXELS: u32 = 8;
+/// Blank modules around the code, as required by scanners.
+const QUIET_ZONE_MODULES: u32 = 4;
+
+/// Wrap the config JSON into a QR payload, compressing large configs.
+/// 将配置 JSON 打包为二维码内容，较大的配置先 gzip 再 base64。
+pub fn encode_config_payload(json: &str) -> AppResult<String> {
+    if json.len() <= COMPRESS_THRESHOLD {
+        return Ok(format!("{}{}", PAYLOAD_PREFIX, json));
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(json.as_bytes())?;
+    let compressed = encoder.finish()?;
+    Ok(format!(
+        "{}{}{}",
+        PAYLOAD_PREFIX,
+        COMPRESSED_MARKER,
+        BASE64.encode(compressed)
+    ))
+}
+
+/// Recover the config JSON from a scanned payload (plain JSON is accepted too).
+/// 从扫描得到的二维码内容中还原配置 JSON。
+pub fn decode_config_payload(payload: &str) -> AppResult<String> {
+    let payload = payload.trim();
+    let Some(body) = payload.strip_prefix(PAYLOAD_PREFIX) else {
+        // Allow pasting a plain JSON export as well.
+        return Ok(payload.to_string());
+    };
+    let Some(encoded) = body.strip_prefix(COMPRESSED_MARKER) else {
+        return Ok(body.to_string());
+    };
+
+    let compressed = BASE64
+        .decode(encoded)
+        .map_err(|e| AppError::ImportFailed(format!("Invalid QR payload: {}", e)))?;
+    let mut json = String::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_string(&mut json)
+        .map_err(|e| AppError::ImportFailed(format!("Invalid QR payload: {}", e)))?;
+    Ok(json)
+}
+
+/// Render a payload as a QR code PNG data URL.
+/// 将内容渲染为二维码 PNG，并以 data URL 返回。
+pub fn render_qr_data_url(payload: &str) -> AppResult<String> {
+    let code = QrCode::with_error_correction_level(payload.as_bytes(), EcLevel::L)
+        .map_err(|e| AppError::ExportFailed(format!("Failed to build QR code: {}", e)))?;
+    let modules = code.width() as u32;
+    let colors = code.to_colors();
+    let size = (modules + QUIET_ZONE_MODULES * 2) * MODULE_PIXELS;
+
+    let image = GrayImage::from_fn(size, size, |x, y| {
+        let mx = (x / MODULE_PIXELS).checked_sub(QUIET_ZONE_MODULES);
+        let my = (y / MODULE_PIXELS).checked_sub(QUIET_ZONE_MODULES);
+        match (mx, my) {
+            (Some(mx), Some(my)) if mx < modules && my < modules => {
+                match colors[(my * modules + mx) as usize] {
+                    Color::Dark => Luma([0u8]),
+                    Color::Light => Luma([255u8]),
+                }
+            }
+            _ => Luma([255u8]),
+        }
+    });
+
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| AppError::ExportFailed(format!("Failed to encode QR image: {}", e)))?;
+    Ok(format!("data:image/png;base64,{}", BASE64.encode(png)))
+}
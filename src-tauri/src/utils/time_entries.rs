@@ -0,0 +1,303 @@
+use crate::models::{Session, SessionType};
+use chrono::{DateTime, Duration, FixedOffset, Local, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Output format of `export_time_entries`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimeEntryFormat {
+    /// Columns accepted by Toggl Track's CSV import
+    TogglCsv,
+    /// RFC 3339 timestamps and a duration in seconds
+    GenericCsv,
+}
+
+/// One billable block of work, never crossing local midnight. Times keep the
+/// local UTC offset they were recorded in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeEntry {
+    pub description: String,
+    pub start: DateTime<FixedOffset>,
+    pub stop: DateTime<FixedOffset>,
+}
+
+impl TimeEntry {
+    fn duration_seconds(&self) -> i64 {
+        (self.stop - self.start).num_seconds()
+    }
+}
+
+/// Description used when a work session has no note.
+const DEFAULT_DESCRIPTION: &str = "Work";
+
+/// Map work sessions to time entries.
+/// Breaks and sessions shorter than `min_seconds` are left out, sessions are
+/// clipped to `[range_start, range_end)` and split at local midnight.
+/// 将工作会话转换为计时条目：跳过休息与过短会话，按查询范围裁剪并在本地午夜拆分。
+pub fn time_entries(
+    sessions: &[Session],
+    range_start: DateTime<Utc>,
+    range_end: DateTime<Utc>,
+    min_seconds: i64,
+) -> Vec<TimeEntry> {
+    time_entries_in(sessions, range_start, range_end, min_seconds, &Local)
+}
+
+/// `time_entries` with days split in `tz` rather than the local time zone.
+fn time_entries_in<Tz: TimeZone>(
+    sessions: &[Session],
+    range_start: DateTime<Utc>,
+    range_end: DateTime<Utc>,
+    min_seconds: i64,
+    tz: &Tz,
+) -> Vec<TimeEntry> {
+    let mut work: Vec<&Session> = sessions
+        .iter()
+        .filter(|s| s.session_type == SessionType::Work && s.duration >= min_seconds)
+        .collect();
+    work.sort_by_key(|s| s.start_time);
+
+    let mut entries = Vec::new();
+    for session in work {
+        let start = session.start_time.max(range_start);
+        let stop = session.end_time.min(range_end);
+        if stop <= start {
+            continue;
+        }
+        let description = session
+            .notes
+            .as_deref()
+            .map(str::trim)
+            .filter(|note| !note.is_empty())
+            .unwrap_or(DEFAULT_DESCRIPTION)
+            .to_string();
+        split_at_midnight(
+            &description,
+            start.with_timezone(tz),
+            stop.with_timezone(tz),
+            &mut entries,
+        );
+    }
+    entries
+}
+
+fn split_at_midnight<Tz: TimeZone>(
+    description: &str,
+    mut start: DateTime<Tz>,
+    stop: DateTime<Tz>,
+    entries: &mut Vec<TimeEntry>,
+) {
+    while start < stop {
+        let next_midnight = next_local_midnight(&start).min(stop.clone());
+        entries.push(TimeEntry {
+            description: description.to_string(),
+            start: start.fixed_offset(),
+            stop: next_midnight.fixed_offset(),
+        });
+        start = next_midnight;
+    }
+}
+
+/// First local midnight strictly after `at`.
+fn next_local_midnight<Tz: TimeZone>(at: &DateTime<Tz>) -> DateTime<Tz> {
+    let next_day = at.date_naive() + Duration::days(1);
+    at.timezone()
+        .from_local_datetime(&next_day.and_time(NaiveTime::MIN))
+        .earliest()
+        // Midnight skipped by a DST change: fall back to a plain 24h step
+        .unwrap_or(at.clone() + Duration::days(1))
+}
+
+/// Render entries as CSV (header included, `\n` line endings).
+/// 按指定格式输出 CSV 文本。
+pub fn render_csv(entries: &[TimeEntry], format: TimeEntryFormat) -> String {
+    let mut out = String::new();
+    match format {
+        TimeEntryFormat::TogglCsv => {
+            out.push_str("Description,Start date,Start time,End date,End time,Duration\n");
+            for entry in entries {
+                push_row(
+                    &mut out,
+                    &[
+                        &entry.description,
+                        &entry.start.format("%Y-%m-%d").to_string(),
+                        &entry.start.format("%H:%M:%S").to_string(),
+                        &entry.stop.format("%Y-%m-%d").to_string(),
+                        &entry.stop.format("%H:%M:%S").to_string(),
+                        &clock_duration(entry.duration_seconds()),
+                    ],
+                );
+            }
+        }
+        TimeEntryFormat::GenericCsv => {
+            out.push_str("description,start,stop,duration_seconds\n");
+            for entry in entries {
+                push_row(
+                    &mut out,
+                    &[
+                        &entry.description,
+                        &entry.start.to_rfc3339(),
+                        &entry.stop.to_rfc3339(),
+                        &entry.duration_seconds().to_string(),
+                    ],
+                );
+            }
+        }
+    }
+    out
+}
+
+/// "HH:MM:SS" as expected by Toggl's duration column.
+fn clock_duration(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    )
+}
+
+fn push_row(out: &mut String, fields: &[&str]) {
+    let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+    out.push_str(&row.join(","));
+    out.push('\n');
+}
+
+/// Quote a field when it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOGGL_FIXTURE: &str = include_str!("../../tests/fixtures/time_entries/toggl.csv");
+    const GENERIC_FIXTURE: &str = include_str!("../../tests/fixtures/time_entries/generic.csv");
+
+    fn tz() -> FixedOffset {
+        FixedOffset::east_opt(8 * 3600).unwrap()
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        tz().with_ymd_and_hms(2026, 3, day, hour, minute, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn session(
+        session_type: SessionType,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        note: Option<&str>,
+    ) -> Session {
+        Session {
+            id: format!("{}", start.timestamp()),
+            session_type,
+            start_time: start,
+            end_time: end,
+            duration: (end - start).num_seconds(),
+            planned_duration: 25 * 60,
+            is_skipped: false,
+            extended_seconds: 0,
+            notes: note.map(str::to_string),
+            rating: None,
+            in_progress: false,
+            anomaly_dismissed: false,
+            acknowledged: None,
+            active_seconds: None,
+            segment_label: None,
+            end_reason: None,
+        }
+    }
+
+    /// Two days of sessions, recorded out of order, in UTC+8.
+    fn fixture_entries() -> Vec<TimeEntry> {
+        use SessionType::{Break, Work};
+
+        let sessions = vec![
+            session(Work, at(3, 13, 0), at(3, 14, 0), None),
+            session(
+                Work,
+                at(2, 9, 0),
+                at(2, 9, 25),
+                Some("Write report, draft 2"),
+            ),
+            session(Break, at(2, 9, 25), at(2, 9, 30), Some("Stretch")),
+            // Shorter than the minimum
+            session(
+                Work,
+                at(2, 10, 0),
+                at(2, 10, 0) + Duration::seconds(30),
+                None,
+            ),
+            session(Work, at(2, 23, 30), at(3, 0, 45), Some("Deploy \"v2\"")),
+            session(Work, at(3, 15, 0), at(3, 15, 10), Some("   ")),
+            session(Work, at(3, 16, 0), at(3, 16, 20), Some("Review\nnotes")),
+            // Ends exactly at midnight and at the end of the range
+            session(Work, at(3, 23, 0), at(4, 0, 0), Some("Late")),
+            // Clipped to the start of the range
+            session(Work, at(2, 7, 30), at(2, 8, 30), Some("Standup")),
+            // After the range
+            session(Work, at(4, 10, 0), at(4, 11, 0), Some("Tomorrow")),
+        ];
+        time_entries_in(&sessions, at(2, 8, 0), at(4, 0, 0), 60, &tz())
+    }
+
+    #[test]
+    fn toggl_csv_matches_the_fixture() {
+        assert_eq!(
+            render_csv(&fixture_entries(), TimeEntryFormat::TogglCsv),
+            TOGGL_FIXTURE
+        );
+    }
+
+    #[test]
+    fn generic_csv_matches_the_fixture() {
+        assert_eq!(
+            render_csv(&fixture_entries(), TimeEntryFormat::GenericCsv),
+            GENERIC_FIXTURE
+        );
+    }
+
+    #[test]
+    fn a_session_over_several_midnights_is_split_per_day() {
+        let sessions = [session(SessionType::Work, at(2, 22, 0), at(4, 2, 0), None)];
+        let entries = time_entries_in(&sessions, at(1, 0, 0), at(5, 0, 0), 60, &tz());
+
+        let days: Vec<(String, i64)> = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.start.format("%m-%d %H:%M").to_string(),
+                    entry.duration_seconds(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            days,
+            [
+                ("03-02 22:00".to_string(), 2 * 3600),
+                ("03-03 00:00".to_string(), 24 * 3600),
+                ("03-04 00:00".to_string(), 2 * 3600),
+            ]
+        );
+    }
+
+    #[test]
+    fn no_sessions_give_just_the_header() {
+        assert_eq!(
+            render_csv(&[], TimeEntryFormat::TogglCsv),
+            "Description,Start date,Start time,End date,End time,Duration\n"
+        );
+        assert_eq!(
+            render_csv(&[], TimeEntryFormat::GenericCsv),
+            "description,start,stop,duration_seconds\n"
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use crate::commands::AppState;
+use crate::models::FloatingPosition;
+use crate::services::accessibility;
+use tauri::{Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+// Positioning math stays next to the reminder flows in lib.rs
+pub(crate) use crate::{resolve_floating_position, resolve_window_size_for_monitor};
+
+/// Label of the main application window (created from tauri.conf.json).
+pub(crate) const MAIN_LABEL: &str = "main";
+/// Label of the custom tray menu window.
+pub(crate) const TRAY_MENU_LABEL: &str = "tray-menu";
+/// Label of the work-note prompt window.
+pub(crate) const WORK_NOTE_LABEL: &str = "work-note";
+/// Prefix shared by every break reminder window, pre-warmed ones included.
+pub(crate) const REMINDER_LABEL_PREFIX: &str = "break-reminder";
+/// Prefix of the hidden reminder windows created ahead of the next break.
+pub(crate) const REMINDER_PREWARM_LABEL: &str = "break-reminder-prewarm";
+/// Label of the short-lived reminder preview window.
+pub(crate) const REMINDER_PREVIEW_LABEL: &str = "reminder-preview";
+
+pub(crate) const TRAY_MENU_WIDTH: f64 = 240.0;
+pub(crate) const TRAY_MENU_HEIGHT: f64 = 192.0;
+pub(crate) const FLOATING_WINDOW_WIDTH: f64 = 340.0;
+pub(crate) const FLOATING_WINDOW_HEIGHT: f64 = 300.0;
+const WORK_NOTE_WINDOW_WIDTH: f64 = 320.0;
+const WORK_NOTE_WINDOW_HEIGHT: f64 = 180.0;
+
+/// The kinds of windows the app creates, recovered from their labels.
+/// 应用创建的窗口类型，可由窗口标签反推。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WindowKind {
+    Main,
+    TrayMenu,
+    WorkNote,
+    /// Reminder shown for a running break (one per monitor)
+    Reminder,
+    /// Hidden reminder created ahead of the next break
+    ReminderPrewarm,
+    ReminderPreview,
+}
+
+impl WindowKind {
+    /// Kind of the window labelled `label`, if the app created it.
+    pub(crate) fn from_label(label: &str) -> Option<Self> {
+        match label {
+            MAIN_LABEL => Some(Self::Main),
+            TRAY_MENU_LABEL => Some(Self::TrayMenu),
+            WORK_NOTE_LABEL => Some(Self::WorkNote),
+            REMINDER_PREVIEW_LABEL => Some(Self::ReminderPreview),
+            _ if label.starts_with(REMINDER_PREWARM_LABEL) => Some(Self::ReminderPrewarm),
+            _ if label.starts_with(REMINDER_LABEL_PREFIX) => Some(Self::Reminder),
+            _ => None,
+        }
+    }
+
+    /// Label of this kind's window; reminder kinds take the index of the
+    /// monitor they cover (None for the single-window fallback).
+    pub(crate) fn label(self, monitor: Option<usize>) -> String {
+        let base = match self {
+            Self::Main => MAIN_LABEL,
+            Self::TrayMenu => TRAY_MENU_LABEL,
+            Self::WorkNote => WORK_NOTE_LABEL,
+            Self::Reminder => REMINDER_LABEL_PREFIX,
+            Self::ReminderPrewarm => REMINDER_PREWARM_LABEL,
+            Self::ReminderPreview => REMINDER_PREVIEW_LABEL,
+        };
+        match (self.is_break_reminder(), monitor) {
+            (true, Some(idx)) => format!("{}-{}", base, idx),
+            _ => base.to_string(),
+        }
+    }
+
+    /// Break reminder windows, shown or pre-warmed (not the preview).
+    pub(crate) fn is_break_reminder(self) -> bool {
+        matches!(self, Self::Reminder | Self::ReminderPrewarm)
+    }
+}
+
+/// Whether `label` names a break reminder window, shown or pre-warmed.
+pub(crate) fn is_reminder_label(label: &str) -> bool {
+    WindowKind::from_label(label).is_some_and(WindowKind::is_break_reminder)
+}
+
+/// How the window-event handler answers a close request (Alt+F4, the title
+/// bar, the taskbar) for a window, decided by its label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CloseRequest {
+    /// Keep the app running in the tray (main window)
+    HideToTray,
+    /// Keep the single tray menu instance around for the next open
+    HideTrayMenu,
+    /// Bring the running break in line first; a force break keeps the window
+    SyncBreak,
+    /// Let the window close
+    Close,
+}
+
+impl CloseRequest {
+    pub(crate) fn for_label(label: &str) -> Self {
+        match WindowKind::from_label(label) {
+            Some(WindowKind::Main) => Self::HideToTray,
+            Some(WindowKind::TrayMenu) => Self::HideTrayMenu,
+            Some(kind) if kind.is_break_reminder() => Self::SyncBreak,
+            _ => Self::Close,
+        }
+    }
+}
+
+/// All open break reminder windows, pre-warmed ones included.
+/// 当前所有休息提醒窗口（包括预热的隐藏窗口）。
+pub(crate) fn reminder_windows(app: &tauri::AppHandle) -> Vec<WebviewWindow> {
+    app.webview_windows()
+        .into_iter()
+        .filter(|(label, _)| is_reminder_label(label))
+        .map(|(_, window)| window)
+        .collect()
+}
+
+/// The main window, if it still exists.
+pub(crate) fn main_window(app: &tauri::AppHandle) -> Option<WebviewWindow> {
+    app.get_webview_window(MAIN_LABEL)
+}
+
+/// Move `window` to the `floating_position` corner of the monitor it is on.
+pub(crate) fn place_floating_window(
+    window: &WebviewWindow,
+    floating_position: FloatingPosition,
+) -> tauri::Result<()> {
+    if let Ok(Some(monitor)) = window.current_monitor() {
+        let window_size = resolve_window_size_for_monitor(window, &monitor);
+        let position = resolve_floating_position(
+            *monitor.position(),
+            *monitor.size(),
+            window_size,
+            floating_position,
+        );
+        window.set_position(tauri::Position::Physical(position))?;
+    }
+    Ok(())
+}
+
+/// How a reminder window is built; placement happens afterwards.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ReminderWindowOptions {
+    /// Build the window fullscreen right away
+    pub fullscreen: bool,
+    /// Build the window at the floating reminder size
+    pub floating: bool,
+    /// Let the window take focus when shown (pre-warmed windows must not)
+    pub focused: bool,
+}
+
+/// Reminder page URL carrying the accessibility preferences and whether force
+/// break is on (the page then shows no way to close it), so the page can adapt
+/// on first paint without another round trip.
+fn reminder_webview_url(app: &tauri::AppHandle) -> WebviewUrl {
+    let prefs = accessibility::current_prefs();
+    let force_break = app
+        .try_state::<AppState>()
+        .is_some_and(|state| state.timer_service.is_force_break_enabled());
+    WebviewUrl::App(
+        format!(
+            "index.html#reminder?highContrast={}&reducedMotion={}&forceBreak={}",
+            prefs.high_contrast as u8, prefs.reduced_motion as u8, force_break as u8
+        )
+        .into(),
+    )
+}
+
+/// Build a hidden, undecorated, always-on-top reminder window of `kind`
+/// (reminder, pre-warmed or preview) for the monitor with index `monitor`.
+/// 创建隐藏的提醒窗口（休息提醒/预热/预览），显示与定位由调用方负责。
+pub(crate) fn create_reminder_window(
+    app: &tauri::AppHandle,
+    kind: WindowKind,
+    monitor: Option<usize>,
+    opts: ReminderWindowOptions,
+) -> tauri::Result<WebviewWindow> {
+    let label = kind.label(monitor);
+    let mut builder = WebviewWindowBuilder::new(app, &label, reminder_webview_url(app))
+        .title("Break Time - RESTY")
+        .visible(false)
+        .focused(opts.focused)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true);
+    if opts.fullscreen {
+        builder = builder.fullscreen(true);
+    } else if opts.floating {
+        builder = builder
+            .inner_size(FLOATING_WINDOW_WIDTH, FLOATING_WINDOW_HEIGHT)
+            .maximized(false);
+    }
+    builder.build()
+}
+
+/// Build the custom tray menu window at logical position `x`, `y`.
+pub(crate) fn create_tray_menu_window(
+    app: &tauri::AppHandle,
+    x: f64,
+    y: f64,
+) -> tauri::Result<WebviewWindow> {
+    let window_builder = WebviewWindowBuilder::new(
+        app,
+        TRAY_MENU_LABEL,
+        WebviewUrl::App("index.html#tray-menu".into()),
+    )
+    .title("")
+    .inner_size(TRAY_MENU_WIDTH, TRAY_MENU_HEIGHT)
+    .position(x, y)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .focused(true)
+    .visible(true);
+
+    #[cfg(target_os = "windows")]
+    let window_builder = window_builder.transparent(true);
+
+    window_builder.build()
+}
+
+/// Build the hidden work-note window for the session `session_id`.
+pub(crate) fn create_work_note_window(
+    app: &tauri::AppHandle,
+    session_id: &str,
+) -> tauri::Result<WebviewWindow> {
+    WebviewWindowBuilder::new(
+        app,
+        WORK_NOTE_LABEL,
+        WebviewUrl::App(format!("index.html#work-note?session={}", session_id).into()),
+    )
+    .title("RESTY")
+    .inner_size(WORK_NOTE_WINDOW_WIDTH, WORK_NOTE_WINDOW_HEIGHT)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .visible(false)
+    .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_KINDS: [WindowKind; 6] = [
+        WindowKind::Main,
+        WindowKind::TrayMenu,
+        WindowKind::WorkNote,
+        WindowKind::Reminder,
+        WindowKind::ReminderPrewarm,
+        WindowKind::ReminderPreview,
+    ];
+
+    #[test]
+    fn labels_map_back_to_their_kind() {
+        for kind in ALL_KINDS {
+            for monitor in [None, Some(0), Some(3)] {
+                let label = kind.label(monitor);
+                assert_eq!(WindowKind::from_label(&label), Some(kind), "{label}");
+            }
+        }
+    }
+
+    #[test]
+    fn only_reminder_kinds_take_the_monitor_index() {
+        assert_eq!(WindowKind::Reminder.label(None), "break-reminder");
+        assert_eq!(WindowKind::Reminder.label(Some(2)), "break-reminder-2");
+        assert_eq!(
+            WindowKind::ReminderPrewarm.label(Some(1)),
+            "break-reminder-prewarm-1"
+        );
+        assert_eq!(WindowKind::Main.label(Some(1)), "main");
+        assert_eq!(
+            WindowKind::ReminderPreview.label(Some(1)),
+            "reminder-preview"
+        );
+    }
+
+    #[test]
+    fn reminder_labels_cover_shown_and_prewarmed_windows_only() {
+        for label in [
+            "break-reminder",
+            "break-reminder-0",
+            "break-reminder-12",
+            "break-reminder-prewarm",
+            "break-reminder-prewarm-1",
+        ] {
+            assert!(is_reminder_label(label), "{label}");
+        }
+        for label in [
+            "main",
+            "tray-menu",
+            "work-note",
+            "reminder-preview",
+            "break",
+            "Break-Reminder-0",
+            "my-break-reminder",
+            "",
+        ] {
+            assert!(!is_reminder_label(label), "{label}");
+        }
+    }
+
+    #[test]
+    fn prewarmed_reminders_are_not_shown_reminders() {
+        assert_eq!(
+            WindowKind::from_label("break-reminder-prewarm-0"),
+            Some(WindowKind::ReminderPrewarm)
+        );
+        assert_eq!(
+            WindowKind::from_label("break-reminder-0"),
+            Some(WindowKind::Reminder)
+        );
+        assert_eq!(WindowKind::from_label("main-2"), None);
+        assert_eq!(WindowKind::from_label("tray-menu-0"), None);
+    }
+
+    #[test]
+    fn close_requests_are_intercepted_by_label() {
+        let cases = [
+            ("main", CloseRequest::HideToTray),
+            ("tray-menu", CloseRequest::HideTrayMenu),
+            ("break-reminder", CloseRequest::SyncBreak),
+            ("break-reminder-0", CloseRequest::SyncBreak),
+            ("break-reminder-prewarm-1", CloseRequest::SyncBreak),
+            ("reminder-preview", CloseRequest::Close),
+            ("work-note", CloseRequest::Close),
+            ("some-plugin-window", CloseRequest::Close),
+        ];
+        for (label, expected) in cases {
+            assert_eq!(CloseRequest::for_label(label), expected, "{label}");
+        }
+    }
+}
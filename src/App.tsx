@@ -2,10 +2,11 @@ import { useCallback, useEffect, useRef } from 'react';
 import { BrowserRouter, Routes, Route, Navigate, useNavigate } from 'react-router-dom';
 import { useTranslation } from 'react-i18next';
 import { convertFileSrc } from '@tauri-apps/api/core';
-import { getVersion } from '@tauri-apps/api/app';
+import { message } from '@tauri-apps/plugin-dialog';
 import { ThemeProvider } from './components/Common/ThemeProvider';
 import { Reminder } from './components/Reminder/Reminder';
 import { TrayMenu } from './components/TrayMenu/TrayMenu';
+import { WorkNote } from './components/WorkNote/WorkNote';
 import { Layout } from './components/Common/Layout';
 import { Dashboard } from './pages/Dashboard';
 import { Settings } from './pages/Settings';
@@ -17,16 +18,22 @@ import {
   clearRestStartsSoonNotification,
   listenPreBreakNotificationAction,
   notifyAchievementUnlocked,
+  notifyHydrationReminder,
   notifyRestStartsSoon,
+  notifyStorageError,
 } from './services/notifications';
 import { useAppStore } from './store';
 import type { Settings as AppSettings } from './types';
 import * as api from './utils/api';
 import { changeLanguage, normalizeLanguage } from './i18n';
 import { isNewerVersion } from './utils/version';
+import { playChime } from './utils/chime';
 import './App.css';
 import './i18n';
 
+/** Minimum gap between two "history not being saved" notifications. */
+const STORAGE_ERROR_NOTIFY_INTERVAL_MS = 10 * 60 * 1000;
+
 /**
  * Helper to cleanup event unsubscribers safely
  */
@@ -68,12 +75,21 @@ function App() {
     return hash.startsWith('tray-menu');
   })();
 
-  const isSpecialWindow = isReminderWindow || isTrayMenuWindow;
+  const isWorkNoteWindow = (() => {
+    if (typeof window === 'undefined') return false;
+    const hash = window.location.hash.replace(/^#\/?/, '');
+    return hash.startsWith('work-note');
+  })();
+
+  const isSpecialWindow = isReminderWindow || isTrayMenuWindow || isWorkNoteWindow;
 
   const audioRef = useRef<HTMLAudioElement | null>(null);
   const currentTrackRef = useRef<string | null>(null);
   const notifiedAchievementKeysRef = useRef<Set<string>>(new Set());
   const preBreakNotifiedTargetRef = useRef<string | null>(null);
+  const soundMutedRef = useRef(false);
+  const storageErrorNotifiedAtRef = useRef(0);
+  const backendPanicDialogOpenRef = useRef(false);
 
   const stopRestMusic = useCallback(() => {
     const audio = audioRef.current;
@@ -88,7 +104,7 @@ function App() {
   const startRestMusic = useCallback(
     async (enabled: boolean, directory: string) => {
       if (isSpecialWindow) return;
-      if (!enabled || !directory) {
+      if (!enabled || !directory || soundMutedRef.current) {
         stopRestMusic();
         return;
       }
@@ -96,26 +112,23 @@ function App() {
       const previousTrack = currentTrackRef.current;
 
       try {
-        const files = await api.getRestMusicFiles();
-        if (files.length === 0) {
+        // The backend checks the file can still be opened and falls back to the chime
+        const nextTrack = await api.pickRestMusicTrack(previousTrack);
+        if (!nextTrack) {
           stopRestMusic();
           return;
         }
 
-        let nextTrack = files[Math.floor(Math.random() * files.length)];
-        if (files.length > 1 && previousTrack) {
-          const maxAttempts = files.length;
-          let attempts = 0;
-          while (nextTrack === previousTrack && attempts < maxAttempts) {
-            nextTrack = files[Math.floor(Math.random() * files.length)];
-            attempts += 1;
-          }
-        }
-
         stopRestMusic();
 
         const audio = new Audio(convertFileSrc(nextTrack));
         audio.loop = true;
+        // The file went away mid-break (deleted, drive unplugged): move on to another track
+        audio.onerror = () => {
+          if (audioRef.current !== audio) return;
+          console.error('Rest music track became unavailable:', nextTrack);
+          void startRestMusic(enabled, directory);
+        };
 
         try {
           await audio.play();
@@ -149,7 +162,8 @@ function App() {
 
       // Opening reminder window is handled by backend (timer service events) to avoid race conditions.
       // We only handle closing here to ensure windows are cleaned up when phase changes away from break.
-      if (phase !== 'break') {
+      // The reminder stays up through the back-to-work countdown
+      if (phase !== 'break' && phase !== 'transition') {
         api.closeReminderWindow().catch((error) => {
           console.error('Failed to close reminder window:', error);
         });
@@ -164,11 +178,30 @@ function App() {
       }
     };
 
+    const windowKind = isReminderWindow
+      ? 'reminder'
+      : isTrayMenuWindow
+        ? 'tray-menu'
+        : isWorkNoteWindow
+          ? 'work-note'
+          : 'main';
+
     // 初始化加载持久化设置，并同步语言环境
-    api.loadSettings().then(async (loaded) => {
+    api.getBootstrap(windowKind).then(async (bootstrap) => {
       if (!isMountedRef.current) return;
+      if (bootstrap.errors.length > 0) {
+        console.warn('Bootstrap loaded partially:', bootstrap.errors);
+      }
 
+      const loaded = bootstrap.settings;
       const normalizedLanguage = normalizeLanguage(loaded.language);
+      if (bootstrap.translation) {
+        i18n.addResourceBundle(normalizedLanguage, 'translation', bootstrap.translation, true, true);
+      }
+      // Special windows render from this snapshot until the first timer-update event
+      if (isSpecialWindow) {
+        setTimerInfo(bootstrap.timerInfo);
+      }
       const normalizedSettings = {
         ...loaded,
         language: normalizedLanguage,
@@ -206,7 +239,8 @@ function App() {
         store.setTimerInfo(info);
 
         if (!isSpecialWindow) {
-          const breakCompleted = previousPhase === 'break' && info.phase === 'work';
+          const breakCompleted =
+            (previousPhase === 'break' || previousPhase === 'transition') && info.phase === 'work';
           const switchedToIdle = previousPhase !== 'idle' && info.phase === 'idle';
           if (breakCompleted || switchedToIdle) {
             preBreakNotifiedTargetRef.current = null;
@@ -250,14 +284,16 @@ function App() {
                     i18n.t('notifications.restStartSoon.body', {
                       defaultValue: 'Break starts in 1 minute.',
                     }),
-                    {
-                      dismiss: i18n.t('notifications.restStartSoon.dismissAction', {
-                        defaultValue: 'Got it',
-                      }),
-                      breakNow: i18n.t('notifications.restStartSoon.breakNowAction', {
-                        defaultValue: 'Break now',
-                      }),
-                    }
+                    store.settings.notificationActions.restStartSoon
+                      ? {
+                          dismiss: i18n.t('notifications.restStartSoon.dismissAction', {
+                            defaultValue: 'Got it',
+                          }),
+                          breakNow: i18n.t('notifications.restStartSoon.breakNowAction', {
+                            defaultValue: 'Break now',
+                          }),
+                        }
+                      : undefined
                   );
                 }
               }
@@ -280,6 +316,58 @@ function App() {
       })
     );
 
+    // Transient mute: stop rest music right away, resume it when unmuted mid-break
+    if (!isSpecialWindow) {
+      api.getAudioStatus().then((status) => {
+        soundMutedRef.current = status.muted;
+      }).catch((error) => {
+        console.error('Failed to load audio status:', error);
+      });
+
+      unsubscribers.push(
+        api.onSoundMutedChanged((muted) => {
+          if (!isMountedRef.current) return;
+          soundMutedRef.current = muted;
+          const store = useAppStore.getState();
+          if (muted) {
+            stopRestMusic();
+          } else if (store.timerInfo.phase === 'break') {
+            void startRestMusic(store.settings.restMusicEnabled, store.settings.restMusicDirectory);
+          }
+        })
+      );
+
+      unsubscribers.push(
+        api.onPlayChime((event) => {
+          if (!isMountedRef.current || soundMutedRef.current) return;
+          playChime(event);
+        })
+      );
+    }
+
+    // Settings saved while this window is open (e.g. mid-break): re-fetch the
+    // bootstrap so content follows; window size and position are applied by the backend
+    if (isSpecialWindow) {
+      unsubscribers.push(
+        api.onSettingsChanged(() => {
+          api.getBootstrap(windowKind).then(async (bootstrap) => {
+            if (!isMountedRef.current) return;
+            const normalizedLanguage = normalizeLanguage(bootstrap.settings.language);
+            if (bootstrap.translation) {
+              i18n.addResourceBundle(normalizedLanguage, 'translation', bootstrap.translation, true, true);
+            }
+            useAppStore.getState().setSettings({
+              ...bootstrap.settings,
+              language: normalizedLanguage,
+            } as AppSettings);
+            await changeLanguage(normalizedLanguage);
+          }).catch((error) => {
+            console.error('Failed to refresh settings:', error);
+          });
+        })
+      );
+    }
+
     // Listen for timer finished (for logging only)
     unsubscribers.push(
       api.onTimerFinished(() => {
@@ -287,17 +375,6 @@ function App() {
       })
     );
 
-    // For reminder window, proactively fetch timer info once for immediate render
-    if (isSpecialWindow) {
-      api.getTimerInfo().then((info) => {
-        if (isMountedRef.current) {
-          setTimerInfo(info);
-        }
-      }).catch((error) => {
-        console.error('Failed to load timer info:', error);
-      });
-    }
-
     let preBreakActionListener: { unregister: () => void } | null = null;
     let preBreakNativeListenerDispose: (() => void) | null = null;
     if (!isSpecialWindow) {
@@ -406,7 +483,8 @@ function App() {
 
     const checkForUpdates = async () => {
       try {
-        const currentVersion = await getVersion();
+        // Same version source as the Rust updater
+        const { version: currentVersion } = await api.getAppInfo();
         setAppVersion(currentVersion);
 
         const isWindowsPlatform =
@@ -468,16 +546,82 @@ function App() {
       })
     );
 
+    unsubscribers.push(
+      api.onHydrationReminder((progress) => {
+        if (!isMountedRef.current) {
+          return;
+        }
+        void notifyHydrationReminder(
+          i18n.t('notifications.hydration.title'),
+          i18n.t('notifications.hydration.body', { count: progress.count, goal: progress.goal })
+        );
+      })
+    );
+
+    // Checkpoints fail again every minute; don't repeat the warning each time
+    unsubscribers.push(
+      api.onStorageError((failure) => {
+        if (!isMountedRef.current) {
+          return;
+        }
+        console.error('Session history write failed:', failure.operation, failure.error);
+        const now = Date.now();
+        if (now - storageErrorNotifiedAtRef.current < STORAGE_ERROR_NOTIFY_INTERVAL_MS) {
+          return;
+        }
+        storageErrorNotifiedAtRef.current = now;
+        void notifyStorageError(
+          i18n.t('notifications.storageError.title'),
+          i18n.t('notifications.storageError.body')
+        );
+      })
+    );
+
+    // One crash dialog at a time
+    unsubscribers.push(
+      api.onBackendPanic((report) => {
+        if (!isMountedRef.current) {
+          return;
+        }
+        console.error('Backend task panicked:', report.task, report.message);
+        if (backendPanicDialogOpenRef.current) {
+          return;
+        }
+        backendPanicDialogOpenRef.current = true;
+        const bodyKey = report.recovered
+          ? 'errors.backendPanic.recovered'
+          : 'errors.backendPanic.body';
+        void message(i18n.t(bodyKey, { path: report.logPath }), {
+          title: i18n.t('errors.backendPanic.title'),
+          kind: 'error',
+        }).finally(() => {
+          backendPanicDialogOpenRef.current = false;
+        });
+      })
+    );
+
+    // Auto-install is off: offer the staged update through the update banner
+    unsubscribers.push(
+      api.onUpdateReadyToInstall((staged) => {
+        if (!isMountedRef.current) {
+          return;
+        }
+        setUpdateManifest({ version: staged.version, notes: staged.notes ?? undefined });
+      })
+    );
+
     return () => {
       isMountedRef.current = false;
       cleanupUnsubscribers(unsubscribers, isMountedRef);
     };
-  }, [i18n, isSpecialWindow]);
+  }, [i18n, isSpecialWindow, setUpdateManifest]);
 
   return (
     <ThemeProvider>
       {isTrayMenuWindow ? (
         <TrayMenu />
+      ) : isWorkNoteWindow ? (
+        <WorkNote />
       ) : isReminderWindow ? (
         <Reminder isFullscreen={settings.reminderMode === 'fullscreen'} />
       ) : (
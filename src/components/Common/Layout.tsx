@@ -1,10 +1,19 @@
-import { ReactNode, useCallback } from 'react';
+import { ReactNode, useCallback, useEffect, useState } from 'react';
 import { useTranslation } from 'react-i18next';
 import { openUrl } from '@tauri-apps/plugin-opener';
 import { useAppStore } from '../../store';
 import { Navigation } from './Navigation';
 import { useTheme } from './ThemeProvider';
-import { installUpdate } from '../../utils/api';
+import {
+  getEncryptionStatus,
+  getSystemStatus,
+  installUpdate,
+  onTrayAvailable,
+  onTrayUnavailable,
+  quitApp,
+  unlockData,
+} from '../../utils/api';
+import { normalizeLanguage } from '../../i18n';
 import iconLight from '../../../src-tauri/icons/128x128.png';
 import iconDark from '../../../src-tauri/icons/128x128Night.png';
 import { WindowControls } from './WindowControls';
@@ -30,6 +39,84 @@ export function Layout({ children, showNavigation = true }: LayoutProps) {
     setUpdateError,
   } = useAppStore();
   const { effectiveTheme } = useTheme();
+  const [trayUnavailable, setTrayUnavailable] = useState(false);
+  const [dataLocked, setDataLocked] = useState(false);
+  const [passphrase, setPassphrase] = useState('');
+  const [isUnlocking, setIsUnlocking] = useState(false);
+  const [unlockError, setUnlockError] = useState<string | null>(null);
+
+  // Encrypted settings and sessions stay unreadable until the passphrase is entered
+  useEffect(() => {
+    let disposed = false;
+    getEncryptionStatus()
+      .then((status) => {
+        if (!disposed) setDataLocked(status.locked);
+      })
+      .catch((error) => console.error('Failed to read encryption status:', error));
+    return () => {
+      disposed = true;
+    };
+  }, []);
+
+  // Without a tray icon the window is the only way to reach settings or quit
+  useEffect(() => {
+    let disposed = false;
+    const unlisteners: Array<() => void> = [];
+    getSystemStatus()
+      .then((status) => {
+        if (!disposed) setTrayUnavailable(!status.trayAvailable);
+      })
+      .catch((error) => console.error('Failed to read system status:', error));
+    Promise.all([
+      onTrayUnavailable(() => setTrayUnavailable(true)),
+      onTrayAvailable(() => setTrayUnavailable(false)),
+    ])
+      .then((fns) => {
+        if (disposed) {
+          fns.forEach((fn) => fn());
+        } else {
+          unlisteners.push(...fns);
+        }
+      })
+      .catch((error) => console.error('Failed to listen for tray events:', error));
+    return () => {
+      disposed = true;
+      unlisteners.forEach((fn) => fn());
+    };
+  }, []);
+
+  const handleQuit = useCallback(async () => {
+    try {
+      await quitApp();
+    } catch (error) {
+      console.error('Failed to quit:', error);
+    }
+  }, []);
+
+  const handleUnlock = useCallback(async () => {
+    if (!passphrase) return;
+    setUnlockError(null);
+    setIsUnlocking(true);
+    try {
+      const unlocked = await unlockData(passphrase);
+      useAppStore.getState().setSettings({
+        ...unlocked,
+        language: normalizeLanguage(unlocked.language),
+      });
+      setPassphrase('');
+      setDataLocked(false);
+    } catch (error) {
+      console.error('Failed to unlock data:', error);
+      const reason = error instanceof Error ? error.message : String(error);
+      setUnlockError(
+        reason === 'Wrong passphrase'
+          ? t('encryption.wrongPassphrase')
+          : t('encryption.unlockFailed', { reason })
+      );
+    } finally {
+      setIsUnlocking(false);
+    }
+  }, [passphrase, t]);
 
   const handleOpenWebsite = useCallback(async () => {
     const target = updateManifest?.website;
@@ -128,6 +215,52 @@ export function Layout({ children, showNavigation = true }: LayoutProps) {
           {updateError && <div className="update-banner__error">{updateError}</div>}
         </div>
       )}
+      {dataLocked && (
+        <div className="update-banner" role="status" aria-live="polite">
+          <div className="update-banner__content">
+            <span className="update-banner__title">{t('encryption.locked.title')}</span>
+            <span className="update-banner__current">{t('encryption.locked.body')}</span>
+          </div>
+          <form
+            className="update-banner__actions"
+            onSubmit={(event) => {
+              event.preventDefault();
+              void handleUnlock();
+            }}
+          >
+            <input
+              type="password"
+              className="update-banner__input"
+              value={passphrase}
+              onChange={(event) => setPassphrase(event.target.value)}
+              placeholder={t('encryption.passphrase')}
+              aria-label={t('encryption.passphrase')}
+              autoFocus
+            />
+            <button
+              type="submit"
+              className="update-banner__button"
+              disabled={isUnlocking || !passphrase}
+            >
+              {isUnlocking ? t('encryption.unlocking') : t('encryption.unlock')}
+            </button>
+          </form>
+          {unlockError && <div className="update-banner__error">{unlockError}</div>}
+        </div>
+      )}
+      {trayUnavailable && (
+        <div className="update-banner" role="status" aria-live="polite">
+          <div className="update-banner__content">
+            <span className="update-banner__title">{t('tray.unavailable.title')}</span>
+            <span className="update-banner__current">{t('tray.unavailable.body')}</span>
+          </div>
+          <div className="update-banner__actions">
+            <button type="button" className="update-banner__button" onClick={handleQuit}>
+              {t('tray.quit')}
+            </button>
+          </div>
+        </div>
+      )}
       <div className="layout-content">
         {showNavigation && <Navigation />}
         <main className="layout-main">{children}</main>
@@ -1,7 +1,9 @@
 import { useEffect, useMemo, useRef, useState } from 'react';
 import { useTranslation } from 'react-i18next';
+import { getCurrentWindow } from '@tauri-apps/api/window';
 import { useAppStore } from '../../store';
 import * as api from '../../utils/api';
+import type { BreakExercise, HydrationProgress } from '../../types';
 import './Reminder.css';
 import { useTheme } from '../Common/ThemeProvider';
 import { Dashboard } from '../../pages/Dashboard';
@@ -11,6 +13,22 @@ interface ReminderProps {
 }
 
 const TIMER_SYNC_KEY = 'resty-timer-sync';
+// The backend treats a reminder silent for 10 seconds as hung
+const REMINDER_HEARTBEAT_MS = 2000;
+
+/**
+ * Flags passed by the backend in the reminder URL (`#reminder?highContrast=1`):
+ * accessibility preferences and whether force break was on when the window was built.
+ */
+const readReminderParams = () => {
+  const query = window.location.hash.split('?')[1] ?? '';
+  const params = new URLSearchParams(query);
+  return {
+    highContrast: params.get('highContrast') === '1',
+    reducedMotion: params.get('reducedMotion') === '1',
+    forceBreak: params.get('forceBreak') === '1',
+  };
+};
 
 export function Reminder({ isFullscreen = true }: ReminderProps) {
   const { t, i18n } = useTranslation();
@@ -19,13 +37,36 @@ export function Reminder({ isFullscreen = true }: ReminderProps) {
   const [optimisticSeconds, setOptimisticSeconds] = useState<number | null>(null);
   const [optimisticTargetTotal, setOptimisticTargetTotal] = useState<number | null>(null);
   const [isReady, setIsReady] = useState(false);
+  const [exercise, setExercise] = useState<BreakExercise | null>(null);
+  const [hydration, setHydration] = useState<HydrationProgress | null>(null);
   const syncChannelRef = useRef<BroadcastChannel | null>(null);
   const safeRemainingSeconds = Math.max(0, timerInfo.remainingSeconds);
   const isBreak = timerInfo.phase === 'break';
-  const canSkip = !settings.enableForceBreak || !isBreak;
+  const isTransition = timerInfo.phase === 'transition';
+  const awaitingAcknowledgment = timerInfo.awaitingBreakAcknowledgment;
+  const [transitionSeconds, setTransitionSeconds] = useState<number | null>(null);
+  const dismissibleAt = timerInfo.breakDismissibleAt
+    ? Date.parse(timerInfo.breakDismissibleAt)
+    : Number.NaN;
+  const forceBreakCapReached = !Number.isNaN(dismissibleAt) && Date.now() >= dismissibleAt;
+  const canSkip = !settings.enableForceBreak || !isBreak || forceBreakCapReached;
+  const reminderParams = useMemo(readReminderParams, []);
+  // Force break windows offer no way out until the cap is reached
+  const hideSkip = reminderParams.forceBreak && isBreak && !forceBreakCapReached;
   const isPanelDisplay =
     isFullscreen && settings.reminderFullscreenDisplay === 'panel';
   const isZh = i18n.language.startsWith('zh');
+  // Time at the computer since the last real break, shown from one minute on
+  const screenSeconds = timerInfo.continuousScreenSeconds ?? 0;
+  const screenHours = Math.floor(screenSeconds / 3600);
+  const screenMinutes = Math.floor((screenSeconds % 3600) / 60);
+  const screenTimeLabel =
+    screenSeconds < 60
+      ? null
+      : screenHours > 0
+        ? t('reminder.screenTime.hours', { hours: screenHours, minutes: screenMinutes })
+        : t('reminder.screenTime.minutes', { minutes: screenMinutes });
+  const restDebtExtraMinutes = isBreak ? timerInfo.restDebtExtraMinutes ?? 0 : 0;
   // Compute base remaining seconds using nextTransitionTime for higher precision
   const computeBaseSeconds = useMemo(() => {
     return () => {
@@ -109,6 +150,28 @@ export function Reminder({ isFullscreen = true }: ReminderProps) {
     }
   };
 
+  const handleAcknowledge = () => {
+    api.acknowledgeBreak().catch((err) => {
+      console.error('Failed to acknowledge break:', err);
+    });
+  };
+
+  // Any key counts as acknowledging the finished break
+  useEffect(() => {
+    if (!awaitingAcknowledgment) return undefined;
+    const handleKeyDown = () => handleAcknowledge();
+    window.addEventListener('keydown', handleKeyDown);
+    return () => window.removeEventListener('keydown', handleKeyDown);
+  }, [awaitingAcknowledgment]);
+
+  // Back-to-work countdown pushed by the backend every second
+  useEffect(() => {
+    const unlisten = api.onTransitionCountdown((seconds) => setTransitionSeconds(seconds));
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => {});
+    };
+  }, []);
+
   // Reconcile optimistic state when real timer info catches up
   useEffect(() => {
     const shouldClearByTotal =
@@ -189,9 +252,9 @@ export function Reminder({ isFullscreen = true }: ReminderProps) {
     let raf1 = 0;
     let raf2 = 0;
     raf1 = requestAnimationFrame(() => {
-      // Show the hidden window as soon as we have a frame
+      // Report readiness; the backend reveals the hidden window after this signal
       api
-        .showReminderWindow()
+        .reminderReady(getCurrentWindow().label)
         .catch((err) => console.error('Failed to show reminder window:', err));
       // Next frame, enable fade-in for panel
       raf2 = requestAnimationFrame(() => setIsReady(true));
@@ -202,6 +265,55 @@ export function Reminder({ isFullscreen = true }: ReminderProps) {
     };
   }, []);
 
+  // Heartbeat for the backend watchdog, which recreates a reminder that hangs
+  useEffect(() => {
+    if (!isBreak) return;
+    const label = getCurrentWindow().label;
+    const id = window.setInterval(() => {
+      api.reminderHeartbeat(label).catch(() => {});
+    }, REMINDER_HEARTBEAT_MS);
+    return () => window.clearInterval(id);
+  }, [isBreak]);
+
+  // A new suggestion each break; every monitor's window gets the same one
+  useEffect(() => {
+    if (!isBreak) return;
+    let cancelled = false;
+    api
+      .getBreakExercise(i18n.language)
+      .then((next) => {
+        if (!cancelled) setExercise(next);
+      })
+      .catch((err) => console.error('Failed to load break exercise:', err));
+    return () => {
+      cancelled = true;
+    };
+  }, [isBreak, i18n.language]);
+
+  // Today's glasses of water, kept in sync with logs from other windows
+  const hydrationEnabled = settings.hydration?.enabled ?? false;
+  useEffect(() => {
+    if (!isBreak || !hydrationEnabled) {
+      setHydration(null);
+      return undefined;
+    }
+    api
+      .getHydrationProgress()
+      .then(setHydration)
+      .catch((err) => console.error('Failed to load hydration progress:', err));
+    const unlisten = api.onHydrationChanged(setHydration);
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => {});
+    };
+  }, [isBreak, hydrationEnabled]);
+
+  const handleLogWater = () => {
+    api
+      .incrementHydration()
+      .then(setHydration)
+      .catch((err) => console.error('Failed to log water:', err));
+  };
+
   const rootClassName = [
     'reminder',
     isFullscreen ? 'reminder-fullscreen' : 'reminder-floating',
@@ -209,10 +321,31 @@ export function Reminder({ isFullscreen = true }: ReminderProps) {
     `theme-${effectiveTheme}`,
     isReady ? 'is-ready' : '',
     isPanelDisplay ? 'reminder-panel-mode' : '',
+    reminderParams.highContrast ? 'reminder-high-contrast' : '',
+    reminderParams.reducedMotion ? 'reminder-reduced-motion' : '',
   ].join(' ');
 
+  const transitionOverlay = isTransition ? (
+    <div className="reminder-transition-overlay" role="status" aria-live="assertive">
+      {t('reminder.transition.countdown', {
+        count: transitionSeconds ?? safeRemainingSeconds,
+      })}
+    </div>
+  ) : null;
+
+  const acknowledgmentOverlay = awaitingAcknowledgment ? (
+    <div className="reminder-acknowledge-overlay" role="alertdialog" aria-live="assertive">
+      <p className="reminder-acknowledge-text">{t('reminder.acknowledge.prompt')}</p>
+      <button className="btn btn-primary btn-lg" onClick={handleAcknowledge} autoFocus>
+        {t('reminder.acknowledge.action')}
+      </button>
+    </div>
+  ) : null;
+
   return (
     <div className={rootClassName}>
+      {transitionOverlay}
+      {acknowledgmentOverlay}
       {isPanelDisplay ? (
         <div className="reminder-dashboard">
           <Dashboard
@@ -266,16 +399,38 @@ export function Reminder({ isFullscreen = true }: ReminderProps) {
             <div className="reminder-content">
               <div className="reminder-simple-label">{timerLabel}</div>
               <div className="reminder-simple-timer" aria-live="polite">{formattedTime}</div>
+              {screenTimeLabel && <div className="reminder-screen-time">{screenTimeLabel}</div>}
+              {restDebtExtraMinutes > 0 && (
+                <div className="reminder-rest-debt">
+                  {t('reminder.restDebtExtra', { minutes: restDebtExtraMinutes })}
+                </div>
+              )}
+              {isBreak && exercise && <div className="reminder-exercise">{exercise.text}</div>}
+              {hydration && (
+                <div className="reminder-hydration">
+                  <span>
+                    {t('reminder.hydration.progress', {
+                      count: hydration.count,
+                      goal: hydration.goal,
+                    })}
+                  </span>
+                  <button className="btn btn-secondary" onClick={handleLogWater}>
+                    {t('reminder.hydration.logGlass')}
+                  </button>
+                </div>
+              )}
 
               <div className="reminder-actions">
-                <button
-                  className="btn btn-secondary btn-lg"
-                  onClick={handleSkip}
-                  disabled={!canSkip}
-                  title={!canSkip && isBreak ? t('reminder.forceBreakTooltip') : undefined}
-                >
-                  {skipLabel}
-                </button>
+                {!hideSkip && (
+                  <button
+                    className="btn btn-secondary btn-lg"
+                    onClick={handleSkip}
+                    disabled={!canSkip}
+                    title={!canSkip && isBreak ? t('reminder.forceBreakTooltip') : undefined}
+                  >
+                    {skipLabel}
+                  </button>
+                )}
 
                 <button className="btn btn-primary btn-lg" onClick={handleExtend}>
                   {extendLabel}
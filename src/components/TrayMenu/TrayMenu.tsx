@@ -15,12 +15,23 @@ interface TrayMenuProps {
 export function TrayMenu({ onClose }: TrayMenuProps) {
   const { t } = useTranslation();
   const [menuLevel, setMenuLevel] = useState<'main' | 'no_break'>('main');
+  const [noBreakActions, setNoBreakActions] = useState<string[] | null>(null);
+
+  // Options longer than the configured suppression cap are hidden
+  useEffect(() => {
+    invoke<string[]>('get_tray_suppression_actions')
+      .then(setNoBreakActions)
+      .catch((error) => console.error('Failed to load suppression options:', error));
+  }, [menuLevel]);
+
+  const isNoBreakAllowed = (hours: string) =>
+    noBreakActions == null || noBreakActions.includes(`no_break_${hours}`);
 
   const closeMenu = useCallback(async () => {
     setMenuLevel('main');
     onClose?.();
     try {
-      await getCurrentWindow().hide();
+      await invoke('close_tray_menu');
     } catch (error) {
       console.error('Failed to hide tray menu:', error);
     }
@@ -69,6 +80,19 @@ export function TrayMenu({ onClose }: TrayMenuProps) {
     const handleKeyDown = (e: KeyboardEvent) => {
       if (e.key === 'Escape') {
         void closeMenu();
+        return;
+      }
+      // Arrow keys move between items so the menu is usable without a mouse
+      if (e.key === 'ArrowDown' || e.key === 'ArrowUp') {
+        e.preventDefault();
+        const items = Array.from(
+          document.querySelectorAll<HTMLButtonElement>('.tray-menu-item')
+        );
+        if (items.length === 0) return;
+        const current = items.indexOf(document.activeElement as HTMLButtonElement);
+        const step = e.key === 'ArrowDown' ? 1 : -1;
+        const next = current < 0 ? 0 : (current + step + items.length) % items.length;
+        items[next].focus();
       }
     };
 
@@ -109,34 +133,42 @@ export function TrayMenu({ onClose }: TrayMenuProps) {
           <span className="tray-menu-text">{t('tray.back', '返回')}</span>
         </button>
         <div className="tray-menu-divider" />
-        <button
-          type="button"
-          className="tray-menu-item"
-          onClick={() => handleNoBreak('1h')}
-        >
-          <span className="tray-menu-text" style={{ paddingLeft: '30px' }}>{t('tray.noBreak1h', '1 小时不休息')}</span>
-        </button>
-        <button
-          type="button"
-          className="tray-menu-item"
-          onClick={() => handleNoBreak('2h')}
-        >
-          <span className="tray-menu-text" style={{ paddingLeft: '30px' }}>{t('tray.noBreak2h', '2 小时不休息')}</span>
-        </button>
-        <button
-          type="button"
-          className="tray-menu-item"
-          onClick={() => handleNoBreak('5h')}
-        >
-          <span className="tray-menu-text" style={{ paddingLeft: '30px' }}>{t('tray.noBreak5h', '5 小时不休息')}</span>
-        </button>
-        <button
-          type="button"
-          className="tray-menu-item"
-          onClick={() => handleNoBreak('tomorrow')}
-        >
-          <span className="tray-menu-text" style={{ paddingLeft: '30px' }}>{t('tray.noBreakTomorrow', '直到明天早晨')}</span>
-        </button>
+        {isNoBreakAllowed('1h') && (
+          <button
+            type="button"
+            className="tray-menu-item"
+            onClick={() => handleNoBreak('1h')}
+          >
+            <span className="tray-menu-text" style={{ paddingLeft: '30px' }}>{t('tray.noBreak1h', '1 小时不休息')}</span>
+          </button>
+        )}
+        {isNoBreakAllowed('2h') && (
+          <button
+            type="button"
+            className="tray-menu-item"
+            onClick={() => handleNoBreak('2h')}
+          >
+            <span className="tray-menu-text" style={{ paddingLeft: '30px' }}>{t('tray.noBreak2h', '2 小时不休息')}</span>
+          </button>
+        )}
+        {isNoBreakAllowed('5h') && (
+          <button
+            type="button"
+            className="tray-menu-item"
+            onClick={() => handleNoBreak('5h')}
+          >
+            <span className="tray-menu-text" style={{ paddingLeft: '30px' }}>{t('tray.noBreak5h', '5 小时不休息')}</span>
+          </button>
+        )}
+        {isNoBreakAllowed('tomorrow') && (
+          <button
+            type="button"
+            className="tray-menu-item"
+            onClick={() => handleNoBreak('tomorrow')}
+          >
+            <span className="tray-menu-text" style={{ paddingLeft: '30px' }}>{t('tray.noBreakTomorrow', '直到明天早晨')}</span>
+          </button>
+        )}
       </div>
     );
   }
@@ -0,0 +1,115 @@
+import { useCallback, useEffect, useRef, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { listen } from '@tauri-apps/api/event';
+import { getCurrentWindow } from '@tauri-apps/api/window';
+import * as api from '../../utils/api';
+import type { WorkNotePrompt } from '../../types';
+import './WorkNote.css';
+
+/** 无操作时自动关闭的等待时长（毫秒） */
+const AUTO_DISMISS_MS = 60_000;
+
+const readSessionFromHash = (): string | null => {
+  const query = window.location.hash.split('?')[1] ?? '';
+  return new URLSearchParams(query).get('session');
+};
+
+/**
+ * 工作阶段结束后的快速记录窗口
+ * 提交后写入会话备注，60 秒无操作自动关闭
+ */
+export function WorkNote() {
+  const { t } = useTranslation();
+  const [sessionId, setSessionId] = useState<string | null>(() => readSessionFromHash());
+  const [note, setNote] = useState('');
+  const [saving, setSaving] = useState(false);
+  const dismissTimerRef = useRef<number | null>(null);
+
+  const closeWindow = useCallback(async () => {
+    try {
+      await getCurrentWindow().close();
+    } catch (error) {
+      console.error('Failed to close work note window:', error);
+    }
+  }, []);
+
+  // Restart the auto-dismiss countdown whenever a new session is prompted
+  useEffect(() => {
+    if (dismissTimerRef.current != null) {
+      window.clearTimeout(dismissTimerRef.current);
+    }
+    dismissTimerRef.current = window.setTimeout(() => {
+      void closeWindow();
+    }, AUTO_DISMISS_MS);
+    return () => {
+      if (dismissTimerRef.current != null) {
+        window.clearTimeout(dismissTimerRef.current);
+      }
+    };
+  }, [sessionId, closeWindow]);
+
+  // A reused window receives the next session through the prompt event
+  useEffect(() => {
+    const unlisten = listen<WorkNotePrompt>('work-note-prompt', (event) => {
+      setSessionId(event.payload.sessionId);
+      setNote('');
+    });
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const submitNote = useCallback(async () => {
+    if (!sessionId || saving || !note.trim()) return;
+    setSaving(true);
+    try {
+      await api.updateSessionNote(sessionId, note);
+    } catch (error) {
+      console.error('Failed to save work note:', error);
+    }
+    setSaving(false);
+    await closeWindow();
+  }, [sessionId, note, saving, closeWindow]);
+
+  return (
+    <form
+      className="work-note"
+      onSubmit={(event) => {
+        event.preventDefault();
+        void submitNote();
+      }}
+    >
+      <label className="work-note-label" htmlFor="work-note-input">
+        {t('workNote.prompt', '刚才在做什么？')}
+      </label>
+      <textarea
+        id="work-note-input"
+        className="work-note-input"
+        value={note}
+        autoFocus
+        maxLength={500}
+        onChange={(event) => setNote(event.target.value)}
+        onKeyDown={(event) => {
+          if (event.key === 'Escape') {
+            void closeWindow();
+          } else if (event.key === 'Enter' && !event.shiftKey) {
+            event.preventDefault();
+            void submitNote();
+          }
+        }}
+      />
+      <div className="work-note-actions">
+        <button type="button" className="work-note-button" onClick={() => void closeWindow()}>
+          {t('workNote.dismiss', '跳过')}
+        </button>
+        <button
+          type="submit"
+          className="work-note-button work-note-button--primary"
+          disabled={saving || !note.trim()}
+        >
+          {t('workNote.save', '保存')}
+        </button>
+      </div>
+    </form>
+  );
+}
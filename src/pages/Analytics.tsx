@@ -2,8 +2,8 @@ import { useCallback, useEffect, useLayoutEffect, useMemo, useRef, useState, typ
 import { useTranslation } from 'react-i18next';
 import { useAppStore } from '../store';
 import * as api from '../utils/api';
-import type { AnalyticsData, AnalyticsQuery, Session } from '../types';
-import { augmentSessionsWithMoreRest } from '../utils/analytics';
+import type { AnalyticsData, AnalyticsQuery, Session, WeekStartDay } from '../types';
+import { augmentSessionsWithMoreRest, getStartOfWeek } from '../utils/analytics';
 import './Analytics.css';
 
 type TimeRange = 'today' | 'week' | 'month' | 'year' | 'custom';
@@ -144,9 +144,9 @@ const parseDateInputValue = (value: string): Date | null => {
 };
 
 // 根据区间类型计算时间范围（包含结束日）
-const getPresetBounds = (range: TimeRange): TimelineBounds => {
+const getPresetBounds = (range: TimeRange, weekStartDay: WeekStartDay): TimelineBounds => {
   const now = new Date();
-  const start = new Date(now);
+  let start = new Date(now);
   const end = new Date(now);
   switch (range) {
     case 'today':
@@ -154,10 +154,7 @@ const getPresetBounds = (range: TimeRange): TimelineBounds => {
       end.setHours(23, 59, 59, 999);
       break;
     case 'week': {
-      const day = start.getDay();
-      const diff = (day + 6) % 7;
-      start.setDate(start.getDate() - diff);
-      start.setHours(0, 0, 0, 0);
+      start = getStartOfWeek(now, weekStartDay);
       end.setTime(start.getTime());
       end.setDate(end.getDate() + 6);
       end.setHours(23, 59, 59, 999);
@@ -487,7 +484,11 @@ export function Analytics() {
     };
   }, [timelineNode]);
 
-  const presetBounds = useMemo<TimelineBounds>(() => getPresetBounds(range), [range]);
+  const weekStartDay = settings.weekStartDay ?? 'monday';
+  const presetBounds = useMemo<TimelineBounds>(
+    () => getPresetBounds(range, weekStartDay),
+    [range, weekStartDay]
+  );
   const customAppliedBounds = useMemo(
     () => getCustomBounds(customAppliedStart, customAppliedEnd),
     [customAppliedStart, customAppliedEnd]
@@ -640,15 +641,17 @@ export function Analytics() {
   // 实时更新：会话新增/完成/跳过时刷新数据
   useEffect(() => {
     let active = true;
-    let unlisten: (() => void) | undefined;
+    const unlisteners: Array<() => void> = [];
+    const refresh = () => {
+      if (!active) return;
+      void loadAnalytics();
+      void loadHeatmapData();
+    };
 
     const subscribe = async () => {
       try {
-        unlisten = await api.onSessionUpserted(() => {
-          if (!active) return;
-          void loadAnalytics();
-          void loadHeatmapData();
-        });
+        unlisteners.push(await api.onSessionUpserted(refresh));
+        unlisteners.push(await api.onSessionsReloaded(refresh));
       } catch (error) {
         console.error('Failed to subscribe to session updates:', error);
       }
@@ -658,7 +661,7 @@ export function Analytics() {
 
     return () => {
       active = false;
-      if (unlisten) unlisten();
+      unlisteners.forEach((unlisten) => unlisten());
     };
   }, [loadAnalytics, loadHeatmapData]);
 
@@ -681,6 +684,18 @@ export function Analytics() {
     return parts.join(' ');
   };
 
+  // Sessions recorded before activity tracking have no activeSeconds and are left out
+  const activitySummary = useMemo(() => {
+    const days = (data?.dailyActivity ?? []).filter((day) => day.trackedSeconds > 0);
+    if (days.length === 0) return null;
+    const active = days.reduce((sum, day) => sum + day.activeSeconds, 0);
+    const tracked = days.reduce((sum, day) => sum + day.trackedSeconds, 0);
+    const perDay = days
+      .map((day) => `${day.date}: ${Math.round((day.activityRatio ?? 0) * 100)}%`)
+      .join('\n');
+    return { percent: Math.round((active / tracked) * 100), perDay };
+  }, [data]);
+
   const sessionsWithMoreRest = useMemo(() => {
     if (!data) return [] as Session[];
     return augmentSessionsWithMoreRest(data.sessions, moreRestEnabled);
@@ -1028,6 +1043,25 @@ export function Analytics() {
                       {derivedStats.skippedBreaks}
                   </div>
                 </div>
+                {data && data.acknowledgedBreaks + data.unacknowledgedBreaks > 0 && (
+                  <div className="stat-item">
+                    <span className="stat-item-label">{t('analytics.acknowledgedBreaks')}</span>
+                    <div className="stat-item-value">
+                      {data.acknowledgedBreaks} / {data.acknowledgedBreaks + data.unacknowledgedBreaks}
+                    </div>
+                  </div>
+                )}
+                {activitySummary && (
+                  <div className="stat-item" title={activitySummary.perDay}>
+                    <span className="stat-item-label">{t('analytics.activeTime')}</span>
+                    <div className="stat-item-value">
+                      {formatDuration(data?.totalActiveSeconds ?? 0)}
+                      <span className="stat-item-total">
+                        {t('analytics.activityRatio', { percent: activitySummary.percent })}
+                      </span>
+                    </div>
+                  </div>
+                )}
                 </div>
               </section>
 
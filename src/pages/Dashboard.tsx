@@ -14,6 +14,7 @@ import type { CSSProperties, SVGProps } from "react";
 import { useTranslation } from "react-i18next";
 import { useAppStore } from "../store";
 import type { TimerPhase } from "../types";
+import { getStartOfWeek } from "../utils/analytics";
 import * as api from "../utils/api";
 import "./Dashboard.css";
 
@@ -111,15 +112,6 @@ const formatCountdown = (ms: number) => {
   return `${pad2(minutes)}:${pad2(seconds)}`;
 };
 
-const getStartOfWeek = (date: Date) => {
-  const start = new Date(date);
-  start.setHours(0, 0, 0, 0);
-  const day = start.getDay();
-  const diff = (day + 6) % 7; // ISO week: Monday = 0
-  start.setDate(start.getDate() - diff);
-  return start;
-};
-
 const addDays = (date: Date, days: number) => {
   const next = new Date(date);
   next.setDate(next.getDate() + days);
@@ -1561,10 +1553,10 @@ export function Dashboard({
   }, [now]);
 
   const weekProgress = useMemo(() => {
-    const start = getStartOfWeek(now);
+    const start = getStartOfWeek(now, settings.weekStartDay ?? "monday");
     const end = addDays(start, 7);
     return getProgressBetween(start, end, now);
-  }, [now]);
+  }, [now, settings.weekStartDay]);
 
   const monthProgress = useMemo(() => {
     const start = new Date(now.getFullYear(), now.getMonth(), 1);
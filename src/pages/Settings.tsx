@@ -6,8 +6,13 @@ import { useAppStore } from '../store';
 import * as api from '../utils/api';
 import {
   DEFAULT_SETTINGS,
+  type EncryptionStatus,
   type Language,
+  type OutputCheck,
+  type ReminderOutputTest,
   type Settings as SettingsType,
+  type StorageFormat,
+  type WeekStartDay,
   type WorkSegment,
 } from '../types';
 import './Settings.css';
@@ -15,6 +20,10 @@ import './Settings.css';
 const MAX_SEGMENTS = 12;
 const MAX_DURATION_MINUTES = 120;
 const MAX_REPEAT = 12;
+/** Matches MAX_SEGMENT_LABEL_CHARS in the backend. */
+const MAX_SEGMENT_LABEL_LENGTH = 40;
+/** Matches MIN_PASSPHRASE_CHARS in the backend. */
+const MIN_PASSPHRASE_LENGTH = 8;
 const IS_WINDOWS_PLATFORM =
   typeof navigator !== 'undefined' && /windows/i.test(navigator.userAgent);
 
@@ -34,6 +43,7 @@ const normalizeSegmentValues = (segment: WorkSegment): WorkSegment => ({
   workMinutes: clampNumber(toInt(segment.workMinutes), 1, MAX_DURATION_MINUTES),
   breakMinutes: clampNumber(toInt(segment.breakMinutes), 1, MAX_DURATION_MINUTES),
   repeat: clampNumber(toInt(segment.repeat) || 1, 1, MAX_REPEAT),
+  label: segment.label?.trim().slice(0, MAX_SEGMENT_LABEL_LENGTH) || null,
 });
 
 const normalizeSegmentWithFallback = (
@@ -45,6 +55,7 @@ const normalizeSegmentWithFallback = (
     workMinutes: segment.workMinutes ?? fallbackWork,
     breakMinutes: segment.breakMinutes ?? fallbackBreak,
     repeat: segment.repeat ?? 1,
+    label: segment.label,
   });
 
 const normalizeSegments = (
@@ -69,6 +80,10 @@ const buildBackupFilename = () => {
   )}-${pad(now.getHours())}${pad(now.getMinutes())}${pad(now.getSeconds())}.json`;
 };
 
+const buildFullExportFilename = () => buildBackupFilename().replace('resty-backup-', 'resty-data-');
+
+const formatMegabytes = (bytes: number) => (bytes / (1024 * 1024)).toFixed(1);
+
 const enforceTrayDefaults = (settings: SettingsType): SettingsType => {
   const baseWork = clampNumber(
     toInt(settings.workDuration || DEFAULT_SETTINGS.workDuration),
@@ -94,10 +109,27 @@ const enforceTrayDefaults = (settings: SettingsType): SettingsType => {
     moreRestEnabled: settings.moreRestEnabled ?? DEFAULT_SETTINGS.moreRestEnabled,
     autoSilentUpdateEnabled:
       settings.autoSilentUpdateEnabled ?? DEFAULT_SETTINGS.autoSilentUpdateEnabled,
+    autoInstallUpdates: settings.autoInstallUpdates ?? DEFAULT_SETTINGS.autoInstallUpdates,
     restStartSoonNotificationEnabled:
       settings.restStartSoonNotificationEnabled ??
       DEFAULT_SETTINGS.restStartSoonNotificationEnabled,
+    breakEndNotificationEnabled:
+      settings.breakEndNotificationEnabled ?? DEFAULT_SETTINGS.breakEndNotificationEnabled,
+    notificationActions: {
+      ...DEFAULT_SETTINGS.notificationActions,
+      ...settings.notificationActions,
+    },
+    soundEvents: {
+      ...DEFAULT_SETTINGS.soundEvents,
+      ...settings.soundEvents,
+    },
+    anomalyDetection: {
+      ...DEFAULT_SETTINGS.anomalyDetection,
+      ...settings.anomalyDetection,
+    },
     disableAnalytics: settings.disableAnalytics ?? DEFAULT_SETTINGS.disableAnalytics,
+    storageFormat: settings.storageFormat ?? DEFAULT_SETTINGS.storageFormat,
+    weekStartDay: settings.weekStartDay ?? DEFAULT_SETTINGS.weekStartDay,
     segmentedWorkEnabled:
       (settings.segmentedWorkEnabled ?? false) && normalizedSegments.length > 0,
     workSegments: normalizedSegments,
@@ -132,7 +164,16 @@ const FLOATING_POSITION_OPTIONS: Array<{
   { value: 'top-left', labelKey: 'settings.reminder.positionOptions.topLeft' },
   { value: 'bottom-left', labelKey: 'settings.reminder.positionOptions.bottomLeft' },
 ];
-
+
+const SOUND_EVENT_OPTIONS: Array<{
+  value: keyof SettingsType['soundEvents'];
+  labelKey: string;
+}> = [
+  { value: 'workEnd', labelKey: 'settings.reminder.soundEvents.workEnd' },
+  { value: 'breakStart', labelKey: 'settings.reminder.soundEvents.breakStart' },
+  { value: 'breakEnd', labelKey: 'settings.reminder.soundEvents.breakEnd' },
+];
+
 /**
  * 设置页面：负责从后端加载配置、提供表单编辑与导入导出能力。
  */
@@ -147,21 +188,29 @@ export function Settings() {
   const [clearAnalyticsInput, setClearAnalyticsInput] = useState('');
   const [isClearingAnalytics, setIsClearingAnalytics] = useState(false);
   const [isTransferringData, setIsTransferringData] = useState(false);
+  const [isTestingOutput, setIsTestingOutput] = useState(false);
+  const [outputTest, setOutputTest] = useState<ReminderOutputTest | null>(null);
+  const [isTestingWebhook, setIsTestingWebhook] = useState(false);
+  const [webhookStatus, setWebhookStatus] = useState<number | null>(null);
+  const [encryptionStatus, setEncryptionStatus] = useState<EncryptionStatus | null>(null);
+  const [encryptionPassphrase, setEncryptionPassphrase] = useState('');
+  const [encryptionConfirm, setEncryptionConfirm] = useState('');
+  const [isChangingEncryption, setIsChangingEncryption] = useState(false);
   const toastTimer = useRef<ReturnType<typeof setTimeout> | null>(null);
   const isMountedRef = useRef(true);
   const sectionDefs = useMemo(
     () => [
       { id: 'timer', label: t('settings.timer.title') },
-      { id: 'reminder', label: t('settings.reminder.title') },
-      { id: 'appearance', label: t('settings.appearance.title') },
-      { id: 'system', label: t('settings.system.title') },
-      { id: 'language', label: t('settings.language.title') },
-      { id: 'about', label: t('settings.about.title') },
-    ],
-    [t]
-  );
-  const [activeSection, setActiveSection] = useState(sectionDefs[0]?.id ?? 'timer');
-
+      { id: 'reminder', label: t('settings.reminder.title') },
+      { id: 'appearance', label: t('settings.appearance.title') },
+      { id: 'system', label: t('settings.system.title') },
+      { id: 'language', label: t('settings.language.title') },
+      { id: 'about', label: t('settings.about.title') },
+    ],
+    [t]
+  );
+  const [activeSection, setActiveSection] = useState(sectionDefs[0]?.id ?? 'timer');
+
   useEffect(() => {
     isMountedRef.current = true;
     return () => {
@@ -175,24 +224,43 @@ export function Settings() {
   const analyticsConfirmPhrase = t('settings.system.analytics.confirmPhrase', {
     defaultValue: '清除统计数据',
   });
-
+
   /** 从后端加载配置并同步全局 store。 */
   const loadSettings = useCallback(async () => {
-    try {
-      const loaded = await api.loadSettings();
-      if (!isMountedRef.current) return;
-      const normalized = enforceTrayDefaults(loaded);
-      setSettings(normalized);
-      setLocalSettings(normalized);
-    } catch (error) {
-      console.error('Failed to load settings:', error);
-    }
-  }, [setSettings]);
-
-  useEffect(() => {
-    void loadSettings();
-  }, [loadSettings]);
-
+    try {
+      const loaded = await api.loadSettings();
+      if (!isMountedRef.current) return;
+      const normalized = enforceTrayDefaults(loaded);
+      setSettings(normalized);
+      setLocalSettings(normalized);
+      // Tell the user when loading had to adjust the saved settings
+      if (await api.isSettingsNormalized()) {
+        if (!isMountedRef.current) return;
+        setMessage(t('settings.normalizedNotice'));
+      }
+    } catch (error) {
+      console.error('Failed to load settings:', error);
+    }
+  }, [setSettings, t]);
+
+  useEffect(() => {
+    void loadSettings();
+  }, [loadSettings]);
+
+  const loadEncryptionStatus = useCallback(async () => {
+    try {
+      const status = await api.getEncryptionStatus();
+      if (!isMountedRef.current) return;
+      setEncryptionStatus(status);
+    } catch (error) {
+      console.error('Failed to load encryption status:', error);
+    }
+  }, []);
+
+  useEffect(() => {
+    void loadEncryptionStatus();
+  }, [loadEncryptionStatus]);
+
   /** 自动保存：将传入的新设置保存到后端并同步全局状态。 */
   const saveSettingsAuto = useCallback(
     async (next: SettingsType) => {
@@ -204,28 +272,28 @@ export function Settings() {
           autoSilentUpdateEnabled: isWindows ? next.autoSilentUpdateEnabled : false,
         });
         await api.saveSettings(normalized);
-        if (!isMountedRef.current) return;
-        setSettings(normalized);
-        setLocalSettings(normalized);
-
-        // 同步系统开机自启状态
-        api.setAutostart(normalized.autostart).catch((err) => {
-          console.error('Failed to sync autostart:', err);
-        });
-
-        if (toastTimer.current) {
-          clearTimeout(toastTimer.current);
-        }
-        setShowSuccessToast(true);
-        toastTimer.current = setTimeout(() => {
-          if (!isMountedRef.current) return;
-          setShowSuccessToast(false);
-          toastTimer.current = null;
-        }, 2200);
-      } catch (error) {
-        console.error('Failed to save settings:', error);
-        if (!isMountedRef.current) return;
-        setMessage(t('errors.saveFailed'));
+        if (!isMountedRef.current) return;
+        setSettings(normalized);
+        setLocalSettings(normalized);
+
+        // 同步系统开机自启状态
+        api.setAutostart(normalized.autostart).catch((err) => {
+          console.error('Failed to sync autostart:', err);
+        });
+
+        if (toastTimer.current) {
+          clearTimeout(toastTimer.current);
+        }
+        setShowSuccessToast(true);
+        toastTimer.current = setTimeout(() => {
+          if (!isMountedRef.current) return;
+          setShowSuccessToast(false);
+          toastTimer.current = null;
+        }, 2200);
+      } catch (error) {
+        console.error('Failed to save settings:', error);
+        if (!isMountedRef.current) return;
+        setMessage(t('errors.saveFailed'));
       }
     },
     [isWindows, setSettings, t]
@@ -278,7 +346,7 @@ export function Settings() {
   );
 
   const handleSegmentChange = useCallback(
-    (index: number, key: keyof WorkSegment, value: number) => {
+    (index: number, key: keyof WorkSegment, value: number | string) => {
       updateSegments((segments) => {
         if (!segments[index]) return segments;
         const next = [...segments];
@@ -299,7 +367,10 @@ export function Settings() {
         return prev;
       }
       const template = prev.workSegments[prev.workSegments.length - 1] ?? DEFAULT_SETTINGS.workSegments[0];
-      const nextSegments = [...prev.workSegments.map((segment) => ({ ...segment })), { ...template }];
+      const nextSegments = [
+        ...prev.workSegments.map((segment) => ({ ...segment })),
+        { ...template, label: null },
+      ];
       const fallbackWork = clampNumber(
         toInt(prev.workDuration || DEFAULT_SETTINGS.workDuration),
         1,
@@ -414,6 +485,55 @@ export function Settings() {
     }
   }, [analyticsConfirmPhrase, clearAnalyticsInput, t]);
 
+  const handleTestOutput = useCallback(async () => {
+    setIsTestingOutput(true);
+    try {
+      const result = await api.testReminderOutput(localSettings);
+      if (!isMountedRef.current) return;
+      setOutputTest(result);
+    } catch (error) {
+      console.error('Failed to test reminder output:', error);
+      if (!isMountedRef.current) return;
+      setOutputTest(null);
+      setMessage(String(error));
+    } finally {
+      if (isMountedRef.current) {
+        setIsTestingOutput(false);
+      }
+    }
+  }, [localSettings]);
+
+  const handleTestWebhook = useCallback(async () => {
+    const url = localSettings.webhookUrl?.trim();
+    if (!url) return;
+    setIsTestingWebhook(true);
+    try {
+      const status = await api.testWebhook(url);
+      if (!isMountedRef.current) return;
+      setWebhookStatus(status);
+    } catch (error) {
+      console.error('Failed to test webhook:', error);
+      if (!isMountedRef.current) return;
+      setWebhookStatus(null);
+      setMessage(String(error));
+    } finally {
+      if (isMountedRef.current) {
+        setIsTestingWebhook(false);
+      }
+    }
+  }, [localSettings.webhookUrl]);
+
+  const describeOutputCheck = (check: OutputCheck) => {
+    switch (check.status) {
+      case 'passed':
+        return t('settings.reminder.testOutput.passed');
+      case 'skipped':
+        return `${t('settings.reminder.testOutput.skipped')}: ${check.reason}`;
+      case 'failed':
+        return `${t('settings.reminder.testOutput.failed')}: ${check.error}`;
+    }
+  };
+
   const handleExportData = useCallback(async () => {
     setMessage('');
     setIsTransferringData(true);
@@ -443,6 +563,91 @@ export function Settings() {
     }
   }, [t]);
 
+  const handleExportAllData = useCallback(async () => {
+    setMessage('');
+    setIsTransferringData(true);
+    let unlisten: (() => void) | undefined;
+    try {
+      const estimate = await api.estimateDataExport();
+      const confirmed = await confirmDialog(
+        t('settings.system.dataTransfer.exportAllConfirmMessage', {
+          sessions: estimate.sessions,
+          size: formatMegabytes(estimate.estimatedBytes),
+        }),
+        { title: t('settings.system.dataTransfer.exportAll'), kind: 'info' }
+      );
+      if (!confirmed) return;
+      const path = await save({
+        title: t('settings.system.dataTransfer.exportAll'),
+        defaultPath: buildFullExportFilename(),
+        filters: [{ name: 'JSON', extensions: ['json'] }],
+      });
+      if (!path) return;
+      unlisten = await api.onDataExportProgress((progress) => {
+        if (!isMountedRef.current) return;
+        setMessage(
+          t('settings.system.dataTransfer.exportAllProgress', {
+            written: progress.sessionsWritten,
+            total: progress.totalSessions,
+          })
+        );
+      });
+      await api.exportAllData(path);
+      if (!isMountedRef.current) return;
+      setMessage(t('notifications.dataExported'));
+    } catch (error) {
+      console.error('Failed to export all data:', error);
+      if (!isMountedRef.current) return;
+      setMessage(t('errors.dataExportFailed'));
+    } finally {
+      unlisten?.();
+      if (isMountedRef.current) {
+        setIsTransferringData(false);
+      }
+    }
+  }, [t]);
+
+  /** 开启或关闭数据加密；开启时需两次输入相同的密码。 */
+  const handleToggleEncryption = useCallback(async () => {
+    if (!encryptionStatus) return;
+    setMessage('');
+    const enabling = !encryptionStatus.enabled;
+    if (enabling && encryptionPassphrase.length < MIN_PASSPHRASE_LENGTH) {
+      setMessage(t('encryption.tooShort', { min: MIN_PASSPHRASE_LENGTH }));
+      return;
+    }
+    if (enabling && encryptionPassphrase !== encryptionConfirm) {
+      setMessage(t('encryption.mismatch'));
+      return;
+    }
+    setIsChangingEncryption(true);
+    try {
+      if (enabling) {
+        await api.enableDataEncryption(encryptionPassphrase);
+      } else {
+        await api.disableDataEncryption(encryptionPassphrase);
+      }
+      if (!isMountedRef.current) return;
+      setEncryptionPassphrase('');
+      setEncryptionConfirm('');
+      setMessage(enabling ? t('encryption.enabled') : t('encryption.disabled'));
+      await loadEncryptionStatus();
+    } catch (error) {
+      console.error('Failed to change data encryption:', error);
+      if (!isMountedRef.current) return;
+      const reason = error instanceof Error ? error.message : String(error);
+      setMessage(
+        reason === 'Wrong passphrase'
+          ? t('encryption.wrongPassphrase')
+          : t('encryption.changeFailed', { reason })
+      );
+    } finally {
+      if (isMountedRef.current) {
+        setIsChangingEncryption(false);
+      }
+    }
+  }, [encryptionConfirm, encryptionPassphrase, encryptionStatus, loadEncryptionStatus, t]);
+
   const handleImportData = useCallback(async () => {
     setMessage('');
     setIsTransferringData(true);
@@ -492,28 +697,28 @@ export function Settings() {
 
   // 外部链接/目录打开
   const handleOpenMusicDirectory = useCallback(async () => {
-    const directory = localSettings.restMusicDirectory;
-    if (!directory) return;
-    try {
-      await revealItemInDir(directory);
-    } catch (error) {
-      console.error('Failed to open music directory:', error);
-      if (!isMountedRef.current) return;
-      setMessage(t('settings.reminder.restMusic.openFailed'));
-    }
-  }, [localSettings.restMusicDirectory, t]);
-
+    const directory = localSettings.restMusicDirectory;
+    if (!directory) return;
+    try {
+      await revealItemInDir(directory);
+    } catch (error) {
+      console.error('Failed to open music directory:', error);
+      if (!isMountedRef.current) return;
+      setMessage(t('settings.reminder.restMusic.openFailed'));
+    }
+  }, [localSettings.restMusicDirectory, t]);
+
   const handleOpenWebsite = useCallback(async () => {
-    try {
-      await openUrl('https://resty.tokisantike.net');
-    } catch (error) {
-      console.error('Failed to open RESTY website:', error);
-      if (typeof window !== 'undefined') {
-        window.open('https://resty.tokisantike.net', '_blank', 'noopener,noreferrer');
-      }
-    }
-  }, []);
-
+    try {
+      await openUrl('https://resty.tokisantike.net');
+    } catch (error) {
+      console.error('Failed to open RESTY website:', error);
+      if (typeof window !== 'undefined') {
+        window.open('https://resty.tokisantike.net', '_blank', 'noopener,noreferrer');
+      }
+    }
+  }, []);
+
   const isSegmented = localSettings.segmentedWorkEnabled;
 
   // 页面布局：左侧导航 + 右侧分区表单
@@ -523,11 +728,11 @@ export function Settings() {
         <div className="settings-toast" role="status" aria-live="polite">
           {t('notifications.settingsSaved')}
         </div>
-      )}
-      <div className="container settings-container">
-        <h1 className="page-title">{t('settings.title')}</h1>
-
-        <div className="settings-layout">
+      )}
+      <div className="container settings-container">
+        <h1 className="page-title">{t('settings.title')}</h1>
+
+        <div className="settings-layout">
           <nav
             className="settings-nav"
             aria-label={t('settings.navigation.label', { defaultValue: 'Settings sections' })}
@@ -683,6 +888,21 @@ export function Settings() {
                         <div className="segment-label">
                           {t('settings.timer.segmented.segmentLabel', { index: index + 1 })}
                         </div>
+                        <div className="segment-field">
+                          <label htmlFor={`segment-name-${index}`}>
+                            {t('settings.timer.segmented.name')}
+                          </label>
+                          <input
+                            id={`segment-name-${index}`}
+                            type="text"
+                            className="input"
+                            value={segment.label ?? ''}
+                            maxLength={MAX_SEGMENT_LABEL_LENGTH}
+                            placeholder={t('settings.timer.segmented.namePlaceholder')}
+                            onChange={(e) => handleSegmentChange(index, 'label', e.target.value)}
+                            onBlur={handleSegmentBlur}
+                          />
+                        </div>
                         <div className="segment-field">
                           <label htmlFor={`segment-work-${index}`}>
                             {t('settings.timer.segmented.work')}
@@ -765,50 +985,19 @@ export function Settings() {
               <div className="form-group toggle-group timer-toggle-offset">
                 <label className="toggle-row">
                   <span className="toggle-text">{t('settings.timer.enableForceBreak')}</span>
-                  <span className="switch">
-                    <input
-                      type="checkbox"
-                      checked={localSettings.enableForceBreak}
-                      onChange={(e) => {
-                        const next = { ...localSettings, enableForceBreak: e.target.checked };
-                        setLocalSettings(next);
-                        saveSettingsAuto(next);
-                      }}
-                    />
-                    <span className="slider" />
-                  </span>
-                </label>
-                <p className="helper-text">{t('settings.timer.forceBreakDescription')}</p>
-              </div>
-
-              <div className="form-group toggle-group">
-                <label className="toggle-row">
-                  <span className="toggle-text">{t('settings.timer.flowMode')}</span>
-                  <span className="switch">
-                    <input
-                      type="checkbox"
-                      checked={localSettings.flowModeEnabled}
-                      onChange={(e) => {
-                        const next = { ...localSettings, flowModeEnabled: e.target.checked };
-                        setLocalSettings(next);
-                        saveSettingsAuto(next);
-                      }}
-                    />
-                    <span className="slider" />
-                  </span>
-                </label>
-                <p className="helper-text">{t('settings.timer.flowModeDescription')}</p>
-              </div>
-
-              <div className="form-group toggle-group">
-                <label className="toggle-row">
-                  <span className="toggle-text">{t('settings.timer.moreRest')}</span>
                   <span className="switch">
                     <input
                       type="checkbox"
-                      checked={localSettings.moreRestEnabled}
+                      checked={localSettings.enableForceBreak}
                       onChange={(e) => {
-                        const next = { ...localSettings, moreRestEnabled: e.target.checked };
+                        // Click-through reminders are not allowed together with force break
+                        const next = {
+                          ...localSettings,
+                          enableForceBreak: e.target.checked,
+                          reminderClickThrough: e.target.checked
+                            ? false
+                            : localSettings.reminderClickThrough,
+                        };
                         setLocalSettings(next);
                         saveSettingsAuto(next);
                       }}
@@ -816,102 +1005,51 @@ export function Settings() {
                     <span className="slider" />
                   </span>
                 </label>
-                <p className="helper-text">{t('settings.timer.moreRestDescription')}</p>
-              </div>
-
-              </section>
-            )}
-
-            {/* Reminder Settings */}
-            {activeSection === 'reminder' && (
-              <section id="settings-reminder" className="settings-card settings-section">
-                <h2 className="card-header">{t('settings.reminder.title')}</h2>
-
-              <div className="form-group">
-                <label htmlFor="reminderMode">{t('settings.reminder.mode')}</label>
-                <select
-                  id="reminderMode"
-                  className="input"
-                  value={localSettings.reminderMode}
-                  onChange={(e) => {
-                    const next = {
-                      ...localSettings,
-                      reminderMode: e.target.value as 'fullscreen' | 'floating',
-                    } as SettingsType;
-                    setLocalSettings(next);
-                    saveSettingsAuto(next);
-                  }}
-                >
-                  <option value="fullscreen">{t('settings.reminder.fullscreen')}</option>
-                  <option value="floating">{t('settings.reminder.floating')}</option>
-                </select>
+                <p className="helper-text">{t('settings.timer.forceBreakDescription')}</p>
               </div>
 
-              {localSettings.reminderMode === 'floating' && (
+              {localSettings.enableForceBreak && (
                 <div className="form-group">
-                  <label htmlFor="floatingPosition">
-                    {t('settings.reminder.floatingPosition')}
-                  </label>
-                  <select
-                    id="floatingPosition"
+                  <label htmlFor="maxReminderMinutes">{t('settings.timer.maxReminderMinutes')}</label>
+                  <input
+                    id="maxReminderMinutes"
+                    type="number"
                     className="input"
-                    value={localSettings.floatingPosition}
+                    value={localSettings.maxReminderMinutes}
                     onChange={(e) => {
-                      const next = {
-                        ...localSettings,
-                        floatingPosition: e.target.value as SettingsType['floatingPosition'],
-                      } as SettingsType;
-                      setLocalSettings(next);
-                      saveSettingsAuto(next);
+                      const value = parseInt(e.target.value);
+                      setLocalSettings({ ...localSettings, maxReminderMinutes: value });
                     }}
-                  >
-                    {FLOATING_POSITION_OPTIONS.map((option) => (
-                      <option key={option.value} value={option.value}>
-                        {t(option.labelKey)}
-                      </option>
-                    ))}
-                  </select>
-                </div>
-              )}
-
-              {localSettings.reminderMode === 'fullscreen' && (
-                <div className="form-group">
-                  <label htmlFor="reminderFullscreenDisplay">
-                    {t('settings.reminder.display')}
-                  </label>
-                  <select
-                    id="reminderFullscreenDisplay"
-                    className="input"
-                    value={localSettings.reminderFullscreenDisplay}
-                    onChange={(e) => {
-                      const next = {
-                        ...localSettings,
-                        reminderFullscreenDisplay:
-                          e.target.value as SettingsType['reminderFullscreenDisplay'],
-                      } as SettingsType;
+                    onBlur={(e) => {
+                      let value = parseInt(e.target.value);
+                      if (Number.isNaN(value)) value = DEFAULT_SETTINGS.maxReminderMinutes;
+                      value = Math.max(1, Math.min(120, value));
+                      const next = { ...localSettings, maxReminderMinutes: value };
                       setLocalSettings(next);
                       saveSettingsAuto(next);
                     }}
-                  >
-                    <option value="scene">{t('settings.reminder.displayScene')}</option>
-                    <option value="panel">{t('settings.reminder.displayPanel')}</option>
-                  </select>
+                    min={1}
+                    max={120}
+                  />
+                  <p className="helper-text">{t('settings.timer.maxReminderMinutesDescription')}</p>
                 </div>
               )}
 
               <div className="form-group toggle-group">
                 <label className="toggle-row">
-                  <span className="toggle-text">
-                    {t('settings.reminder.restStartSoonNotification.enable')}
-                  </span>
+                  <span className="toggle-text">{t('settings.timer.requireBreakAcknowledgment')}</span>
                   <span className="switch">
                     <input
                       type="checkbox"
-                      checked={localSettings.restStartSoonNotificationEnabled}
+                      checked={localSettings.requireBreakAcknowledgment}
                       onChange={(e) => {
+                        // The acknowledgment needs a clickable reminder
                         const next = {
                           ...localSettings,
-                          restStartSoonNotificationEnabled: e.target.checked,
+                          requireBreakAcknowledgment: e.target.checked,
+                          reminderClickThrough: e.target.checked
+                            ? false
+                            : localSettings.reminderClickThrough,
                         };
                         setLocalSettings(next);
                         saveSettingsAuto(next);
@@ -921,133 +1059,19 @@ export function Settings() {
                   </span>
                 </label>
                 <p className="helper-text">
-                  {t('settings.reminder.restStartSoonNotification.description')}
+                  {t('settings.timer.requireBreakAcknowledgmentDescription')}
                 </p>
               </div>
 
-              <h3 className="card-subtitle">{t('settings.reminder.restMusic.title')}</h3>
-
-              <div className="form-group toggle-group">
-                <label className="toggle-row">
-                  <span className="toggle-text">{t('settings.reminder.restMusic.enable')}</span>
-                  <span className="switch">
-                    <input
-                      type="checkbox"
-                      checked={localSettings.restMusicEnabled}
-                      onChange={(e) => {
-                        const next = { ...localSettings, restMusicEnabled: e.target.checked };
-                        setLocalSettings(next);
-                        saveSettingsAuto(next);
-                      }}
-                    />
-                    <span className="slider" />
-                  </span>
-                </label>
-                <p className="helper-text">{t('settings.reminder.restMusic.description')}</p>
-              </div>
-
-              <div className="form-group">
-                <label>{t('settings.reminder.restMusic.directory')}</label>
-                <button
-                  type="button"
-                  className="link-button directory-display"
-                  onClick={handleOpenMusicDirectory}
-                  disabled={!localSettings.restMusicDirectory}
-                >
-                  {localSettings.restMusicDirectory || t('settings.reminder.restMusic.directoryMissing')}
-                </button>
-              </div>
-              </section>
-            )}
-
-            {/* Appearance Settings */}
-            {activeSection === 'appearance' && (
-              <section id="settings-appearance" className="settings-card settings-section">
-                <h2 className="card-header">{t('settings.appearance.title')}</h2>
-
-              <div className="form-group">
-                <label htmlFor="theme">{t('settings.appearance.theme')}</label>
-                <select
-                  id="theme"
-                  className="input"
-                  value={localSettings.theme}
-                  onChange={(e) => {
-                    const next = {
-                      ...localSettings,
-                      theme: e.target.value as 'light' | 'dark' | 'auto',
-                    } as SettingsType;
-                    setLocalSettings(next);
-                    saveSettingsAuto(next);
-                  }}
-                >
-                  <option value="light">{t('settings.appearance.light')}</option>
-                  <option value="dark">{t('settings.appearance.dark')}</option>
-                  <option value="auto">{t('settings.appearance.auto')}</option>
-                </select>
-              </div>
-              </section>
-            )}
-
-            {/* System Settings */}
-            {activeSection === 'system' && (
-              <section id="settings-system" className="settings-card settings-section">
-                <h2 className="card-header">{t('settings.system.title')}</h2>
-
-              <div className="form-group toggle-group">
-                <label className="toggle-row">
-                  <span className="toggle-text">{t('settings.system.autostart')}</span>
-                  <span className="switch">
-                    <input
-                      type="checkbox"
-                      checked={localSettings.autostart}
-                      onChange={(e) => {
-                        const enabled = e.target.checked;
-                        const next = {
-                          ...localSettings,
-                          autostart: enabled,
-                          silentAutostart: enabled ? localSettings.silentAutostart : false,
-                        };
-                        setLocalSettings(next);
-                        saveSettingsAuto(next);
-                      }}
-                    />
-                    <span className="slider" />
-                  </span>
-                </label>
-              </div>
-
-              <div className="form-group toggle-group">
-                <label className="toggle-row">
-                  <span className="toggle-text">{t('settings.system.silentAutostart')}</span>
-                  <span className="switch">
-                    <input
-                      type="checkbox"
-                      checked={localSettings.silentAutostart}
-                      disabled={!localSettings.autostart}
-                      onChange={(e) => {
-                        const next = {
-                          ...localSettings,
-                          silentAutostart: e.target.checked,
-                        };
-                        setLocalSettings(next);
-                        saveSettingsAuto(next);
-                      }}
-                    />
-                    <span className="slider" />
-                  </span>
-                </label>
-                <p className="helper-text">{t('settings.system.silentAutostartHint')}</p>
-              </div>
-
               <div className="form-group toggle-group">
                 <label className="toggle-row">
-                  <span className="toggle-text">{t('settings.system.analytics.disable')}</span>
+                  <span className="toggle-text">{t('settings.timer.flowMode')}</span>
                   <span className="switch">
                     <input
                       type="checkbox"
-                      checked={localSettings.disableAnalytics}
+                      checked={localSettings.flowModeEnabled}
                       onChange={(e) => {
-                        const next = { ...localSettings, disableAnalytics: e.target.checked };
+                        const next = { ...localSettings, flowModeEnabled: e.target.checked };
                         setLocalSettings(next);
                         saveSettingsAuto(next);
                       }}
@@ -1055,34 +1079,1029 @@ export function Settings() {
                     <span className="slider" />
                   </span>
                 </label>
-                <p className="helper-text">{t('settings.system.analytics.disableHint')}</p>
+                <p className="helper-text">{t('settings.timer.flowModeDescription')}</p>
               </div>
 
-              {isWindows && (
-                <div className="form-group toggle-group">
-                  <label className="toggle-row">
-                    <span className="toggle-text">
-                      {t('settings.system.autoSilentUpdate')}
-                    </span>
-                    <span className="switch">
-                      <input
-                        type="checkbox"
-                        checked={localSettings.autoSilentUpdateEnabled}
-                        onChange={(e) => {
-                          const next = {
-                            ...localSettings,
-                            autoSilentUpdateEnabled: e.target.checked,
-                          };
-                          setLocalSettings(next);
-                          saveSettingsAuto(next);
-                        }}
-                      />
-                      <span className="slider" />
-                    </span>
-                  </label>
-                  <p className="helper-text">{t('settings.system.autoSilentUpdateHint')}</p>
-                </div>
-              )}
+              <div className="form-group">
+                <label htmlFor="suppressionExpiryWarning">
+                  {t('settings.timer.suppressionExpiryWarning')}
+                </label>
+                <input
+                  id="suppressionExpiryWarning"
+                  type="number"
+                  className="input"
+                  value={localSettings.suppressionExpiryWarningMinutes}
+                  onChange={(e) => {
+                    const value = parseInt(e.target.value);
+                    setLocalSettings({ ...localSettings, suppressionExpiryWarningMinutes: value });
+                  }}
+                  onBlur={(e) => {
+                    let value = parseInt(e.target.value);
+                    if (Number.isNaN(value)) value = localSettings.suppressionExpiryWarningMinutes;
+                    value = Math.max(0, Math.min(60, value));
+                    const next = { ...localSettings, suppressionExpiryWarningMinutes: value };
+                    setLocalSettings(next);
+                    saveSettingsAuto(next);
+                  }}
+                  min={0}
+                  max={60}
+                />
+                <p className="helper-text">{t('settings.timer.suppressionExpiryWarningDescription')}</p>
+              </div>
+
+              <div className="form-group">
+                <label htmlFor="workStartCountdown">
+                  {t('settings.timer.workStartCountdown')}
+                </label>
+                <input
+                  id="workStartCountdown"
+                  type="number"
+                  className="input"
+                  value={localSettings.workStartCountdownSeconds}
+                  onChange={(e) => {
+                    const value = parseInt(e.target.value);
+                    setLocalSettings({ ...localSettings, workStartCountdownSeconds: value });
+                  }}
+                  onBlur={(e) => {
+                    let value = parseInt(e.target.value);
+                    if (Number.isNaN(value)) value = localSettings.workStartCountdownSeconds;
+                    value = Math.max(0, Math.min(30, value));
+                    const next = { ...localSettings, workStartCountdownSeconds: value };
+                    setLocalSettings(next);
+                    saveSettingsAuto(next);
+                  }}
+                  min={0}
+                  max={30}
+                />
+                <p className="helper-text">{t('settings.timer.workStartCountdownDescription')}</p>
+              </div>
+
+              <div className="form-group">
+                <label htmlFor="minRealBreakMinutes">
+                  {t('settings.timer.minRealBreakMinutes')}
+                </label>
+                <input
+                  id="minRealBreakMinutes"
+                  type="number"
+                  className="input"
+                  value={localSettings.minRealBreakMinutes}
+                  onChange={(e) => {
+                    const value = parseInt(e.target.value);
+                    setLocalSettings({ ...localSettings, minRealBreakMinutes: value });
+                  }}
+                  onBlur={(e) => {
+                    let value = parseInt(e.target.value);
+                    if (Number.isNaN(value)) value = localSettings.minRealBreakMinutes;
+                    value = Math.max(1, Math.min(120, value));
+                    const next = { ...localSettings, minRealBreakMinutes: value };
+                    setLocalSettings(next);
+                    saveSettingsAuto(next);
+                  }}
+                  min={1}
+                  max={120}
+                />
+                <p className="helper-text">{t('settings.timer.minRealBreakMinutesDescription')}</p>
+              </div>
+
+              <div className="form-group">
+                <label htmlFor="activitySampleSeconds">
+                  {t('settings.timer.activitySampleSeconds')}
+                </label>
+                <input
+                  id="activitySampleSeconds"
+                  type="number"
+                  className="input"
+                  value={localSettings.activitySampleSeconds}
+                  onChange={(e) => {
+                    const value = parseInt(e.target.value);
+                    setLocalSettings({ ...localSettings, activitySampleSeconds: value });
+                  }}
+                  onBlur={(e) => {
+                    let value = parseInt(e.target.value);
+                    if (Number.isNaN(value)) value = localSettings.activitySampleSeconds;
+                    value = Math.max(5, Math.min(300, value));
+                    // The threshold may not be shorter than the interval
+                    const threshold = Math.max(value, localSettings.activityIdleThresholdSeconds);
+                    const next = {
+                      ...localSettings,
+                      activitySampleSeconds: value,
+                      activityIdleThresholdSeconds: threshold,
+                    };
+                    setLocalSettings(next);
+                    saveSettingsAuto(next);
+                  }}
+                  min={5}
+                  max={300}
+                />
+              </div>
+
+              <div className="form-group">
+                <label htmlFor="activityIdleThresholdSeconds">
+                  {t('settings.timer.activityIdleThresholdSeconds')}
+                </label>
+                <input
+                  id="activityIdleThresholdSeconds"
+                  type="number"
+                  className="input"
+                  value={localSettings.activityIdleThresholdSeconds}
+                  onChange={(e) => {
+                    const value = parseInt(e.target.value);
+                    setLocalSettings({ ...localSettings, activityIdleThresholdSeconds: value });
+                  }}
+                  onBlur={(e) => {
+                    let value = parseInt(e.target.value);
+                    if (Number.isNaN(value)) value = localSettings.activityIdleThresholdSeconds;
+                    value = Math.max(localSettings.activitySampleSeconds, Math.min(3600, value));
+                    const next = { ...localSettings, activityIdleThresholdSeconds: value };
+                    setLocalSettings(next);
+                    saveSettingsAuto(next);
+                  }}
+                  min={localSettings.activitySampleSeconds}
+                  max={3600}
+                />
+                <p className="helper-text">{t('settings.timer.activityTrackingDescription')}</p>
+              </div>
+
+              <div className="form-group">
+                <label htmlFor="restDebtCompensationPercent">
+                  {t('settings.timer.restDebtCompensationPercent')}
+                </label>
+                <input
+                  id="restDebtCompensationPercent"
+                  type="number"
+                  className="input"
+                  value={localSettings.restDebtCompensationPercent}
+                  onChange={(e) => {
+                    const value = parseInt(e.target.value);
+                    setLocalSettings({ ...localSettings, restDebtCompensationPercent: value });
+                  }}
+                  onBlur={(e) => {
+                    let value = parseInt(e.target.value);
+                    if (Number.isNaN(value)) value = localSettings.restDebtCompensationPercent;
+                    value = Math.max(0, Math.min(100, value));
+                    const next = { ...localSettings, restDebtCompensationPercent: value };
+                    setLocalSettings(next);
+                    saveSettingsAuto(next);
+                  }}
+                  min={0}
+                  max={100}
+                />
+                <p className="helper-text">
+                  {t('settings.timer.restDebtCompensationPercentDescription')}
+                </p>
+              </div>
+
+              <div className="form-group toggle-group">
+                <label className="toggle-row">
+                  <span className="toggle-text">{t('settings.timer.hydration.enabled')}</span>
+                  <span className="switch">
+                    <input
+                      type="checkbox"
+                      checked={localSettings.hydration.enabled}
+                      onChange={(e) => {
+                        const next = {
+                          ...localSettings,
+                          hydration: { ...localSettings.hydration, enabled: e.target.checked },
+                        };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                    />
+                    <span className="slider" />
+                  </span>
+                </label>
+                <p className="helper-text">{t('settings.timer.hydration.enabledDescription')}</p>
+              </div>
+
+              {localSettings.hydration.enabled && (
+                <>
+                  <div className="form-group">
+                    <label htmlFor="hydrationDailyGoalGlasses">
+                      {t('settings.timer.hydration.dailyGoalGlasses')}
+                    </label>
+                    <input
+                      id="hydrationDailyGoalGlasses"
+                      type="number"
+                      className="input"
+                      value={localSettings.hydration.dailyGoalGlasses}
+                      onChange={(e) => {
+                        const value = parseInt(e.target.value);
+                        setLocalSettings({
+                          ...localSettings,
+                          hydration: { ...localSettings.hydration, dailyGoalGlasses: value },
+                        });
+                      }}
+                      onBlur={(e) => {
+                        let value = parseInt(e.target.value);
+                        if (Number.isNaN(value)) value = localSettings.hydration.dailyGoalGlasses;
+                        value = Math.max(1, Math.min(50, value));
+                        const next = {
+                          ...localSettings,
+                          hydration: { ...localSettings.hydration, dailyGoalGlasses: value },
+                        };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                      min={1}
+                      max={50}
+                    />
+                  </div>
+
+                  <div className="form-group">
+                    <label htmlFor="hydrationReminderIntervalMinutes">
+                      {t('settings.timer.hydration.reminderIntervalMinutes')}
+                    </label>
+                    <input
+                      id="hydrationReminderIntervalMinutes"
+                      type="number"
+                      className="input"
+                      value={localSettings.hydration.reminderIntervalMinutes}
+                      onChange={(e) => {
+                        const value = parseInt(e.target.value);
+                        setLocalSettings({
+                          ...localSettings,
+                          hydration: { ...localSettings.hydration, reminderIntervalMinutes: value },
+                        });
+                      }}
+                      onBlur={(e) => {
+                        let value = parseInt(e.target.value);
+                        if (Number.isNaN(value)) value = localSettings.hydration.reminderIntervalMinutes;
+                        value = Math.max(0, Math.min(480, value));
+                        const next = {
+                          ...localSettings,
+                          hydration: { ...localSettings.hydration, reminderIntervalMinutes: value },
+                        };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                      min={0}
+                      max={480}
+                    />
+                  </div>
+                </>
+              )}
+
+              <div className="form-group toggle-group">
+                <label className="toggle-row">
+                  <span className="toggle-text">{t('settings.timer.travelMode')}</span>
+                  <span className="switch">
+                    <input
+                      type="checkbox"
+                      checked={localSettings.travelMode}
+                      onChange={(e) => {
+                        const next = { ...localSettings, travelMode: e.target.checked };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                    />
+                    <span className="slider" />
+                  </span>
+                </label>
+                <p className="helper-text">{t('settings.timer.travelModeDescription')}</p>
+              </div>
+
+              <div className="form-group toggle-group">
+                <label className="toggle-row">
+                  <span className="toggle-text">{t('settings.timer.moreRest')}</span>
+                  <span className="switch">
+                    <input
+                      type="checkbox"
+                      checked={localSettings.moreRestEnabled}
+                      onChange={(e) => {
+                        const next = { ...localSettings, moreRestEnabled: e.target.checked };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                    />
+                    <span className="slider" />
+                  </span>
+                </label>
+                <p className="helper-text">{t('settings.timer.moreRestDescription')}</p>
+              </div>
+
+              </section>
+            )}
+
+            {/* Reminder Settings */}
+            {activeSection === 'reminder' && (
+              <section id="settings-reminder" className="settings-card settings-section">
+                <h2 className="card-header">{t('settings.reminder.title')}</h2>
+
+              <div className="form-group">
+                <label htmlFor="reminderMode">{t('settings.reminder.mode')}</label>
+                <select
+                  id="reminderMode"
+                  className="input"
+                  value={localSettings.reminderMode}
+                  onChange={(e) => {
+                    const next = {
+                      ...localSettings,
+                      reminderMode: e.target.value as 'fullscreen' | 'floating',
+                    } as SettingsType;
+                    setLocalSettings(next);
+                    saveSettingsAuto(next);
+                  }}
+                >
+                  <option value="fullscreen">{t('settings.reminder.fullscreen')}</option>
+                  <option value="floating">{t('settings.reminder.floating')}</option>
+                </select>
+              </div>
+
+              {localSettings.reminderMode === 'floating' && (
+                <div className="form-group">
+                  <label htmlFor="floatingPosition">
+                    {t('settings.reminder.floatingPosition')}
+                  </label>
+                  <select
+                    id="floatingPosition"
+                    className="input"
+                    value={localSettings.floatingPosition}
+                    onChange={(e) => {
+                      const next = {
+                        ...localSettings,
+                        floatingPosition: e.target.value as SettingsType['floatingPosition'],
+                      } as SettingsType;
+                      setLocalSettings(next);
+                      saveSettingsAuto(next);
+                    }}
+                  >
+                    {FLOATING_POSITION_OPTIONS.map((option) => (
+                      <option key={option.value} value={option.value}>
+                        {t(option.labelKey)}
+                      </option>
+                    ))}
+                  </select>
+                </div>
+              )}
+
+              {localSettings.reminderMode === 'fullscreen' && (
+                <div className="form-group">
+                  <label htmlFor="reminderFullscreenDisplay">
+                    {t('settings.reminder.display')}
+                  </label>
+                  <select
+                    id="reminderFullscreenDisplay"
+                    className="input"
+                    value={localSettings.reminderFullscreenDisplay}
+                    onChange={(e) => {
+                      const next = {
+                        ...localSettings,
+                        reminderFullscreenDisplay:
+                          e.target.value as SettingsType['reminderFullscreenDisplay'],
+                      } as SettingsType;
+                      setLocalSettings(next);
+                      saveSettingsAuto(next);
+                    }}
+                  >
+                    <option value="scene">{t('settings.reminder.displayScene')}</option>
+                    <option value="panel">{t('settings.reminder.displayPanel')}</option>
+                  </select>
+                </div>
+              )}
+
+              {isWindows && (
+                <div className="form-group">
+                  <label htmlFor="remoteSessionBehavior">
+                    {t('settings.reminder.remoteSession.label')}
+                  </label>
+                  <select
+                    id="remoteSessionBehavior"
+                    className="input"
+                    value={localSettings.remoteSessionBehavior}
+                    onChange={(e) => {
+                      const next = {
+                        ...localSettings,
+                        remoteSessionBehavior:
+                          e.target.value as SettingsType['remoteSessionBehavior'],
+                      } as SettingsType;
+                      setLocalSettings(next);
+                      saveSettingsAuto(next);
+                    }}
+                  >
+                    <option value="normal">{t('settings.reminder.remoteSession.normal')}</option>
+                    <option value="floating-only">
+                      {t('settings.reminder.remoteSession.floatingOnly')}
+                    </option>
+                    <option value="notifications-only">
+                      {t('settings.reminder.remoteSession.notificationsOnly')}
+                    </option>
+                    <option value="suppress">{t('settings.reminder.remoteSession.suppress')}</option>
+                  </select>
+                  <p className="helper-text">{t('settings.reminder.remoteSession.hint')}</p>
+                </div>
+              )}
+
+              <div className="form-group toggle-group">
+                <label className="toggle-row">
+                  <span className="toggle-text">{t('settings.reminder.clickThrough.enable')}</span>
+                  <span className="switch">
+                    <input
+                      type="checkbox"
+                      checked={localSettings.reminderClickThrough}
+                      disabled={
+                        localSettings.enableForceBreak || localSettings.requireBreakAcknowledgment
+                      }
+                      onChange={(e) => {
+                        const next = { ...localSettings, reminderClickThrough: e.target.checked };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                    />
+                    <span className="slider" />
+                  </span>
+                </label>
+                <p className="helper-text">{t('settings.reminder.clickThrough.description')}</p>
+              </div>
+
+              <div className="form-group toggle-group">
+                <label className="toggle-row">
+                  <span className="toggle-text">
+                    {t('settings.reminder.restStartSoonNotification.enable')}
+                  </span>
+                  <span className="switch">
+                    <input
+                      type="checkbox"
+                      checked={localSettings.restStartSoonNotificationEnabled}
+                      onChange={(e) => {
+                        const next = {
+                          ...localSettings,
+                          restStartSoonNotificationEnabled: e.target.checked,
+                        };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                    />
+                    <span className="slider" />
+                  </span>
+                </label>
+                <p className="helper-text">
+                  {t('settings.reminder.restStartSoonNotification.description')}
+                </p>
+              </div>
+
+              <div className="form-group toggle-group">
+                <label className="toggle-row">
+                  <span className="toggle-text">
+                    {t('settings.reminder.breakEndNotification.enable')}
+                  </span>
+                  <span className="switch">
+                    <input
+                      type="checkbox"
+                      checked={localSettings.breakEndNotificationEnabled}
+                      onChange={(e) => {
+                        const next = {
+                          ...localSettings,
+                          breakEndNotificationEnabled: e.target.checked,
+                        };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                    />
+                    <span className="slider" />
+                  </span>
+                </label>
+                <p className="helper-text">
+                  {t('settings.reminder.breakEndNotification.description')}
+                </p>
+              </div>
+
+              <div className="form-group toggle-group">
+                <label className="toggle-row">
+                  <span className="toggle-text">
+                    {t('settings.reminder.notificationActions.restStartSoon')}
+                  </span>
+                  <span className="switch">
+                    <input
+                      type="checkbox"
+                      checked={localSettings.notificationActions.restStartSoon}
+                      onChange={(e) => {
+                        const next = {
+                          ...localSettings,
+                          notificationActions: {
+                            ...localSettings.notificationActions,
+                            restStartSoon: e.target.checked,
+                          },
+                        };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                    />
+                    <span className="slider" />
+                  </span>
+                </label>
+                <label className="toggle-row">
+                  <span className="toggle-text">
+                    {t('settings.reminder.notificationActions.breakEnd')}
+                  </span>
+                  <span className="switch">
+                    <input
+                      type="checkbox"
+                      checked={localSettings.notificationActions.breakEnd}
+                      onChange={(e) => {
+                        const next = {
+                          ...localSettings,
+                          notificationActions: {
+                            ...localSettings.notificationActions,
+                            breakEnd: e.target.checked,
+                          },
+                        };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                    />
+                    <span className="slider" />
+                  </span>
+                </label>
+                <p className="helper-text">
+                  {t('settings.reminder.notificationActions.description')}
+                </p>
+              </div>
+
+              <div className="form-group toggle-group">
+                {SOUND_EVENT_OPTIONS.map((option) => (
+                  <label className="toggle-row" key={option.value}>
+                    <span className="toggle-text">{t(option.labelKey)}</span>
+                    <span className="switch">
+                      <input
+                        type="checkbox"
+                        checked={localSettings.soundEvents[option.value]}
+                        onChange={(e) => {
+                          const next = {
+                            ...localSettings,
+                            soundEvents: {
+                              ...localSettings.soundEvents,
+                              [option.value]: e.target.checked,
+                            },
+                          };
+                          setLocalSettings(next);
+                          saveSettingsAuto(next);
+                        }}
+                      />
+                      <span className="slider" />
+                    </span>
+                  </label>
+                ))}
+                <p className="helper-text">
+                  {t('settings.reminder.soundEvents.description')}
+                </p>
+              </div>
+
+              <div className="form-group">
+                <p className="helper-text">{t('settings.reminder.testOutput.description')}</p>
+                <button
+                  type="button"
+                  className="btn btn-secondary"
+                  onClick={handleTestOutput}
+                  disabled={isTestingOutput}
+                >
+                  {t('settings.reminder.testOutput.button')}
+                </button>
+                {outputTest && (
+                  <ul className="helper-text">
+                    <li>
+                      {t('settings.reminder.testOutput.sound')}: {describeOutputCheck(outputTest.sound)}
+                    </li>
+                    <li>
+                      {t('settings.reminder.testOutput.notification')}:{' '}
+                      {describeOutputCheck(outputTest.notification)}
+                    </li>
+                    <li>
+                      {t('settings.reminder.testOutput.preview')}:{' '}
+                      {describeOutputCheck(outputTest.preview)}
+                    </li>
+                  </ul>
+                )}
+              </div>
+
+              <h3 className="card-subtitle">{t('settings.reminder.restMusic.title')}</h3>
+
+              <div className="form-group toggle-group">
+                <label className="toggle-row">
+                  <span className="toggle-text">{t('settings.reminder.restMusic.enable')}</span>
+                  <span className="switch">
+                    <input
+                      type="checkbox"
+                      checked={localSettings.restMusicEnabled}
+                      onChange={(e) => {
+                        const next = { ...localSettings, restMusicEnabled: e.target.checked };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                    />
+                    <span className="slider" />
+                  </span>
+                </label>
+                <p className="helper-text">{t('settings.reminder.restMusic.description')}</p>
+              </div>
+
+              <div className="form-group">
+                <label>{t('settings.reminder.restMusic.directory')}</label>
+                <button
+                  type="button"
+                  className="link-button directory-display"
+                  onClick={handleOpenMusicDirectory}
+                  disabled={!localSettings.restMusicDirectory}
+                >
+                  {localSettings.restMusicDirectory || t('settings.reminder.restMusic.directoryMissing')}
+                </button>
+              </div>
+              </section>
+            )}
+
+            {/* Appearance Settings */}
+            {activeSection === 'appearance' && (
+              <section id="settings-appearance" className="settings-card settings-section">
+                <h2 className="card-header">{t('settings.appearance.title')}</h2>
+
+              <div className="form-group">
+                <label htmlFor="theme">{t('settings.appearance.theme')}</label>
+                <select
+                  id="theme"
+                  className="input"
+                  value={localSettings.theme}
+                  onChange={(e) => {
+                    const next = {
+                      ...localSettings,
+                      theme: e.target.value as 'light' | 'dark' | 'auto',
+                    } as SettingsType;
+                    setLocalSettings(next);
+                    saveSettingsAuto(next);
+                  }}
+                >
+                  <option value="light">{t('settings.appearance.light')}</option>
+                  <option value="dark">{t('settings.appearance.dark')}</option>
+                  <option value="auto">{t('settings.appearance.auto')}</option>
+                </select>
+              </div>
+              </section>
+            )}
+
+            {/* System Settings */}
+            {activeSection === 'system' && (
+              <section id="settings-system" className="settings-card settings-section">
+                <h2 className="card-header">{t('settings.system.title')}</h2>
+
+              <div className="form-group toggle-group">
+                <label className="toggle-row">
+                  <span className="toggle-text">{t('settings.system.autostart')}</span>
+                  <span className="switch">
+                    <input
+                      type="checkbox"
+                      checked={localSettings.autostart}
+                      onChange={(e) => {
+                        const enabled = e.target.checked;
+                        const next = {
+                          ...localSettings,
+                          autostart: enabled,
+                          silentAutostart: enabled ? localSettings.silentAutostart : false,
+                        };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                    />
+                    <span className="slider" />
+                  </span>
+                </label>
+              </div>
+
+              <div className="form-group toggle-group">
+                <label className="toggle-row">
+                  <span className="toggle-text">{t('settings.system.silentAutostart')}</span>
+                  <span className="switch">
+                    <input
+                      type="checkbox"
+                      checked={localSettings.silentAutostart}
+                      disabled={!localSettings.autostart}
+                      onChange={(e) => {
+                        const next = {
+                          ...localSettings,
+                          silentAutostart: e.target.checked,
+                        };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                    />
+                    <span className="slider" />
+                  </span>
+                </label>
+                <p className="helper-text">{t('settings.system.silentAutostartHint')}</p>
+              </div>
+
+              <div className="form-group toggle-group">
+                <label className="toggle-row">
+                  <span className="toggle-text">{t('settings.system.autoStartOnFirstActivity')}</span>
+                  <span className="switch">
+                    <input
+                      type="checkbox"
+                      checked={localSettings.autoStartOnFirstActivity}
+                      onChange={(e) => {
+                        const next = {
+                          ...localSettings,
+                          autoStartOnFirstActivity: e.target.checked,
+                        };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                    />
+                    <span className="slider" />
+                  </span>
+                </label>
+                <p className="helper-text">
+                  {t('settings.system.autoStartOnFirstActivityHint')}
+                </p>
+              </div>
+
+              {isWindows && (
+                <div className="form-group toggle-group">
+                  <label className="toggle-row">
+                    <span className="toggle-text">{t('settings.system.deferBreaksDuringCalls')}</span>
+                    <span className="switch">
+                      <input
+                        type="checkbox"
+                        checked={localSettings.deferBreaksDuringCalls}
+                        onChange={(e) => {
+                          const next = {
+                            ...localSettings,
+                            deferBreaksDuringCalls: e.target.checked,
+                          };
+                          setLocalSettings(next);
+                          saveSettingsAuto(next);
+                        }}
+                      />
+                      <span className="slider" />
+                    </span>
+                  </label>
+                  <p className="helper-text">
+                    {t('settings.system.deferBreaksDuringCallsHint')}
+                  </p>
+                </div>
+              )}
+
+              <div className="form-group toggle-group">
+                <label className="toggle-row">
+                  <span className="toggle-text">{t('settings.system.analytics.disable')}</span>
+                  <span className="switch">
+                    <input
+                      type="checkbox"
+                      checked={localSettings.disableAnalytics}
+                      onChange={(e) => {
+                        const next = { ...localSettings, disableAnalytics: e.target.checked };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                    />
+                    <span className="slider" />
+                  </span>
+                </label>
+                <p className="helper-text">{t('settings.system.analytics.disableHint')}</p>
+              </div>
+
+              <div className="form-group toggle-group">
+                <label className="toggle-row">
+                  <span className="toggle-text">{t('settings.system.lifetimeStats.enable')}</span>
+                  <span className="switch">
+                    <input
+                      type="checkbox"
+                      checked={localSettings.lifetimeStatsEnabled}
+                      onChange={(e) => {
+                        const next = { ...localSettings, lifetimeStatsEnabled: e.target.checked };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                    />
+                    <span className="slider" />
+                  </span>
+                </label>
+                <p className="helper-text">{t('settings.system.lifetimeStats.hint')}</p>
+              </div>
+
+              <div className="form-group toggle-group">
+                <label className="toggle-row">
+                  <span className="toggle-text">{t('settings.system.hooks.allow')}</span>
+                  <span className="switch">
+                    <input
+                      type="checkbox"
+                      checked={localSettings.allowHooks}
+                      onChange={(e) => {
+                        const next = { ...localSettings, allowHooks: e.target.checked };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                    />
+                    <span className="slider" />
+                  </span>
+                </label>
+                <p className="helper-text">{t('settings.system.hooks.allowHint')}</p>
+              </div>
+
+              {localSettings.allowHooks && (
+                <>
+                  <div className="form-group">
+                    <label htmlFor="onWorkStartCommand">
+                      {t('settings.system.hooks.onWorkStart')}
+                    </label>
+                    <input
+                      id="onWorkStartCommand"
+                      className="input"
+                      type="text"
+                      value={localSettings.onWorkStartCommand}
+                      onChange={(e) =>
+                        setLocalSettings({ ...localSettings, onWorkStartCommand: e.target.value })
+                      }
+                      onBlur={() => saveSettingsAuto(localSettings)}
+                    />
+                  </div>
+                  <div className="form-group">
+                    <label htmlFor="onBreakStartCommand">
+                      {t('settings.system.hooks.onBreakStart')}
+                    </label>
+                    <input
+                      id="onBreakStartCommand"
+                      className="input"
+                      type="text"
+                      value={localSettings.onBreakStartCommand}
+                      onChange={(e) =>
+                        setLocalSettings({ ...localSettings, onBreakStartCommand: e.target.value })
+                      }
+                      onBlur={() => saveSettingsAuto(localSettings)}
+                    />
+                  </div>
+                </>
+              )}
+
+              <div className="form-group">
+                <label htmlFor="webhookUrl">{t('settings.system.webhook.label')}</label>
+                <input
+                  id="webhookUrl"
+                  className="input"
+                  type="url"
+                  placeholder="https://"
+                  value={localSettings.webhookUrl ?? ''}
+                  onChange={(e) => {
+                    setWebhookStatus(null);
+                    setLocalSettings({ ...localSettings, webhookUrl: e.target.value || null });
+                  }}
+                  onBlur={() => saveSettingsAuto(localSettings)}
+                />
+                <p className="helper-text">{t('settings.system.webhook.hint')}</p>
+                <button
+                  type="button"
+                  className="btn btn-secondary"
+                  onClick={handleTestWebhook}
+                  disabled={isTestingWebhook || !localSettings.webhookUrl?.trim()}
+                >
+                  {t('settings.system.webhook.test')}
+                </button>
+                {webhookStatus !== null && (
+                  <p className="helper-text">
+                    {t('settings.system.webhook.status', { status: webhookStatus })}
+                  </p>
+                )}
+              </div>
+
+              <div className="form-group">
+                <label htmlFor="storageFormat">{t('settings.system.storageFormat.label')}</label>
+                <select
+                  id="storageFormat"
+                  className="input"
+                  value={localSettings.storageFormat}
+                  onChange={(e) => {
+                    const next = {
+                      ...localSettings,
+                      storageFormat: e.target.value as StorageFormat,
+                    };
+                    setLocalSettings(next);
+                    saveSettingsAuto(next);
+                  }}
+                >
+                  <option value="json">{t('settings.system.storageFormat.json')}</option>
+                  <option value="ndjson">{t('settings.system.storageFormat.ndjson')}</option>
+                </select>
+                <p className="helper-text">{t('settings.system.storageFormat.hint')}</p>
+              </div>
+
+              <div className="form-group">
+                <label htmlFor="analyticsLoadWindowDays">
+                  {t('settings.system.analyticsLoadWindowDays')}
+                </label>
+                <input
+                  id="analyticsLoadWindowDays"
+                  type="number"
+                  className="input"
+                  value={localSettings.analyticsLoadWindowDays}
+                  onChange={(e) => {
+                    const value = parseInt(e.target.value);
+                    setLocalSettings({ ...localSettings, analyticsLoadWindowDays: value });
+                  }}
+                  onBlur={(e) => {
+                    let value = parseInt(e.target.value);
+                    if (Number.isNaN(value)) value = localSettings.analyticsLoadWindowDays;
+                    value = Math.max(0, Math.min(3650, value));
+                    const next = { ...localSettings, analyticsLoadWindowDays: value };
+                    setLocalSettings(next);
+                    saveSettingsAuto(next);
+                  }}
+                  min={0}
+                  max={3650}
+                />
+                <p className="helper-text">{t('settings.system.analyticsLoadWindowDaysHint')}</p>
+              </div>
+
+              {isWindows && (
+                <div className="form-group toggle-group">
+                  <label className="toggle-row">
+                    <span className="toggle-text">
+                      {t('settings.system.autoSilentUpdate')}
+                    </span>
+                    <span className="switch">
+                      <input
+                        type="checkbox"
+                        checked={localSettings.autoSilentUpdateEnabled}
+                        onChange={(e) => {
+                          const next = {
+                            ...localSettings,
+                            autoSilentUpdateEnabled: e.target.checked,
+                          };
+                          setLocalSettings(next);
+                          saveSettingsAuto(next);
+                        }}
+                      />
+                      <span className="slider" />
+                    </span>
+                  </label>
+                  <p className="helper-text">{t('settings.system.autoSilentUpdateHint')}</p>
+                </div>
+              )}
+
+              {isWindows && localSettings.autoSilentUpdateEnabled && (
+                <div className="form-group toggle-group">
+                  <label className="toggle-row">
+                    <span className="toggle-text">{t('settings.system.autoInstallUpdates')}</span>
+                    <span className="switch">
+                      <input
+                        type="checkbox"
+                        checked={localSettings.autoInstallUpdates}
+                        onChange={(e) => {
+                          const next = {
+                            ...localSettings,
+                            autoInstallUpdates: e.target.checked,
+                          };
+                          setLocalSettings(next);
+                          saveSettingsAuto(next);
+                        }}
+                      />
+                      <span className="slider" />
+                    </span>
+                  </label>
+                  <p className="helper-text">{t('settings.system.autoInstallUpdatesHint')}</p>
+                </div>
+              )}
+
+              <div className="form-group toggle-group">
+                <label className="toggle-row">
+                  <span className="toggle-text">{t('settings.system.meteredMode')}</span>
+                  <span className="switch">
+                    <input
+                      type="checkbox"
+                      checked={localSettings.meteredMode}
+                      onChange={(e) => {
+                        const next = {
+                          ...localSettings,
+                          meteredMode: e.target.checked,
+                        };
+                        setLocalSettings(next);
+                        saveSettingsAuto(next);
+                      }}
+                    />
+                    <span className="slider" />
+                  </span>
+                </label>
+                <p className="helper-text">{t('settings.system.meteredModeHint')}</p>
+              </div>
+
+              {isWindows && (
+                <div className="form-group toggle-group">
+                  <label className="toggle-row">
+                    <span className="toggle-text">{t('settings.system.autoDetectMetered')}</span>
+                    <span className="switch">
+                      <input
+                        type="checkbox"
+                        checked={localSettings.autoDetectMetered}
+                        onChange={(e) => {
+                          const next = {
+                            ...localSettings,
+                            autoDetectMetered: e.target.checked,
+                          };
+                          setLocalSettings(next);
+                          saveSettingsAuto(next);
+                        }}
+                      />
+                      <span className="slider" />
+                    </span>
+                  </label>
+                  <p className="helper-text">{t('settings.system.autoDetectMeteredHint')}</p>
+                </div>
+              )}
 
               <h3 className="card-subtitle">{t('settings.system.dataTransfer.title')}</h3>
               <div className="form-group">
@@ -1105,8 +2124,62 @@ export function Settings() {
                     {t('settings.system.dataTransfer.export')}
                   </button>
                 </div>
+                <p className="helper-text">{t('settings.system.dataTransfer.exportAllHint')}</p>
+                <div className="data-transfer-actions">
+                  <button
+                    type="button"
+                    className="btn btn-secondary"
+                    onClick={handleExportAllData}
+                    disabled={isTransferringData}
+                  >
+                    {t('settings.system.dataTransfer.exportAll')}
+                  </button>
+                </div>
               </div>
 
+              {encryptionStatus && (
+                <>
+                  <h3 className="card-subtitle">{t('encryption.title')}</h3>
+                  <div className="form-group">
+                    <p className="helper-text">
+                      {encryptionStatus.enabled ? t('encryption.statusOn') : t('encryption.statusOff')}
+                    </p>
+                    <p className="helper-text">{t('encryption.hint')}</p>
+                    <input
+                      type="password"
+                      className="input"
+                      value={encryptionPassphrase}
+                      onChange={(e) => setEncryptionPassphrase(e.target.value)}
+                      placeholder={t('encryption.passphrase')}
+                      aria-label={t('encryption.passphrase')}
+                      disabled={encryptionStatus.locked}
+                    />
+                    {!encryptionStatus.enabled && (
+                      <input
+                        type="password"
+                        className="input"
+                        value={encryptionConfirm}
+                        onChange={(e) => setEncryptionConfirm(e.target.value)}
+                        placeholder={t('encryption.confirmPassphrase')}
+                        aria-label={t('encryption.confirmPassphrase')}
+                      />
+                    )}
+                    <div className="data-transfer-actions">
+                      <button
+                        type="button"
+                        className={encryptionStatus.enabled ? 'btn btn-secondary' : 'btn'}
+                        onClick={handleToggleEncryption}
+                        disabled={
+                          isChangingEncryption || encryptionStatus.locked || !encryptionPassphrase
+                        }
+                      >
+                        {encryptionStatus.enabled ? t('encryption.disable') : t('encryption.enable')}
+                      </button>
+                    </div>
+                  </div>
+                </>
+              )}
+
               <h3 className="card-subtitle">{t('settings.system.analytics.dangerTitle')}</h3>
               <div className="form-group">
                 <p className="helper-text">{t('settings.system.analytics.clearDescription')}</p>
@@ -1126,27 +2199,48 @@ export function Settings() {
             {activeSection === 'language' && (
               <section id="settings-language" className="settings-card settings-section">
                 <h2 className="card-header">{t('settings.language.title')}</h2>
-
-              <div className="form-group">
-                <label htmlFor="language">{t('settings.language.select')}</label>
-                <select
-                  id="language"
-                  className="input"
-                  value={localSettings.language}
-                  onChange={(e) => {
-                    const newLang = e.target.value as Language;
-                    const next = { ...localSettings, language: newLang } as SettingsType;
-                    setLocalSettings(next);
-                    saveSettingsAuto(next);
-                  }}
-                >
-                  {LANGUAGE_OPTIONS.map(({ value, labelKey }) => (
-                    <option key={value} value={value}>
-                      {t(labelKey)}
-                    </option>
-                  ))}
-                </select>
-              </div>
+
+              <div className="form-group">
+                <label htmlFor="language">{t('settings.language.select')}</label>
+                <select
+                  id="language"
+                  className="input"
+                  value={localSettings.language}
+                  onChange={(e) => {
+                    const newLang = e.target.value as Language;
+                    const next = { ...localSettings, language: newLang } as SettingsType;
+                    setLocalSettings(next);
+                    saveSettingsAuto(next);
+                  }}
+                >
+                  {LANGUAGE_OPTIONS.map(({ value, labelKey }) => (
+                    <option key={value} value={value}>
+                      {t(labelKey)}
+                    </option>
+                  ))}
+                </select>
+              </div>
+
+              <div className="form-group">
+                <label htmlFor="weekStartDay">{t('settings.language.weekStartDay.label')}</label>
+                <select
+                  id="weekStartDay"
+                  className="input"
+                  value={localSettings.weekStartDay}
+                  onChange={(e) => {
+                    const next = {
+                      ...localSettings,
+                      weekStartDay: e.target.value as WeekStartDay,
+                    };
+                    setLocalSettings(next);
+                    saveSettingsAuto(next);
+                  }}
+                >
+                  <option value="monday">{t('settings.language.weekStartDay.monday')}</option>
+                  <option value="sunday">{t('settings.language.weekStartDay.sunday')}</option>
+                </select>
+                <p className="helper-text">{t('settings.language.weekStartDay.hint')}</p>
+              </div>
               </section>
             )}
 
@@ -1253,18 +2347,18 @@ export function Settings() {
                 </div>
               </div>
             )}
-
-            {/* Actions: 仅保留重置，移除手动保存/导出/导入 */}
-            <div className="settings-actions">
-              <button className="btn btn-secondary" onClick={handleReset}>
-                {t('settings.actions.reset')}
-              </button>
-            </div>
-
-            {message && <div className="message">{message}</div>}
-          </div>
-        </div>
-      </div>
-    </div>
-  );
-}
+
+            {/* Actions: 仅保留重置，移除手动保存/导出/导入 */}
+            <div className="settings-actions">
+              <button className="btn btn-secondary" onClick={handleReset}>
+                {t('settings.actions.reset')}
+              </button>
+            </div>
+
+            {message && <div className="message">{message}</div>}
+          </div>
+        </div>
+      </div>
+    </div>
+  );
+}
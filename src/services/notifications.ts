@@ -65,6 +65,30 @@ export async function notifyAchievementUnlocked(title: string, body: string): Pr
   }
 }
 
+export async function notifyHydrationReminder(title: string, body: string): Promise<void> {
+  if (!(await ensureNotificationPermission())) {
+    return;
+  }
+
+  try {
+    sendNotification({ title, body });
+  } catch (error) {
+    console.warn('Failed to send hydration notification:', error);
+  }
+}
+
+export async function notifyStorageError(title: string, body: string): Promise<void> {
+  if (!(await ensureNotificationPermission())) {
+    return;
+  }
+
+  try {
+    sendNotification({ title, body });
+  } catch (error) {
+    console.warn('Failed to send storage error notification:', error);
+  }
+}
+
 export async function notifyRestStartsSoon(
   title: string,
   body: string,
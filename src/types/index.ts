@@ -6,14 +6,196 @@ export type Theme = 'light' | 'dark' | 'auto';
 export type Language = 'en-US' | 'en-GB' | 'zh-CN' | 'zh-TW';
 export type ReminderMode = 'fullscreen' | 'floating';
 export type ReminderFullscreenDisplay = 'scene' | 'panel';
+/** Reminder behaviour inside a remote desktop (RDP) session */
+export type RemoteSessionBehavior = 'normal' | 'floating-only' | 'notifications-only' | 'suppress';
+export type MainWindowBreakAction = 'hide' | 'minimize';
+export type ScheduleCompleteAction = 'loop' | 'stop';
 export type FloatingPosition = 'top-left' | 'top-right' | 'bottom-left' | 'bottom-right';
-export type TimerPhase = 'work' | 'break' | 'idle';
+// 'transition' is the optional back-to-work countdown after a break
+export type TimerPhase = 'work' | 'break' | 'idle' | 'transition';
 export type TimerState = 'running' | 'paused' | 'stopped';
+export type PhaseChangeCause =
+  | 'startup'
+  | 'user-start'
+  | 'user-skip'
+  | 'auto-cycle'
+  | 'suppression'
+  | 'flow-mode'
+  | 'resume-from-idle'
+  | 'first-activity'
+  | 'reminder-closed'
+  | 'recovery';
+
+/**
+ * Payload of the `phase-change` event
+ */
+export interface PhaseChange {
+  phase: TimerPhase;
+  cause: PhaseChangeCause;
+}
+
+/**
+ * Payload of the `break-acknowledged` event
+ */
+export interface BreakAcknowledged {
+  sessionId: string;
+}
+
+/**
+ * Payload of the `day-started` event
+ */
+export interface DayStarted {
+  startedAt: string; // first work session of the local day
+}
+
+/**
+ * Payload of the `clock-changed` event
+ */
+export interface ClockChanged {
+  driftSeconds: number; // negative = the clock went backwards
+}
+
+/**
+ * Payload of the `connectivity-changed` event
+ */
+export interface ConnectivityChanged {
+  online: boolean;
+}
+
+/**
+ * Payload of the `settings-changed` event
+ */
+export interface SettingsChanged {
+  keys: string[]; // top-level settings fields that changed
+}
+
+/**
+ * Payload of the `suppression-expiring` event
+ */
+export interface SuppressionExpiring {
+  until: string; // ISO date string
+  remainingSeconds: number;
+}
+
+export interface WorkNotePrompt {
+  sessionId: string;
+}
+
+/**
+ * Global keyboard shortcuts (accelerator strings, empty = disabled)
+ */
+export interface HotkeySettings {
+  openTrayMenu: string;
+  /** Closes every reminder window from the backend, even when the page hangs */
+  emergencyDismiss: string;
+}
 
 export interface WorkSegment {
   workMinutes: number;
   breakMinutes: number;
   repeat: number;
+  /** Optional name, recorded on the segment's sessions (up to 40 characters) */
+  label?: string | null;
+}
+
+/** Durations for a range of local time; `end` before `start` runs past midnight */
+/** Local time range during which background network calls are deferred */
+export interface NetworkQuietHours {
+  start: string; // "HH:MM"
+  end: string; // "HH:MM"; earlier than start = runs past midnight
+  /** ISO weekdays (1 = Monday … 7 = Sunday); empty = every day */
+  days: number[];
+}
+
+export interface TimeAnchoredSegment {
+  start: string; // "HH:MM"
+  end: string; // "HH:MM"
+  workMinutes: number;
+  breakMinutes: number;
+}
+
+/**
+ * A named work/break configuration the user can switch to
+ */
+export interface TimerPreset {
+  name: string;
+  workDuration: number; // in minutes
+  breakDuration: number; // in minutes
+  segmentedWorkEnabled: boolean;
+  workSegments: WorkSegment[];
+}
+
+export type StorageFormat = 'json' | 'ndjson';
+
+/** 'sequential' = 000042, 'daily' = 20240105-003 (both after the prefix) */
+export type SessionIdScheme = 'uuid' | 'sequential' | 'daily';
+
+/** First day of the week for weekly ranges */
+export type WeekStartDay = 'monday' | 'sunday';
+
+/**
+ * Which notifications carry action buttons (Windows toasts only)
+ */
+export interface NotificationActionSettings {
+  restStartSoon: boolean;
+  breakEnd: boolean;
+}
+
+/**
+ * Thresholds for flagging odd sessions in analytics
+ */
+export interface AnomalySettings {
+  breakOverrunRatio: number; // break ran this many times its planned length
+  maxWorkMinutes: number; // work sessions longer than this
+  flagZeroDuration: boolean;
+}
+
+/**
+ * Daily goal and weights of the focus score; weights count relative to their sum
+ */
+export interface FocusScoreSettings {
+  dailyWorkGoalMinutes: number;
+  workGoalWeight: number;
+  breakCompletionWeight: number;
+  lowSkipsWeight: number;
+}
+
+/**
+ * Hydration tracker: daily goal and reminder cadence (minutes of work, 0 = no reminders)
+ */
+export interface HydrationSettings {
+  enabled: boolean;
+  dailyGoalGlasses: number;
+  reminderIntervalMinutes: number;
+}
+
+/**
+ * Today's glasses of water; payload of `hydration-reminder` and `hydration-changed`
+ */
+export interface HydrationProgress {
+  date: string;
+  count: number;
+  goal: number;
+}
+
+/**
+ * Which transitions play the chime (only while `playSound` is on)
+ */
+export interface SoundEventSettings {
+  workEnd: boolean;
+  breakStart: boolean;
+  breakEnd: boolean;
+}
+
+/**
+ * Gentler cadence shortly before the end of the work day
+ */
+export interface WindDownSettings {
+  enabled: boolean;
+  endOfDay: string; // "HH:MM" local time
+  startsBeforeEndMinutes: number;
+  workMinutes: number;
+  breakMinutes: number;
 }
 
 /**
@@ -24,20 +206,60 @@ export interface Settings {
   workDuration: number; // in minutes
   breakDuration: number; // in minutes
   enableForceBreak: boolean;
+  maxReminderMinutes: number;
+  /** Keep the reminder up after a break until the user clicks or presses a key */
+  requireBreakAcknowledgment: boolean;
+  maxSuppressionMinutes: number | null;
+  /** Warn this many minutes before a break suppression ends (0 = off, max 60) */
+  suppressionExpiryWarningMinutes: number;
   flowModeEnabled: boolean;
   moreRestEnabled: boolean;
   segmentedWorkEnabled: boolean;
   workSegments: WorkSegment[];
+  /** Pick durations by time of day instead of sequence; exclusive with segmented work */
+  timeAnchoredSegments: TimeAnchoredSegment[];
+  programRepeat: number | null;
+  presets: TimerPreset[];
+  // Name of the applied preset; null once durations are edited by hand
+  activePreset: string | null;
+  windDown: WindDownSettings;
+  onScheduleComplete: ScheduleCompleteAction;
+  /** Randomize each work block by up to ± this many minutes (0 = off) */
+  breakJitterMinutes: number;
+  /** Seed for randomized scheduling; set for reproducible runs */
+  randomSeed: number | null;
+  catchUpBreakEnabled: boolean;
+  /** A break interrupted by display-off/suspend for this long counts as done; null = remaining break only */
+  longAbsenceMinutes: number | null;
+  /** Ignore display-off and suspend/resume; the timer runs on wall-clock time */
+  travelMode: boolean;
+  /** Extends allowed per phase (0 = none); null = unlimited */
+  maxExtendsPerPhase: number | null;
+  /** Extra minutes allowed per phase across all extends; null = unlimited */
+  maxTotalExtendMinutes: number | null;
+  /** Back-to-work countdown after a break, in seconds (0 = off, max 30) */
+  workStartCountdownSeconds: number;
 
   // Reminder settings
   reminderMode: ReminderMode;
   reminderFullscreenDisplay: ReminderFullscreenDisplay;
   floatingPosition: FloatingPosition;
   opacity: number; // 0-100
+  // Clicks pass through reminder windows to the app beneath (not with force break)
+  reminderClickThrough: boolean;
+  // Re-checked at every break; only detected on Windows
+  remoteSessionBehavior: RemoteSessionBehavior;
   playSound: boolean;
+  soundEvents: SoundEventSettings;
   restMusicEnabled: boolean;
   restMusicDirectory: string;
   restStartSoonNotificationEnabled: boolean;
+  breakEndNotificationEnabled: boolean;
+  notificationActions: NotificationActionSettings;
+  promptWorkNote: boolean;
+  workNoteInFlowMode: boolean;
+  hideMainWindowDuringBreak: boolean;
+  mainWindowBreakAction: MainWindowBreakAction;
 
   // Appearance
   theme: Theme;
@@ -46,9 +268,68 @@ export interface Settings {
   autostart: boolean;
   silentAutostart: boolean;
   autoSilentUpdateEnabled: boolean;
+  /** Install background updates once downloaded; off = wait for confirmation */
+  autoInstallUpdates: boolean;
   minimizeToTray: boolean;
   closeToTray: boolean;
+  alwaysOnTop: boolean;
+  forceHighContrast: boolean;
+  forceReducedMotion: boolean;
   disableAnalytics: boolean;
+  /** Keep the local all-time rollup used by getLifetimeStats (never leaves the device) */
+  lifetimeStatsEnabled: boolean;
+  /** Run the phase commands below; always off after importing a configuration */
+  allowHooks: boolean;
+  /** Shell commands run when work / a break starts (empty = none) */
+  onWorkStartCommand: string;
+  onBreakStartCommand: string;
+  /** URL POSTed a JSON payload on each phase transition; null/empty = off */
+  webhookUrl: string | null;
+  /** Quote sources tried in order; the first with a quote wins */
+  quoteProviders: QuoteProvider[];
+  /** No quote fetches or update checks during these ranges (cache is used instead) */
+  networkQuietHours: NetworkQuietHours[];
+  /** Treat the connection as metered: no background network calls */
+  meteredMode: boolean;
+  /** Detect metered connections automatically (Windows only) */
+  autoDetectMetered: boolean;
+  /** Exercise categories suggested on the break screen; empty = all */
+  preferredExerciseCategories: string[];
+  /** User exercises that join the break suggestions */
+  customExercises: CustomExercise[];
+  /** Break themes, one per day in turn; empty = no theme */
+  breakThemes: BreakTheme[];
+  /** Session history file format; switching migrates existing records */
+  storageFormat: StorageFormat;
+  /** Days of sessions kept in memory from startup (0 = all); older ones are read on demand */
+  analyticsLoadWindowDays: number;
+  /** How new session IDs are formed; default 'uuid' */
+  sessionIdScheme: SessionIdScheme;
+  sessionIdPrefix: string; // up to 16 letters, digits, '-' or '_'
+  /** Wait for the first sustained input of the day before starting work */
+  autoStartOnFirstActivity: boolean;
+  /** Hold breaks back while the microphone is in use (Windows only) */
+  deferBreaksDuringCalls: boolean;
+  devMode: boolean;
+  minBreakCompletionRatio: number;
+  // Sessions shorter than this many seconds are not saved (0 = keep all)
+  minSessionSeconds: number;
+  /** A break or absence this long (minutes) resets the time at the computer */
+  minRealBreakMinutes: number;
+  /** Seconds between input idle-time samples while working (5-300) */
+  activitySampleSeconds: number;
+  /** A sample counts as active when the last input is at most this many seconds old */
+  activityIdleThresholdSeconds: number;
+  /** Share (%) of the rest debt added to the next break once it builds up; 0 = off */
+  restDebtCompensationPercent: number;
+  /** First day of "this week" in analytics and dashboard progress */
+  weekStartDay: WeekStartDay;
+  anomalyDetection: AnomalySettings;
+  focusScore: FocusScoreSettings;
+  hydration: HydrationSettings;
+  hotkeys: HotkeySettings;
+  /** Action groups each entry point may use; missing sources get their defaults */
+  externalControlPermissions: Partial<Record<ControlSource, ActionGroup[]>>;
 
   // Language
   language: Language;
@@ -68,6 +349,51 @@ export interface UpdateManifest {
   pubDate?: string;
 }
 
+/**
+ * A remote quote source: `pointers` are JSON pointers to the quote text
+ */
+export interface QuoteProvider {
+  name: string;
+  url: string;
+  pointers: string[];
+  language: string | null; // language code prefix, null = all languages
+}
+
+/**
+ * An exercise the user added to the break suggestions
+ */
+export interface CustomExercise {
+  id: string;
+  text: string;
+  durationSeconds: number;
+  category: string; // e.g. 'stretch', 'eyes', 'movement', 'breathing'
+}
+
+/**
+ * A break theme; themes take turns by day
+ */
+export interface BreakTheme {
+  name: string;
+  backgroundImage?: string | null;
+  /** Break texts shown instead of quotes on this theme's day */
+  messages: string[];
+  /** Overrides restMusicDirectory on this theme's day */
+  musicDirectory?: string | null;
+}
+
+/** A downloaded and verified update waiting to be installed */
+export interface StagedUpdate {
+  version: string;
+  notes: string | null;
+  path: string;
+}
+
+export interface UpdateDownloadProgress {
+  version: string;
+  downloaded: number; // bytes
+  total: number | null;
+}
+
 /**
  * Work/Break session record
  */
@@ -81,6 +407,40 @@ export interface Session {
   isSkipped: boolean;
   extendedSeconds: number;
   notes?: string;
+  rating?: number | null; // optional 1-5 self-reported energy/mood
+  inProgress?: boolean; // still running when written (placeholder or checkpoint)
+  anomalyDismissed?: boolean; // anomaly reviewed; no longer reported
+  acknowledged?: boolean | null; // break acknowledged; null when not required
+  activeSeconds?: number | null; // work time with input; null when not tracked
+  segmentLabel?: string | null; // name of the work segment it ran in
+  endReason?: SessionEndReason | null; // null while running and for older records
+}
+
+/** Why a session ended */
+export type SessionEndReason =
+  | 'completed'
+  | 'skipped'
+  | 'absence'
+  | 'power-interrupt'
+  | 'exit'
+  | 'recovery'
+  | 'interrupted';
+
+/** Entry point a control action came from */
+export type ControlSource = 'tray' | 'webview' | 'http' | 'cli' | 'deeplink';
+
+/** Permission groups of control actions */
+export type ActionGroup = 'readStatus' | 'timerControl' | 'settingsWrite' | 'quit';
+
+export type AnomalyKind = 'breakOverrun' | 'longWork' | 'zeroDuration';
+
+/**
+ * A session flagged by the anomaly thresholds
+ */
+export interface SessionAnomaly {
+  sessionId: string;
+  kind: AnomalyKind;
+  detail: string;
 }
 
 export interface AchievementUnlock {
@@ -99,6 +459,35 @@ export interface TimerInfo {
   nextTransitionTime: string | null;
   // 涓嬩竴娆＄湡姝?寮€濮嬩紤鎭?鐨勬椂闂达紙UTC ISO 瀛楃涓诧級锛岃嫢涓嶅彲棰勬祴鍒欎负 null
   nextBreakTime?: string | null;
+  // Earliest time a force break may be dismissed (null when not locked)
+  breakDismissibleAt?: string | null;
+  // Why breaks/reminders are held back right now ('suppression', 'flowMode'); empty when allowed
+  interruptionsBlockedBy?: string[];
+  // Whether the timer runs a segmented program
+  segmented?: boolean;
+  // Name of the applied timer preset, null when configured by hand
+  activePreset?: string | null;
+  // Travel mode: display-off / suspend do not pause the timer
+  travelMode?: boolean;
+  // Finished break whose reminder waits for the user's acknowledgment
+  awaitingBreakAcknowledgment?: boolean;
+  // Seconds at the computer since the last real break (not tied to work sessions)
+  continuousScreenSeconds?: number;
+  // Rest owed in minutes (planned minus taken break time, decaying over time)
+  restDebtMinutes?: number;
+  // Minutes the current break was lengthened by to pay the rest debt back
+  restDebtExtraMinutes?: number;
+  // Mode that decides breaks: flow mode overrides a segmented program
+  effectiveMode?: 'flow' | 'segmented' | 'simple';
+}
+
+/**
+ * Projected phase returned by `simulate_schedule`
+ */
+export interface ScheduledPhase {
+  phase: TimerPhase;
+  start: string; // ISO 8601
+  end: string; // ISO 8601
 }
 
 /**
@@ -110,7 +499,151 @@ export interface AnalyticsData {
   breakCount: number;
   completedBreaks: number;
   skippedBreaks: number;
+  totalExtendedSeconds: number; // time added with extend, in seconds
+  acknowledgedBreaks: number; // breaks that required acknowledgment and got it
+  unacknowledgedBreaks: number;
+  anomalies: SessionAnomaly[]; // not yet dismissed
+  dayStarts: string[]; // first work of each local day (first-activity start)
+  totalActiveSeconds: number; // work time at the keyboard (tracked sessions only)
+  dailyActivity: DailyActivity[];
+  sessions: Session[];
+}
+
+/** Active against elapsed work time for one local day */
+export interface DailyActivity {
+  date: string; // YYYY-MM-DD
+  activeSeconds: number;
+  trackedSeconds: number; // work time of the sessions that tracked activity
+  activityRatio: number | null; // 0-1; null when nothing was tracked
+}
+
+/**
+ * Payload of the `analytics-delta` event: change to the totals caused by one session write
+ */
+export interface AnalyticsDelta {
+  sessionId: string;
+  /** Local day (YYYY-MM-DD) the session starts on */
+  date: string;
+  workSeconds: number;
+  breakSeconds: number;
+  breakCount: number;
+  completedBreaks: number;
+  skippedBreaks: number;
+}
+
+/**
+ * Break reminder text and the source that produced it
+ */
+export interface BreakContent {
+  text: string;
+  source: 'remote' | 'cache' | 'local' | 'theme';
+  /** Today's break theme, if any are configured */
+  theme?: BreakTheme | null;
+  /** Today's hydration progress while the tracker is on */
+  hydration?: HydrationProgress | null;
+}
+
+/**
+ * Exercise suggested for a break, with its text in the requested language
+ */
+export interface BreakExercise {
+  id: string;
+  text: string;
+  durationSeconds: number;
+  category: string;
+  custom: boolean;
+}
+
+export interface DurationBucket {
+  startMinutes: number;
+  endMinutes: number;
+  count: number;
+}
+
+/**
+ * Work and break duration distribution; buckets run contiguously from 0
+ */
+export interface DurationHistogram {
+  bucketMinutes: number;
+  work: DurationBucket[];
+  breaks: DurationBucket[];
+}
+
+export interface RatingBucket {
+  rating: number;
+  sessionCount: number;
+  averageWorkSeconds: number;
+}
+
+/** One part of the focus score */
+export interface FocusScoreComponent {
+  ratio: number; // 0-1
+  weight: number;
+  points: number; // ratio × weight / total weight × 100
+}
+
+/**
+ * Focus score of a day: the weighted average of work time toward the goal
+ * (capped at 1), completed / finished breaks, and 1 - skipped breaks / 4, × 100
+ */
+export interface FocusScore {
+  date: string;
+  score: number;
+  workSeconds: number;
+  goalSeconds: number;
+  breakCount: number;
+  completedBreaks: number;
+  skippedBreaks: number;
+  workGoal: FocusScoreComponent;
+  breakCompletion: FocusScoreComponent;
+  lowSkips: FocusScoreComponent;
+}
+
+export interface RatingStats {
+  ratedSessions: number;
+  averageRating: number | null;
+  buckets: RatingBucket[];
+}
+
+/**
+ * High-contrast / reduced-motion preferences (OS detection plus settings overrides)
+ */
+export interface AccessibilityPrefs {
+  highContrast: boolean;
+  reducedMotion: boolean;
+}
+
+/**
+ * One setting an imported config would change (see previewImport)
+ */
+export interface SettingChange {
+  /** camelCase field path, nested fields joined with '.' */
+  field: string;
+  oldValue: unknown;
+  newValue: unknown;
+}
+
+/** Filters and page of `list_sessions`; every filter is optional */
+export interface SessionListQuery {
+  /** Keep endDate fixed while paging so new sessions don't shift the pages */
+  startDate?: string;
+  endDate?: string;
+  sessionType?: 'work' | 'break';
+  /** Case-insensitive text searched in notes, segment labels and end reasons */
+  search?: string;
+  isSkipped?: boolean;
+  endReason?: SessionEndReason;
+  minDurationSeconds?: number;
+  maxDurationSeconds?: number;
+  offset?: number;
+  /** 1–500, default 50 */
+  limit?: number;
+}
+
+/** One page of sessions, newest first, and how many matched in total */
+export interface SessionListPage {
   sessions: Session[];
+  total: number;
 }
 
 export interface SessionsBounds {
@@ -118,6 +651,38 @@ export interface SessionsBounds {
   latestEnd: string | null;
 }
 
+/** All-time counters of finished sessions */
+export interface LifetimeTotals {
+  workSessions: number;
+  workSeconds: number;
+  breaks: number;
+  completedBreaks: number;
+  skippedBreaks: number;
+  breakSeconds: number;
+}
+
+export interface YearStats {
+  year: number;
+  totals: LifetimeTotals;
+  /** Month (1-12) with the highest share of completed breaks */
+  mostDisciplinedMonth: number | null;
+}
+
+/** All-time aggregates kept locally; deleting sessions does not lower them */
+export interface LifetimeStats {
+  firstUseDate: string | null; // YYYY-MM-DD
+  totals: LifetimeTotals;
+  longestStreakDays: number;
+  /** "YYYY-MM" */
+  mostDisciplinedMonth: string | null;
+  years: YearStats[];
+}
+
+/**
+ * CSV layout for exportTimeEntries
+ */
+export type TimeEntryFormat = 'toggl-csv' | 'generic-csv';
+
 /**
  * Analytics query parameters
  */
@@ -137,12 +702,183 @@ export interface MonitorInfo {
   isPrimary: boolean;
 }
 
+/**
+ * Result of one channel in the reminder output test
+ */
+export type OutputCheck =
+  | { status: 'passed' }
+  | { status: 'skipped'; reason: string }
+  | { status: 'failed'; error: string };
+
+/**
+ * Per-channel results of testReminderOutput
+ */
+export interface ReminderOutputTest {
+  sound: OutputCheck;
+  notification: OutputCheck;
+  preview: OutputCheck;
+}
+
+/**
+ * Saved sound preference plus the transient mute flag
+ */
+export interface AudioStatus {
+  playSound: boolean;
+  muted: boolean;
+  soundEnabled: boolean;
+}
+
+/**
+ * First-paint payload for a window (see getBootstrap)
+ */
+export interface Bootstrap {
+  settings: Settings;
+  timerInfo: TimerInfo;
+  today: AnalyticsData | null;
+  translation: Record<string, unknown> | null;
+  breakContent: BreakContent | null;
+  musicTrack: string | null;
+  // Parts that failed to load; the rest of the payload is still valid
+  errors: string[];
+}
+
+/**
+ * A reminder window that could not be created
+ */
+/**
+ * Display power monitor watchdog state (only supervised on Windows)
+ */
+export interface PowerMonitorHealth {
+  supported: boolean;
+  running: boolean;
+  healthy: boolean;
+  lastHeartbeat: string | null; // ISO 8601
+  restarts: number;
+  lastError: string | null;
+}
+
+/** Timer ticker liveness (diagnostics) */
+export interface TickerHealth {
+  healthy: boolean; // ticked within the last few seconds
+  lastTick: string | null; // ISO 8601
+  restarts: number; // watchdog restarts since launch
+  panics: number;
+  errors: number;
+  lastError: string | null;
+}
+
+/**
+ * Countdown accuracy over the current phase (diagnostics)
+ */
+export interface TimerAccuracy {
+  phase: TimerPhase;
+  state: TimerState;
+  phaseEndTime: string | null; // ISO 8601
+  remainingSeconds: number;
+  samples: number; // ticks compared in this phase
+  maxDriftMs: number;
+  lastDriftMs: number;
+}
+
+/** Rest music files that could not be opened (`music-track-unavailable`) */
+export interface MusicTrackUnavailable {
+  paths: string[];
+  // Nothing could be played; the break-start chime played instead
+  fellBackToChime: boolean;
+}
+
+/** Payload of `storage-error`: a session write failed and was rolled back */
+export interface StorageFailure {
+  operation: string; // e.g. "saveSession"
+  error: string;
+  failedAt: string; // ISO 8601
+}
+
+/** Size of a full data export, shown before exporting */
+export interface DataExportEstimate {
+  sessions: number;
+  estimatedBytes: number;
+}
+
+/** Progress of a full data export with many sessions */
+export interface DataExportProgress {
+  sessionsWritten: number;
+  totalSessions: number;
+  bytesWritten: number;
+  estimatedBytes: number;
+}
+
+export interface DataExportSummary {
+  path: string;
+  sessions: number;
+  bytes: number;
+}
+
+/** Whether settings and sessions are encrypted at rest */
+export interface EncryptionStatus {
+  enabled: boolean;
+  locked: boolean; // encrypted and not unlocked yet this launch
+}
+
+/** A background task panicked; details are in the panic log */
+export interface BackendPanic {
+  task: string; // e.g. "timer"
+  message: string;
+  logPath: string;
+  recovered: boolean; // the task keeps running
+}
+
+export interface ReminderFailure {
+  label: string;
+  error: string;
+  failedAt: string; // ISO 8601
+  // True when no reminder window opened and a notification was shown instead
+  fallback: boolean;
+}
+
+/**
+ * Build information for bug reports
+ */
+export interface AppInfo {
+  version: string;
+  os: string;
+  arch: string;
+  commit: string | null;
+  buildTimestamp: string | null;
+  isDevBuild: boolean;
+  isPortable: boolean;
+  dataDir: string;
+}
+
+export type StorageLocation = 'appData' | 'executableDir' | 'tempDir';
+
+/**
+ * Data directory in use; `primaryError` is set when a fallback was needed
+ */
+export interface StorageInfo {
+  dataDir: string;
+  location: StorageLocation;
+  primaryError: string | null;
+}
+
 /**
  * System status
  */
 export interface SystemStatus {
   isFullscreen: boolean;
   isDoNotDisturb: boolean;
+  isRemoteSession: boolean;
+  // False only once a connectivity probe found no network
+  isOnline: boolean;
+  // False when no tray icon could be created (no status notifier host)
+  trayAvailable: boolean;
+}
+
+/** Payload of `tray-unavailable` */
+export interface TrayUnavailable {
+  error: string;
+  // Another attempt follows shortly (`tray-available` on success)
+  retrying: boolean;
 }
 
 /**
@@ -152,25 +888,135 @@ export const DEFAULT_SETTINGS: Settings = {
   workDuration: 25,
   breakDuration: 5,
   enableForceBreak: false,
+  maxReminderMinutes: 60,
+  requireBreakAcknowledgment: false,
+  maxSuppressionMinutes: null,
+  suppressionExpiryWarningMinutes: 0,
   flowModeEnabled: false,
   moreRestEnabled: false,
   segmentedWorkEnabled: false,
   workSegments: [{ workMinutes: 25, breakMinutes: 5, repeat: 1 }],
+  timeAnchoredSegments: [],
+  programRepeat: null,
+  presets: [],
+  activePreset: null,
+  windDown: {
+    enabled: false,
+    endOfDay: '18:00',
+    startsBeforeEndMinutes: 10,
+    workMinutes: 15,
+    breakMinutes: 5,
+  },
+  onScheduleComplete: 'loop',
+  breakJitterMinutes: 0,
+  randomSeed: null,
+  catchUpBreakEnabled: false,
+  longAbsenceMinutes: null,
+  travelMode: false,
+  maxExtendsPerPhase: null,
+  maxTotalExtendMinutes: null,
+  workStartCountdownSeconds: 0,
   reminderMode: 'fullscreen',
   reminderFullscreenDisplay: 'panel',
   floatingPosition: 'top-right',
   opacity: 95,
+  reminderClickThrough: false,
+  remoteSessionBehavior: 'normal',
   playSound: true,
+  soundEvents: {
+    workEnd: true,
+    breakStart: true,
+    breakEnd: true,
+  },
   restMusicEnabled: false,
   restMusicDirectory: '',
   restStartSoonNotificationEnabled: false,
+  breakEndNotificationEnabled: false,
+  notificationActions: {
+    restStartSoon: true,
+    breakEnd: true,
+  },
+  promptWorkNote: false,
+  workNoteInFlowMode: false,
+  hideMainWindowDuringBreak: false,
+  mainWindowBreakAction: 'hide',
   theme: 'auto',
   autostart: false,
   silentAutostart: false,
   autoSilentUpdateEnabled: false,
+  autoInstallUpdates: true,
   minimizeToTray: true,
   closeToTray: true,
+  alwaysOnTop: false,
+  forceHighContrast: false,
+  forceReducedMotion: false,
   disableAnalytics: false,
+  lifetimeStatsEnabled: false,
+  allowHooks: false,
+  onWorkStartCommand: '',
+  onBreakStartCommand: '',
+  webhookUrl: null,
+  quoteProviders: [
+    {
+      name: 'Hitokoto',
+      url: 'https://v1.hitokoto.cn/?encode=json',
+      pointers: ['/hitokoto'],
+      language: 'zh',
+    },
+    {
+      name: 'ViewBits',
+      url: 'https://api.viewbits.com/v1/zenquotes?mode=random',
+      pointers: ['/q', '/data/0/q', '/0/q'],
+      language: 'en',
+    },
+  ],
+  networkQuietHours: [],
+  meteredMode: false,
+  autoDetectMetered: false,
+  preferredExerciseCategories: [],
+  customExercises: [],
+  breakThemes: [],
+  storageFormat: 'json',
+  analyticsLoadWindowDays: 0,
+  sessionIdScheme: 'uuid',
+  sessionIdPrefix: '',
+  autoStartOnFirstActivity: false,
+  deferBreaksDuringCalls: false,
+  devMode: false,
+  minBreakCompletionRatio: 0.8,
+  minSessionSeconds: 0,
+  minRealBreakMinutes: 5,
+  activitySampleSeconds: 15,
+  activityIdleThresholdSeconds: 60,
+  restDebtCompensationPercent: 0,
+  weekStartDay: 'monday',
+  anomalyDetection: {
+    breakOverrunRatio: 3,
+    maxWorkMinutes: 540,
+    flagZeroDuration: true,
+  },
+  focusScore: {
+    dailyWorkGoalMinutes: 240,
+    workGoalWeight: 40,
+    breakCompletionWeight: 40,
+    lowSkipsWeight: 20,
+  },
+  hydration: {
+    enabled: false,
+    dailyGoalGlasses: 8,
+    reminderIntervalMinutes: 60,
+  },
+  hotkeys: {
+    openTrayMenu: 'CommandOrControl+Shift+R',
+    emergencyDismiss: 'CommandOrControl+Alt+Escape',
+  },
+  externalControlPermissions: {
+    tray: ['readStatus', 'timerControl', 'settingsWrite', 'quit'],
+    webview: ['readStatus', 'timerControl', 'settingsWrite', 'quit'],
+    http: ['readStatus'],
+    cli: ['readStatus'],
+    deeplink: ['readStatus'],
+  },
   language: 'en-US',
   version: '0.1.0',
   updatedAt: new Date().toISOString(),
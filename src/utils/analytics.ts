@@ -1,4 +1,4 @@
-import type { Session } from '../types';
+import type { Session, WeekStartDay } from '../types';
 
 const POWER_INTERRUPT_BREAK_NOTE = 'power-interrupt-break';
 const POWER_INTERRUPT_WORK_NOTE = 'power-interrupt-work';
@@ -67,3 +67,13 @@ export const augmentSessionsWithMoreRest = (sessions: Session[], enabled: boolea
   }
   return sortSessions([...sessions, ...gaps]);
 };
+
+// 本周第一天的零点（按设置从周一或周日开始）
+export const getStartOfWeek = (date: Date, weekStartDay: WeekStartDay) => {
+  const start = new Date(date);
+  start.setHours(0, 0, 0, 0);
+  const day = start.getDay(); // Sunday = 0
+  const diff = weekStartDay === 'sunday' ? day : (day + 6) % 7;
+  start.setDate(start.getDate() - diff);
+  return start;
+};
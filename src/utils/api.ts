@@ -1,17 +1,58 @@
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import type {
+  AccessibilityPrefs,
   AchievementUnlock,
+  AppInfo,
+  AudioStatus,
+  BreakAcknowledged,
+  BreakExercise,
+  ClockChanged,
+  ConnectivityChanged,
+  QuoteProvider,
+  DayStarted,
+  Bootstrap,
   Settings,
+  StorageInfo,
   TimerInfo,
+  TimerPhase,
+  TimerPreset,
+  TimeEntryFormat,
   AnalyticsData,
+  AnalyticsDelta,
   AnalyticsQuery,
+  DurationHistogram,
+  FocusScore,
   FloatingPosition,
+  HydrationProgress,
   MonitorInfo,
   SystemStatus,
   SessionsBounds,
+  SessionListPage,
+  SessionListQuery,
+  LifetimeStats,
   Session,
   UpdateManifest,
+  StagedUpdate,
+  UpdateDownloadProgress,
+  PhaseChange,
+  PhaseChangeCause,
+  PowerMonitorHealth,
+  TickerHealth,
+  TimerAccuracy,
+  MusicTrackUnavailable,
+  ReminderFailure,
+  ReminderOutputTest,
+  StorageFailure,
+  BackendPanic,
+  DataExportEstimate,
+  DataExportProgress,
+  DataExportSummary,
+  EncryptionStatus,
+  TrayUnavailable,
+  SettingChange,
+  SettingsChanged,
+  SuppressionExpiring,
 } from '../types';
 
 /**
@@ -59,6 +100,16 @@ export async function extendPhase(): Promise<void> {
   return await invoke('extend_phase');
 }
 
+/** Push the active break suppression back by `minutes`. */
+export async function extendSuppression(minutes: number): Promise<void> {
+  return await invoke('extend_suppression', { minutes });
+}
+
+/** End break suppression now. */
+export async function resumeBreaks(): Promise<void> {
+  return await invoke('resume_breaks');
+}
+
 /** 鑾峰彇鏈€鏂拌鏃跺櫒鐘舵€侊紝甯哥敤浜庡簲鐢ㄥ垵濮嬪寲銆?*/
 export async function getTimerInfo(): Promise<TimerInfo> {
   return await invoke('get_timer_info');
@@ -70,6 +121,52 @@ export async function getAnalytics(query: AnalyticsQuery): Promise<AnalyticsData
   return await invoke('get_analytics', { query });
 }
 
+/** Count work and break sessions per `bucketMinutes` of duration. */
+export async function getDurationHistogram(
+  query: AnalyticsQuery,
+  bucketMinutes: number
+): Promise<DurationHistogram> {
+  return await invoke('get_duration_histogram', { query, bucketMinutes });
+}
+
+/** Focus score (0-100) of a local day ("YYYY-MM-DD") with its breakdown. */
+export async function getFocusScore(date: string): Promise<FocusScore> {
+  return await invoke('get_focus_score', { date });
+}
+
+/**
+ * When today's work is projected to reach the daily work goal at the current
+ * pace (ISO 8601); null when the goal is met or out of reach today.
+ */
+export async function getGoalEta(): Promise<string | null> {
+  return await invoke('get_goal_eta');
+}
+
+/** Today's glasses of water against the daily goal. */
+export async function getHydrationProgress(): Promise<HydrationProgress> {
+  return await invoke('get_hydration_progress');
+}
+
+/** Log one glass of water today (fails while hydration tracking is off). */
+export async function incrementHydration(): Promise<HydrationProgress> {
+  return await invoke('increment_hydration');
+}
+
+/** Save (or clear with an empty string) the note of a session. */
+export async function updateSessionNote(id: string, note: string): Promise<Session> {
+  return await invoke('update_session_note', { id, note });
+}
+
+/** One page of sessions matching the history filters, with the total match count. */
+export async function listSessions(query: SessionListQuery): Promise<SessionListPage> {
+  return await invoke('list_sessions', { query });
+}
+
+/** Stop reporting a session's anomaly in analytics. */
+export async function dismissAnomaly(sessionId: string): Promise<Session> {
+  return await invoke('dismiss_anomaly', { sessionId });
+}
+
 /** 鑾峰彇浼氳瘽鏁版嵁鐨勬椂闂磋寖鍥达紙鐢ㄤ簬鍒嗛〉绛夊満鏅級銆?*/
 export async function getSessionsBounds(): Promise<SessionsBounds> {
   return await invoke('get_sessions_bounds');
@@ -80,6 +177,21 @@ export async function clearAnalyticsData(): Promise<void> {
   return await invoke('clear_analytics_data');
 }
 
+/** All-time statistics; rejects while lifetimeStatsEnabled is off. */
+export async function getLifetimeStats(): Promise<LifetimeStats> {
+  return await invoke('get_lifetime_stats');
+}
+
+/** Recompute the lifetime statistics from the stored sessions. */
+export async function rebuildLifetimeStats(): Promise<LifetimeStats> {
+  return await invoke('rebuild_lifetime_stats');
+}
+
+/** Re-read sessions.json from disk after external edits; resolves to the session count. */
+export async function reloadSessions(): Promise<number> {
+  return await invoke('reload_sessions');
+}
+
 // Achievements commands
 /** 鑾峰彇宸茶В閿佹垚灏卞垪琛ㄣ€?*/
 export async function getAchievements(): Promise<AchievementUnlock[]> {
@@ -92,17 +204,69 @@ export async function importConfig(jsonStr: string): Promise<Settings> {
   return await invoke('import_config', { jsonStr });
 }
 
+/** Validate a config and list the settings it would change, without saving. */
+export async function previewImport(jsonStr: string): Promise<SettingChange[]> {
+  return await invoke('preview_import', { jsonStr });
+}
+
 /** 瀵煎嚭褰撳墠閰嶇疆涓?JSON 瀛楃涓层€?*/
 export async function exportConfig(): Promise<string> {
   return await invoke('export_config');
 }
 
+/** Export the current config as a QR code PNG data URL. */
+export async function exportConfigQr(): Promise<string> {
+  return await invoke('export_config_qr');
+}
+
+/** Import config from the text of a scanned QR code. */
+export async function importConfigFromQr(payload: string): Promise<Settings> {
+  return await invoke('import_config_from_qr', { payload });
+}
+
 // Data transfer commands
 /** 瀵煎嚭璁剧疆涓庣粺璁℃暟鎹埌鎸囧畾璺緞銆?*/
 export async function exportAppDataToFile(path: string): Promise<void> {
   return await invoke('export_app_data_to_file', { path });
 }
 
+/** Size of a full data export (sessions and estimated bytes). */
+export async function estimateDataExport(): Promise<DataExportEstimate> {
+  return await invoke('estimate_data_export');
+}
+
+/** Export everything RESTY stores to one JSON file (secrets redacted). */
+export async function exportAllData(path: string): Promise<DataExportSummary> {
+  return await invoke('export_all_data', { path });
+}
+
+/** Fired every few hundred sessions while a large full export is written. */
+export async function onDataExportProgress(callback: (payload: DataExportProgress) => void) {
+  return await listen<DataExportProgress>('data-export-progress', (event) =>
+    callback(event.payload)
+  );
+}
+
+/** Whether data encryption is on and waiting for the passphrase. */
+export async function getEncryptionStatus(): Promise<EncryptionStatus> {
+  return await invoke('get_encryption_status');
+}
+
+/** Unlock encrypted data; rejects with "Wrong passphrase" on a bad passphrase. */
+export async function unlockData(passphrase: string): Promise<Settings> {
+  return await invoke('unlock_data', { passphrase });
+}
+
+/** Encrypt settings and sessions with a passphrase (at least 8 characters). */
+export async function enableDataEncryption(passphrase: string): Promise<void> {
+  return await invoke('enable_data_encryption', { passphrase });
+}
+
+/** Store settings and sessions as plaintext again. */
+export async function disableDataEncryption(passphrase: string): Promise<void> {
+  return await invoke('disable_data_encryption', { passphrase });
+}
+
 /** 浠庢寚瀹氳矾寰勫鍏ヨ缃笌缁熻鏁版嵁銆?*/
 export async function importAppDataFromFile(path: string): Promise<Settings> {
   return await invoke('import_app_data_from_file', { path });
@@ -120,12 +284,111 @@ export async function getSystemStatus(): Promise<SystemStatus> {
   return await invoke('get_system_status');
 }
 
+/** Version, platform, build details and data directory, for bug reports and update checks. */
+export async function getAppInfo(): Promise<AppInfo> {
+  return await invoke('get_app_info');
+}
+
+/** Whether loading adjusted the saved settings (old file migrated or values normalized). */
+export async function isSettingsNormalized(): Promise<boolean> {
+  return await invoke('is_settings_normalized');
+}
+
+/** Data directory in use and whether the app data dir had to be replaced by a fallback. */
+export async function getStorageInfo(): Promise<StorageInfo> {
+  return await invoke('get_storage_info');
+}
+
+/** Send a test notification and show a short reminder preview using `settings`. */
+export async function testReminderOutput(settings: Settings): Promise<ReminderOutputTest> {
+  return await invoke('test_reminder_output', { settings });
+}
+
+/** Turn travel mode on or off (saved); returns the updated settings. */
+export async function setTravelMode(enabled: boolean): Promise<Settings> {
+  return await invoke('set_travel_mode', { enabled });
+}
+
+/** Temporarily mute all sounds; the saved `playSound` preference is left untouched. */
+export async function setSoundMuted(muted: boolean): Promise<void> {
+  return await invoke('set_sound_muted', { muted });
+}
+
+/** Saved sound preference combined with the runtime mute flag. */
+export async function getAudioStatus(): Promise<AudioStatus> {
+  return await invoke('get_audio_status');
+}
+
+/** Most recent reminder window creation failure since launch, if any. */
+export async function getLastReminderFailure(): Promise<ReminderFailure | null> {
+  return await invoke('get_last_reminder_failure');
+}
+
+/** Heartbeat and restart count of the display power monitor, for diagnostics. */
+export async function getPowerMonitorHealth(): Promise<PowerMonitorHealth> {
+  return await invoke('get_power_monitor_health');
+}
+
+/** Timer ticker health: last tick, watchdog restarts and failed ticks. */
+export async function getTickerHealth(): Promise<TickerHealth> {
+  return await invoke('get_ticker_health');
+}
+
+/** Largest countdown drift (ms) seen by the timer ticks of the current phase. */
+export async function getTimerAccuracy(): Promise<TimerAccuracy> {
+  return await invoke('get_timer_accuracy');
+}
+
+/**
+ * Export work sessions as CSV time entries; returns the number of entries written.
+ * Sessions shorter than `minMinutes` (default 1) are skipped.
+ */
+export async function exportTimeEntries(
+  query: AnalyticsQuery,
+  format: TimeEntryFormat,
+  path: string,
+  minMinutes?: number
+): Promise<number> {
+  return await invoke('export_time_entries', { query, format, path, minMinutes });
+}
+
+/** Breaks projected for the next `hours` as iCalendar (.ics) text. */
+export async function exportScheduleIcs(hours: number): Promise<string> {
+  return await invoke('export_schedule_ics', { hours });
+}
+
+// Timer presets
+/** Save a preset (replacing one with the same name); returns all presets. */
+export async function savePreset(preset: TimerPreset): Promise<TimerPreset[]> {
+  return await invoke('save_preset', { preset });
+}
+
+/** Delete a preset by name; returns the remaining presets. */
+export async function deletePreset(name: string): Promise<TimerPreset[]> {
+  return await invoke('delete_preset', { name });
+}
+
+/** Name of the preset the timer is currently running, if any. */
+export async function getActivePreset(): Promise<string | null> {
+  return await invoke('get_active_preset');
+}
+
+/** Switch the timer to a saved preset; returns the updated settings. */
+export async function applyPreset(name: string): Promise<Settings> {
+  return await invoke('apply_preset', { name });
+}
+
 // Rest music commands
 /** 鍒楀嚭浼戞伅闊充箰鐩綍涓殑闊抽鏂囦欢璺緞銆?*/
 export async function getRestMusicFiles(): Promise<string[]> {
   return await invoke('get_rest_music_files');
 }
 
+/** Pick a playable rest music track, avoiding `previous`; null when none can be played. */
+export async function pickRestMusicTrack(previous: string | null): Promise<string | null> {
+  return await invoke('pick_rest_music_track', { previous });
+}
+
 // Autostart plugin commands (via Tauri v2 plugin)
 /** 妫€鏌ユ槸鍚﹀凡鍚敤寮€鏈鸿嚜鍚紙鍏煎涓嶅悓鍛戒护鍛藉悕锛夈€?*/
 export async function isAutostartEnabled(): Promise<boolean> {
@@ -180,11 +443,54 @@ export async function closeReminderWindow(): Promise<void> {
   return await invoke('close_reminder_window');
 }
 
+/** Acknowledge the finished break and close its reminder. */
+export async function acknowledgeBreak(): Promise<Session | null> {
+  return await invoke<Session | null>('acknowledge_break');
+}
+
 /** 鍓嶇鍑嗗濂藉悗鏄剧ず鎻愰啋绐楀彛锛堥伩鍏嶇櫧灞忛棯鐑侊級銆?*/
 export async function showReminderWindow(): Promise<void> {
   return await invoke('show_reminder_window');
 }
 
+/** Report that the reminder page has mounted so the backend can reveal its window. */
+export async function reminderReady(label: string): Promise<void> {
+  return await invoke('reminder_ready', { label });
+}
+
+/** Tell the backend watchdog this reminder page is still responsive. */
+export async function reminderHeartbeat(label: string): Promise<void> {
+  return await invoke('reminder_heartbeat', { label });
+}
+
+/** Pre-create hidden reminder windows so the next break shows instantly. */
+export async function prewarmReminder(): Promise<void> {
+  return await invoke('prewarm_reminder');
+}
+
+/** Fast-forward the running phase (only in `dev-tools` builds with devMode on). */
+export async function debugAdvanceTimer(seconds: number): Promise<void> {
+  return await invoke('debug_advance_timer', { seconds });
+}
+
+/**
+ * Recovery: end the running phase and start a clean one with `minutes` left
+ * ('idle' just stops). Requires devMode; available in every build.
+ */
+export async function setTimerPhase(phase: TimerPhase, minutes: number): Promise<void> {
+  return await invoke('set_timer_phase', { phase, minutes });
+}
+
+/** OS accessibility preferences combined with the settings overrides. */
+export async function getAccessibilityPrefs(): Promise<AccessibilityPrefs> {
+  return await invoke('get_accessibility_prefs');
+}
+
+/** Pin or unpin the main window immediately (persist via saveSettings). */
+export async function setMainWindowAlwaysOnTop(enabled: boolean): Promise<void> {
+  return await invoke('set_main_window_always_on_top', { enabled });
+}
+
 /** 鏄剧ず涓荤獥鍙ｏ紙鐢ㄤ簬鍓嶇鍒濆鍖栧畬鎴愬悗璋冪敤锛夈€?*/
 export async function showMainWindow(): Promise<void> {
   return await invoke('show_main_window');
@@ -197,8 +503,12 @@ export async function onTimerUpdate(callback: (info: TimerInfo) => void) {
 }
 
 /** 璁㈤槄闃舵鍒囨崲浜嬩欢锛堝伐浣?<-> 浼戞伅锛夈€?*/
-export async function onPhaseChange(callback: (phase: string) => void) {
-  return await listen<string>('phase-change', (event) => callback(event.payload));
+export async function onPhaseChange(
+  callback: (phase: string, cause: PhaseChangeCause) => void
+) {
+  return await listen<PhaseChange>('phase-change', (event) =>
+    callback(event.payload.phase, event.payload.cause)
+  );
 }
 
 /** 璁㈤槄璁℃椂缁撴潫浜嬩欢锛屽彲鐢ㄤ簬鎾斁鎻愮ず闊崇瓑浜屾鍔ㄤ綔銆?*/
@@ -221,6 +531,21 @@ export async function onSessionUpserted(callback: (session: Session) => void) {
   return await listen<Session>('session-upserted', (event) => callback(event.payload));
 }
 
+/** Subscribe to session reloads from disk; the payload is the new session count. */
+export async function onSessionsReloaded(callback: (count: number) => void) {
+  return await listen<number>('sessions-reloaded', (event) => callback(event.payload));
+}
+
+/** Subscribe to a break finished by a long display-off/suspend; the payload is the absence in seconds. */
+export async function onResumedAfterAbsence(callback: (absenceSeconds: number) => void) {
+  return await listen<number>('resumed-after-absence', (event) => callback(event.payload));
+}
+
+/** Subscribe to incremental analytics totals produced by session writes. */
+export async function onAnalyticsDelta(callback: (delta: AnalyticsDelta) => void) {
+  return await listen<AnalyticsDelta>('analytics-delta', (event) => callback(event.payload));
+}
+
 /** 璁㈤槄鎴愬氨瑙ｉ攣浜嬩欢銆?*/
 export async function onAchievementUnlocked(callback: (achievement: AchievementUnlock) => void) {
   return await listen<AchievementUnlock>('achievement-unlocked', (event) => callback(event.payload));
@@ -237,13 +562,62 @@ export async function installUpdate(): Promise<void> {
   return await invoke('install_update');
 }
 
+/** Download and verify the latest update without installing it. */
+export async function downloadUpdate(): Promise<StagedUpdate> {
+  return await invoke<StagedUpdate>('download_update');
+}
+
+/** Install an update staged by `downloadUpdate`; the running session is saved first. */
+export async function installDownloadedUpdate(path: string): Promise<void> {
+  return await invoke('install_downloaded_update', { path });
+}
+
+/** Background updater downloaded an update and waits for confirmation to install it. */
+export async function onUpdateReadyToInstall(callback: (update: StagedUpdate) => void) {
+  return await listen<StagedUpdate>('update-ready-to-install', (event) => callback(event.payload));
+}
+
+export async function onUpdateDownloadProgress(
+  callback: (progress: UpdateDownloadProgress) => void
+) {
+  return await listen<UpdateDownloadProgress>('update-download-progress', (event) =>
+    callback(event.payload)
+  );
+}
+
 // Network proxy commands
+/** POST a sample phase transition to `url`; resolves to the HTTP status. */
+export async function testWebhook(url: string): Promise<number> {
+  return await invoke('test_webhook', { url });
+}
+
 /** 鑾峰彇璐村＋寮曠敤鏂囨锛堝悗绔唬鐞嗭級銆?*/
 export async function fetchTipQuote(language: string): Promise<string | null> {
   return await invoke('fetch_tip_quote', { language });
 }
 
+/** Next exercise to suggest on the break screen, in `language`. */
+export async function getBreakExercise(language: string): Promise<BreakExercise | null> {
+  return await invoke('get_break_exercise', { language });
+}
+
+/**
+ * Fetch one quote from a provider being configured; rejects with the reason
+ * (network, HTTP status, invalid JSON or no text at the pointers).
+ */
+export async function testQuoteSource(config: QuoteProvider): Promise<string> {
+  return await invoke('test_quote_source', { config });
+}
+
 /** 鍔犺浇缈昏瘧璧勬簮锛堝悗绔唬鐞嗭級銆?*/
+/**
+ * Settings, timer info, translations and per-window extras in one call.
+ * `window` is 'main', 'tray-menu', 'work-note' or 'reminder'.
+ */
+export async function getBootstrap(window: string): Promise<Bootstrap> {
+  return await invoke('get_bootstrap', { window });
+}
+
 export async function loadTranslation(language: string): Promise<Record<string, unknown>> {
   return await invoke('load_translation', { language });
 }
@@ -262,6 +636,103 @@ export async function sendPreBreakToast(
   });
 }
 
+export async function onSoundMutedChanged(callback: (muted: boolean) => void) {
+  return await listen<boolean>('sound-muted-changed', (event) => callback(event.payload));
+}
+
+/** Seconds left in the back-to-work countdown after a break (fired every second). */
+export async function onTransitionCountdown(callback: (seconds: number) => void) {
+  return await listen<number>('transition-countdown', (event) => callback(event.payload));
+}
+
+/** Play the chime for a transition: 'workEnd', 'breakStart' or 'breakEnd'. */
+export async function onPlayChime(callback: (event: string) => void) {
+  return await listen<string>('play-chime', (event) => callback(event.payload));
+}
+
+/** Fired when the user acknowledges a finished break. */
+export async function onBreakAcknowledged(callback: (payload: BreakAcknowledged) => void) {
+  return await listen<BreakAcknowledged>('break-acknowledged', (event) => callback(event.payload));
+}
+
+/** Fired when the first work session of the day starts. */
+export async function onDayStarted(callback: (payload: DayStarted) => void) {
+  return await listen<DayStarted>('day-started', (event) => callback(event.payload));
+}
+
+/** Fired when the system clock jumped and the running phase was re-anchored. */
+export async function onClockChanged(callback: (payload: ClockChanged) => void) {
+  return await listen<ClockChanged>('clock-changed', (event) => callback(event.payload));
+}
+
+/** Fired when rest music files could not be opened (deleted, drive unplugged). */
+export async function onMusicTrackUnavailable(
+  callback: (payload: MusicTrackUnavailable) => void
+) {
+  return await listen<MusicTrackUnavailable>('music-track-unavailable', (event) =>
+    callback(event.payload)
+  );
+}
+
+/** Fired when the periodic connectivity probe finds the network gone or back. */
+export async function onConnectivityChanged(callback: (payload: ConnectivityChanged) => void) {
+  return await listen<ConnectivityChanged>('connectivity-changed', (event) =>
+    callback(event.payload)
+  );
+}
+
+/** Fired when the tray icon could not be created; closing the window then minimizes it. */
+export async function onTrayUnavailable(callback: (payload: TrayUnavailable) => void) {
+  return await listen<TrayUnavailable>('tray-unavailable', (event) => callback(event.payload));
+}
+
+/** Fired when creating the tray icon succeeded on the later retry. */
+export async function onTrayAvailable(callback: () => void) {
+  return await listen('tray-available', () => callback());
+}
+
+/** Quit the app, as the tray menu's Quit item does. */
+export async function quitApp(): Promise<void> {
+  return await invoke('tray_menu_action', { action: 'quit' });
+}
+
+/** Fired when session history could not be written (disk full, no permission). */
+export async function onStorageError(callback: (payload: StorageFailure) => void) {
+  return await listen<StorageFailure>('storage-error', (event) => callback(event.payload));
+}
+
+/** Fired when a background task panicked; the payload says where the log was saved. */
+export async function onBackendPanic(callback: (payload: BackendPanic) => void) {
+  return await listen<BackendPanic>('backend-panic', (event) => callback(event.payload));
+}
+
+/** Fired after settings are saved or imported, with the top-level fields that changed. */
+export async function onSettingsChanged(callback: (payload: SettingsChanged) => void) {
+  return await listen<SettingsChanged>('settings-changed', (event) => callback(event.payload));
+}
+
+/** Fired when work has run `hydration.reminderIntervalMinutes` and today's goal isn't met. */
+export async function onHydrationReminder(callback: (payload: HydrationProgress) => void) {
+  return await listen<HydrationProgress>('hydration-reminder', (event) => callback(event.payload));
+}
+
+/** Fired after a glass of water is logged, with today's new progress. */
+export async function onHydrationChanged(callback: (payload: HydrationProgress) => void) {
+  return await listen<HydrationProgress>('hydration-changed', (event) => callback(event.payload));
+}
+
+/** Fired once per suppression window, `suppressionExpiryWarningMinutes` before it ends. */
+export async function onSuppressionExpiring(callback: (payload: SuppressionExpiring) => void) {
+  return await listen<SuppressionExpiring>('suppression-expiring', (event) =>
+    callback(event.payload)
+  );
+}
+
 export async function onPreBreakAction(callback: (actionId: string) => void) {
   return await listen<string>('pre-break-action', (event) => callback(event.payload));
-}
\ No newline at end of file
+}
+
+/** Fired once when the first phase inside the wind-down window starts. */
+export async function onWindDownStarted(callback: () => void) {
+  return await listen('wind-down-started', () => callback());
+}
@@ -0,0 +1,32 @@
+/** Pitch of the chime per transition, so the cues can be told apart by ear. */
+const CHIME_FREQUENCIES: Record<string, number> = {
+  workEnd: 660,
+  breakStart: 880,
+  breakEnd: 523,
+};
+
+let audioContext: AudioContext | null = null;
+
+/**
+ * 播放阶段切换提示音（短促的正弦音）。
+ * 在不支持 Web Audio 的环境下静默失败。
+ */
+export function playChime(event: string): void {
+  try {
+    audioContext ??= new AudioContext();
+    const context = audioContext;
+    const oscillator = context.createOscillator();
+    const gain = context.createGain();
+    const start = context.currentTime;
+
+    oscillator.type = 'sine';
+    oscillator.frequency.value = CHIME_FREQUENCIES[event] ?? 660;
+    gain.gain.setValueAtTime(0.2, start);
+    gain.gain.exponentialRampToValueAtTime(0.001, start + 0.6);
+    oscillator.connect(gain).connect(context.destination);
+    oscillator.start(start);
+    oscillator.stop(start + 0.6);
+  } catch (error) {
+    console.warn('Failed to play chime:', error);
+  }
+}